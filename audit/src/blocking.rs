@@ -0,0 +1,86 @@
+use std::{future::Future, io};
+
+use futures::stream::TryStreamExt;
+
+use crate::{
+    packet::{AuditMessage, NetlinkMessage, RuleMessage, StatusMessage},
+    proto::{blocking::Connection, DecoderQuirks},
+    sys::{protocols::NETLINK_AUDIT, SocketAddr},
+    Error,
+    Handle,
+};
+
+/// Like [`Handle`], but the requests block the current thread instead of returning futures, so
+/// that no async runtime is needed.
+///
+/// ```rust,no_run
+/// use audit::BlockingHandle;
+///
+/// fn main() -> Result<(), audit::Error> {
+///     let mut handle = BlockingHandle::new().unwrap();
+///     handle.enable_events()?;
+///     loop {
+///         let (event, _) = handle.next_event()?;
+///         println!("{:?}", event);
+///     }
+/// }
+/// ```
+pub struct BlockingHandle {
+    connection: Connection<AuditMessage>,
+    handle: Handle,
+}
+
+impl BlockingHandle {
+    pub fn new() -> io::Result<Self> {
+        let mut connection = Connection::new(NETLINK_AUDIT)?;
+        connection.set_decoder_quirks(DecoderQuirks::audit());
+        let handle = Handle::new(connection.handle());
+        Ok(BlockingHandle { connection, handle })
+    }
+
+    /// Add the given rule
+    pub fn add_rule(&mut self, rule: RuleMessage) -> Result<(), Error> {
+        let mut handle = self.handle.clone();
+        self.block_on(async move { handle.add_rule(rule).await })
+    }
+
+    /// Deletes a given rule
+    pub fn del_rule(&mut self, rule: RuleMessage) -> Result<(), Error> {
+        let mut handle = self.handle.clone();
+        self.block_on(async move { handle.del_rule(rule).await })
+    }
+
+    /// List the current rules
+    pub fn list_rules(&mut self) -> Result<Vec<RuleMessage>, Error> {
+        let rules = self.handle.list_rules().try_collect();
+        self.block_on(rules)
+    }
+
+    /// Enable receiving audit events
+    pub fn enable_events(&mut self) -> Result<(), Error> {
+        let mut handle = self.handle.clone();
+        self.block_on(async move { handle.enable_events().await })
+    }
+
+    /// Get current audit status
+    pub fn get_status(&mut self) -> Result<StatusMessage, Error> {
+        let mut handle = self.handle.clone();
+        self.block_on(async move { handle.get_status().await })
+    }
+
+    /// Wait for the next audit event (see [`enable_events`](#method.enable_events)).
+    pub fn next_event(&mut self) -> Result<(NetlinkMessage<AuditMessage>, SocketAddr), Error> {
+        self.connection
+            .next_unsolicited_message()
+            .map_err(|e| Error::SocketError(e.to_string()))
+    }
+
+    fn block_on<F, R>(&mut self, request: F) -> Result<R, Error>
+    where
+        F: Future<Output = Result<R, Error>>,
+    {
+        self.connection
+            .block_on(request)
+            .map_err(|e| Error::SocketError(e.to_string()))?
+    }
+}
@@ -0,0 +1,120 @@
+use std::path::PathBuf;
+
+use crate::{
+    packet::AuditMessage,
+    proto::{self, Connection, DecoderQuirks, OverflowPolicy, UnsolicitedMessages},
+    sys::{protocols::NETLINK_AUDIT, AsyncNetlinkSocket, SocketAddr},
+    Handle,
+};
+
+/// Builder for a `NETLINK_AUDIT` connection, to open it in another network namespace, join
+/// the multicast group of the audit events or set socket options (see
+/// [`proto::ConnectionBuilder`]). Like with [`new_connection`](crate::new_connection), the
+/// connection works around the malformed messages sent by the kernel.
+#[derive(Debug, Clone)]
+pub struct ConnectionBuilder(proto::ConnectionBuilder<AuditMessage>);
+
+impl Default for ConnectionBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConnectionBuilder {
+    pub fn new() -> Self {
+        ConnectionBuilder(
+            proto::ConnectionBuilder::new(NETLINK_AUDIT).decoder_quirks(DecoderQuirks::audit()),
+        )
+    }
+
+    /// Join a multicast group, like `AUDIT_NLGRP_READLOG` to receive the audit events as
+    /// unsolicited messages.
+    pub fn membership(self, group: u32) -> Self {
+        ConnectionBuilder(self.0.membership(group))
+    }
+
+    /// See [`proto::ConnectionBuilder::decoder_quirks`].
+    pub fn decoder_quirks(self, quirks: DecoderQuirks) -> Self {
+        ConnectionBuilder(self.0.decoder_quirks(quirks))
+    }
+
+    /// See [`proto::ConnectionBuilder::network_namespace`].
+    pub fn network_namespace<P: Into<PathBuf>>(self, path: P) -> Self {
+        ConnectionBuilder(self.0.network_namespace(path))
+    }
+
+    /// See [`proto::ConnectionBuilder::bind_address`].
+    pub fn bind_address(self, address: SocketAddr) -> Self {
+        ConnectionBuilder(self.0.bind_address(address))
+    }
+
+    /// See [`proto::ConnectionBuilder::send_buffer_size`].
+    pub fn send_buffer_size(self, size: usize, force: bool) -> Self {
+        ConnectionBuilder(self.0.send_buffer_size(size, force))
+    }
+
+    /// See [`proto::ConnectionBuilder::receive_buffer_size`].
+    pub fn receive_buffer_size(self, size: usize, force: bool) -> Self {
+        ConnectionBuilder(self.0.receive_buffer_size(size, force))
+    }
+
+    /// See [`proto::ConnectionBuilder::extended_ack`].
+    pub fn extended_ack(self, value: bool) -> Self {
+        ConnectionBuilder(self.0.extended_ack(value))
+    }
+
+    /// See [`proto::ConnectionBuilder::no_enobufs`].
+    pub fn no_enobufs(self, value: bool) -> Self {
+        ConnectionBuilder(self.0.no_enobufs(value))
+    }
+
+    /// See [`proto::ConnectionBuilder::cap_ack`].
+    pub fn cap_ack(self, value: bool) -> Self {
+        ConnectionBuilder(self.0.cap_ack(value))
+    }
+
+    /// See [`proto::ConnectionBuilder::reader_capacity`].
+    pub fn reader_capacity(self, capacity: usize) -> Self {
+        ConnectionBuilder(self.0.reader_capacity(capacity))
+    }
+
+    /// See [`proto::ConnectionBuilder::requests_capacity`].
+    pub fn requests_capacity(self, capacity: usize) -> Self {
+        ConnectionBuilder(self.0.requests_capacity(capacity))
+    }
+
+    /// See [`proto::ConnectionBuilder::unsolicited_messages_capacity`].
+    pub fn unsolicited_messages_capacity(self, capacity: usize, policy: OverflowPolicy) -> Self {
+        ConnectionBuilder(self.0.unsolicited_messages_capacity(capacity, policy))
+    }
+
+    /// Like [`new_connection`](crate::new_connection), but with the settings of the builder.
+    #[cfg(any(feature = "tokio_socket", feature = "smol_socket"))]
+    pub fn build(
+        self,
+    ) -> std::io::Result<(
+        Connection<AuditMessage>,
+        Handle,
+        UnsolicitedMessages<AuditMessage>,
+    )> {
+        let (conn, handle, messages) = self.0.build()?;
+        Ok((conn, Handle::new(handle), messages))
+    }
+
+    /// Like [`new_connection_with_socket`](crate::new_connection_with_socket): the network
+    /// namespace, address, multicast groups and socket options of the builder are ignored.
+    pub fn build_with_socket<S>(
+        self,
+        socket: S,
+    ) -> (
+        Connection<AuditMessage, S>,
+        Handle,
+        UnsolicitedMessages<AuditMessage>,
+    )
+    where
+        S: AsyncNetlinkSocket,
+    {
+        let (conn, handle, messages) = self.0.build_with_socket(socket);
+        (conn, Handle::new(handle), messages)
+    }
+}
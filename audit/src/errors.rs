@@ -1,6 +1,9 @@
 use thiserror::Error;
 
-use crate::packet::{AuditMessage, ErrorMessage, NetlinkMessage};
+use crate::{
+    packet::{AuditMessage, ErrorMessage, NetlinkMessage},
+    proto,
+};
 
 #[derive(Clone, Eq, PartialEq, Debug, Error)]
 pub enum Error {
@@ -12,4 +15,21 @@ pub enum Error {
 
     #[error("Request failed")]
     RequestFailed,
+
+    #[error("A netlink request timed out")]
+    Timeout,
+
+    #[error("Error while reading from or writing to the netlink socket: {0}")]
+    SocketError(String),
+}
+
+impl From<proto::Error<AuditMessage>> for Error {
+    /// Convert an error that ended a response stream.
+    fn from(err: proto::Error<AuditMessage>) -> Self {
+        match err.into_inner() {
+            proto::ErrorKind::Timeout => Error::Timeout,
+            proto::ErrorKind::SocketIo(e) => Error::SocketError(e.to_string()),
+            _ => Error::RequestFailed,
+        }
+    }
 }
@@ -2,7 +2,7 @@ use std::process;
 
 use futures::{
     future::{self, Either},
-    stream::{Stream, StreamExt, TryStream},
+    stream::{Stream, TryStream, TryStreamExt},
     FutureExt,
 };
 use netlink_proto::{sys::SocketAddr, ConnectionHandle};
@@ -58,9 +58,10 @@ impl Handle {
     pub fn request(
         &mut self,
         message: NetlinkMessage<AuditMessage>,
-    ) -> Result<impl Stream<Item = NetlinkMessage<AuditMessage>>, Error> {
+    ) -> Result<impl Stream<Item = Result<NetlinkMessage<AuditMessage>, Error>>, Error> {
         self.0
             .request(message, SocketAddr::new(0, 0))
+            .map(|response| response.map_err(Error::from))
             .map_err(|_| Error::RequestFailed)
     }
 
@@ -68,7 +69,7 @@ impl Handle {
     /// that ACK is received. If anything else is received, the future resolves into an error.
     async fn acked_request(&mut self, message: NetlinkMessage<AuditMessage>) -> Result<(), Error> {
         let mut response = self.request(message)?;
-        if let Some(message) = response.next().await {
+        if let Some(message) = response.try_next().await? {
             let (header, payload) = message.into_parts();
             // NetlinkError and AuditMessage are forwarded to the
             // handle. Ack is signaled by the stream finishing.
@@ -104,14 +105,14 @@ impl Handle {
         req.header.flags = NLM_F_REQUEST | NLM_F_DUMP;
 
         match self.request(req) {
-            Ok(response) => Either::Left(response.map(move |msg| {
+            Ok(response) => Either::Left(response.and_then(move |msg| {
                 let (header, payload) = msg.into_parts();
                 match payload {
                     NetlinkPayload::InnerMessage(AuditMessage::ListRules(Some(rule_msg))) => {
-                        Ok(rule_msg)
+                        future::ok(rule_msg)
                     }
-                    NetlinkPayload::Error(err_msg) => Err(Error::NetlinkError(err_msg)),
-                    _ => Err(Error::UnexpectedMessage(NetlinkMessage::new(
+                    NetlinkPayload::Error(err_msg) => future::err(Error::NetlinkError(err_msg)),
+                    _ => future::err(Error::UnexpectedMessage(NetlinkMessage::new(
                         header, payload,
                     ))),
                 }
@@ -137,7 +138,7 @@ impl Handle {
         req.header.flags = NLM_F_REQUEST | NLM_F_DUMP;
         let mut request = self.request(req)?;
 
-        let response = request.next().await.ok_or(Error::RequestFailed)?;
+        let response = request.try_next().await?.ok_or(Error::RequestFailed)?;
 
         match response.into_parts() {
             (_, NetlinkPayload::InnerMessage(AuditMessage::GetStatus(Some(status)))) => Ok(status),
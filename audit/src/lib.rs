@@ -4,18 +4,34 @@ pub use crate::handle::*;
 mod errors;
 pub use crate::errors::*;
 
+mod blocking;
+pub use crate::blocking::*;
+
+mod builder;
+pub use crate::builder::*;
+
 pub use netlink_packet_audit as packet;
 pub mod proto {
-    pub use netlink_proto::{Connection, ConnectionHandle, Error, ErrorKind};
+    pub use netlink_proto::{
+        blocking,
+        Connection,
+        ConnectionBuilder,
+        ConnectionHandle,
+        DecoderQuirks,
+        Error,
+        ErrorKind,
+        MalformedPolicy,
+        OverflowPolicy,
+        UnsolicitedMessages,
+    };
 }
 pub use netlink_proto::sys;
 
-use std::io;
-
 use futures::channel::mpsc::UnboundedReceiver;
 
+#[cfg(any(feature = "tokio_socket", feature = "smol_socket"))]
 #[allow(clippy::type_complexity)]
-pub fn new_connection() -> io::Result<(
+pub fn new_connection() -> std::io::Result<(
     proto::Connection<packet::AuditMessage>,
     Handle,
     UnboundedReceiver<(
@@ -23,6 +39,29 @@ pub fn new_connection() -> io::Result<(
         sys::SocketAddr,
     )>,
 )> {
-    let (conn, handle, messages) = netlink_proto::new_connection(sys::protocols::NETLINK_AUDIT)?;
-    Ok((conn, Handle::new(handle), messages))
+    Ok(new_connection_with_socket(sys::Socket::new(
+        sys::protocols::NETLINK_AUDIT,
+    )?))
+}
+
+/// Like [`new_connection`], but the connection uses the given socket, for instance one end of a
+/// [`MemorySocket`](crate::sys::MemorySocket) pair, the other end playing the part of the kernel.
+#[allow(clippy::type_complexity)]
+pub fn new_connection_with_socket<S>(
+    socket: S,
+) -> (
+    proto::Connection<packet::AuditMessage, S>,
+    Handle,
+    UnboundedReceiver<(
+        packet::NetlinkMessage<packet::AuditMessage>,
+        sys::SocketAddr,
+    )>,
+)
+where
+    S: sys::AsyncNetlinkSocket,
+{
+    let (mut conn, handle, messages) = netlink_proto::new_connection_with_socket(socket);
+    // The kernel sends audit messages whose length is wrong
+    conn.set_decoder_quirks(proto::DecoderQuirks::audit());
+    (conn, Handle::new(handle), messages)
 }
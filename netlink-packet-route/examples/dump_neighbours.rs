@@ -103,7 +103,7 @@ fn state_str(value: u16) -> &'static str {
 }
 
 fn print_entry(entry: NeighbourMessage) {
-    let state = state_str(entry.header.state);
+    let state = state_str(entry.header.state.bits());
     let dest = entry
         .nlas
         .iter()
@@ -1,13 +1,54 @@
 use anyhow::Context;
+use std::net::IpAddr;
 
 use crate::{
+    constants::*,
     nlas::address::Nla,
+    rtnl::ip::{check_family, check_prefix_len, ip_octets, parse_ip},
     traits::{Emitable, Parseable},
     AddressMessageBuffer,
     DecodeError,
+    IpAddrError,
     ADDRESS_HEADER_LEN,
 };
 
+bitflags! {
+    /// Flags of an address, as found in [`AddressHeader::flags`]. Only the eight lower `IFA_F_*`
+    /// flags fit in the header, the complete set is carried by the
+    /// [`Nla::Flags`](crate::nlas::address::Nla::Flags) attribute. Bits that do not correspond to
+    /// a known flag are preserved.
+    pub struct AddressHeaderFlags: u8 {
+        const IFA_F_SECONDARY = IFA_F_SECONDARY as u8;
+        const IFA_F_TEMPORARY = IFA_F_TEMPORARY as u8;
+        const IFA_F_NODAD = IFA_F_NODAD as u8;
+        const IFA_F_OPTIMISTIC = IFA_F_OPTIMISTIC as u8;
+        const IFA_F_DADFAILED = IFA_F_DADFAILED as u8;
+        const IFA_F_HOMEADDRESS = IFA_F_HOMEADDRESS as u8;
+        const IFA_F_DEPRECATED = IFA_F_DEPRECATED as u8;
+        const IFA_F_TENTATIVE = IFA_F_TENTATIVE as u8;
+        const IFA_F_PERMANENT = IFA_F_PERMANENT as u8;
+    }
+}
+
+impl Default for AddressHeaderFlags {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl From<u8> for AddressHeaderFlags {
+    fn from(bits: u8) -> Self {
+        // Unknown bits are valid for this type: they are kept as is.
+        Self { bits }
+    }
+}
+
+impl From<AddressHeaderFlags> for u8 {
+    fn from(flags: AddressHeaderFlags) -> Self {
+        flags.bits()
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Default)]
 pub struct AddressMessage {
     pub header: AddressHeader,
@@ -18,7 +59,7 @@ pub struct AddressMessage {
 pub struct AddressHeader {
     pub family: u8,
     pub prefix_len: u8,
-    pub flags: u8,
+    pub flags: AddressHeaderFlags,
     pub scope: u8,
     pub index: u32,
 }
@@ -32,7 +73,7 @@ impl Emitable for AddressHeader {
         let mut packet = AddressMessageBuffer::new(buffer);
         packet.set_family(self.family);
         packet.set_prefix_len(self.prefix_len);
-        packet.set_flags(self.flags);
+        packet.set_flags(self.flags.bits());
         packet.set_scope(self.scope);
         packet.set_index(self.index);
     }
@@ -56,7 +97,7 @@ impl<T: AsRef<[u8]>> Parseable<AddressMessageBuffer<T>> for AddressHeader {
         Ok(Self {
             family: buf.family(),
             prefix_len: buf.prefix_len(),
-            flags: buf.flags(),
+            flags: AddressHeaderFlags::from(buf.flags()),
             scope: buf.scope(),
             index: buf.index(),
         })
@@ -81,3 +122,88 @@ impl<'a, T: AsRef<[u8]> + 'a> Parseable<AddressMessageBuffer<&'a T>> for Vec<Nla
         Ok(nlas)
     }
 }
+
+impl AddressMessage {
+    /// Returns the `IFA_ADDRESS` attribute, if present and valid for the address family of the
+    /// header. For point-to-point links, this is the address of the peer.
+    pub fn address(&self) -> Option<IpAddr> {
+        self.nlas.iter().find_map(|nla| {
+            if let Nla::Address(v) = nla {
+                parse_ip(self.header.family, v)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Set the `IFA_ADDRESS` attribute. If the address family of the header is not set yet, it
+    /// is set to the family of `addr`.
+    pub fn set_address(&mut self, addr: IpAddr) -> Result<(), IpAddrError> {
+        check_family(&mut self.header.family, addr)?;
+        self.nlas.retain(|nla| !matches!(nla, Nla::Address(_)));
+        self.nlas.push(Nla::Address(ip_octets(addr)));
+        Ok(())
+    }
+
+    /// Returns the `IFA_LOCAL` attribute, if present and valid for the address family of the
+    /// header.
+    pub fn local(&self) -> Option<IpAddr> {
+        self.nlas.iter().find_map(|nla| {
+            if let Nla::Local(v) = nla {
+                parse_ip(self.header.family, v)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Set the `IFA_LOCAL` attribute. If the address family of the header is not set yet, it is
+    /// set to the family of `addr`.
+    pub fn set_local(&mut self, addr: IpAddr) -> Result<(), IpAddrError> {
+        check_family(&mut self.header.family, addr)?;
+        self.nlas.retain(|nla| !matches!(nla, Nla::Local(_)));
+        self.nlas.push(Nla::Local(ip_octets(addr)));
+        Ok(())
+    }
+
+    /// Returns the address assigned to the interface with its prefix length. Like the kernel,
+    /// this is `IFA_LOCAL` if present and `IFA_ADDRESS` otherwise.
+    pub fn prefix(&self) -> Option<(IpAddr, u8)> {
+        self.local()
+            .or_else(|| self.address())
+            .map(|addr| (addr, self.header.prefix_len))
+    }
+
+    /// Set the address assigned to the interface with its prefix length. Both `IFA_LOCAL` and
+    /// `IFA_ADDRESS` are set to `addr`.
+    pub fn set_prefix(&mut self, addr: IpAddr, prefix_len: u8) -> Result<(), IpAddrError> {
+        check_prefix_len(addr, prefix_len)?;
+        self.set_local(addr)?;
+        self.set_address(addr)?;
+        self.header.prefix_len = prefix_len;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        traits::{Emitable, Parseable},
+        AddressHeader,
+        AddressHeaderFlags,
+        AddressMessageBuffer,
+    };
+
+    #[test]
+    fn flags_round_trip() {
+        let header = AddressHeader {
+            flags: AddressHeaderFlags::from(0xff),
+            ..Default::default()
+        };
+        let mut buf = vec![0; header.buffer_len()];
+        header.emit(&mut buf[..]);
+        let parsed = AddressHeader::parse(&AddressMessageBuffer::new(&buf[..])).unwrap();
+        assert_eq!(u8::from(parsed.flags), 0xff);
+        assert_eq!(parsed, header);
+    }
+}
@@ -5,9 +5,15 @@ use crate::{
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
 pub struct CacheInfo {
+    /// Remaining preferred lifetime of the address, in seconds. `INFINITY_LIFE_TIME` (`-1` once
+    /// casted) means the address is never deprecated.
     pub ifa_preferred: i32,
+    /// Remaining valid lifetime of the address, in seconds. `INFINITY_LIFE_TIME` (`-1` once
+    /// casted) means the address never expires.
     pub ifa_valid: i32,
+    /// Creation time of the address, in hundredths of seconds since boot
     pub cstamp: i32,
+    /// Last update time of the address, in hundredths of seconds since boot
     pub tstamp: i32,
 }
 
@@ -10,7 +10,7 @@ use crate::{
     constants::*,
     nlas::{self, DefaultNla, NlaBuffer},
     parsers::{parse_string, parse_u32},
-    traits::Parseable,
+    traits::{Emitable, Parseable},
     DecodeError,
 };
 
@@ -22,7 +22,7 @@ pub enum Nla {
     Label(String),
     Broadcast(Vec<u8>),
     Anycast(Vec<u8>),
-    CacheInfo(Vec<u8>),
+    CacheInfo(CacheInfo),
     Multicast(Vec<u8>),
     Flags(u32),
     Other(DefaultNla),
@@ -48,7 +48,7 @@ impl nlas::Nla for Nla {
             Flags(_) => size_of::<u32>(),
 
             // Native
-            CacheInfo(ref buffer) => buffer.len(),
+            CacheInfo(_) => ADDRESSS_CACHE_INFO_LEN,
 
             // Defaults
             Other(ref attr)  => attr.value_len(),
@@ -65,7 +65,6 @@ impl nlas::Nla for Nla {
                 | Local(ref bytes)
                 | Broadcast(ref bytes)
                 | Anycast(ref bytes)
-                | CacheInfo(ref bytes)
                 | Multicast(ref bytes) => buffer.copy_from_slice(bytes.as_slice()),
 
             // String
@@ -77,6 +76,9 @@ impl nlas::Nla for Nla {
             // u32
             Flags(ref value) => NativeEndian::write_u32(buffer, *value),
 
+            // Native
+            CacheInfo(ref cache_info) => cache_info.emit(buffer),
+
 
             // Default
             Other(ref attr) => attr.emit_value(buffer),
@@ -111,10 +113,54 @@ impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>> for Nla {
             IFA_LABEL => Label(parse_string(payload).context("invalid IFA_LABEL value")?),
             IFA_BROADCAST => Broadcast(payload.to_vec()),
             IFA_ANYCAST => Anycast(payload.to_vec()),
-            IFA_CACHEINFO => CacheInfo(payload.to_vec()),
+            IFA_CACHEINFO => CacheInfo(
+                cache_info::CacheInfo::parse(
+                    &CacheInfoBuffer::new_checked(payload).context("invalid IFA_CACHEINFO value")?,
+                )
+                .context("invalid IFA_CACHEINFO value")?,
+            ),
             IFA_MULTICAST => Multicast(payload.to_vec()),
             IFA_FLAGS => Flags(parse_u32(payload).context("invalid IFA_FLAGS value")?),
             kind => Other(DefaultNla::parse(buf).context(format!("unknown NLA type {}", kind))?),
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Emitable;
+
+    #[rustfmt::skip]
+    static CACHE_INFO: [u8; 20] = [
+        0x14, 0x00, // length = 20
+        0x06, 0x00, // type = 6 = IFA_CACHEINFO
+        0x2c, 0x01, 0x00, 0x00, // ifa_preferred = 300
+        0xff, 0xff, 0xff, 0xff, // ifa_valid = INFINITY_LIFE_TIME
+        0x10, 0x27, 0x00, 0x00, // cstamp = 10000
+        0x20, 0x4e, 0x00, 0x00, // tstamp = 20000
+    ];
+
+    lazy_static! {
+        static ref CACHE_INFO_NLA: Nla = Nla::CacheInfo(CacheInfo {
+            ifa_preferred: 300,
+            ifa_valid: INFINITY_LIFE_TIME as i32,
+            cstamp: 10000,
+            tstamp: 20000,
+        });
+    }
+
+    #[test]
+    fn parse_cache_info() {
+        let nla = NlaBuffer::new_checked(&CACHE_INFO[..]).unwrap();
+        assert_eq!(Nla::parse(&nla).unwrap(), *CACHE_INFO_NLA);
+    }
+
+    #[test]
+    fn emit_cache_info() {
+        assert_eq!(CACHE_INFO_NLA.buffer_len(), 20);
+        let mut vec = vec![0xff; 20];
+        CACHE_INFO_NLA.emit(&mut vec);
+        assert_eq!(&vec[..], &CACHE_INFO[..]);
+    }
+}
@@ -35,7 +35,7 @@ impl<'a, T: AsRef<[u8]> + ?Sized> ParseableParametrized<RtnlMessageBuffer<&'a T>
         let message = match message_type {
 
             // Link messages
-            RTM_NEWLINK | RTM_GETLINK | RTM_DELLINK | RTM_SETLINK => {
+            RTM_NEWLINK | RTM_GETLINK | RTM_DELLINK | RTM_SETLINK | RTM_NEWLINKPROP | RTM_DELLINKPROP => {
                 let msg = match LinkMessageBuffer::new_checked(&buf.inner()) {
                     Ok(buf) => LinkMessage::parse(&buf).context("invalid link message")?,
                     // HACK: iproute2 sends invalid RTM_GETLINK message, where the header is
@@ -55,6 +55,8 @@ impl<'a, T: AsRef<[u8]> + ?Sized> ParseableParametrized<RtnlMessageBuffer<&'a T>
                     RTM_GETLINK => GetLink(msg),
                     RTM_DELLINK => DelLink(msg),
                     RTM_SETLINK => SetLink(msg),
+                    RTM_NEWLINKPROP => NewLinkProp(msg),
+                    RTM_DELLINKPROP => DelLinkProp(msg),
                     _ => unreachable!(),
                 }
             }
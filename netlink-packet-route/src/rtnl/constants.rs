@@ -56,6 +56,8 @@ pub const RTM_NEWCACHEREPORT: u16 = 96;
 pub const RTM_NEWCHAIN: u16 = 100;
 pub const RTM_DELCHAIN: u16 = 101;
 pub const RTM_GETCHAIN: u16 = 102;
+pub const RTM_NEWLINKPROP: u16 = 108;
+pub const RTM_DELLINKPROP: u16 = 109;
 
 /// Unknown route
 pub const RTN_UNSPEC: u8 = 0;
@@ -102,7 +104,14 @@ pub const RTPROT_XORP: u8 = 14;
 pub const RTPROT_NTK: u8 = 15;
 pub const RTPROT_DHCP: u8 = 16;
 pub const RTPROT_MROUTED: u8 = 17;
+pub const RTPROT_KEEPALIVED: u8 = 18;
 pub const RTPROT_BABEL: u8 = 42;
+pub const RTPROT_OPENR: u8 = 99;
+pub const RTPROT_BGP: u8 = 186;
+pub const RTPROT_ISIS: u8 = 187;
+pub const RTPROT_OSPF: u8 = 188;
+pub const RTPROT_RIP: u8 = 189;
+pub const RTPROT_EIGRP: u8 = 192;
 
 /// The destination is globally valid.
 pub const RT_SCOPE_UNIVERSE: u8 = 0;
@@ -225,6 +234,12 @@ pub const RTM_F_PREFIX: u32 = 2048;
 pub const RTM_F_LOOKUP_TABLE: u32 = 4096;
 /// Return the full FIB lookup match (see commit `b61798130f1be5bff08712308126c2d7ebe390ef`)
 pub const RTM_F_FIB_MATCH: u32 = 8192;
+/// The route is offloaded to the hardware
+pub const RTM_F_OFFLOAD: u32 = 16384;
+/// The route traps packets to the CPU
+pub const RTM_F_TRAP: u32 = 32768;
+/// The route could not be offloaded to the hardware
+pub const RTM_F_OFFLOAD_FAILED: u32 = 0x2000_0000;
 
 pub const AF_UNSPEC: u16 = libc::AF_UNSPEC as u16;
 pub const AF_UNIX: u16 = libc::AF_UNIX as u16;
@@ -270,6 +285,8 @@ pub const NETNSA_NONE: u16 = 0;
 pub const NETNSA_NSID: u16 = 1;
 pub const NETNSA_PID: u16 = 2;
 pub const NETNSA_FD: u16 = 3;
+pub const NETNSA_TARGET_NSID: u16 = 4;
+pub const NETNSA_CURRENT_NSID: u16 = 5;
 pub const NETNSA_NSID_NOT_ASSIGNED: i32 = -1;
 
 /// Neighbour cache entry state: the neighbour has not (yet) been resolved
@@ -300,8 +317,15 @@ pub const NTF_MASTER: u8 = 4;
 pub const NTF_PROXY: u8 = 8;
 pub const NTF_EXT_LEARNED: u8 = 16;
 pub const NTF_OFFLOADED: u8 = 32;
+pub const NTF_STICKY: u8 = 64;
 pub const NTF_ROUTER: u8 = 128;
 
+// Extended neighbour cache entry flags, carried by the `NDA_FLAGS_EXT` attribute
+/// The kernel keeps the entry resolved by periodically probing it, even when no traffic is sent
+pub const NTF_EXT_MANAGED: u32 = 1;
+/// The entry was created by a bridge port in locked mode and is not authorized yet
+pub const NTF_EXT_LOCKED: u32 = 2;
+
 pub const TCA_UNSPEC: u16 = 0;
 pub const TCA_KIND: u16 = 1;
 pub const TCA_OPTIONS: u16 = 2;
@@ -337,6 +361,27 @@ pub const NDTA_STATS: u16 = 7;
 pub const NDTA_GC_INTERVAL: u16 = 8;
 pub const NDTA_PAD: u16 = 9;
 
+pub const NDTPA_UNSPEC: u16 = 0;
+pub const NDTPA_IFINDEX: u16 = 1;
+pub const NDTPA_REFCNT: u16 = 2;
+pub const NDTPA_REACHABLE_TIME: u16 = 3;
+pub const NDTPA_BASE_REACHABLE_TIME: u16 = 4;
+pub const NDTPA_RETRANS_TIME: u16 = 5;
+pub const NDTPA_GC_STALETIME: u16 = 6;
+pub const NDTPA_DELAY_PROBE_TIME: u16 = 7;
+pub const NDTPA_QUEUE_LEN: u16 = 8;
+pub const NDTPA_APP_PROBES: u16 = 9;
+pub const NDTPA_UCAST_PROBES: u16 = 10;
+pub const NDTPA_MCAST_PROBES: u16 = 11;
+pub const NDTPA_ANYCAST_DELAY: u16 = 12;
+pub const NDTPA_PROXY_DELAY: u16 = 13;
+pub const NDTPA_PROXY_QLEN: u16 = 14;
+pub const NDTPA_LOCKTIME: u16 = 15;
+pub const NDTPA_QUEUE_LENBYTES: u16 = 16;
+pub const NDTPA_MCAST_REPROBES: u16 = 17;
+pub const NDTPA_PAD: u16 = 18;
+pub const NDTPA_INTERVAL_PROBE_TIME_MS: u16 = 19;
+
 pub const RTA_UNSPEC: u16 = 0;
 pub const RTA_DST: u16 = 1;
 pub const RTA_SRC: u16 = 2;
@@ -596,8 +641,56 @@ pub const IFLA_PROP_LIST: u16 = 52;
 pub const IFLA_ALT_IFNAME: u16 = 53;
 pub const IFLA_PERM_ADDRESS: u16 = 54;
 pub const IFLA_PROTO_DOWN_REASON: u16 = 55;
+
+pub const IFLA_EVENT_NONE: u32 = 0;
+pub const IFLA_EVENT_REBOOT: u32 = 1;
+pub const IFLA_EVENT_FEATURES: u32 = 2;
+pub const IFLA_EVENT_BONDING_FAILOVER: u32 = 3;
+pub const IFLA_EVENT_NOTIFY_PEERS: u32 = 4;
+pub const IFLA_EVENT_IGMP_RESEND: u32 = 5;
+pub const IFLA_EVENT_BONDING_OPTIONS: u32 = 6;
+
+pub const IFLA_PROTO_DOWN_REASON_UNSPEC: u16 = 0;
+pub const IFLA_PROTO_DOWN_REASON_MASK: u16 = 1;
+pub const IFLA_PROTO_DOWN_REASON_VALUE: u16 = 2;
+
 pub const IFLA_INET_UNSPEC: u16 = 0;
 pub const IFLA_INET_CONF: u16 = 1;
+
+pub const IPV4_DEVCONF_FORWARDING: u16 = 1;
+pub const IPV4_DEVCONF_MC_FORWARDING: u16 = 2;
+pub const IPV4_DEVCONF_PROXY_ARP: u16 = 3;
+pub const IPV4_DEVCONF_ACCEPT_REDIRECTS: u16 = 4;
+pub const IPV4_DEVCONF_SECURE_REDIRECTS: u16 = 5;
+pub const IPV4_DEVCONF_SEND_REDIRECTS: u16 = 6;
+pub const IPV4_DEVCONF_SHARED_MEDIA: u16 = 7;
+pub const IPV4_DEVCONF_RP_FILTER: u16 = 8;
+pub const IPV4_DEVCONF_ACCEPT_SOURCE_ROUTE: u16 = 9;
+pub const IPV4_DEVCONF_BOOTP_RELAY: u16 = 10;
+pub const IPV4_DEVCONF_LOG_MARTIANS: u16 = 11;
+pub const IPV4_DEVCONF_TAG: u16 = 12;
+pub const IPV4_DEVCONF_ARPFILTER: u16 = 13;
+pub const IPV4_DEVCONF_MEDIUM_ID: u16 = 14;
+pub const IPV4_DEVCONF_NOXFRM: u16 = 15;
+pub const IPV4_DEVCONF_NOPOLICY: u16 = 16;
+pub const IPV4_DEVCONF_FORCE_IGMP_VERSION: u16 = 17;
+pub const IPV4_DEVCONF_ARP_ANNOUNCE: u16 = 18;
+pub const IPV4_DEVCONF_ARP_IGNORE: u16 = 19;
+pub const IPV4_DEVCONF_PROMOTE_SECONDARIES: u16 = 20;
+pub const IPV4_DEVCONF_ARP_ACCEPT: u16 = 21;
+pub const IPV4_DEVCONF_ARP_NOTIFY: u16 = 22;
+pub const IPV4_DEVCONF_ACCEPT_LOCAL: u16 = 23;
+pub const IPV4_DEVCONF_SRC_VMARK: u16 = 24;
+pub const IPV4_DEVCONF_PROXY_ARP_PVLAN: u16 = 25;
+pub const IPV4_DEVCONF_ROUTE_LOCALNET: u16 = 26;
+pub const IPV4_DEVCONF_IGMPV2_UNSOLICITED_REPORT_INTERVAL: u16 = 27;
+pub const IPV4_DEVCONF_IGMPV3_UNSOLICITED_REPORT_INTERVAL: u16 = 28;
+pub const IPV4_DEVCONF_IGNORE_ROUTES_WITH_LINKDOWN: u16 = 29;
+pub const IPV4_DEVCONF_DROP_UNICAST_IN_L2_MULTICAST: u16 = 30;
+pub const IPV4_DEVCONF_DROP_GRATUITOUS_ARP: u16 = 31;
+pub const IPV4_DEVCONF_BC_FORWARDING: u16 = 32;
+pub const IPV4_DEVCONF_ARP_EVICT_NOCARRIER: u16 = 33;
+
 pub const IFLA_INET6_UNSPEC: u16 = 0;
 pub const IFLA_INET6_FLAGS: u16 = 1;
 pub const IFLA_INET6_CONF: u16 = 2;
@@ -608,6 +701,40 @@ pub const IFLA_INET6_ICMP6STATS: u16 = 6;
 pub const IFLA_INET6_TOKEN: u16 = 7;
 pub const IFLA_INET6_ADDR_GEN_MODE: u16 = 8;
 
+pub const IN6_ADDR_GEN_MODE_EUI64: u8 = 0;
+pub const IN6_ADDR_GEN_MODE_NONE: u8 = 1;
+pub const IN6_ADDR_GEN_MODE_STABLE_PRIVACY: u8 = 2;
+pub const IN6_ADDR_GEN_MODE_RANDOM: u8 = 3;
+
+pub const IFLA_XDP_UNSPEC: u16 = 0;
+pub const IFLA_XDP_FD: u16 = 1;
+pub const IFLA_XDP_ATTACHED: u16 = 2;
+pub const IFLA_XDP_FLAGS: u16 = 3;
+pub const IFLA_XDP_PROG_ID: u16 = 4;
+pub const IFLA_XDP_DRV_PROG_ID: u16 = 5;
+pub const IFLA_XDP_SKB_PROG_ID: u16 = 6;
+pub const IFLA_XDP_HW_PROG_ID: u16 = 7;
+pub const IFLA_XDP_EXPECTED_FD: u16 = 8;
+
+pub const XDP_ATTACHED_NONE: u8 = 0;
+pub const XDP_ATTACHED_DRV: u8 = 1;
+pub const XDP_ATTACHED_SKB: u8 = 2;
+pub const XDP_ATTACHED_HW: u8 = 3;
+pub const XDP_ATTACHED_MULTI: u8 = 4;
+
+/// Only attach the program if no program is currently attached to the device
+pub const XDP_FLAGS_UPDATE_IF_NOEXIST: u32 = 1;
+/// Attach the program in generic (skb) mode
+pub const XDP_FLAGS_SKB_MODE: u32 = 2;
+/// Attach the program in native (driver) mode
+pub const XDP_FLAGS_DRV_MODE: u32 = 4;
+/// Offload the program to the NIC
+pub const XDP_FLAGS_HW_MODE: u32 = 8;
+/// Only replace the program currently attached if it matches `IFLA_XDP_EXPECTED_FD`
+pub const XDP_FLAGS_REPLACE: u32 = 16;
+pub const XDP_FLAGS_MODES: u32 = XDP_FLAGS_SKB_MODE | XDP_FLAGS_DRV_MODE | XDP_FLAGS_HW_MODE;
+pub const XDP_FLAGS_MASK: u32 = XDP_FLAGS_UPDATE_IF_NOEXIST | XDP_FLAGS_MODES | XDP_FLAGS_REPLACE;
+
 /// Link is up (administratively).
 pub const IFF_UP: u32 = libc::IFF_UP as u32;
 /// Link is up and carrier is OK (RFC2863 OPER_UP)
@@ -673,6 +800,12 @@ pub const NDA_IFINDEX: u16 = 8;
 pub const NDA_MASTER: u16 = 9;
 pub const NDA_LINK_NETNSID: u16 = 10;
 pub const NDA_SRC_VNI: u16 = 11;
+pub const NDA_PROTOCOL: u16 = 12;
+pub const NDA_NH_ID: u16 = 13;
+pub const NDA_FDB_EXT_ATTRS: u16 = 14;
+pub const NDA_FLAGS_EXT: u16 = 15;
+pub const NDA_NDM_STATE_MASK: u16 = 16;
+pub const NDA_NDM_FLAGS_MASK: u16 = 17;
 
 /// see `https://github.com/torvalds/linux/blob/master/include/uapi/linux/fib_rules.h`
 
@@ -742,9 +875,9 @@ pub const FIB_RULE_INVERT: u32 = 2;
 pub const FIB_RULE_UNRESOLVED: u32 = 4;
 pub const FIB_RULE_IIF_DETACHED: u32 = 8;
 pub const FIB_RULE_DEV_DETACHED: u32 = FIB_RULE_IIF_DETACHED;
-pub const FIB_RULE_OIF_DETACHED: u32 = 10;
+pub const FIB_RULE_OIF_DETACHED: u32 = 0x10;
 /// try to find source address in routing lookups
-pub const FIB_RULE_FIND_SADDR: u32 = 10000;
+pub const FIB_RULE_FIND_SADDR: u32 = 0x10000;
 
 // pub const MACVLAN_FLAG_NOPROMISC: int = 1;
 // pub const IPVLAN_F_PRIVATE: int = 1;
@@ -753,12 +886,6 @@ pub const FIB_RULE_FIND_SADDR: u32 = 10000;
 // pub const PORT_PROFILE_MAX: int = 40;
 // pub const PORT_UUID_MAX: int = 16;
 // pub const PORT_SELF_VF: int = -1;
-// pub const XDP_FLAGS_UPDATE_IF_NOEXIST: int = 1;
-// pub const XDP_FLAGS_SKB_MODE: int = 2;
-// pub const XDP_FLAGS_DRV_MODE: int = 4;
-// pub const XDP_FLAGS_HW_MODE: int = 8;
-// pub const XDP_FLAGS_MODES: int = 14;
-// pub const XDP_FLAGS_MASK: int = 15;
 
 pub const IFA_F_SECONDARY: u32 = 1;
 pub const IFA_F_TEMPORARY: u32 = 1;
@@ -774,17 +901,30 @@ pub const IFA_F_NOPREFIXROUTE: u32 = 512;
 pub const IFA_F_MCAUTOJOIN: u32 = 1024;
 pub const IFA_F_STABLE_PRIVACY: u32 = 2048;
 
+/// Value of the `ifa_valid` and `ifa_preferred` fields of an address `CacheInfo` for an address
+/// that never expires
+pub const INFINITY_LIFE_TIME: u32 = 0xffff_ffff;
+
 // pub const RTNL_FAMILY_IPMR: int = 128;
 // pub const RTNL_FAMILY_IP6MR: int = 129;
 // pub const RTNL_FAMILY_MAX: int = 129;
 // pub const RTA_ALIGNTO: int = 4;
-//
-// pub const RTNH_F_DEAD: int = 1;
-// pub const RTNH_F_PERVASIVE: int = 2;
-// pub const RTNH_F_ONLINK: int = 4;
-// pub const RTNH_F_OFFLOAD: int = 8;
-// pub const RTNH_F_LINKDOWN: int = 16;
-// pub const RTNH_F_UNRESOLVED: int = 32;
+
+/// Next hop flag: the next hop is dead
+pub const RTNH_F_DEAD: u8 = 1;
+/// Next hop flag: do recursive gateway lookup
+pub const RTNH_F_PERVASIVE: u8 = 2;
+/// Next hop flag: the gateway is directly reachable on the interface, even if it does not match
+/// the interface prefix
+pub const RTNH_F_ONLINK: u8 = 4;
+/// Next hop flag: the next hop is offloaded to the hardware
+pub const RTNH_F_OFFLOAD: u8 = 8;
+/// Next hop flag: the carrier of the next hop interface is down
+pub const RTNH_F_LINKDOWN: u8 = 16;
+/// Next hop flag: the next hop is not resolved yet (multicast routes only)
+pub const RTNH_F_UNRESOLVED: u8 = 32;
+/// Next hop flag: the next hop traps packets to the CPU
+pub const RTNH_F_TRAP: u8 = 64;
 // pub const RTNH_COMPARE_MASK: int = 25;
 // pub const RTNH_ALIGNTO: int = 4;
 // pub const RTNETLINK_HAVE_PEERINFO: int = 1;
@@ -805,11 +945,6 @@ pub const RTEXT_FILTER_SKIP_STATS: u32 = 8;
 // pub const ARPOP_REQUEST: int = 1;
 // pub const ARPOP_REPLY: int = 2;
 //
-// pub const IN6_ADDR_GEN_MODE_EUI64: int = 0;
-// pub const IN6_ADDR_GEN_MODE_NONE: int = 1;
-// pub const IN6_ADDR_GEN_MODE_STABLE_PRIVACY: int = 2;
-// pub const IN6_ADDR_GEN_MODE_RANDOM: int = 3;
-//
 // pub const BRIDGE_MODE_UNSPEC: int = 0;
 // pub const BRIDGE_MODE_HAIRPIN: int = 1;
 //
@@ -1064,6 +1199,11 @@ pub const RTNLGRP_NSID: u32 = 28;
 pub const RTNLGRP_MPLS_NETCONF: u32 = 29;
 pub const RTNLGRP_IPV4_MROUTE_R: u32 = 30;
 pub const RTNLGRP_IPV6_MROUTE_R: u32 = 31;
+pub const RTNLGRP_NEXTHOP: u32 = 32;
+pub const RTNLGRP_BRVLAN: u32 = 33;
+pub const RTNLGRP_MCTP_IFADDR: u32 = 34;
+pub const RTNLGRP_TUNNEL: u32 = 35;
+pub const RTNLGRP_STATS: u32 = 36;
 //
 // pub const IFLA_VF_LINK_STATE_AUTO: int = 0;
 // pub const IFLA_VF_LINK_STATE_ENABLE: int = 1;
@@ -1139,45 +1279,6 @@ pub const RTNLGRP_IPV6_MROUTE_R: u32 = 31;
 // pub const IFLA_OFFLOAD_XSTATS_UNSPEC: int = 0;
 // pub const IFLA_OFFLOAD_XSTATS_CPU_HIT: int = 1;
 //
-// pub const XDP_ATTACHED_NONE: int = 0;
-// pub const XDP_ATTACHED_DRV: int = 1;
-// pub const XDP_ATTACHED_SKB: int = 2;
-// pub const XDP_ATTACHED_HW: int = 3;
-//
-// pub const IFLA_XDP_UNSPEC: int = 0;
-// pub const IFLA_XDP_FD: int = 1;
-// pub const IFLA_XDP_ATTACHED: int = 2;
-// pub const IFLA_XDP_FLAGS: int = 3;
-// pub const IFLA_XDP_PROG_ID: int = 4;
-//
-// pub const IFLA_EVENT_NONE: int = 0;
-// pub const IFLA_EVENT_REBOOT: int = 1;
-// pub const IFLA_EVENT_FEATURES: int = 2;
-// pub const IFLA_EVENT_BONDING_FAILOVER: int = 3;
-// pub const IFLA_EVENT_NOTIFY_PEERS: int = 4;
-// pub const IFLA_EVENT_IGMP_RESEND: int = 5;
-// pub const IFLA_EVENT_BONDING_OPTIONS: int = 6;
-//
-// pub const NDTPA_UNSPEC: int = 0;
-// pub const NDTPA_IFINDEX: int = 1;
-// pub const NDTPA_REFCNT: int = 2;
-// pub const NDTPA_REACHABLE_TIME: int = 3;
-// pub const NDTPA_BASE_REACHABLE_TIME: int = 4;
-// pub const NDTPA_RETRANS_TIME: int = 5;
-// pub const NDTPA_GC_STALETIME: int = 6;
-// pub const NDTPA_DELAY_PROBE_TIME: int = 7;
-// pub const NDTPA_QUEUE_LEN: int = 8;
-// pub const NDTPA_APP_PROBES: int = 9;
-// pub const NDTPA_UCAST_PROBES: int = 10;
-// pub const NDTPA_MCAST_PROBES: int = 11;
-// pub const NDTPA_ANYCAST_DELAY: int = 12;
-// pub const NDTPA_PROXY_DELAY: int = 13;
-// pub const NDTPA_PROXY_QLEN: int = 14;
-// pub const NDTPA_LOCKTIME: int = 15;
-// pub const NDTPA_QUEUE_LENBYTES: int = 16;
-// pub const NDTPA_MCAST_REPROBES: int = 17;
-// pub const NDTPA_PAD: int = 18;
-//
 // #[allow(overflowing_literals)]
 // pub const RT_TABLE_MAX: int = 0xffff_ffff;
 //
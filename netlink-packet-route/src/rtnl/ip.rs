@@ -0,0 +1,96 @@
+use std::{error::Error, fmt, net::IpAddr};
+
+use crate::constants::{AF_INET, AF_INET6, AF_UNSPEC};
+
+/// Error returned when setting an IP address attribute on a message fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IpAddrError {
+    /// The address does not belong to the address family of the message header.
+    FamilyMismatch { family: u8, address: IpAddr },
+    /// The prefix length is larger than the address length (32 for IPv4, 128 for IPv6).
+    PrefixLength { address: IpAddr, prefix_len: u8 },
+}
+
+impl fmt::Display for IpAddrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IpAddrError::FamilyMismatch { family, address } => write!(
+                f,
+                "address {} does not match the message address family {}",
+                address, family
+            ),
+            IpAddrError::PrefixLength {
+                address,
+                prefix_len,
+            } => write!(f, "invalid prefix length {} for {}", prefix_len, address),
+        }
+    }
+}
+
+impl Error for IpAddrError {}
+
+/// Return the `AF_*` constant corresponding to the given address.
+pub(crate) fn ip_family(address: IpAddr) -> u8 {
+    match address {
+        IpAddr::V4(_) => AF_INET as u8,
+        IpAddr::V6(_) => AF_INET6 as u8,
+    }
+}
+
+pub(crate) fn ip_octets(address: IpAddr) -> Vec<u8> {
+    match address {
+        IpAddr::V4(v4) => v4.octets().to_vec(),
+        IpAddr::V6(v6) => v6.octets().to_vec(),
+    }
+}
+
+/// Decode an IP address attribute. For `AF_INET` and `AF_INET6` headers the length of the
+/// attribute must match the family. For other families (`AF_UNSPEC`, `AF_BRIDGE`, ...) the
+/// address version is inferred from the length of the attribute.
+pub(crate) fn parse_ip(family: u8, octets: &[u8]) -> Option<IpAddr> {
+    let family = u16::from(family);
+    match octets.len() {
+        4 if family != AF_INET6 => {
+            let mut ary = [0; 4];
+            ary.copy_from_slice(octets);
+            Some(IpAddr::from(ary))
+        }
+        16 if family != AF_INET => {
+            let mut ary = [0; 16];
+            ary.copy_from_slice(octets);
+            Some(IpAddr::from(ary))
+        }
+        _ => None,
+    }
+}
+
+/// Check that `address` can be carried by a message with the given family. If the family is
+/// `AF_UNSPEC`, it is set to the family of `address`.
+pub(crate) fn check_family(family: &mut u8, address: IpAddr) -> Result<(), IpAddrError> {
+    let expected = ip_family(address);
+    if u16::from(*family) == AF_UNSPEC {
+        *family = expected;
+    } else if (u16::from(*family) == AF_INET || u16::from(*family) == AF_INET6)
+        && *family != expected
+    {
+        return Err(IpAddrError::FamilyMismatch {
+            family: *family,
+            address,
+        });
+    }
+    Ok(())
+}
+
+pub(crate) fn check_prefix_len(address: IpAddr, prefix_len: u8) -> Result<(), IpAddrError> {
+    let max = match address {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    };
+    if prefix_len > max {
+        return Err(IpAddrError::PrefixLength {
+            address,
+            prefix_len,
+        });
+    }
+    Ok(())
+}
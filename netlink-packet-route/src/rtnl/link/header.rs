@@ -1,10 +1,69 @@
 use crate::{
+    constants::*,
     traits::{Emitable, Parseable},
     DecodeError,
     LinkMessageBuffer,
     LINK_HEADER_LEN,
 };
 
+bitflags! {
+    /// Flags describing the state of a link, as found in [`LinkHeader::flags`]. Bits that do not
+    /// correspond to a known flag are preserved.
+    pub struct LinkFlags: u32 {
+        /// Link is up (administratively)
+        const IFF_UP = IFF_UP;
+        /// Link is up and carrier is OK (RFC2863 OPER_UP)
+        const IFF_RUNNING = IFF_RUNNING;
+        /// Link layer is operational
+        const IFF_LOWER_UP = IFF_LOWER_UP;
+        /// Driver signals IFF_DORMANT
+        const IFF_DORMANT = IFF_DORMANT;
+        /// Link supports broadcasting
+        const IFF_BROADCAST = IFF_BROADCAST;
+        /// Link supports multicasting
+        const IFF_MULTICAST = IFF_MULTICAST;
+        /// Link supports multicast routing
+        const IFF_ALLMULTI = IFF_ALLMULTI;
+        /// Tell driver to do debugging (currently unused)
+        const IFF_DEBUG = IFF_DEBUG;
+        /// Link loopback network
+        const IFF_LOOPBACK = IFF_LOOPBACK;
+        /// Interface is a point-to-point link
+        const IFF_POINTOPOINT = IFF_POINTOPOINT;
+        /// ARP is not supported
+        const IFF_NOARP = IFF_NOARP;
+        /// Receive all packets
+        const IFF_PROMISC = IFF_PROMISC;
+        /// Master of a load balancer (bonding)
+        const IFF_MASTER = IFF_MASTER;
+        /// Slave of a load balancer
+        const IFF_SLAVE = IFF_SLAVE;
+        /// Link selects port automatically (only used by ARM ethernet)
+        const IFF_PORTSEL = IFF_PORTSEL;
+        /// Driver supports setting media type (only used by ARM ethernet)
+        const IFF_AUTOMEDIA = IFF_AUTOMEDIA;
+    }
+}
+
+impl Default for LinkFlags {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl From<u32> for LinkFlags {
+    fn from(bits: u32) -> Self {
+        // Unknown bits are valid for this type: they are kept as is.
+        Self { bits }
+    }
+}
+
+impl From<LinkFlags> for u32 {
+    fn from(flags: LinkFlags) -> Self {
+        flags.bits()
+    }
+}
+
 /// High level representation of `RTM_GETLINK`, `RTM_SETLINK`, `RTM_NEWLINK` and `RTM_DELLINK`
 /// messages headers.
 ///
@@ -35,11 +94,11 @@ pub struct LinkHeader {
     /// Ethernet.
     pub link_layer_type: u16,
     /// State of the link, described by a combinations of `IFF_*`
-    /// constants, for instance `IFF_UP | IFF_LOWER_UP`.
-    pub flags: u32,
+    /// constants, for instance `LinkFlags::IFF_UP | LinkFlags::IFF_LOWER_UP`.
+    pub flags: LinkFlags,
     /// Change mask for the `flags` field. Reserved, it should be set
     /// to `0xffff_ffff`.
-    pub change_mask: u32,
+    pub change_mask: LinkFlags,
 }
 
 impl Emitable for LinkHeader {
@@ -51,9 +110,9 @@ impl Emitable for LinkHeader {
         let mut packet = LinkMessageBuffer::new(buffer);
         packet.set_interface_family(self.interface_family);
         packet.set_link_index(self.index);
-        packet.set_change_mask(self.change_mask);
+        packet.set_change_mask(self.change_mask.bits());
         packet.set_link_layer_type(self.link_layer_type);
-        packet.set_flags(self.flags);
+        packet.set_flags(self.flags.bits());
     }
 }
 
@@ -63,8 +122,8 @@ impl<T: AsRef<[u8]>> Parseable<LinkMessageBuffer<T>> for LinkHeader {
             interface_family: buf.interface_family(),
             link_layer_type: buf.link_layer_type(),
             index: buf.link_index(),
-            change_mask: buf.change_mask(),
-            flags: buf.flags(),
+            change_mask: LinkFlags::from(buf.change_mask()),
+            flags: LinkFlags::from(buf.flags()),
         })
     }
 }
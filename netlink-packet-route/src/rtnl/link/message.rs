@@ -58,7 +58,8 @@ mod test {
     use crate::{
         constants::*,
         nlas::link::{Nla, State},
-        traits::{Emitable, ParseableParametrized},
+        traits::{Emitable, Parseable, ParseableParametrized},
+        LinkFlags,
         LinkHeader,
         LinkMessage,
         LinkMessageBuffer,
@@ -203,7 +204,10 @@ mod test {
         let mut header = LinkHeader::default();
         header.link_layer_type = ARPHRD_LOOPBACK;
         header.index = 1;
-        header.flags = IFF_UP | IFF_LOOPBACK | IFF_RUNNING | IFF_LOWER_UP;
+        header.flags = LinkFlags::IFF_UP
+            | LinkFlags::IFF_LOOPBACK
+            | LinkFlags::IFF_RUNNING
+            | LinkFlags::IFF_LOWER_UP;
 
         let nlas = vec![
             Nla::IfName("lo".into()),
@@ -225,4 +229,19 @@ mod test {
         assert_eq!(packet.buffer_len(), 96);
         packet.emit(&mut buf[..]);
     }
+
+    #[test]
+    fn unknown_flags_round_trip() {
+        let header = LinkHeader {
+            flags: LinkFlags::from(0x8000_0001),
+            change_mask: LinkFlags::from(0xffff_ffff),
+            ..Default::default()
+        };
+        let mut buf = vec![0; header.buffer_len()];
+        header.emit(&mut buf[..]);
+        let parsed = LinkHeader::parse(&LinkMessageBuffer::new(&buf[..])).unwrap();
+        assert_eq!(u32::from(parsed.flags), 0x8000_0001);
+        assert_eq!(u32::from(parsed.change_mask), 0xffff_ffff);
+        assert_eq!(parsed, header);
+    }
 }
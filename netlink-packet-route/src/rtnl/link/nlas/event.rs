@@ -0,0 +1,54 @@
+use crate::constants::*;
+
+/// Reason of a link notification, reported in the `IFLA_EVENT` attribute of the
+/// `RTM_NEWLINK` messages broadcast by the kernel
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum LinkEvent {
+    None,
+    /// The device is rebooting
+    Reboot,
+    /// The features of the device changed
+    Features,
+    /// A bonding device changed its active slave
+    BondingFailover,
+    /// The device asks its peers to be notified of its addresses (gratuitous ARP, unsolicited
+    /// neighbour advertisement)
+    NotifyPeers,
+    /// The device asks for the IGMP memberships to be resent
+    IgmpResend,
+    /// The options of a bonding device changed
+    BondingOptions,
+    Other(u32),
+}
+
+impl From<u32> for LinkEvent {
+    fn from(value: u32) -> Self {
+        use self::LinkEvent::*;
+        match value {
+            IFLA_EVENT_NONE => None,
+            IFLA_EVENT_REBOOT => Reboot,
+            IFLA_EVENT_FEATURES => Features,
+            IFLA_EVENT_BONDING_FAILOVER => BondingFailover,
+            IFLA_EVENT_NOTIFY_PEERS => NotifyPeers,
+            IFLA_EVENT_IGMP_RESEND => IgmpResend,
+            IFLA_EVENT_BONDING_OPTIONS => BondingOptions,
+            _ => Other(value),
+        }
+    }
+}
+
+impl From<LinkEvent> for u32 {
+    fn from(value: LinkEvent) -> Self {
+        use self::LinkEvent::*;
+        match value {
+            None => IFLA_EVENT_NONE,
+            Reboot => IFLA_EVENT_REBOOT,
+            Features => IFLA_EVENT_FEATURES,
+            BondingFailover => IFLA_EVENT_BONDING_FAILOVER,
+            NotifyPeers => IFLA_EVENT_NOTIFY_PEERS,
+            IgmpResend => IFLA_EVENT_IGMP_RESEND,
+            BondingOptions => IFLA_EVENT_BONDING_OPTIONS,
+            Other(other) => other,
+        }
+    }
+}
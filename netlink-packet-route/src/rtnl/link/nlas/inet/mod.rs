@@ -1,9 +1,12 @@
 use anyhow::Context;
+use byteorder::{ByteOrder, NativeEndian};
 
+use super::resize;
 use crate::{
     constants::{IFLA_INET_CONF, IFLA_INET_UNSPEC},
-    nlas::{DefaultNla, Nla, NlaBuffer},
-    traits::Parseable,
+    nlas::{DefaultNla, Nla, NlaBuffer, NlasIterator, NLA_F_NESTED},
+    parsers::parse_i32,
+    traits::{Emitable, Parseable},
     DecodeError,
 };
 
@@ -12,17 +15,53 @@ pub use self::dev_conf::*;
 
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub enum Inet {
-    DevConf(Vec<u8>),
+    /// All the IPv4 settings of the interface, as reported by the kernel
+    DevConf(InetDevConf),
+    /// Change some IPv4 settings of the interface, leaving the others untouched. This is how
+    /// `IFLA_INET_CONF` must be sent to the kernel.
+    DevConfUpdate(Vec<InetDevConfValue>),
     Unspec(Vec<u8>),
     Other(DefaultNla),
 }
 
+/// The value of a single IPv4 setting of an interface, identified by one of the
+/// `IPV4_DEVCONF_*` constants
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub struct InetDevConfValue {
+    pub id: u16,
+    pub value: i32,
+}
+
+impl Nla for InetDevConfValue {
+    fn value_len(&self) -> usize {
+        4
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        NativeEndian::write_i32(buffer, self.value)
+    }
+
+    fn kind(&self) -> u16 {
+        self.id
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>> for InetDevConfValue {
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        Ok(InetDevConfValue {
+            id: buf.kind(),
+            value: parse_i32(buf.value()).context("invalid IPv4 devconf value")?,
+        })
+    }
+}
+
 impl Nla for Inet {
     fn value_len(&self) -> usize {
         use self::Inet::*;
         match *self {
             Unspec(ref bytes) => bytes.len(),
             DevConf(_) => DEV_CONF_LEN,
+            DevConfUpdate(ref nlas) => nlas.as_slice().buffer_len(),
             Other(ref nla) => nla.value_len(),
         }
     }
@@ -31,7 +70,8 @@ impl Nla for Inet {
         use self::Inet::*;
         match *self {
             Unspec(ref bytes) => (&mut buffer[..bytes.len()]).copy_from_slice(bytes.as_slice()),
-            DevConf(ref dev_conf) => buffer[..dev_conf.len()].copy_from_slice(dev_conf.as_slice()),
+            DevConf(ref dev_conf) => dev_conf.emit(buffer),
+            DevConfUpdate(ref nlas) => nlas.as_slice().emit(buffer),
             Other(ref nla) => nla.emit_value(buffer),
         }
     }
@@ -41,6 +81,7 @@ impl Nla for Inet {
         match *self {
             Unspec(_) => IFLA_INET_UNSPEC,
             DevConf(_) => IFLA_INET_CONF,
+            DevConfUpdate(_) => IFLA_INET_CONF | NLA_F_NESTED,
             Other(ref nla) => nla.kind(),
         }
     }
@@ -53,7 +94,24 @@ impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>> for Inet {
         let payload = buf.value();
         Ok(match buf.kind() {
             IFLA_INET_UNSPEC => Unspec(payload.to_vec()),
-            IFLA_INET_CONF => DevConf(payload.to_vec()),
+            // The kernel reports the settings as an array, but expects changes as nested
+            // attributes, which we flag as such when emitting them
+            IFLA_INET_CONF if buf.nested_flag() => {
+                let mut nlas = vec![];
+                let err = "invalid IFLA_INET_CONF value";
+                for nla in NlasIterator::new(payload) {
+                    let nla = nla.context(err)?;
+                    nlas.push(InetDevConfValue::parse(&nla).context(err)?);
+                }
+                DevConfUpdate(nlas)
+            }
+            IFLA_INET_CONF => {
+                let payload = resize(payload, DEV_CONF_LEN);
+                DevConf(
+                    InetDevConf::parse(&InetDevConfBuffer::new(&payload[..]))
+                        .context("invalid IFLA_INET_CONF value")?,
+                )
+            }
             kind => Other(DefaultNla::parse(buf).context(format!("unknown NLA type {}", kind))?),
         })
     }
@@ -1,11 +1,12 @@
 use anyhow::Context;
 use byteorder::{ByteOrder, NativeEndian};
 
+use super::resize;
 use crate::{
     constants::*,
     nlas::{DefaultNla, Nla, NlaBuffer},
     parsers::{parse_ipv6, parse_u32, parse_u8},
-    traits::Parseable,
+    traits::{Emitable, Parseable},
     DecodeError,
 };
 
@@ -21,11 +22,11 @@ pub use self::stats::*;
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub enum Inet6 {
     Flags(u32),
-    CacheInfo(Vec<u8>),
-    DevConf(Vec<u8>),
+    CacheInfo(Inet6CacheInfo),
+    DevConf(Inet6DevConf),
     Unspec(Vec<u8>),
-    Stats(Vec<u8>),
-    IcmpStats(Vec<u8>),
+    Stats(Inet6Stats),
+    IcmpStats(Icmp6Stats),
     Token([u8; 16]),
     AddrGenMode(u8),
     Other(DefaultNla),
@@ -36,10 +37,10 @@ impl Nla for Inet6 {
         use self::Inet6::*;
         match *self {
             Unspec(ref bytes) => bytes.len(),
-            CacheInfo(ref cache_info) => cache_info.len(),
-            DevConf(ref dev_conf) => dev_conf.len(),
-            Stats(ref stats) => stats.len(),
-            IcmpStats(ref icmp_stats) => icmp_stats.len(),
+            CacheInfo(_) => LINK_INET6_CACHE_INFO_LEN,
+            DevConf(_) => LINK_INET6_DEV_CONF_LEN,
+            Stats(_) => INET6_STATS_LEN,
+            IcmpStats(_) => ICMP6_STATS_LEN,
             Flags(_) => 4,
             Token(_) => 16,
             AddrGenMode(_) => 1,
@@ -52,10 +53,10 @@ impl Nla for Inet6 {
         match *self {
             Unspec(ref bytes) => buffer.copy_from_slice(bytes.as_slice()),
             Flags(ref value) => NativeEndian::write_u32(buffer, *value),
-            CacheInfo(ref cache_info) => buffer.copy_from_slice(cache_info.as_slice()),
-            DevConf(ref bytes) => buffer.copy_from_slice(bytes.as_slice()),
-            Stats(ref inet6_stats) => buffer.copy_from_slice(inet6_stats.as_slice()),
-            IcmpStats(ref icmp6_stats) => buffer.copy_from_slice(icmp6_stats.as_slice()),
+            CacheInfo(ref cache_info) => cache_info.emit(buffer),
+            DevConf(ref dev_conf) => dev_conf.emit(buffer),
+            Stats(ref inet6_stats) => inet6_stats.emit(buffer),
+            IcmpStats(ref icmp6_stats) => icmp6_stats.emit(buffer),
             Token(ref ipv6) => buffer.copy_from_slice(&ipv6[..]),
             AddrGenMode(value) => buffer[0] = value,
             Other(ref nla) => nla.emit_value(buffer),
@@ -87,10 +88,34 @@ impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>> for Inet6 {
             IFLA_INET6_FLAGS => {
                 Flags(parse_u32(payload).context("invalid IFLA_INET6_FLAGS value")?)
             }
-            IFLA_INET6_CACHEINFO => CacheInfo(payload.to_vec()),
-            IFLA_INET6_CONF => DevConf(payload.to_vec()),
-            IFLA_INET6_STATS => Stats(payload.to_vec()),
-            IFLA_INET6_ICMP6STATS => IcmpStats(payload.to_vec()),
+            IFLA_INET6_CACHEINFO => CacheInfo(
+                Inet6CacheInfo::parse(
+                    &Inet6CacheInfoBuffer::new_checked(payload)
+                        .context("invalid IFLA_INET6_CACHEINFO value")?,
+                )
+                .context("invalid IFLA_INET6_CACHEINFO value")?,
+            ),
+            IFLA_INET6_CONF => {
+                let payload = resize(payload, LINK_INET6_DEV_CONF_LEN);
+                DevConf(
+                    Inet6DevConf::parse(&Inet6DevConfBuffer::new(&payload[..]))
+                        .context("invalid IFLA_INET6_CONF value")?,
+                )
+            }
+            IFLA_INET6_STATS => {
+                let payload = resize(payload, INET6_STATS_LEN);
+                Stats(
+                    Inet6Stats::parse(&Inet6StatsBuffer::new(&payload[..]))
+                        .context("invalid IFLA_INET6_STATS value")?,
+                )
+            }
+            IFLA_INET6_ICMP6STATS => {
+                let payload = resize(payload, ICMP6_STATS_LEN);
+                IcmpStats(
+                    Icmp6Stats::parse(&Icmp6StatsBuffer::new(&payload[..]))
+                        .context("invalid IFLA_INET6_ICMP6STATS value")?,
+                )
+            }
             IFLA_INET6_TOKEN => {
                 Token(parse_ipv6(payload).context("invalid IFLA_INET6_TOKEN value")?)
             }
@@ -1594,7 +1594,7 @@ impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>> for InfoMacVtap {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{nlas::link::Nla, traits::Emitable, LinkHeader, LinkMessage};
+    use crate::{nlas::link::Nla, traits::Emitable, LinkFlags, LinkHeader, LinkMessage};
 
     #[rustfmt::skip]
     static BRIDGE: [u8; 424] = [
@@ -1909,8 +1909,8 @@ mod tests {
                     interface_family: 0,
                     index: 0,
                     link_layer_type: ARPHRD_NETROM,
-                    flags: 0,
-                    change_mask: 0,
+                    flags: LinkFlags::empty(),
+                    change_mask: LinkFlags::empty(),
                 },
                 nlas: vec![
                     Nla::IfName("vethc0e60d6".to_string()),
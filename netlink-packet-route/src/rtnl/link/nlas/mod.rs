@@ -22,6 +22,21 @@ pub use self::stats64::*;
 mod link_state;
 pub use self::link_state::*;
 
+mod prop_list;
+pub use self::prop_list::*;
+
+mod xdp;
+pub use self::xdp::*;
+
+mod event;
+pub use self::event::*;
+
+mod phys_id;
+pub use self::phys_id::*;
+
+mod proto_down_reason;
+pub use self::proto_down_reason::*;
+
 #[cfg(test)]
 mod tests;
 
@@ -32,7 +47,7 @@ use byteorder::{ByteOrder, NativeEndian};
 
 use crate::{
     constants::*,
-    nlas::{self, DefaultNla, NlaBuffer, NlasIterator},
+    nlas::{self, DefaultNla, NlaBuffer, NlasIterator, NLA_F_NESTED},
     parsers::{parse_i32, parse_string, parse_u32, parse_u8},
     traits::{Emitable, Parseable, ParseableParametrized},
     DecodeError,
@@ -48,17 +63,9 @@ pub enum Nla {
     VfInfoList(Vec<u8>),
     VfPorts(Vec<u8>),
     PortSelf(Vec<u8>),
-    PhysPortId(Vec<u8>),
-    PhysSwitchId(Vec<u8>),
     Pad(Vec<u8>),
-    Xdp(Vec<u8>),
-    Event(Vec<u8>),
-    NewNetnsId(Vec<u8>),
-    IfNetnsId(Vec<u8>),
-    CarrierUpCount(Vec<u8>),
-    CarrierDownCount(Vec<u8>),
-    NewIfIndex(Vec<u8>),
     Info(Vec<Info>),
+    Xdp(Vec<Xdp>),
     Wireless(Vec<u8>),
     ProtoInfo(Vec<u8>),
     /// A list of properties for the device. For additional context see the related linux kernel
@@ -68,14 +75,18 @@ pub enum Nla {
     /// [1]: https://lwn.net/ml/netdev/20190719110029.29466-1-jiri@resnulli.us/
     /// [2]: https://lwn.net/ml/netdev/20190930094820.11281-1-jiri@resnulli.us/
     /// [defining message]: https://lwn.net/ml/netdev/20190913145012.GB2276@nanopsycho.orion/
-    PropList(Vec<u8>),
+    PropList(Vec<Prop>),
     /// `protodown` is a mechanism that allows protocols to hold an interface down.
     /// This field is used to specify the reason why it is held down.
     /// For additional context see the related linux kernel threads<sup>[1][1],[2][2]</sup>.
     ///
     /// [1]: https://lwn.net/ml/netdev/1595877677-45849-1-git-send-email-roopa%40cumulusnetworks.com/
     /// [2]: https://lwn.net/ml/netdev/1596242041-14347-1-git-send-email-roopa%40cumulusnetworks.com/
-    ProtoDownReason(Vec<u8>),
+    ProtoDownReason(Vec<ProtoDownReason>),
+    PhysPortId(PhysId),
+    PhysSwitchId(PhysId),
+    /// Reason of the notification, only present in the messages broadcast by the kernel
+    Event(LinkEvent),
     // mac address (use to be [u8; 6] but it turns out MAC != HW address, for instance for IP over
     // GRE where it's an IPv4!)
     Address(Vec<u8>),
@@ -118,6 +129,8 @@ pub enum Nla {
     CarrierChanges(u32),
     GsoMaxSegs(u32),
     GsoMaxSize(u32),
+    CarrierUpCount(u32),
+    CarrierDownCount(u32),
     /// The minimum MTU for the device.
     /// For additional context see the related [linux kernel message][1].
     ///
@@ -130,6 +143,13 @@ pub enum Nla {
     MaxMtu(u32),
     // i32
     NetnsId(i32),
+    /// New network namespace id of the device, reported when it moves to another namespace
+    NewNetnsId(i32),
+    /// Network namespace id in which the request operates (for instance the namespace to dump
+    /// links from), or in which the device lives
+    IfNetnsId(i32),
+    /// New index of the device, reported when it moves to another namespace
+    NewIfIndex(i32),
     // custom
     OperState(State),
     Stats(Vec<u8>),
@@ -156,28 +176,19 @@ impl nlas::Nla for Nla {
                 | VfInfoList(ref bytes)
                 | VfPorts(ref bytes)
                 | PortSelf(ref bytes)
-                | PhysPortId(ref bytes)
-                | PhysSwitchId(ref bytes)
                 | Pad(ref bytes)
-                | Xdp(ref bytes)
-                | Event(ref bytes)
-                | NewNetnsId(ref bytes)
-                | IfNetnsId(ref bytes)
                 | Wireless(ref bytes)
                 | ProtoInfo(ref bytes)
-                | CarrierUpCount(ref bytes)
-                | CarrierDownCount(ref bytes)
-                | NewIfIndex(ref bytes)
                 | Address(ref bytes)
                 | Broadcast(ref bytes)
                 | PermAddress(ref bytes)
                 | AfSpecUnknown(ref bytes)
                 | AfSpecBridge(ref bytes)
                 | Map(ref bytes)
-                | PropList(ref bytes)
-                | ProtoDownReason(ref bytes)
                 => bytes.len(),
 
+            PhysPortId(ref id) | PhysSwitchId(ref id) => id.0.len(),
+
             // strings: +1 because we need to append a nul byte
             IfName(ref string)
                 | Qdisc(ref string)
@@ -208,6 +219,12 @@ impl nlas::Nla for Nla {
                 | CarrierChanges(_)
                 | GsoMaxSegs(_)
                 | GsoMaxSize(_)
+                | CarrierUpCount(_)
+                | CarrierDownCount(_)
+                | Event(_)
+                | NewNetnsId(_)
+                | IfNetnsId(_)
+                | NewIfIndex(_)
                 | NetnsId(_)
                 | MinMtu(_)
                 | MaxMtu(_) => 4,
@@ -217,6 +234,9 @@ impl nlas::Nla for Nla {
             Stats(_) => LINK_STATS_LEN,
             Stats64(_) => LINK_STATS64_LEN,
             Info(ref nlas) => nlas.as_slice().buffer_len(),
+            Xdp(ref nlas) => nlas.as_slice().buffer_len(),
+            PropList(ref nlas) => nlas.as_slice().buffer_len(),
+            ProtoDownReason(ref nlas) => nlas.as_slice().buffer_len(),
             AfSpecInet(ref nlas) => nlas.as_slice().buffer_len(),
             // AfSpecBridge(ref nlas) => nlas.as_slice().buffer_len(),
             Other(ref attr)  => attr.value_len(),
@@ -235,18 +255,9 @@ impl nlas::Nla for Nla {
                 | VfInfoList(ref bytes)
                 | VfPorts(ref bytes)
                 | PortSelf(ref bytes)
-                | PhysPortId(ref bytes)
-                | PhysSwitchId(ref bytes)
                 | Wireless(ref bytes)
                 | ProtoInfo(ref bytes)
                 | Pad(ref bytes)
-                | Xdp(ref bytes)
-                | Event(ref bytes)
-                | NewNetnsId(ref bytes)
-                | IfNetnsId(ref bytes)
-                | CarrierUpCount(ref bytes)
-                | CarrierDownCount(ref bytes)
-                | NewIfIndex(ref bytes)
                 // mac address (could be [u8; 6] or [u8; 4] for example. Not sure if we should have
                 // a separate type for them
                 | Address(ref bytes)
@@ -257,10 +268,10 @@ impl nlas::Nla for Nla {
                 | Stats(ref bytes)
                 | Stats64(ref bytes)
                 | Map(ref bytes)
-                | PropList(ref bytes)
-                | ProtoDownReason(ref bytes)
                 => buffer.copy_from_slice(bytes.as_slice()),
 
+            PhysPortId(ref id) | PhysSwitchId(ref id) => buffer.copy_from_slice(id.as_bytes()),
+
             // String
             IfName(ref string)
                 | Qdisc(ref string)
@@ -293,16 +304,25 @@ impl nlas::Nla for Nla {
                 | CarrierChanges(ref value)
                 | GsoMaxSegs(ref value)
                 | GsoMaxSize(ref value)
+                | CarrierUpCount(ref value)
+                | CarrierDownCount(ref value)
                 | MinMtu(ref value)
                 | MaxMtu(ref value)
                 => NativeEndian::write_u32(buffer, *value),
 
             NetnsId(ref value)
                 | NetNsFd(ref value)
+                | NewNetnsId(ref value)
+                | IfNetnsId(ref value)
+                | NewIfIndex(ref value)
                 => NativeEndian::write_i32(buffer, *value),
 
             OperState(state) => buffer[0] = state.into(),
+            Event(event) => NativeEndian::write_u32(buffer, event.into()),
             Info(ref nlas) => nlas.as_slice().emit(buffer),
+            Xdp(ref nlas) => nlas.as_slice().emit(buffer),
+            PropList(ref nlas) => nlas.as_slice().emit(buffer),
+            ProtoDownReason(ref nlas) => nlas.as_slice().emit(buffer),
             AfSpecInet(ref nlas) => nlas.as_slice().emit(buffer),
             // AfSpecBridge(ref nlas) => nlas.as_slice().emit(buffer),
             // default nlas
@@ -334,8 +354,11 @@ impl nlas::Nla for Nla {
             CarrierUpCount(_) => IFLA_CARRIER_UP_COUNT,
             CarrierDownCount(_) => IFLA_CARRIER_DOWN_COUNT,
             NewIfIndex(_) => IFLA_NEW_IFINDEX,
-            PropList(_) => IFLA_PROP_LIST,
-            ProtoDownReason(_) => IFLA_PROTO_DOWN_REASON,
+            // the kernel validates IFLA_PROP_LIST strictly and rejects it if the nested flag is
+            // not set
+            PropList(_) => IFLA_PROP_LIST | NLA_F_NESTED,
+            // same as IFLA_PROP_LIST
+            ProtoDownReason(_) => IFLA_PROTO_DOWN_REASON | NLA_F_NESTED,
             // Mac address
             Address(_) => IFLA_ADDRESS,
             Broadcast(_) => IFLA_BROADCAST,
@@ -397,20 +420,40 @@ impl<'a, T: AsRef<[u8]> + ?Sized> ParseableParametrized<NlaBuffer<&'a T>, u16> f
             IFLA_VFINFO_LIST => VfInfoList(payload.to_vec()),
             IFLA_VF_PORTS => VfPorts(payload.to_vec()),
             IFLA_PORT_SELF => PortSelf(payload.to_vec()),
-            IFLA_PHYS_PORT_ID => PhysPortId(payload.to_vec()),
-            IFLA_PHYS_SWITCH_ID => PhysSwitchId(payload.to_vec()),
+            IFLA_PHYS_PORT_ID => PhysPortId(payload.into()),
+            IFLA_PHYS_SWITCH_ID => PhysSwitchId(payload.into()),
             IFLA_WIRELESS => Wireless(payload.to_vec()),
             IFLA_PROTINFO => ProtoInfo(payload.to_vec()),
             IFLA_PAD => Pad(payload.to_vec()),
-            IFLA_XDP => Xdp(payload.to_vec()),
-            IFLA_EVENT => Event(payload.to_vec()),
-            IFLA_NEW_NETNSID => NewNetnsId(payload.to_vec()),
-            IFLA_IF_NETNSID => IfNetnsId(payload.to_vec()),
-            IFLA_CARRIER_UP_COUNT => CarrierUpCount(payload.to_vec()),
-            IFLA_CARRIER_DOWN_COUNT => CarrierDownCount(payload.to_vec()),
-            IFLA_NEW_IFINDEX => NewIfIndex(payload.to_vec()),
-            IFLA_PROP_LIST => PropList(payload.to_vec()),
-            IFLA_PROTO_DOWN_REASON => ProtoDownReason(payload.to_vec()),
+            IFLA_EVENT => Event(
+                parse_u32(payload)
+                    .context("invalid IFLA_EVENT value")?
+                    .into(),
+            ),
+            IFLA_NEW_NETNSID => {
+                NewNetnsId(parse_i32(payload).context("invalid IFLA_NEW_NETNSID value")?)
+            }
+            IFLA_IF_NETNSID => {
+                IfNetnsId(parse_i32(payload).context("invalid IFLA_IF_NETNSID value")?)
+            }
+            IFLA_CARRIER_UP_COUNT => CarrierUpCount(
+                parse_u32(payload).context("invalid IFLA_CARRIER_UP_COUNT value")?,
+            ),
+            IFLA_CARRIER_DOWN_COUNT => CarrierDownCount(
+                parse_u32(payload).context("invalid IFLA_CARRIER_DOWN_COUNT value")?,
+            ),
+            IFLA_NEW_IFINDEX => {
+                NewIfIndex(parse_i32(payload).context("invalid IFLA_NEW_IFINDEX value")?)
+            }
+            IFLA_PROTO_DOWN_REASON => {
+                let mut nlas = vec![];
+                let err = "invalid IFLA_PROTO_DOWN_REASON value";
+                for nla in NlasIterator::new(payload) {
+                    let nla = nla.context(err)?;
+                    nlas.push(proto_down_reason::ProtoDownReason::parse(&nla).context(err)?);
+                }
+                ProtoDownReason(nlas)
+            }
             // HW address (we parse them as Vec for now, because for IP over GRE, the HW address is
             // an IP instead of a MAC for example
             IFLA_ADDRESS => Address(payload.to_vec()),
@@ -489,6 +532,24 @@ impl<'a, T: AsRef<[u8]> + ?Sized> ParseableParametrized<NlaBuffer<&'a T>, u16> f
                 AF_BRIDGE => AfSpecBridge(payload.to_vec()),
                 _ => AfSpecUnknown(payload.to_vec()),
             },
+            IFLA_XDP => {
+                let mut nlas = vec![];
+                let err = "invalid IFLA_XDP value";
+                for nla in NlasIterator::new(payload) {
+                    let nla = nla.context(err)?;
+                    nlas.push(xdp::Xdp::parse(&nla).context(err)?);
+                }
+                Xdp(nlas)
+            }
+            IFLA_PROP_LIST => {
+                let mut nlas = vec![];
+                let err = "invalid IFLA_PROP_LIST value";
+                for nla in NlasIterator::new(payload) {
+                    let nla = nla.context(err)?;
+                    nlas.push(prop_list::Prop::parse(&nla).context(err)?);
+                }
+                PropList(nlas)
+            }
             IFLA_LINKINFO => {
                 let err = "invalid IFLA_LINKINFO value";
                 let buf = NlaBuffer::new_checked(payload).context(err)?;
@@ -499,3 +560,12 @@ impl<'a, T: AsRef<[u8]> + ?Sized> ParseableParametrized<NlaBuffer<&'a T>, u16> f
         })
     }
 }
+
+// The devconf and statistics attributes of AF_INET and AF_INET6 are arrays that grow with new
+// kernel releases. Fields appended by a newer kernel are ignored, and the fields an older kernel
+// does not report are left to zero, so that parsing does not depend on the kernel version.
+fn resize(payload: &[u8], len: usize) -> Vec<u8> {
+    let mut payload = payload.to_vec();
+    payload.resize(len, 0);
+    payload
+}
@@ -0,0 +1,34 @@
+use std::fmt;
+
+/// Physical identifier of a port (`IFLA_PHYS_PORT_ID`) or of a switch (`IFLA_PHYS_SWITCH_ID`).
+/// This is an opaque identifier of at most 32 bytes, chosen by the driver. It is displayed in
+/// hexadecimal, like `ip link` does.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+pub struct PhysId(pub Vec<u8>);
+
+impl PhysId {
+    pub fn as_bytes(&self) -> &[u8] {
+        self.0.as_slice()
+    }
+}
+
+impl From<Vec<u8>> for PhysId {
+    fn from(value: Vec<u8>) -> Self {
+        PhysId(value)
+    }
+}
+
+impl From<&[u8]> for PhysId {
+    fn from(value: &[u8]) -> Self {
+        PhysId(value.to_vec())
+    }
+}
+
+impl fmt::Display for PhysId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in &self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
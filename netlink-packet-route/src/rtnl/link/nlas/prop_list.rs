@@ -0,0 +1,100 @@
+use anyhow::Context;
+
+use crate::{
+    constants::*,
+    nlas::{DefaultNla, Nla, NlaBuffer},
+    parsers::parse_string,
+    traits::Parseable,
+    DecodeError,
+};
+
+/// Attributes nested in `IFLA_PROP_LIST`. These are sent in `RTM_NEWLINKPROP` and
+/// `RTM_DELLINKPROP` requests, and reported by the kernel in link dumps.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Prop {
+    /// Alternative name of the device
+    AltIfName(String),
+    Other(DefaultNla),
+}
+
+impl Nla for Prop {
+    fn value_len(&self) -> usize {
+        use self::Prop::*;
+        match self {
+            // +1 because we need to append a nul byte
+            AltIfName(string) => string.len() + 1,
+            Other(nla) => nla.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        use self::Prop::*;
+        match self {
+            AltIfName(string) => {
+                buffer[..string.len()].copy_from_slice(string.as_bytes());
+                buffer[string.len()] = 0;
+            }
+            Other(nla) => nla.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        use self::Prop::*;
+        match self {
+            AltIfName(_) => IFLA_ALT_IFNAME,
+            Other(nla) => nla.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>> for Prop {
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        use self::Prop::*;
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            IFLA_ALT_IFNAME => {
+                AltIfName(parse_string(payload).context("invalid IFLA_ALT_IFNAME value")?)
+            }
+            kind => Other(DefaultNla::parse(buf).context(format!("unknown NLA type {}", kind))?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        nlas::link,
+        traits::{Emitable, ParseableParametrized},
+    };
+
+    #[rustfmt::skip]
+    static PROP_LIST: [u8; 20] = [
+        0x14, 0x00, // length = 20
+        0x34, 0x80, // type = 52 = IFLA_PROP_LIST, with NLA_F_NESTED
+            0x0e, 0x00, // length = 14
+            0x35, 0x00, // type = 53 = IFLA_ALT_IFNAME
+            0x75, 0x70, 0x6c, 0x69, 0x6e, 0x6b, 0x2d, 0x61, 0x30, 0x00, // V = "uplink-a0\0"
+            0x00, 0x00, // padding
+    ];
+
+    #[test]
+    fn parse_prop_list() {
+        let nla = NlaBuffer::new_checked(&PROP_LIST[..]).unwrap();
+        let parsed = link::Nla::parse_with_param(&nla, AF_UNSPEC).unwrap();
+        assert_eq!(
+            parsed,
+            link::Nla::PropList(vec![Prop::AltIfName("uplink-a0".into())])
+        );
+    }
+
+    #[test]
+    fn emit_prop_list() {
+        let nla = link::Nla::PropList(vec![Prop::AltIfName("uplink-a0".into())]);
+        assert_eq!(nla.buffer_len(), 20);
+
+        let mut vec = vec![0xff; 20];
+        nla.emit(&mut vec);
+        assert_eq!(&vec[..], &PROP_LIST[..]);
+    }
+}
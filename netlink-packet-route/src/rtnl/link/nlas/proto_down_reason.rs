@@ -0,0 +1,112 @@
+use anyhow::Context;
+use byteorder::{ByteOrder, NativeEndian};
+
+use crate::{
+    constants::*,
+    nlas::{DefaultNla, Nla, NlaBuffer},
+    parsers::parse_u32,
+    traits::Parseable,
+    DecodeError,
+};
+
+/// Attributes nested in `IFLA_PROTO_DOWN_REASON`.
+///
+/// The reason is a 32 bits bitmap where each bit is owned by a protocol or an application. When
+/// setting it, only the bits present in `Mask` are updated to their value in `Value`. The kernel
+/// only reports the current `Value` when dumping links.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ProtoDownReason {
+    Unspec(Vec<u8>),
+    Mask(u32),
+    Value(u32),
+    Other(DefaultNla),
+}
+
+impl Nla for ProtoDownReason {
+    fn value_len(&self) -> usize {
+        use self::ProtoDownReason::*;
+        match self {
+            Unspec(bytes) => bytes.len(),
+            Mask(_) | Value(_) => 4,
+            Other(nla) => nla.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        use self::ProtoDownReason::*;
+        match self {
+            Unspec(bytes) => buffer.copy_from_slice(bytes.as_slice()),
+            Mask(value) | Value(value) => NativeEndian::write_u32(buffer, *value),
+            Other(nla) => nla.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        use self::ProtoDownReason::*;
+        match self {
+            Unspec(_) => IFLA_PROTO_DOWN_REASON_UNSPEC,
+            Mask(_) => IFLA_PROTO_DOWN_REASON_MASK,
+            Value(_) => IFLA_PROTO_DOWN_REASON_VALUE,
+            Other(nla) => nla.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>> for ProtoDownReason {
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        use self::ProtoDownReason::*;
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            IFLA_PROTO_DOWN_REASON_UNSPEC => Unspec(payload.to_vec()),
+            IFLA_PROTO_DOWN_REASON_MASK => {
+                Mask(parse_u32(payload).context("invalid IFLA_PROTO_DOWN_REASON_MASK value")?)
+            }
+            IFLA_PROTO_DOWN_REASON_VALUE => {
+                Value(parse_u32(payload).context("invalid IFLA_PROTO_DOWN_REASON_VALUE value")?)
+            }
+            kind => Other(DefaultNla::parse(buf).context(format!("unknown NLA type {}", kind))?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        nlas::link,
+        traits::{Emitable, ParseableParametrized},
+    };
+
+    #[rustfmt::skip]
+    static PROTO_DOWN_REASON: [u8; 20] = [
+        0x14, 0x00, // length = 20
+        0x37, 0x80, // type = 55 = IFLA_PROTO_DOWN_REASON, with NLA_F_NESTED
+            0x08, 0x00, // length = 8
+            0x01, 0x00, // type = 1 = IFLA_PROTO_DOWN_REASON_MASK
+            0x04, 0x00, 0x00, 0x00, // V = 4
+            0x08, 0x00, // length = 8
+            0x02, 0x00, // type = 2 = IFLA_PROTO_DOWN_REASON_VALUE
+            0x04, 0x00, 0x00, 0x00, // V = 4
+    ];
+
+    #[test]
+    fn parse_proto_down_reason() {
+        let nla = NlaBuffer::new_checked(&PROTO_DOWN_REASON[..]).unwrap();
+        let parsed = link::Nla::parse_with_param(&nla, AF_UNSPEC).unwrap();
+        assert_eq!(
+            parsed,
+            link::Nla::ProtoDownReason(vec![ProtoDownReason::Mask(4), ProtoDownReason::Value(4)])
+        );
+    }
+
+    #[test]
+    fn emit_proto_down_reason() {
+        let nla =
+            link::Nla::ProtoDownReason(vec![ProtoDownReason::Mask(4), ProtoDownReason::Value(4)]);
+        assert_eq!(nla.buffer_len(), 20);
+
+        let mut vec = vec![0xff; 20];
+        nla.emit(&mut vec);
+        assert_eq!(&vec[..], &PROTO_DOWN_REASON[..]);
+    }
+}
@@ -174,22 +174,16 @@ fn get_nlas() -> impl Iterator<Item = Result<NlaBuffer<&'static [u8]>, DecodeErr
     NlasIterator::new(&*BUFFER.value())
 }
 
-fn get_byte_buffer(nla: &dyn Emitable) -> Vec<u8> {
-    let mut buf = vec![0u8; nla.buffer_len()];
-    nla.emit(&mut buf);
-    buf
-}
-
 lazy_static! {
     static ref PARSED_AF_INET6: AfSpecInet = AfSpecInet::Inet6(vec![
         Inet6::Flags(2147483648),
-        Inet6::CacheInfo(get_byte_buffer(&Inet6CacheInfo {
+        Inet6::CacheInfo(Inet6CacheInfo {
             max_reasm_len: 65535,
             tstamp: 175,
             reachable_time: 25730,
             retrans_time: 1000,
-        })),
-        Inet6::DevConf(get_byte_buffer(&Inet6DevConf {
+        }),
+        Inet6::DevConf(Inet6DevConf {
             forwarding: 0,
             hoplimit: 64,
             mtu6: 65536,
@@ -241,8 +235,8 @@ lazy_static! {
             disable_policy: 0,
             accept_ra_rt_info_min_plen: 0,
             ndisc_tclass: 0,
-        })),
-        Inet6::Stats(get_byte_buffer(&Inet6Stats {
+        }),
+        Inet6::Stats(Inet6Stats {
             num: 36,
             in_pkts: 6,
             in_octets: 420,
@@ -279,55 +273,54 @@ lazy_static! {
             in_ect1_pkts: 0,
             in_ect0_pkts: 0,
             in_ce_pkts: 0,
-        })),
-        Inet6::IcmpStats(get_byte_buffer(&Icmp6Stats {
+        }),
+        Inet6::IcmpStats(Icmp6Stats {
             num: 6,
             in_msgs: 0,
             in_errors: 0,
             out_msgs: 0,
             out_errors: 0,
             csum_errors: 0,
-        })),
+        }),
         Inet6::Token([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]),
         Inet6::AddrGenMode(0),
     ]);
 }
 
 lazy_static! {
-    static ref PARSED_AF_INET: AfSpecInet =
-        AfSpecInet::Inet(vec![Inet::DevConf(get_byte_buffer(&InetDevConf {
-            forwarding: 1,
-            mc_forwarding: 0,
-            proxy_arp: 0,
-            accept_redirects: 1,
-            secure_redirects: 1,
-            send_redirects: 1,
-            shared_media: 1,
-            rp_filter: 0,
-            accept_source_route: 1,
-            bootp_relay: 0,
-            log_martians: 0,
-            tag: 0,
-            arpfilter: 0,
-            medium_id: 0,
-            noxfrm: 1,
-            nopolicy: 1,
-            force_igmp_version: 0,
-            arp_announce: 0,
-            arp_ignore: 0,
-            promote_secondaries: 0,
-            arp_accept: 0,
-            arp_notify: 0,
-            accept_local: 0,
-            src_vmark: 0,
-            proxy_arp_pvlan: 0,
-            route_localnet: 0,
-            igmpv2_unsolicited_report_interval: 10000,
-            igmpv3_unsolicited_report_interval: 1000,
-            ignore_routes_with_linkdown: 0,
-            drop_unicast_in_l2_multicast: 0,
-            drop_gratuitous_arp: 0,
-        }))]);
+    static ref PARSED_AF_INET: AfSpecInet = AfSpecInet::Inet(vec![Inet::DevConf(InetDevConf {
+        forwarding: 1,
+        mc_forwarding: 0,
+        proxy_arp: 0,
+        accept_redirects: 1,
+        secure_redirects: 1,
+        send_redirects: 1,
+        shared_media: 1,
+        rp_filter: 0,
+        accept_source_route: 1,
+        bootp_relay: 0,
+        log_martians: 0,
+        tag: 0,
+        arpfilter: 0,
+        medium_id: 0,
+        noxfrm: 1,
+        nopolicy: 1,
+        force_igmp_version: 0,
+        arp_announce: 0,
+        arp_ignore: 0,
+        promote_secondaries: 0,
+        arp_accept: 0,
+        arp_notify: 0,
+        accept_local: 0,
+        src_vmark: 0,
+        proxy_arp_pvlan: 0,
+        route_localnet: 0,
+        igmpv2_unsolicited_report_interval: 10000,
+        igmpv3_unsolicited_report_interval: 1000,
+        ignore_routes_with_linkdown: 0,
+        drop_unicast_in_l2_multicast: 0,
+        drop_gratuitous_arp: 0,
+    })]);
 }
 
 #[test]
@@ -409,3 +402,78 @@ fn parse_af_inet6() {
     // Normally this is the end of the nla iterator
     assert!(nlas.next().is_none());
 }
+
+#[test]
+fn parse_inet6_dev_conf_with_unknown_fields() {
+    // a newer kernel reporting one more field than we know about
+    let len = 4 + LINK_INET6_DEV_CONF_LEN + 4;
+    let mut bytes = vec![0x00; len];
+    NativeEndian::write_u16(&mut bytes[0..2], len as u16);
+    NativeEndian::write_u16(&mut bytes[2..4], IFLA_INET6_CONF);
+    NativeEndian::write_i32(&mut bytes[4..8], 1); // forwarding
+    NativeEndian::write_i32(&mut bytes[4 + LINK_INET6_DEV_CONF_LEN..], 42);
+
+    let nla = NlaBuffer::new_checked(&bytes[..]).unwrap();
+    match Inet6::parse(&nla).unwrap() {
+        Inet6::DevConf(dev_conf) => {
+            assert_eq!(dev_conf.forwarding, 1);
+            assert_eq!(dev_conf.ndisc_tclass, 0);
+        }
+        other => panic!("unexpected NLA {:?}", other),
+    }
+
+    // an older kernel that does not report the last field
+    let len = LINK_INET6_DEV_CONF_LEN;
+    let mut bytes = vec![0x00; len];
+    NativeEndian::write_u16(&mut bytes[0..2], len as u16);
+    NativeEndian::write_u16(&mut bytes[2..4], IFLA_INET6_CONF);
+    NativeEndian::write_i32(&mut bytes[4..8], 1); // forwarding
+
+    let nla = NlaBuffer::new_checked(&bytes[..]).unwrap();
+    match Inet6::parse(&nla).unwrap() {
+        Inet6::DevConf(dev_conf) => {
+            assert_eq!(dev_conf.forwarding, 1);
+            assert_eq!(dev_conf.ndisc_tclass, 0);
+        }
+        other => panic!("unexpected NLA {:?}", other),
+    }
+}
+
+#[rustfmt::skip]
+static INET_DEV_CONF_UPDATE: [u8; 20] = [
+    0x14, 0x00, // length = 20
+    0x01, 0x80, // type = 1 = IFLA_INET_CONF, with NLA_F_NESTED
+        0x08, 0x00, // length = 8
+        0x01, 0x00, // type = 1 = IPV4_DEVCONF_FORWARDING
+        0x01, 0x00, 0x00, 0x00, // V = 1
+        0x08, 0x00, // length = 8
+        0x08, 0x00, // type = 8 = IPV4_DEVCONF_RP_FILTER
+        0x02, 0x00, 0x00, 0x00, // V = 2
+];
+
+lazy_static! {
+    static ref PARSED_INET_DEV_CONF_UPDATE: Inet = Inet::DevConfUpdate(vec![
+        InetDevConfValue {
+            id: IPV4_DEVCONF_FORWARDING,
+            value: 1,
+        },
+        InetDevConfValue {
+            id: IPV4_DEVCONF_RP_FILTER,
+            value: 2,
+        },
+    ]);
+}
+
+#[test]
+fn parse_inet_dev_conf_update() {
+    let nla = NlaBuffer::new_checked(&INET_DEV_CONF_UPDATE[..]).unwrap();
+    assert_eq!(Inet::parse(&nla).unwrap(), *PARSED_INET_DEV_CONF_UPDATE);
+}
+
+#[test]
+fn emit_inet_dev_conf_update() {
+    assert_eq!(PARSED_INET_DEV_CONF_UPDATE.buffer_len(), 20);
+    let mut bytes = [0xff; 20];
+    PARSED_INET_DEV_CONF_UPDATE.emit(&mut bytes[..]);
+    assert_eq!(&bytes[..], &INET_DEV_CONF_UPDATE[..]);
+}
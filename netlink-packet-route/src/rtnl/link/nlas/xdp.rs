@@ -0,0 +1,219 @@
+use std::os::unix::io::RawFd;
+
+use anyhow::Context;
+use byteorder::{ByteOrder, NativeEndian};
+
+use crate::{
+    constants::*,
+    nlas::{DefaultNla, Nla, NlaBuffer},
+    parsers::{parse_i32, parse_u32, parse_u8},
+    traits::Parseable,
+    DecodeError,
+};
+
+/// Mode in which an XDP program is attached to a device
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum XdpAttached {
+    /// No program is attached
+    None,
+    /// The program runs in the driver (native mode)
+    Driver,
+    /// The program runs in the generic, skb based, code path
+    Skb,
+    /// The program is offloaded to the NIC
+    Hardware,
+    /// Several programs are attached in different modes. The program ids must be read from
+    /// [`Xdp::DrvProgId`], [`Xdp::SkbProgId`] and [`Xdp::HwProgId`].
+    Multi,
+    Other(u8),
+}
+
+impl From<u8> for XdpAttached {
+    fn from(value: u8) -> Self {
+        use self::XdpAttached::*;
+        match value {
+            XDP_ATTACHED_NONE => None,
+            XDP_ATTACHED_DRV => Driver,
+            XDP_ATTACHED_SKB => Skb,
+            XDP_ATTACHED_HW => Hardware,
+            XDP_ATTACHED_MULTI => Multi,
+            _ => Other(value),
+        }
+    }
+}
+
+impl From<XdpAttached> for u8 {
+    fn from(value: XdpAttached) -> Self {
+        use self::XdpAttached::*;
+        match value {
+            None => XDP_ATTACHED_NONE,
+            Driver => XDP_ATTACHED_DRV,
+            Skb => XDP_ATTACHED_SKB,
+            Hardware => XDP_ATTACHED_HW,
+            Multi => XDP_ATTACHED_MULTI,
+            Other(other) => other,
+        }
+    }
+}
+
+/// Attributes nested in `IFLA_XDP`.
+///
+/// `Fd`, `ExpectedFd` and `Flags` are used to attach or detach a program, while `Attached` and
+/// the various program ids are reported by the kernel when dumping links.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Xdp {
+    Unspec(Vec<u8>),
+    /// File descriptor of the BPF program to attach. `-1` detaches the current program.
+    Fd(RawFd),
+    /// With `XDP_FLAGS_REPLACE`, file descriptor of the program expected to be currently attached
+    ExpectedFd(RawFd),
+    Attached(XdpAttached),
+    /// A combination of the `XDP_FLAGS_*` constants
+    Flags(u32),
+    ProgId(u32),
+    DrvProgId(u32),
+    SkbProgId(u32),
+    HwProgId(u32),
+    Other(DefaultNla),
+}
+
+impl Nla for Xdp {
+    #[rustfmt::skip]
+    fn value_len(&self) -> usize {
+        use self::Xdp::*;
+        match self {
+            Unspec(bytes) => bytes.len(),
+            Attached(_) => 1,
+            Fd(_)
+                | ExpectedFd(_)
+                | Flags(_)
+                | ProgId(_)
+                | DrvProgId(_)
+                | SkbProgId(_)
+                | HwProgId(_)
+                => 4,
+            Other(nla) => nla.value_len(),
+        }
+    }
+
+    #[rustfmt::skip]
+    fn emit_value(&self, buffer: &mut [u8]) {
+        use self::Xdp::*;
+        match self {
+            Unspec(bytes) => buffer.copy_from_slice(bytes.as_slice()),
+            Fd(value) | ExpectedFd(value) => NativeEndian::write_i32(buffer, *value),
+            Attached(value) => buffer[0] = (*value).into(),
+            Flags(value)
+                | ProgId(value)
+                | DrvProgId(value)
+                | SkbProgId(value)
+                | HwProgId(value)
+                => NativeEndian::write_u32(buffer, *value),
+            Other(nla) => nla.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        use self::Xdp::*;
+        match self {
+            Unspec(_) => IFLA_XDP_UNSPEC,
+            Fd(_) => IFLA_XDP_FD,
+            ExpectedFd(_) => IFLA_XDP_EXPECTED_FD,
+            Attached(_) => IFLA_XDP_ATTACHED,
+            Flags(_) => IFLA_XDP_FLAGS,
+            ProgId(_) => IFLA_XDP_PROG_ID,
+            DrvProgId(_) => IFLA_XDP_DRV_PROG_ID,
+            SkbProgId(_) => IFLA_XDP_SKB_PROG_ID,
+            HwProgId(_) => IFLA_XDP_HW_PROG_ID,
+            Other(nla) => nla.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>> for Xdp {
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        use self::Xdp::*;
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            IFLA_XDP_UNSPEC => Unspec(payload.to_vec()),
+            IFLA_XDP_FD => Fd(parse_i32(payload).context("invalid IFLA_XDP_FD value")?),
+            IFLA_XDP_EXPECTED_FD => {
+                ExpectedFd(parse_i32(payload).context("invalid IFLA_XDP_EXPECTED_FD value")?)
+            }
+            IFLA_XDP_ATTACHED => Attached(
+                parse_u8(payload)
+                    .context("invalid IFLA_XDP_ATTACHED value")?
+                    .into(),
+            ),
+            IFLA_XDP_FLAGS => Flags(parse_u32(payload).context("invalid IFLA_XDP_FLAGS value")?),
+            IFLA_XDP_PROG_ID => {
+                ProgId(parse_u32(payload).context("invalid IFLA_XDP_PROG_ID value")?)
+            }
+            IFLA_XDP_DRV_PROG_ID => {
+                DrvProgId(parse_u32(payload).context("invalid IFLA_XDP_DRV_PROG_ID value")?)
+            }
+            IFLA_XDP_SKB_PROG_ID => {
+                SkbProgId(parse_u32(payload).context("invalid IFLA_XDP_SKB_PROG_ID value")?)
+            }
+            IFLA_XDP_HW_PROG_ID => {
+                HwProgId(parse_u32(payload).context("invalid IFLA_XDP_HW_PROG_ID value")?)
+            }
+            kind => Other(DefaultNla::parse(buf).context(format!("unknown NLA type {}", kind))?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        nlas::{link, NlasIterator},
+        traits::{Emitable, ParseableParametrized},
+    };
+
+    #[rustfmt::skip]
+    static XDP: [u8; 28] = [
+        0x1c, 0x00, // length = 28
+        0x2b, 0x00, // type = 43 = IFLA_XDP
+            0x05, 0x00, // length = 5
+            0x02, 0x00, // type = 2 = IFLA_XDP_ATTACHED
+            0x02, // V = 2 = XDP_ATTACHED_SKB
+            0x00, 0x00, 0x00, // padding
+            0x08, 0x00, // length = 8
+            0x06, 0x00, // type = 6 = IFLA_XDP_SKB_PROG_ID
+            0x2a, 0x00, 0x00, 0x00, // V = 42
+            0x08, 0x00, // length = 8
+            0x04, 0x00, // type = 4 = IFLA_XDP_PROG_ID
+            0x2a, 0x00, 0x00, 0x00, // V = 42
+    ];
+
+    lazy_static! {
+        static ref XDP_NLAS: Vec<Xdp> = vec![
+            Xdp::Attached(XdpAttached::Skb),
+            Xdp::SkbProgId(42),
+            Xdp::ProgId(42),
+        ];
+    }
+
+    #[test]
+    fn parse_xdp() {
+        let nla = NlaBuffer::new_checked(&XDP[..]).unwrap();
+        let parsed = link::Nla::parse_with_param(&nla, AF_UNSPEC).unwrap();
+        assert_eq!(parsed, link::Nla::Xdp(XDP_NLAS.clone()));
+
+        let nested: Vec<Xdp> = NlasIterator::new(nla.value())
+            .map(|nla| Xdp::parse(&nla.unwrap()).unwrap())
+            .collect();
+        assert_eq!(nested, *XDP_NLAS);
+    }
+
+    #[test]
+    fn emit_xdp() {
+        let nla = link::Nla::Xdp(XDP_NLAS.clone());
+        assert_eq!(nla.buffer_len(), 28);
+
+        let mut vec = vec![0xff; 28];
+        nla.emit(&mut vec);
+        assert_eq!(&vec[..], &XDP[..]);
+    }
+}
@@ -23,6 +23,8 @@ pub enum RtnlMessage {
     DelLink(LinkMessage),
     GetLink(LinkMessage),
     SetLink(LinkMessage),
+    NewLinkProp(LinkMessage),
+    DelLinkProp(LinkMessage),
     NewAddress(AddressMessage),
     DelAddress(AddressMessage),
     GetAddress(AddressMessage),
@@ -72,6 +74,14 @@ impl RtnlMessage {
         matches!(self, RtnlMessage::SetLink(_))
     }
 
+    pub fn is_new_link_prop(&self) -> bool {
+        matches!(self, RtnlMessage::NewLinkProp(_))
+    }
+
+    pub fn is_del_link_prop(&self) -> bool {
+        matches!(self, RtnlMessage::DelLinkProp(_))
+    }
+
     pub fn is_new_address(&self) -> bool {
         matches!(self, RtnlMessage::NewAddress(_))
     }
@@ -200,6 +210,8 @@ impl RtnlMessage {
             DelLink(_) => RTM_DELLINK,
             GetLink(_) => RTM_GETLINK,
             SetLink(_) => RTM_SETLINK,
+            NewLinkProp(_) => RTM_NEWLINKPROP,
+            DelLinkProp(_) => RTM_DELLINKPROP,
             NewAddress(_) => RTM_NEWADDR,
             DelAddress(_) => RTM_DELADDR,
             GetAddress(_) => RTM_GETADDR,
@@ -243,6 +255,8 @@ impl Emitable for RtnlMessage {
             | DelLink(ref msg)
             | GetLink(ref msg)
             | SetLink(ref msg)
+            | NewLinkProp(ref msg)
+            | DelLinkProp(ref msg)
             =>  msg.buffer_len(),
 
             | NewAddress(ref msg)
@@ -299,6 +313,8 @@ impl Emitable for RtnlMessage {
             | DelLink(ref msg)
             | GetLink(ref msg)
             | SetLink(ref msg)
+            | NewLinkProp(ref msg)
+            | DelLinkProp(ref msg)
             => msg.emit(buffer),
 
             | NewAddress(ref msg)
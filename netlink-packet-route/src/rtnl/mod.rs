@@ -1,14 +1,22 @@
 pub mod address;
-pub use address::{AddressHeader, AddressMessage, AddressMessageBuffer, ADDRESS_HEADER_LEN};
+pub use address::{
+    AddressHeader,
+    AddressHeaderFlags,
+    AddressMessage,
+    AddressMessageBuffer,
+    ADDRESS_HEADER_LEN,
+};
 
 pub mod link;
-pub use link::{LinkHeader, LinkMessage, LinkMessageBuffer, LINK_HEADER_LEN};
+pub use link::{LinkFlags, LinkHeader, LinkMessage, LinkMessageBuffer, LINK_HEADER_LEN};
 
 pub mod neighbour;
 pub use neighbour::{
     NeighbourHeader,
     NeighbourMessage,
+    NeighbourFlags,
     NeighbourMessageBuffer,
+    NeighbourState,
     NEIGHBOUR_HEADER_LEN,
 };
 
@@ -24,14 +32,27 @@ pub mod nsid;
 pub use nsid::{NsidHeader, NsidMessage, NsidMessageBuffer, NSID_HEADER_LEN};
 
 pub mod route;
-pub use route::{RouteFlags, RouteHeader, RouteMessage, RouteMessageBuffer, ROUTE_HEADER_LEN};
+pub use route::{
+    RouteFlags,
+    RouteHeader,
+    RouteMessage,
+    RouteMessageBuffer,
+    RouteProtocol,
+    RouteScope,
+    RouteTable,
+    RouteType,
+    ROUTE_HEADER_LEN,
+};
 
 pub mod rule;
-pub use rule::{RuleHeader, RuleMessage, RuleMessageBuffer, RULE_HEADER_LEN};
+pub use rule::{RuleFlags, RuleHeader, RuleMessage, RuleMessageBuffer, RULE_HEADER_LEN};
 
 pub mod tc;
 pub use tc::{TcHeader, TcMessage, TcMessageBuffer, TC_HEADER_LEN};
 
+mod ip;
+pub use self::ip::IpAddrError;
+
 pub mod constants;
 pub use self::constants::*;
 
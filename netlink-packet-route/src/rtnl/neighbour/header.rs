@@ -1,10 +1,86 @@
 use crate::{
+    constants::*,
     traits::{Emitable, Parseable},
     DecodeError,
     NeighbourMessageBuffer,
     NEIGHBOUR_HEADER_LEN,
 };
 
+bitflags! {
+    /// State of a neighbour cache entry, as found in [`NeighbourHeader::state`].
+    pub struct NeighbourState: u16 {
+        /// The neighbour has not (yet) been resolved
+        const NUD_INCOMPLETE = NUD_INCOMPLETE;
+        /// The entry is valid until its lifetime expires
+        const NUD_REACHABLE = NUD_REACHABLE;
+        /// The entry is valid but suspicious
+        const NUD_STALE = NUD_STALE;
+        /// The validation of the entry is currently delayed
+        const NUD_DELAY = NUD_DELAY;
+        /// The entry is being probed
+        const NUD_PROBE = NUD_PROBE;
+        /// The validation of the entry has failed
+        const NUD_FAILED = NUD_FAILED;
+        /// The entry is valid and the kernel will not try to validate or refresh it
+        const NUD_NOARP = NUD_NOARP;
+        /// The entry is valid forever and can only be removed explicitly from userspace
+        const NUD_PERMANENT = NUD_PERMANENT;
+    }
+}
+
+impl Default for NeighbourState {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl From<u16> for NeighbourState {
+    fn from(bits: u16) -> Self {
+        // Unknown bits are valid for this type: they are kept as is.
+        Self { bits }
+    }
+}
+
+impl From<NeighbourState> for u16 {
+    fn from(flags: NeighbourState) -> Self {
+        flags.bits()
+    }
+}
+
+bitflags! {
+    /// Flags of a neighbour cache entry, as found in [`NeighbourHeader::flags`]. Bits that do not
+    /// correspond to a known flag are preserved.
+    pub struct NeighbourFlags: u8 {
+        const NTF_USE = NTF_USE;
+        const NTF_SELF = NTF_SELF;
+        const NTF_MASTER = NTF_MASTER;
+        const NTF_PROXY = NTF_PROXY;
+        const NTF_EXT_LEARNED = NTF_EXT_LEARNED;
+        const NTF_OFFLOADED = NTF_OFFLOADED;
+        const NTF_STICKY = NTF_STICKY;
+        const NTF_ROUTER = NTF_ROUTER;
+    }
+}
+
+impl Default for NeighbourFlags {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl From<u8> for NeighbourFlags {
+    fn from(bits: u8) -> Self {
+        // Unknown bits are valid for this type: they are kept as is.
+        Self { bits }
+    }
+}
+
+impl From<NeighbourFlags> for u8 {
+    fn from(flags: NeighbourFlags) -> Self {
+        flags.bits()
+    }
+}
+
 /// Neighbour headers have the following structure:
 ///
 /// ```no_rust
@@ -23,12 +99,10 @@ use crate::{
 pub struct NeighbourHeader {
     pub family: u8,
     pub ifindex: u32,
-    /// Neighbour cache entry state. It should be set to one of the
-    /// `NUD_*` constants
-    pub state: u16,
-    /// Neighbour cache entry flags. It should be set to a combination
-    /// of the `NTF_*` constants
-    pub flags: u8,
+    /// Neighbour cache entry state
+    pub state: NeighbourState,
+    /// Neighbour cache entry flags
+    pub flags: NeighbourFlags,
     /// Neighbour cache entry type. It should be set to one of the
     /// `NDA_*` constants.
     pub ntype: u8,
@@ -39,8 +113,8 @@ impl<T: AsRef<[u8]>> Parseable<NeighbourMessageBuffer<T>> for NeighbourHeader {
         Ok(Self {
             family: buf.family(),
             ifindex: buf.ifindex(),
-            state: buf.state(),
-            flags: buf.flags(),
+            state: NeighbourState::from(buf.state()),
+            flags: NeighbourFlags::from(buf.flags()),
             ntype: buf.ntype(),
         })
     }
@@ -55,8 +129,8 @@ impl Emitable for NeighbourHeader {
         let mut packet = NeighbourMessageBuffer::new(buffer);
         packet.set_family(self.family);
         packet.set_ifindex(self.ifindex);
-        packet.set_state(self.state);
-        packet.set_flags(self.flags);
+        packet.set_state(self.state.bits());
+        packet.set_flags(self.flags.bits());
         packet.set_ntype(self.ntype);
     }
 }
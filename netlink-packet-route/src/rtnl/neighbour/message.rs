@@ -1,9 +1,12 @@
 use anyhow::Context;
+use std::net::IpAddr;
 
 use crate::{
     nlas::neighbour::Nla,
+    rtnl::ip::{check_family, ip_octets, parse_ip},
     traits::{Emitable, Parseable},
     DecodeError,
+    IpAddrError,
     NeighbourHeader,
     NeighbourMessageBuffer,
 };
@@ -47,14 +50,44 @@ impl<'a, T: AsRef<[u8]> + 'a> Parseable<NeighbourMessageBuffer<&'a T>> for Vec<N
     }
 }
 
+impl NeighbourMessage {
+    /// Returns the destination address of the entry, if present and valid for the address family
+    /// of the header.
+    pub fn destination(&self) -> Option<IpAddr> {
+        self.nlas.iter().find_map(|nla| {
+            if let Nla::Destination(v) = nla {
+                parse_ip(self.header.family, v)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Set the destination address of the entry. If the address family of the header is not set
+    /// yet, it is set to the family of `addr`.
+    pub fn set_destination(&mut self, addr: IpAddr) -> Result<(), IpAddrError> {
+        check_family(&mut self.header.family, addr)?;
+        self.nlas.retain(|nla| !matches!(nla, Nla::Destination(_)));
+        self.nlas.push(Nla::Destination(ip_octets(addr)));
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod test {
+    use std::net::IpAddr;
+
     use crate::{
         constants::*,
-        traits::Emitable,
+        nlas::neighbour::Nla,
+        rtnl::ip::ip_octets,
+        traits::{Emitable, Parseable},
+        IpAddrError,
+        NeighbourFlags,
         NeighbourHeader,
         NeighbourMessage,
         NeighbourMessageBuffer,
+        NeighbourState,
     };
 
     // 0020   0a 00 00 00 02 00 00 00 02 00 80 01 14 00 01 00
@@ -105,8 +138,8 @@ mod test {
         let header = NeighbourHeader {
             family: AF_INET6 as u8,
             ifindex: 1,
-            state: NUD_REACHABLE,
-            flags: NTF_ROUTER,
+            state: NeighbourState::NUD_REACHABLE,
+            flags: NeighbourFlags::NTF_ROUTER,
             ntype: NDA_DST as u8,
         };
 
@@ -117,4 +150,45 @@ mod test {
         assert_eq!(packet.buffer_len(), 12);
         packet.emit(&mut buf[..]);
     }
+
+    #[test]
+    fn destination() {
+        let mut message = NeighbourMessage::default();
+        let addr: IpAddr = "fe80::1".parse().unwrap();
+        message.set_destination(addr).unwrap();
+        assert_eq!(message.header.family, AF_INET6 as u8);
+        assert_eq!(message.destination(), Some(addr));
+
+        // setting the destination again replaces the attribute
+        let addr: IpAddr = "fe80::2".parse().unwrap();
+        message.set_destination(addr).unwrap();
+        assert_eq!(message.nlas, vec![Nla::Destination(ip_octets(addr))]);
+
+        assert_eq!(
+            message.set_destination("10.0.0.1".parse().unwrap()),
+            Err(IpAddrError::FamilyMismatch {
+                family: AF_INET6 as u8,
+                address: "10.0.0.1".parse().unwrap(),
+            })
+        );
+
+        // an IPv4 address in an IPv6 entry is not valid
+        message.nlas = vec![Nla::Destination(vec![10, 0, 0, 1])];
+        assert_eq!(message.destination(), None);
+    }
+
+    #[test]
+    fn unknown_flags_round_trip() {
+        let header = NeighbourHeader {
+            state: NeighbourState::from(0x8000 | NUD_REACHABLE),
+            flags: NeighbourFlags::from(0x40),
+            ..Default::default()
+        };
+        let mut buf = vec![0; header.buffer_len()];
+        header.emit(&mut buf[..]);
+        let parsed = NeighbourHeader::parse(&NeighbourMessageBuffer::new(&buf[..])).unwrap();
+        assert_eq!(u16::from(parsed.state), 0x8000 | NUD_REACHABLE);
+        assert_eq!(u8::from(parsed.flags), 0x40);
+        assert_eq!(parsed, header);
+    }
 }
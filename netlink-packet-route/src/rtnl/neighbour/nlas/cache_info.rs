@@ -3,11 +3,17 @@ use crate::{
     DecodeError,
 };
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// Neighbour cache entry timers (`struct nda_cacheinfo`). Times are expressed in hundredths of
+/// seconds elapsed since the corresponding event.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
 pub struct CacheInfo {
+    /// Time since the neighbour was last confirmed to be reachable
     pub confirmed: u32,
+    /// Time since the entry was last used
     pub used: u32,
+    /// Time since the entry was last updated
     pub updated: u32,
+    /// Number of references held on the entry, minus one
     pub refcnt: u32,
 }
 
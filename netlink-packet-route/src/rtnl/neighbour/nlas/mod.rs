@@ -7,8 +7,8 @@ use byteorder::{ByteOrder, NativeEndian};
 use crate::{
     constants::*,
     nlas::{self, DefaultNla, NlaBuffer},
-    parsers::{parse_u16, parse_u32},
-    traits::Parseable,
+    parsers::{parse_u16, parse_u32, parse_u8},
+    traits::{Emitable, Parseable},
     DecodeError,
 };
 
@@ -17,8 +17,9 @@ pub enum Nla {
     Unspec(Vec<u8>),
     Destination(Vec<u8>),
     LinkLocalAddress(Vec<u8>),
-    CacheInfo(Vec<u8>),
-    Probes(Vec<u8>),
+    CacheInfo(CacheInfo),
+    /// Number of probes sent to the neighbour since it was last confirmed
+    Probes(u32),
     Vlan(u16),
     Port(Vec<u8>),
     Vni(u32),
@@ -26,6 +27,12 @@ pub enum Nla {
     Master(Vec<u8>),
     LinkNetNsId(Vec<u8>),
     SourceVni(u32),
+    /// Protocol that installed the entry (`RTPROT_*`)
+    Protocol(u8),
+    /// Id of the next hop object the entry points to
+    NhId(u32),
+    /// Extended flags (`NTF_EXT_*`)
+    FlagsExt(u32),
     Other(DefaultNla),
 }
 
@@ -37,15 +44,18 @@ impl nlas::Nla for Nla {
             Unspec(ref bytes)
             | Destination(ref bytes)
             | LinkLocalAddress(ref bytes)
-            | Probes(ref bytes)
             | Port(ref bytes)
             | Master(ref bytes)
-            | CacheInfo(ref bytes)
             | LinkNetNsId(ref bytes) => bytes.len(),
+            CacheInfo(ref cache_info) => cache_info.buffer_len(),
+            Protocol(_) => 1,
             Vlan(_) => 2,
             Vni(_)
             | IfIndex(_)
-            | SourceVni(_) => 4,
+            | SourceVni(_)
+            | Probes(_)
+            | NhId(_)
+            | FlagsExt(_) => 4,
             Other(ref attr) => attr.value_len(),
         }
     }
@@ -57,15 +67,18 @@ impl nlas::Nla for Nla {
             Unspec(ref bytes)
             | Destination(ref bytes)
             | LinkLocalAddress(ref bytes)
-            | Probes(ref bytes)
             | Port(ref bytes)
             | Master(ref bytes)
-            | CacheInfo(ref bytes)
             | LinkNetNsId(ref bytes) => buffer.copy_from_slice(bytes.as_slice()),
+            CacheInfo(ref cache_info) => cache_info.emit(buffer),
+            Protocol(value) => buffer[0] = value,
             Vlan(ref value) => NativeEndian::write_u16(buffer, *value),
             Vni(ref value)
             | IfIndex(ref value)
-            | SourceVni(ref value) => NativeEndian::write_u32(buffer, *value),
+            | SourceVni(ref value)
+            | Probes(ref value)
+            | NhId(ref value)
+            | FlagsExt(ref value) => NativeEndian::write_u32(buffer, *value),
             Other(ref attr) => attr.emit_value(buffer),
         }
     }
@@ -85,6 +98,9 @@ impl nlas::Nla for Nla {
             Master(_) => NDA_MASTER,
             LinkNetNsId(_) => NDA_LINK_NETNSID,
             SourceVni(_) => NDA_SRC_VNI,
+            Protocol(_) => NDA_PROTOCOL,
+            NhId(_) => NDA_NH_ID,
+            FlagsExt(_) => NDA_FLAGS_EXT,
             Other(ref nla) => nla.kind(),
         }
     }
@@ -99,8 +115,14 @@ impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>> for Nla {
             NDA_UNSPEC => Unspec(payload.to_vec()),
             NDA_DST => Destination(payload.to_vec()),
             NDA_LLADDR => LinkLocalAddress(payload.to_vec()),
-            NDA_CACHEINFO => CacheInfo(payload.to_vec()),
-            NDA_PROBES => Probes(payload.to_vec()),
+            NDA_CACHEINFO => CacheInfo(
+                cache_info::CacheInfo::parse(
+                    &CacheInfoBuffer::new_checked(payload)
+                        .context("invalid NDA_CACHEINFO value")?,
+                )
+                .context("invalid NDA_CACHEINFO value")?,
+            ),
+            NDA_PROBES => Probes(parse_u32(payload).context("invalid NDA_PROBES value")?),
             NDA_VLAN => Vlan(parse_u16(payload)?),
             NDA_PORT => Port(payload.to_vec()),
             NDA_VNI => Vni(parse_u32(payload)?),
@@ -108,7 +130,62 @@ impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>> for Nla {
             NDA_MASTER => Master(payload.to_vec()),
             NDA_LINK_NETNSID => LinkNetNsId(payload.to_vec()),
             NDA_SRC_VNI => SourceVni(parse_u32(payload)?),
+            NDA_PROTOCOL => Protocol(parse_u8(payload).context("invalid NDA_PROTOCOL value")?),
+            NDA_NH_ID => NhId(parse_u32(payload).context("invalid NDA_NH_ID value")?),
+            NDA_FLAGS_EXT => FlagsExt(parse_u32(payload).context("invalid NDA_FLAGS_EXT value")?),
             _ => Other(DefaultNla::parse(buf).context("invalid link NLA value (unknown type)")?),
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[rustfmt::skip]
+    static CACHE_INFO: [u8; 20] = [
+        0x14, 0x00, // length = 20
+        0x03, 0x00, // type = 3 = NDA_CACHEINFO
+        0x10, 0x00, 0x00, 0x00, // confirmed = 16
+        0x20, 0x00, 0x00, 0x00, // used = 32
+        0x30, 0x00, 0x00, 0x00, // updated = 48
+        0x01, 0x00, 0x00, 0x00, // refcnt = 1
+    ];
+
+    lazy_static! {
+        static ref CACHE_INFO_NLA: Nla = Nla::CacheInfo(CacheInfo {
+            confirmed: 16,
+            used: 32,
+            updated: 48,
+            refcnt: 1,
+        });
+    }
+
+    #[test]
+    fn parse_cache_info() {
+        let nla = NlaBuffer::new_checked(&CACHE_INFO[..]).unwrap();
+        let parsed = Nla::parse(&nla).unwrap();
+        assert_eq!(parsed, *CACHE_INFO_NLA);
+    }
+
+    #[test]
+    fn emit_cache_info() {
+        assert_eq!(CACHE_INFO_NLA.buffer_len(), 20);
+
+        let mut buf = [0xff; 20];
+        CACHE_INFO_NLA.emit(&mut buf);
+        assert_eq!(&buf[..], &CACHE_INFO[..]);
+    }
+
+    #[test]
+    fn parse_probes() {
+        #[rustfmt::skip]
+        let bytes = [
+            0x08, 0x00, // length = 8
+            0x04, 0x00, // type = 4 = NDA_PROBES
+            0x03, 0x00, 0x00, 0x00, // V = 3
+        ];
+        let nla = NlaBuffer::new_checked(&bytes[..]).unwrap();
+        assert_eq!(Nla::parse(&nla).unwrap(), Nla::Probes(3));
+    }
+}
@@ -5,7 +5,7 @@ use crate::{
 
 use super::buffer::{NeighbourTableMessageBuffer, NEIGHBOUR_TABLE_HEADER_LEN};
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
 pub struct NeighbourTableHeader {
     pub family: u8,
 }
@@ -7,7 +7,7 @@ use crate::{
 };
 use anyhow::Context;
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
 pub struct NeighbourTableMessage {
     pub header: NeighbourTableHeader,
     pub nlas: Vec<Nla>,
@@ -20,7 +20,9 @@ impl Emitable for NeighbourTableMessage {
 
     fn emit(&self, buffer: &mut [u8]) {
         self.header.emit(buffer);
-        self.nlas.as_slice().emit(buffer);
+        self.nlas
+            .as_slice()
+            .emit(&mut buffer[self.header.buffer_len()..]);
     }
 }
 
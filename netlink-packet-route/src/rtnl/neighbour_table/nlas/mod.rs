@@ -4,28 +4,30 @@ pub use config::*;
 mod stats;
 pub use stats::*;
 
+mod param;
+pub use param::*;
+
 use anyhow::Context;
 use byteorder::{ByteOrder, NativeEndian};
 
 use crate::{
     constants::*,
-    nlas::{self, DefaultNla, NlaBuffer},
+    nlas::{self, DefaultNla, NlaBuffer, NlasIterator},
     parsers::{parse_string, parse_u32, parse_u64},
-    traits::Parseable,
+    traits::{Emitable, Parseable},
     DecodeError,
 };
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Nla {
     Unspec(Vec<u8>),
-    // FIXME: parse this nla
-    Parms(Vec<u8>),
+    Parms(Vec<NeighbourTableParameter>),
     Name(String),
     Threshold1(u32),
     Threshold2(u32),
     Threshold3(u32),
-    Config(Vec<u8>),
-    Stats(Vec<u8>),
+    Config(Config),
+    Stats(Stats),
     GcInterval(u64),
     Other(DefaultNla),
 }
@@ -35,7 +37,10 @@ impl nlas::Nla for Nla {
     fn value_len(&self) -> usize {
         use self::Nla::*;
         match *self {
-            Unspec(ref bytes) | Parms(ref bytes) | Config(ref bytes) | Stats(ref bytes)=> bytes.len(),
+            Unspec(ref bytes) => bytes.len(),
+            Parms(ref nlas) => nlas.as_slice().buffer_len(),
+            Config(_) => CONFIG_LEN,
+            Stats(_) => STATS_LEN,
             // strings: +1 because we need to append a nul byte
             Name(ref s) => s.len() + 1,
             Threshold1(_) | Threshold2(_) | Threshold3(_) => 4,
@@ -47,9 +52,10 @@ impl nlas::Nla for Nla {
     fn emit_value(&self, buffer: &mut [u8]) {
         use self::Nla::*;
         match *self {
-            Unspec(ref bytes) | Parms(ref bytes) | Config(ref bytes) | Stats(ref bytes) => {
-                buffer.copy_from_slice(bytes.as_slice())
-            }
+            Unspec(ref bytes) => buffer.copy_from_slice(bytes.as_slice()),
+            Parms(ref nlas) => nlas.as_slice().emit(buffer),
+            Config(ref config) => config.emit(buffer),
+            Stats(ref stats) => stats.emit(buffer),
             Name(ref string) => {
                 buffer[..string.len()].copy_from_slice(string.as_bytes());
                 buffer[string.len()] = 0;
@@ -87,9 +93,32 @@ impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>> for Nla {
         Ok(match buf.kind() {
             NDTA_UNSPEC => Unspec(payload.to_vec()),
             NDTA_NAME => Name(parse_string(payload).context("invalid NDTA_NAME value")?),
-            NDTA_CONFIG => Config(payload.to_vec()),
-            NDTA_STATS => Stats(payload.to_vec()),
-            NDTA_PARMS => Parms(payload.to_vec()),
+            NDTA_CONFIG => Config(
+                super::Config::parse(
+                    &ConfigBuffer::new_checked(payload).context("invalid NDTA_CONFIG value")?,
+                )
+                .context("invalid NDTA_CONFIG value")?,
+            ),
+            NDTA_STATS => {
+                let err = "invalid NDTA_STATS value";
+                let stats = if payload.len() == STATS_LEN_WITHOUT_TABLE_FULLS {
+                    let mut padded = payload.to_vec();
+                    padded.resize(STATS_LEN, 0);
+                    super::Stats::parse(&StatsBuffer::new(&padded[..]))
+                } else {
+                    super::Stats::parse(&StatsBuffer::new_checked(payload).context(err)?)
+                };
+                Stats(stats.context(err)?)
+            }
+            NDTA_PARMS => {
+                let mut nlas = vec![];
+                let err = "invalid NDTA_PARMS value";
+                for nla in NlasIterator::new(payload) {
+                    let nla = nla.context(err)?;
+                    nlas.push(NeighbourTableParameter::parse(&nla).context(err)?);
+                }
+                Parms(nlas)
+            }
             NDTA_GC_INTERVAL => {
                 GcInterval(parse_u64(payload).context("invalid NDTA_GC_INTERVAL value")?)
             }
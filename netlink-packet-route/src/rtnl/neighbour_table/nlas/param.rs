@@ -0,0 +1,229 @@
+use anyhow::Context;
+use byteorder::{ByteOrder, NativeEndian};
+
+use crate::{
+    constants::*,
+    nlas::{self, DefaultNla, NlaBuffer},
+    parsers::{parse_u32, parse_u64},
+    traits::Parseable,
+    DecodeError,
+};
+
+/// Parameters of a neighbour table, nested in `NDTA_PARMS`. When `Ifindex` is present, the
+/// parameters apply to the given interface only, otherwise they are the defaults of the table.
+/// Times are expressed in milliseconds.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum NeighbourTableParameter {
+    Unspec(Vec<u8>),
+    Ifindex(u32),
+    ReferenceCount(u32),
+    ReachableTime(u64),
+    BaseReachableTime(u64),
+    RetransTime(u64),
+    GcStaleTime(u64),
+    DelayProbeTime(u64),
+    QueueLen(u32),
+    AppProbes(u32),
+    UcastProbes(u32),
+    McastProbes(u32),
+    AnycastDelay(u64),
+    ProxyDelay(u64),
+    ProxyQueueLen(u32),
+    Locktime(u64),
+    QueueLenBytes(u32),
+    McastReprobes(u32),
+    IntervalProbeTime(u64),
+    Pad(Vec<u8>),
+    Other(DefaultNla),
+}
+
+impl nlas::Nla for NeighbourTableParameter {
+    #[rustfmt::skip]
+    fn value_len(&self) -> usize {
+        use self::NeighbourTableParameter::*;
+        match *self {
+            Unspec(ref bytes) | Pad(ref bytes) => bytes.len(),
+            Ifindex(_)
+                | ReferenceCount(_)
+                | QueueLen(_)
+                | AppProbes(_)
+                | UcastProbes(_)
+                | McastProbes(_)
+                | ProxyQueueLen(_)
+                | QueueLenBytes(_)
+                | McastReprobes(_)
+                => 4,
+            ReachableTime(_)
+                | BaseReachableTime(_)
+                | RetransTime(_)
+                | GcStaleTime(_)
+                | DelayProbeTime(_)
+                | AnycastDelay(_)
+                | ProxyDelay(_)
+                | Locktime(_)
+                | IntervalProbeTime(_)
+                => 8,
+            Other(ref attr) => attr.value_len(),
+        }
+    }
+
+    #[rustfmt::skip]
+    fn emit_value(&self, buffer: &mut [u8]) {
+        use self::NeighbourTableParameter::*;
+        match *self {
+            Unspec(ref bytes) | Pad(ref bytes) => buffer.copy_from_slice(bytes.as_slice()),
+            Ifindex(ref value)
+                | ReferenceCount(ref value)
+                | QueueLen(ref value)
+                | AppProbes(ref value)
+                | UcastProbes(ref value)
+                | McastProbes(ref value)
+                | ProxyQueueLen(ref value)
+                | QueueLenBytes(ref value)
+                | McastReprobes(ref value)
+                => NativeEndian::write_u32(buffer, *value),
+            ReachableTime(ref value)
+                | BaseReachableTime(ref value)
+                | RetransTime(ref value)
+                | GcStaleTime(ref value)
+                | DelayProbeTime(ref value)
+                | AnycastDelay(ref value)
+                | ProxyDelay(ref value)
+                | Locktime(ref value)
+                | IntervalProbeTime(ref value)
+                => NativeEndian::write_u64(buffer, *value),
+            Other(ref attr) => attr.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        use self::NeighbourTableParameter::*;
+        match *self {
+            Unspec(_) => NDTPA_UNSPEC,
+            Ifindex(_) => NDTPA_IFINDEX,
+            ReferenceCount(_) => NDTPA_REFCNT,
+            ReachableTime(_) => NDTPA_REACHABLE_TIME,
+            BaseReachableTime(_) => NDTPA_BASE_REACHABLE_TIME,
+            RetransTime(_) => NDTPA_RETRANS_TIME,
+            GcStaleTime(_) => NDTPA_GC_STALETIME,
+            DelayProbeTime(_) => NDTPA_DELAY_PROBE_TIME,
+            QueueLen(_) => NDTPA_QUEUE_LEN,
+            AppProbes(_) => NDTPA_APP_PROBES,
+            UcastProbes(_) => NDTPA_UCAST_PROBES,
+            McastProbes(_) => NDTPA_MCAST_PROBES,
+            AnycastDelay(_) => NDTPA_ANYCAST_DELAY,
+            ProxyDelay(_) => NDTPA_PROXY_DELAY,
+            ProxyQueueLen(_) => NDTPA_PROXY_QLEN,
+            Locktime(_) => NDTPA_LOCKTIME,
+            QueueLenBytes(_) => NDTPA_QUEUE_LENBYTES,
+            McastReprobes(_) => NDTPA_MCAST_REPROBES,
+            IntervalProbeTime(_) => NDTPA_INTERVAL_PROBE_TIME_MS,
+            Pad(_) => NDTPA_PAD,
+            Other(ref attr) => attr.kind(),
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>> for NeighbourTableParameter {
+    fn parse(buf: &NlaBuffer<&'a T>) -> Result<Self, DecodeError> {
+        use self::NeighbourTableParameter::*;
+        let payload = buf.value();
+        Ok(match buf.kind() {
+            NDTPA_UNSPEC => Unspec(payload.to_vec()),
+            NDTPA_IFINDEX => Ifindex(parse_u32(payload).context("invalid NDTPA_IFINDEX value")?),
+            NDTPA_REFCNT => {
+                ReferenceCount(parse_u32(payload).context("invalid NDTPA_REFCNT value")?)
+            }
+            NDTPA_REACHABLE_TIME => {
+                ReachableTime(parse_u64(payload).context("invalid NDTPA_REACHABLE_TIME value")?)
+            }
+            NDTPA_BASE_REACHABLE_TIME => BaseReachableTime(
+                parse_u64(payload).context("invalid NDTPA_BASE_REACHABLE_TIME value")?,
+            ),
+            NDTPA_RETRANS_TIME => {
+                RetransTime(parse_u64(payload).context("invalid NDTPA_RETRANS_TIME value")?)
+            }
+            NDTPA_GC_STALETIME => {
+                GcStaleTime(parse_u64(payload).context("invalid NDTPA_GC_STALETIME value")?)
+            }
+            NDTPA_DELAY_PROBE_TIME => {
+                DelayProbeTime(parse_u64(payload).context("invalid NDTPA_DELAY_PROBE_TIME value")?)
+            }
+            NDTPA_QUEUE_LEN => {
+                QueueLen(parse_u32(payload).context("invalid NDTPA_QUEUE_LEN value")?)
+            }
+            NDTPA_APP_PROBES => {
+                AppProbes(parse_u32(payload).context("invalid NDTPA_APP_PROBES value")?)
+            }
+            NDTPA_UCAST_PROBES => {
+                UcastProbes(parse_u32(payload).context("invalid NDTPA_UCAST_PROBES value")?)
+            }
+            NDTPA_MCAST_PROBES => {
+                McastProbes(parse_u32(payload).context("invalid NDTPA_MCAST_PROBES value")?)
+            }
+            NDTPA_ANYCAST_DELAY => {
+                AnycastDelay(parse_u64(payload).context("invalid NDTPA_ANYCAST_DELAY value")?)
+            }
+            NDTPA_PROXY_DELAY => {
+                ProxyDelay(parse_u64(payload).context("invalid NDTPA_PROXY_DELAY value")?)
+            }
+            NDTPA_PROXY_QLEN => {
+                ProxyQueueLen(parse_u32(payload).context("invalid NDTPA_PROXY_QLEN value")?)
+            }
+            NDTPA_LOCKTIME => Locktime(parse_u64(payload).context("invalid NDTPA_LOCKTIME value")?),
+            NDTPA_QUEUE_LENBYTES => {
+                QueueLenBytes(parse_u32(payload).context("invalid NDTPA_QUEUE_LENBYTES value")?)
+            }
+            NDTPA_MCAST_REPROBES => {
+                McastReprobes(parse_u32(payload).context("invalid NDTPA_MCAST_REPROBES value")?)
+            }
+            NDTPA_INTERVAL_PROBE_TIME_MS => IntervalProbeTime(
+                parse_u64(payload).context("invalid NDTPA_INTERVAL_PROBE_TIME_MS value")?,
+            ),
+            NDTPA_PAD => Pad(payload.to_vec()),
+            kind => Other(DefaultNla::parse(buf).context(format!("unknown NLA type {}", kind))?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nlas::neighbour_table::Nla, traits::Emitable};
+
+    #[rustfmt::skip]
+    static PARMS: [u8; 24] = [
+        0x18, 0x00, // length = 24
+        0x06, 0x00, // type = 6 = NDTA_PARMS
+            0x08, 0x00, // length = 8
+            0x01, 0x00, // type = 1 = NDTPA_IFINDEX
+            0x02, 0x00, 0x00, 0x00, // V = 2
+            0x0c, 0x00, // length = 12
+            0x03, 0x00, // type = 3 = NDTPA_REACHABLE_TIME
+            0x30, 0x75, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // V = 30000
+    ];
+
+    lazy_static! {
+        static ref PARMS_NLAS: Vec<NeighbourTableParameter> = vec![
+            NeighbourTableParameter::Ifindex(2),
+            NeighbourTableParameter::ReachableTime(30000),
+        ];
+    }
+
+    #[test]
+    fn parse_parms() {
+        let nla = NlaBuffer::new_checked(&PARMS[..]).unwrap();
+        let parsed = Nla::parse(&nla).unwrap();
+        assert_eq!(parsed, Nla::Parms(PARMS_NLAS.clone()));
+    }
+
+    #[test]
+    fn emit_parms() {
+        let nla = Nla::Parms(PARMS_NLAS.clone());
+        assert_eq!(nla.buffer_len(), 24);
+
+        let mut vec = vec![0xff; 24];
+        nla.emit(&mut vec);
+        assert_eq!(&vec[..], &PARMS[..]);
+    }
+}
@@ -15,9 +15,15 @@ pub struct Stats {
     pub unicast_probes_received: u64,
     pub periodic_gc_runs: u64,
     pub forced_gc_runs: u64,
+    /// Number of times the table was full when an entry was added. Older kernels do not report
+    /// it, in which case it is 0.
+    pub table_fulls: u64,
 }
 
-pub const STATS_LEN: usize = 80;
+pub const STATS_LEN: usize = 88;
+/// Length of the statistics reported by the kernels that do not have `table_fulls`
+pub const STATS_LEN_WITHOUT_TABLE_FULLS: usize = 80;
+
 buffer!(StatsBuffer(STATS_LEN) {
     allocs: (u64, 0..8),
     destroys: (u64, 8..16),
@@ -29,6 +35,7 @@ buffer!(StatsBuffer(STATS_LEN) {
     unicast_probes_received: (u64, 56..64),
     periodic_gc_runs: (u64, 64..72),
     forced_gc_runs: (u64, 72..80),
+    table_fulls: (u64, 80..88),
 });
 
 impl<T: AsRef<[u8]>> Parseable<StatsBuffer<T>> for Stats {
@@ -44,6 +51,7 @@ impl<T: AsRef<[u8]>> Parseable<StatsBuffer<T>> for Stats {
             unicast_probes_received: buf.unicast_probes_received(),
             periodic_gc_runs: buf.periodic_gc_runs(),
             forced_gc_runs: buf.forced_gc_runs(),
+            table_fulls: buf.table_fulls(),
         })
     }
 }
@@ -65,5 +73,53 @@ impl Emitable for Stats {
         buffer.set_unicast_probes_received(self.unicast_probes_received);
         buffer.set_periodic_gc_runs(self.periodic_gc_runs);
         buffer.set_forced_gc_runs(self.forced_gc_runs);
+        buffer.set_table_fulls(self.table_fulls);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nlas::{neighbour_table::Nla, NlaBuffer};
+
+    fn stats_nla(len: usize) -> Vec<u8> {
+        let mut nla = vec![4 + len as u8, 0x00, 0x07, 0x00]; // type = 7 = NDTA_STATS
+        for counter in 1..=len as u64 / 8 {
+            nla.extend_from_slice(&counter.to_ne_bytes());
+        }
+        nla
+    }
+
+    #[test]
+    fn parse_and_emit_stats() {
+        let bytes = stats_nla(STATS_LEN);
+        let nla = Nla::parse(&NlaBuffer::new_checked(&bytes[..]).unwrap()).unwrap();
+        let stats = match nla {
+            Nla::Stats(ref stats) => *stats,
+            _ => panic!("expected NDTA_STATS, got {:?}", nla),
+        };
+        assert_eq!(stats.allocs, 1);
+        assert_eq!(stats.forced_gc_runs, 10);
+        assert_eq!(stats.table_fulls, 11);
+
+        let mut buf = vec![0xff; nla.buffer_len()];
+        nla.emit(&mut buf);
+        assert_eq!(buf, bytes);
+    }
+
+    #[test]
+    fn parse_stats_without_table_fulls() {
+        let bytes = stats_nla(STATS_LEN_WITHOUT_TABLE_FULLS);
+        let nla = Nla::parse(&NlaBuffer::new_checked(&bytes[..]).unwrap()).unwrap();
+        match nla {
+            Nla::Stats(stats) => {
+                assert_eq!(stats.forced_gc_runs, 10);
+                assert_eq!(stats.table_fulls, 0);
+            }
+            _ => panic!("expected NDTA_STATS, got {:?}", nla),
+        }
+
+        let truncated = stats_nla(72);
+        assert!(Nla::parse(&NlaBuffer::new_checked(&truncated[..]).unwrap()).is_err());
     }
 }
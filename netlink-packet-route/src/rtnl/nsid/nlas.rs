@@ -15,6 +15,11 @@ pub enum Nla {
     Id(i32),
     Pid(u32),
     Fd(u32),
+    /// Id of the namespace in which the request should be handled. The ids of the response
+    /// are then relative to this namespace.
+    TargetNsid(i32),
+    /// Id of the namespace of the requester, as seen from the target namespace
+    CurrentNsid(i32),
     Other(DefaultNla),
 }
 
@@ -23,7 +28,7 @@ impl nlas::Nla for Nla {
         use self::Nla::*;
         match *self {
             Unspec(ref bytes) => bytes.len(),
-            Id(_) | Pid(_) | Fd(_) => 4,
+            Id(_) | Pid(_) | Fd(_) | TargetNsid(_) | CurrentNsid(_) => 4,
             Other(ref attr) => attr.value_len(),
         }
     }
@@ -33,7 +38,9 @@ impl nlas::Nla for Nla {
         match *self {
             Unspec(ref bytes) => buffer.copy_from_slice(bytes.as_slice()),
             Fd(ref value) | Pid(ref value) => NativeEndian::write_u32(buffer, *value),
-            Id(ref value) => NativeEndian::write_i32(buffer, *value),
+            Id(ref value) | TargetNsid(ref value) | CurrentNsid(ref value) => {
+                NativeEndian::write_i32(buffer, *value)
+            }
             Other(ref attr) => attr.emit_value(buffer),
         }
     }
@@ -45,6 +52,8 @@ impl nlas::Nla for Nla {
             Id(_) => NETNSA_NSID,
             Pid(_) => NETNSA_PID,
             Fd(_) => NETNSA_FD,
+            TargetNsid(_) => NETNSA_TARGET_NSID,
+            CurrentNsid(_) => NETNSA_CURRENT_NSID,
             Other(ref attr) => attr.kind(),
         }
     }
@@ -59,6 +68,12 @@ impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>> for Nla {
             NETNSA_NSID => Id(parse_i32(payload).context("invalid NETNSA_NSID")?),
             NETNSA_PID => Pid(parse_u32(payload).context("invalid NETNSA_PID")?),
             NETNSA_FD => Fd(parse_u32(payload).context("invalid NETNSA_FD")?),
+            NETNSA_TARGET_NSID => {
+                TargetNsid(parse_i32(payload).context("invalid NETNSA_TARGET_NSID")?)
+            }
+            NETNSA_CURRENT_NSID => {
+                CurrentNsid(parse_i32(payload).context("invalid NETNSA_CURRENT_NSID")?)
+            }
             kind => Other(DefaultNla::parse(buf).context(format!("unknown NLA type {}", kind))?),
         })
     }
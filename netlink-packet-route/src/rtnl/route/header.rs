@@ -7,8 +7,24 @@ use crate::{
 };
 
 bitflags! {
-    /// Flags that can be set in a `RTM_GETROUTE` ([`RtnlMessage::GetRoute`]) message.
+    /// Flags of a route, as found in [`RouteHeader::flags`]. The `RTM_F_*` flags can be set in a
+    /// `RTM_GETROUTE` ([`RtnlMessage::GetRoute`]) message, while the `RTNH_F_*` flags describe the
+    /// next hop of single path routes. Bits that do not correspond to a known flag are preserved.
     pub struct RouteFlags: u32 {
+        /// The next hop is dead
+        const RTNH_F_DEAD = RTNH_F_DEAD as u32;
+        /// Do recursive gateway lookup
+        const RTNH_F_PERVASIVE = RTNH_F_PERVASIVE as u32;
+        /// The gateway is directly reachable on the interface
+        const RTNH_F_ONLINK = RTNH_F_ONLINK as u32;
+        /// The next hop is offloaded to the hardware
+        const RTNH_F_OFFLOAD = RTNH_F_OFFLOAD as u32;
+        /// The carrier of the next hop interface is down
+        const RTNH_F_LINKDOWN = RTNH_F_LINKDOWN as u32;
+        /// The next hop is not resolved yet
+        const RTNH_F_UNRESOLVED = RTNH_F_UNRESOLVED as u32;
+        /// The next hop traps packets to the CPU
+        const RTNH_F_TRAP = RTNH_F_TRAP as u32;
         /// If the route changes, notify the user via rtnetlink
         const RTM_F_NOTIFY = RTM_F_NOTIFY;
         /// This route is cloned. Cloned routes are routes coming from the cache instead of the
@@ -29,6 +45,12 @@ bitflags! {
         const RTM_F_LOOKUP_TABLE = RTM_F_LOOKUP_TABLE;
         /// Return the full FIB lookup match (see commit `b61798130f1be5bff08712308126c2d7ebe390ef`)
         const RTM_F_FIB_MATCH = RTM_F_FIB_MATCH;
+        /// The route is offloaded to the hardware
+        const RTM_F_OFFLOAD = RTM_F_OFFLOAD;
+        /// The route traps packets to the CPU
+        const RTM_F_TRAP = RTM_F_TRAP;
+        /// The route could not be offloaded to the hardware
+        const RTM_F_OFFLOAD_FAILED = RTM_F_OFFLOAD_FAILED;
     }
 }
 
@@ -38,6 +60,306 @@ impl Default for RouteFlags {
     }
 }
 
+impl From<u32> for RouteFlags {
+    fn from(bits: u32) -> Self {
+        // Unknown bits are valid for this type: they are kept as is.
+        Self { bits }
+    }
+}
+
+impl From<RouteFlags> for u32 {
+    fn from(flags: RouteFlags) -> Self {
+        flags.bits()
+    }
+}
+
+/// Scope of a route (`RT_SCOPE_*`), as found in [`RouteHeader::scope`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum RouteScope {
+    /// The destination is globally valid
+    Universe,
+    /// (IPv6 only) The destination is site local
+    Site,
+    /// The destination is link local
+    Link,
+    /// The destination is valid only on this host
+    Host,
+    /// The destination doesn't exist
+    Nowhere,
+    Other(u8),
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for RouteScope {
+    fn default() -> Self {
+        RouteScope::Universe
+    }
+}
+
+impl From<u8> for RouteScope {
+    fn from(value: u8) -> Self {
+        use self::RouteScope::*;
+        match value {
+            RT_SCOPE_UNIVERSE => Universe,
+            RT_SCOPE_SITE => Site,
+            RT_SCOPE_LINK => Link,
+            RT_SCOPE_HOST => Host,
+            RT_SCOPE_NOWHERE => Nowhere,
+            _ => Other(value),
+        }
+    }
+}
+
+impl From<RouteScope> for u8 {
+    fn from(value: RouteScope) -> Self {
+        use self::RouteScope::*;
+        match value {
+            Universe => RT_SCOPE_UNIVERSE,
+            Site => RT_SCOPE_SITE,
+            Link => RT_SCOPE_LINK,
+            Host => RT_SCOPE_HOST,
+            Nowhere => RT_SCOPE_NOWHERE,
+            Other(other) => other,
+        }
+    }
+}
+
+/// Origin of a route (`RTPROT_*`), as found in [`RouteHeader::protocol`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum RouteProtocol {
+    Unspec,
+    /// Route was learnt by an ICMP redirect
+    Redirect,
+    /// Route was learnt by the kernel
+    Kernel,
+    /// Route was learnt during boot
+    Boot,
+    /// Route was set statically
+    Static,
+    Gated,
+    /// Route was learnt from a router advertisement
+    Ra,
+    Mrt,
+    Zebra,
+    Bird,
+    DnRouted,
+    Xorp,
+    Ntk,
+    Dhcp,
+    Mrouted,
+    Keepalived,
+    Babel,
+    OpenR,
+    Bgp,
+    Isis,
+    Ospf,
+    Rip,
+    Eigrp,
+    Other(u8),
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for RouteProtocol {
+    fn default() -> Self {
+        RouteProtocol::Unspec
+    }
+}
+
+impl From<u8> for RouteProtocol {
+    fn from(value: u8) -> Self {
+        use self::RouteProtocol::*;
+        match value {
+            RTPROT_UNSPEC => Unspec,
+            RTPROT_REDIRECT => Redirect,
+            RTPROT_KERNEL => Kernel,
+            RTPROT_BOOT => Boot,
+            RTPROT_STATIC => Static,
+            RTPROT_GATED => Gated,
+            RTPROT_RA => Ra,
+            RTPROT_MRT => Mrt,
+            RTPROT_ZEBRA => Zebra,
+            RTPROT_BIRD => Bird,
+            RTPROT_DNROUTED => DnRouted,
+            RTPROT_XORP => Xorp,
+            RTPROT_NTK => Ntk,
+            RTPROT_DHCP => Dhcp,
+            RTPROT_MROUTED => Mrouted,
+            RTPROT_KEEPALIVED => Keepalived,
+            RTPROT_BABEL => Babel,
+            RTPROT_OPENR => OpenR,
+            RTPROT_BGP => Bgp,
+            RTPROT_ISIS => Isis,
+            RTPROT_OSPF => Ospf,
+            RTPROT_RIP => Rip,
+            RTPROT_EIGRP => Eigrp,
+            _ => Other(value),
+        }
+    }
+}
+
+impl From<RouteProtocol> for u8 {
+    fn from(value: RouteProtocol) -> Self {
+        use self::RouteProtocol::*;
+        match value {
+            Unspec => RTPROT_UNSPEC,
+            Redirect => RTPROT_REDIRECT,
+            Kernel => RTPROT_KERNEL,
+            Boot => RTPROT_BOOT,
+            Static => RTPROT_STATIC,
+            Gated => RTPROT_GATED,
+            Ra => RTPROT_RA,
+            Mrt => RTPROT_MRT,
+            Zebra => RTPROT_ZEBRA,
+            Bird => RTPROT_BIRD,
+            DnRouted => RTPROT_DNROUTED,
+            Xorp => RTPROT_XORP,
+            Ntk => RTPROT_NTK,
+            Dhcp => RTPROT_DHCP,
+            Mrouted => RTPROT_MROUTED,
+            Keepalived => RTPROT_KEEPALIVED,
+            Babel => RTPROT_BABEL,
+            OpenR => RTPROT_OPENR,
+            Bgp => RTPROT_BGP,
+            Isis => RTPROT_ISIS,
+            Ospf => RTPROT_OSPF,
+            Rip => RTPROT_RIP,
+            Eigrp => RTPROT_EIGRP,
+            Other(other) => other,
+        }
+    }
+}
+
+/// Type of a route (`RTN_*`), as found in [`RouteHeader::kind`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum RouteType {
+    /// Unknown route
+    Unspec,
+    /// A gateway or direct route
+    Unicast,
+    /// A local interface route
+    Local,
+    /// A local broadcast route (sent as a broadcast)
+    Broadcast,
+    /// A local broadcast route (sent as a unicast)
+    Anycast,
+    /// A multicast route
+    Multicast,
+    /// A packet dropping route
+    Blackhole,
+    /// An unreachable destination
+    Unreachable,
+    /// A packet rejection route
+    Prohibit,
+    /// Continue routing lookup in another table
+    Throw,
+    /// A network address translation rule
+    Nat,
+    /// Refer to an external resolver (not implemented)
+    ExternalResolve,
+    Other(u8),
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for RouteType {
+    fn default() -> Self {
+        RouteType::Unspec
+    }
+}
+
+impl From<u8> for RouteType {
+    fn from(value: u8) -> Self {
+        use self::RouteType::*;
+        match value {
+            RTN_UNSPEC => Unspec,
+            RTN_UNICAST => Unicast,
+            RTN_LOCAL => Local,
+            RTN_BROADCAST => Broadcast,
+            RTN_ANYCAST => Anycast,
+            RTN_MULTICAST => Multicast,
+            RTN_BLACKHOLE => Blackhole,
+            RTN_UNREACHABLE => Unreachable,
+            RTN_PROHIBIT => Prohibit,
+            RTN_THROW => Throw,
+            RTN_NAT => Nat,
+            RTN_XRESOLVE => ExternalResolve,
+            _ => Other(value),
+        }
+    }
+}
+
+impl From<RouteType> for u8 {
+    fn from(value: RouteType) -> Self {
+        use self::RouteType::*;
+        match value {
+            Unspec => RTN_UNSPEC,
+            Unicast => RTN_UNICAST,
+            Local => RTN_LOCAL,
+            Broadcast => RTN_BROADCAST,
+            Anycast => RTN_ANYCAST,
+            Multicast => RTN_MULTICAST,
+            Blackhole => RTN_BLACKHOLE,
+            Unreachable => RTN_UNREACHABLE,
+            Prohibit => RTN_PROHIBIT,
+            Throw => RTN_THROW,
+            Nat => RTN_NAT,
+            ExternalResolve => RTN_XRESOLVE,
+            Other(other) => other,
+        }
+    }
+}
+
+/// Routing table (`RT_TABLE_*`), as found in [`RouteHeader::table`]. Custom tables with an id lower
+/// than 256 are represented by `Other`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum RouteTable {
+    /// An unspecified routing table
+    Unspec,
+    /// The table id is greater than 255 and is carried by the `Table` attribute
+    Compat,
+    /// The default table
+    Default,
+    /// The main table
+    Main,
+    /// The local table, with local and broadcast addresses routes
+    Local,
+    Other(u8),
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for RouteTable {
+    fn default() -> Self {
+        RouteTable::Unspec
+    }
+}
+
+impl From<u8> for RouteTable {
+    fn from(value: u8) -> Self {
+        use self::RouteTable::*;
+        match value {
+            RT_TABLE_UNSPEC => Unspec,
+            RT_TABLE_COMPAT => Compat,
+            RT_TABLE_DEFAULT => Default,
+            RT_TABLE_MAIN => Main,
+            RT_TABLE_LOCAL => Local,
+            _ => Other(value),
+        }
+    }
+}
+
+impl From<RouteTable> for u8 {
+    fn from(value: RouteTable) -> Self {
+        use self::RouteTable::*;
+        match value {
+            Unspec => RT_TABLE_UNSPEC,
+            Compat => RT_TABLE_COMPAT,
+            Default => RT_TABLE_DEFAULT,
+            Main => RT_TABLE_MAIN,
+            Local => RT_TABLE_LOCAL,
+            Other(other) => other,
+        }
+    }
+}
+
 /// High level representation of `RTM_GETROUTE`, `RTM_ADDROUTE`, `RTM_DELROUTE`
 /// messages headers.
 ///
@@ -58,7 +380,7 @@ impl Default for RouteFlags {
 ///
 /// ```rust
 /// extern crate netlink_packet_route;
-/// use netlink_packet_route::{constants::*, RouteFlags, RouteHeader};
+/// use netlink_packet_route::{RouteHeader, RouteProtocol, RouteScope, RouteTable, RouteType};
 ///
 /// fn main() {
 ///     let mut hdr = RouteHeader::default();
@@ -66,17 +388,17 @@ impl Default for RouteFlags {
 ///     assert_eq!(hdr.destination_prefix_length, 0u8);
 ///     assert_eq!(hdr.source_prefix_length, 0u8);
 ///     assert_eq!(hdr.tos, 0u8);
-///     assert_eq!(hdr.table, RT_TABLE_UNSPEC);
-///     assert_eq!(hdr.protocol, RTPROT_UNSPEC);
-///     assert_eq!(hdr.scope, RT_SCOPE_UNIVERSE);
-///     assert_eq!(hdr.kind, RTN_UNSPEC);
+///     assert_eq!(hdr.table, RouteTable::Unspec);
+///     assert_eq!(hdr.protocol, RouteProtocol::Unspec);
+///     assert_eq!(hdr.scope, RouteScope::Universe);
+///     assert_eq!(hdr.kind, RouteType::Unspec);
 ///     assert_eq!(hdr.flags.bits(), 0u32);
 ///
 ///     // set some values
 ///     hdr.destination_prefix_length = 8;
-///     hdr.table = RT_TABLE_MAIN;
-///     hdr.protocol = RTPROT_KERNEL;
-///     hdr.scope = RT_SCOPE_NOWHERE;
+///     hdr.table = RouteTable::Main;
+///     hdr.protocol = RouteProtocol::Kernel;
+///     hdr.scope = RouteScope::Nowhere;
 ///
 ///     // ...
 /// }
@@ -112,20 +434,19 @@ pub struct RouteHeader {
     pub source_prefix_length: u8,
     /// TOS filter
     pub tos: u8,
-    /// Routing table ID. It can be one of the `RT_TABLE_*` constants or a custom table number
+    /// Routing table ID. It can be one of the well known tables or a custom table number
     /// between 1 and 251 (included). Note that Linux supports routing table with an ID greater than
-    /// 255, in which case this attribute will be set to [`RT_TABLE_COMPAT`] and an [`Nla::Table`]
+    /// 255, in which case this attribute will be set to [`RouteTable::Compat`] and an [`Nla::Table`]
     /// netlink attribute will be present in the message.
-    pub table: u8,
-    /// Protocol from which the route was learnt. It should be set to one of the `RTPROT_*`
-    /// constants.
-    pub protocol: u8,
-    /// The scope of the area where the addresses in the destination subnet are valid. Predefined
-    /// scope values are the `RT_SCOPE_*` constants.
-    pub scope: u8,
-    /// Route type. It should be set to one of the `RTN_*` constants.
-    pub kind: u8,
-    /// Flags when querying the kernel with a `RTM_GETROUTE` message. See [`RouteFlags`].
+    pub table: RouteTable,
+    /// Protocol from which the route was learnt.
+    pub protocol: RouteProtocol,
+    /// The scope of the area where the addresses in the destination subnet are valid.
+    pub scope: RouteScope,
+    /// Route type.
+    pub kind: RouteType,
+    /// Route flags, and flags when querying the kernel with a `RTM_GETROUTE` message. See
+    /// [`RouteFlags`].
     pub flags: RouteFlags,
 }
 
@@ -136,11 +457,11 @@ impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<RouteMessageBuffer<&'a T>> for Route
             destination_prefix_length: buf.destination_prefix_length(),
             source_prefix_length: buf.source_prefix_length(),
             tos: buf.tos(),
-            table: buf.table(),
-            protocol: buf.protocol(),
-            scope: buf.scope(),
-            kind: buf.kind(),
-            flags: RouteFlags::from_bits_truncate(buf.flags()),
+            table: RouteTable::from(buf.table()),
+            protocol: RouteProtocol::from(buf.protocol()),
+            scope: RouteScope::from(buf.scope()),
+            kind: RouteType::from(buf.kind()),
+            flags: RouteFlags::from(buf.flags()),
         })
     }
 }
@@ -156,10 +477,45 @@ impl Emitable for RouteHeader {
         buffer.set_destination_prefix_length(self.destination_prefix_length);
         buffer.set_source_prefix_length(self.source_prefix_length);
         buffer.set_tos(self.tos);
-        buffer.set_table(self.table);
-        buffer.set_protocol(self.protocol);
-        buffer.set_scope(self.scope);
-        buffer.set_kind(self.kind);
+        buffer.set_table(self.table.into());
+        buffer.set_protocol(self.protocol.into());
+        buffer.set_scope(self.scope.into());
+        buffer.set_kind(self.kind.into());
         buffer.set_flags(self.flags.bits());
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[rustfmt::skip]
+    static HEADER: [u8; 12] = [
+        0x02, // address family = AF_INET
+        0x18, // destination prefix length = 24
+        0x00, // source prefix length = 0
+        0x00, // tos = 0
+        0x64, // table = 100
+        0xfa, // protocol = 250 (unknown)
+        0xfd, // scope = RT_SCOPE_LINK
+        0x01, // type = RTN_UNICAST
+        0x04, 0x02, 0x00, 0x80, // flags = RTNH_F_ONLINK | RTM_F_CLONED | 0x8000_0000 (unknown)
+    ];
+
+    #[test]
+    fn parse_and_emit_unknown_values() {
+        let header = RouteHeader::parse(&RouteMessageBuffer::new(&HEADER[..])).unwrap();
+        assert_eq!(header.table, RouteTable::Other(100));
+        assert_eq!(header.protocol, RouteProtocol::Other(250));
+        assert_eq!(header.scope, RouteScope::Link);
+        assert_eq!(header.kind, RouteType::Unicast);
+        assert!(header
+            .flags
+            .contains(RouteFlags::RTNH_F_ONLINK | RouteFlags::RTM_F_CLONED));
+        assert_eq!(u32::from(header.flags), 0x8000_0204);
+
+        let mut buf = [0xff; 12];
+        header.emit(&mut buf);
+        assert_eq!(buf, HEADER);
+    }
+}
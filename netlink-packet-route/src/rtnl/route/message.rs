@@ -1,7 +1,9 @@
 use crate::{
     nlas::route::Nla,
+    rtnl::ip::{check_family, check_prefix_len, ip_octets, parse_ip},
     traits::{Emitable, Parseable},
     DecodeError,
+    IpAddrError,
     RouteHeader,
     RouteMessageBuffer,
 };
@@ -46,20 +48,6 @@ impl<'a, T: AsRef<[u8]> + 'a> Parseable<RouteMessageBuffer<&'a T>> for Vec<Nla>
     }
 }
 
-fn octets_to_addr(octets: &[u8]) -> Result<IpAddr, DecodeError> {
-    if octets.len() == 4 {
-        let mut ary: [u8; 4] = Default::default();
-        ary.copy_from_slice(octets);
-        Ok(IpAddr::from(ary))
-    } else if octets.len() == 16 {
-        let mut ary: [u8; 16] = Default::default();
-        ary.copy_from_slice(octets);
-        Ok(IpAddr::from(ary))
-    } else {
-        Err(DecodeError::from("Cannot decode IP address"))
-    }
-}
-
 impl RouteMessage {
     /// Returns the input interface index, if present.
     pub fn input_interface(&self) -> Option<u32> {
@@ -83,12 +71,12 @@ impl RouteMessage {
         })
     }
 
-    /// Returns the source address prefix, if present.
+    /// Returns the source address prefix, if present and valid for the address family of the
+    /// header.
     pub fn source_prefix(&self) -> Option<(IpAddr, u8)> {
         self.nlas.iter().find_map(|nla| {
             if let Nla::Source(v) = nla {
-                octets_to_addr(v)
-                    .ok()
+                parse_ip(self.header.address_family, v)
                     .map(|addr| (addr, self.header.source_prefix_length))
             } else {
                 None
@@ -96,12 +84,27 @@ impl RouteMessage {
         })
     }
 
-    /// Returns the destination subnet prefix, if present.
+    /// Set the source address prefix. If the address family of the header is not set yet, it is
+    /// set to the family of `addr`.
+    pub fn set_source_prefix(
+        &mut self,
+        addr: IpAddr,
+        prefix_length: u8,
+    ) -> Result<(), IpAddrError> {
+        check_prefix_len(addr, prefix_length)?;
+        check_family(&mut self.header.address_family, addr)?;
+        self.header.source_prefix_length = prefix_length;
+        self.nlas.retain(|nla| !matches!(nla, Nla::Source(_)));
+        self.nlas.push(Nla::Source(ip_octets(addr)));
+        Ok(())
+    }
+
+    /// Returns the destination subnet prefix, if present and valid for the address family of the
+    /// header.
     pub fn destination_prefix(&self) -> Option<(IpAddr, u8)> {
         self.nlas.iter().find_map(|nla| {
             if let Nla::Destination(v) = nla {
-                octets_to_addr(v)
-                    .ok()
+                parse_ip(self.header.address_family, v)
                     .map(|addr| (addr, self.header.destination_prefix_length))
             } else {
                 None
@@ -109,14 +112,98 @@ impl RouteMessage {
         })
     }
 
-    /// Returns the gateway address, if present.
+    /// Set the destination subnet prefix. If the address family of the header is not set yet, it
+    /// is set to the family of `addr`.
+    pub fn set_destination_prefix(
+        &mut self,
+        addr: IpAddr,
+        prefix_length: u8,
+    ) -> Result<(), IpAddrError> {
+        check_prefix_len(addr, prefix_length)?;
+        check_family(&mut self.header.address_family, addr)?;
+        self.header.destination_prefix_length = prefix_length;
+        self.nlas.retain(|nla| !matches!(nla, Nla::Destination(_)));
+        self.nlas.push(Nla::Destination(ip_octets(addr)));
+        Ok(())
+    }
+
+    /// Returns the gateway address, if present and valid for the address family of the header.
     pub fn gateway(&self) -> Option<IpAddr> {
         self.nlas.iter().find_map(|nla| {
             if let Nla::Gateway(v) = nla {
-                octets_to_addr(v).ok()
+                parse_ip(self.header.address_family, v)
             } else {
                 None
             }
         })
     }
+
+    /// Set the gateway address. If the address family of the header is not set yet, it is set to
+    /// the family of `addr`.
+    pub fn set_gateway(&mut self, addr: IpAddr) -> Result<(), IpAddrError> {
+        check_family(&mut self.header.address_family, addr)?;
+        self.nlas.retain(|nla| !matches!(nla, Nla::Gateway(_)));
+        self.nlas.push(Nla::Gateway(ip_octets(addr)));
+        Ok(())
+    }
+
+    /// Returns the preferred source address, if present and valid for the address family of the
+    /// header.
+    pub fn preferred_source(&self) -> Option<IpAddr> {
+        self.nlas.iter().find_map(|nla| {
+            if let Nla::PrefSource(v) = nla {
+                parse_ip(self.header.address_family, v)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Set the preferred source address. If the address family of the header is not set yet, it
+    /// is set to the family of `addr`.
+    pub fn set_preferred_source(&mut self, addr: IpAddr) -> Result<(), IpAddrError> {
+        check_family(&mut self.header.address_family, addr)?;
+        self.nlas.retain(|nla| !matches!(nla, Nla::PrefSource(_)));
+        self.nlas.push(Nla::PrefSource(ip_octets(addr)));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::IpAddr;
+
+    use crate::{constants::*, IpAddrError, RouteMessage};
+
+    #[test]
+    fn set_and_get_addresses() {
+        let mut message = RouteMessage::default();
+        let destination: IpAddr = "10.0.0.0".parse().unwrap();
+        let gateway: IpAddr = "192.168.0.1".parse().unwrap();
+
+        message.set_destination_prefix(destination, 8).unwrap();
+        message.set_gateway(gateway).unwrap();
+        assert_eq!(message.header.address_family, AF_INET as u8);
+        assert_eq!(message.destination_prefix(), Some((destination, 8)));
+        assert_eq!(message.gateway(), Some(gateway));
+        assert_eq!(message.source_prefix(), None);
+        assert_eq!(message.preferred_source(), None);
+
+        let source: IpAddr = "2001:db8::1".parse().unwrap();
+        assert_eq!(
+            message.set_preferred_source(source),
+            Err(IpAddrError::FamilyMismatch {
+                family: AF_INET as u8,
+                address: source,
+            })
+        );
+        assert_eq!(
+            message.set_source_prefix(destination, 33),
+            Err(IpAddrError::PrefixLength {
+                address: destination,
+                prefix_len: 33,
+            })
+        );
+        assert_eq!(message.header.source_prefix_length, 0);
+    }
 }
@@ -6,6 +6,8 @@ use crate::{
 };
 
 bitflags! {
+    /// Flags of a rule, as found in [`RuleHeader::flags`]. Bits that do not correspond to a known
+    /// flag are preserved.
     pub struct RuleFlags: u32 {
         const FIB_RULE_PERMANENT = FIB_RULE_PERMANENT;
         const FIB_RULE_INVERT = FIB_RULE_INVERT;
@@ -23,6 +25,19 @@ impl Default for RuleFlags {
     }
 }
 
+impl From<u32> for RuleFlags {
+    fn from(bits: u32) -> Self {
+        // Unknown bits are valid for this type: they are kept as is.
+        Self { bits }
+    }
+}
+
+impl From<RuleFlags> for u32 {
+    fn from(flags: RuleFlags) -> Self {
+        flags.bits()
+    }
+}
+
 // see https://github.com/torvalds/linux/blob/master/include/uapi/linux/fib_rules.h
 // see https://github.com/torvalds/linux/blob/master/include/net/fib_rules.h
 #[derive(Debug, PartialEq, Eq, Clone, Default)]
@@ -37,7 +52,7 @@ pub struct RuleHeader {
     /// FR_ACT_*
     pub action: u8,
     /// fib rule flags
-    pub flags: u32,
+    pub flags: RuleFlags,
 }
 
 impl Emitable for RuleHeader {
@@ -50,7 +65,7 @@ impl Emitable for RuleHeader {
         packet.set_family(self.family);
         packet.set_dst_len(self.dst_len);
         packet.set_src_len(self.src_len);
-        packet.set_flags(self.flags);
+        packet.set_flags(self.flags.bits());
         packet.set_table(self.table);
         packet.set_tos(self.tos);
         packet.set_action(self.action);
@@ -66,7 +81,7 @@ impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<RuleMessageBuffer<&'a T>> for RuleHe
             tos: buf.tos(),
             table: buf.table(),
             action: buf.action(),
-            flags: buf.flags(),
+            flags: RuleFlags::from(buf.flags()),
         })
     }
 }
@@ -1,9 +1,12 @@
 use super::{buffer::RuleMessageBuffer, header::RuleHeader, nlas::Nla};
 use crate::{
+    rtnl::ip::{check_family, check_prefix_len, ip_octets, parse_ip},
     utils::{Emitable, Parseable},
     DecodeError,
+    IpAddrError,
 };
 use anyhow::Context;
+use std::net::IpAddr;
 
 #[derive(Debug, PartialEq, Eq, Clone, Default)]
 pub struct RuleMessage {
@@ -11,6 +14,62 @@ pub struct RuleMessage {
     pub nlas: Vec<Nla>,
 }
 
+impl RuleMessage {
+    /// Returns the source address prefix, if present and valid for the address family of the
+    /// header.
+    pub fn source_prefix(&self) -> Option<(IpAddr, u8)> {
+        self.nlas.iter().find_map(|nla| {
+            if let Nla::Source(v) = nla {
+                parse_ip(self.header.family, v).map(|addr| (addr, self.header.src_len))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Set the source address prefix. If the address family of the header is not set yet, it is
+    /// set to the family of `addr`.
+    pub fn set_source_prefix(
+        &mut self,
+        addr: IpAddr,
+        prefix_length: u8,
+    ) -> Result<(), IpAddrError> {
+        check_prefix_len(addr, prefix_length)?;
+        check_family(&mut self.header.family, addr)?;
+        self.header.src_len = prefix_length;
+        self.nlas.retain(|nla| !matches!(nla, Nla::Source(_)));
+        self.nlas.push(Nla::Source(ip_octets(addr)));
+        Ok(())
+    }
+
+    /// Returns the destination address prefix, if present and valid for the address family of
+    /// the header.
+    pub fn destination_prefix(&self) -> Option<(IpAddr, u8)> {
+        self.nlas.iter().find_map(|nla| {
+            if let Nla::Destination(v) = nla {
+                parse_ip(self.header.family, v).map(|addr| (addr, self.header.dst_len))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Set the destination address prefix. If the address family of the header is not set yet,
+    /// it is set to the family of `addr`.
+    pub fn set_destination_prefix(
+        &mut self,
+        addr: IpAddr,
+        prefix_length: u8,
+    ) -> Result<(), IpAddrError> {
+        check_prefix_len(addr, prefix_length)?;
+        check_family(&mut self.header.family, addr)?;
+        self.header.dst_len = prefix_length;
+        self.nlas.retain(|nla| !matches!(nla, Nla::Destination(_)));
+        self.nlas.push(Nla::Destination(ip_octets(addr)));
+        Ok(())
+    }
+}
+
 impl Emitable for RuleMessage {
     fn buffer_len(&self) -> usize {
         self.header.buffer_len() + self.nlas.as_slice().buffer_len()
@@ -41,3 +100,59 @@ impl<'a, T: AsRef<[u8]> + 'a> Parseable<RuleMessageBuffer<&'a T>> for Vec<Nla> {
         Ok(nlas)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::net::IpAddr;
+
+    use crate::{
+        constants::*,
+        traits::{Emitable, Parseable},
+        IpAddrError,
+        RuleFlags,
+        RuleHeader,
+        RuleMessage,
+        RuleMessageBuffer,
+    };
+
+    #[test]
+    fn set_and_get_prefixes() {
+        let mut message = RuleMessage::default();
+        let source: IpAddr = "2001:db8::".parse().unwrap();
+
+        message.set_source_prefix(source, 32).unwrap();
+        assert_eq!(message.header.family, AF_INET6 as u8);
+        assert_eq!(message.source_prefix(), Some((source, 32)));
+        assert_eq!(message.destination_prefix(), None);
+
+        let destination: IpAddr = "10.0.0.0".parse().unwrap();
+        assert_eq!(
+            message.set_destination_prefix(destination, 8),
+            Err(IpAddrError::FamilyMismatch {
+                family: AF_INET6 as u8,
+                address: destination,
+            })
+        );
+        assert_eq!(
+            message.set_source_prefix(source, 129),
+            Err(IpAddrError::PrefixLength {
+                address: source,
+                prefix_len: 129,
+            })
+        );
+        assert_eq!(message.header.src_len, 32);
+    }
+
+    #[test]
+    fn unknown_flags_round_trip() {
+        let header = RuleHeader {
+            flags: RuleFlags::from(0x8000_0000 | FIB_RULE_INVERT),
+            ..Default::default()
+        };
+        let mut buf = vec![0; header.buffer_len()];
+        header.emit(&mut buf[..]);
+        let parsed = RuleHeader::parse(&RuleMessageBuffer::new(&buf[..])).unwrap();
+        assert_eq!(u32::from(parsed.flags), 0x8000_0000 | FIB_RULE_INVERT);
+        assert_eq!(parsed, header);
+    }
+}
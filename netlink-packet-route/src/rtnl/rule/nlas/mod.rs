@@ -1,10 +1,17 @@
+mod uid_range;
+pub use self::uid_range::*;
+
+mod port_range;
+pub use self::port_range::*;
+
 use crate::{
     nlas,
     nlas::DefaultNla,
     utils::{
-        byteorder::{ByteOrder, NativeEndian},
+        byteorder::{BigEndian, ByteOrder, NativeEndian},
         nla::NlaBuffer,
-        parsers::{parse_string, parse_u32, parse_u8},
+        parsers::{parse_string, parse_u32, parse_u64, parse_u8},
+        Emitable,
         Parseable,
     },
     DecodeError,
@@ -48,7 +55,8 @@ pub enum Nla {
     FwMask(u32),
     /// flow class id,
     Flow(u32),
-    TunId(u32),
+    /// tunnel id, in host byte order (it is sent in network byte order)
+    TunId(u64),
     SuppressIfGroup(u32),
     SuppressPrefixLen(u32),
     Table(u32),
@@ -57,13 +65,16 @@ pub enum Nla {
     Pad(Vec<u8>),
     /// iif or oif is l3mdev goto its table
     L3MDev(u8),
-    UidRange(Vec<u8>),
+    /// range of user ids the rule applies to
+    UidRange(UidRange),
     /// RTPROT_*
     Protocol(u8),
     /// AF_*
     IpProto(u8),
-    SourcePortRange(Vec<u8>),
-    DestinationPortRange(Vec<u8>),
+    /// range of source ports the rule applies to
+    SourcePortRange(PortRange),
+    /// range of destination ports the rule applies to
+    DestinationPortRange(PortRange),
     Other(DefaultNla),
 }
 
@@ -71,16 +82,15 @@ impl nlas::Nla for Nla {
     fn value_len(&self) -> usize {
         use self::Nla::*;
         match self {
-            Unspec(ref bytes)
-            | Destination(ref bytes)
-            | Source(ref bytes)
-            | Pad(ref bytes)
-            | UidRange(ref bytes)
-            | SourcePortRange(ref bytes)
-            | DestinationPortRange(ref bytes) => bytes.len(),
+            Unspec(ref bytes) | Destination(ref bytes) | Source(ref bytes) | Pad(ref bytes) => {
+                bytes.len()
+            }
             Iifname(ref s) | OifName(ref s) => s.as_bytes().len() + 1,
-            Priority(_) | FwMark(_) | FwMask(_) | Flow(_) | TunId(_) | Goto(_)
-            | SuppressIfGroup(_) | SuppressPrefixLen(_) | Table(_) => 4,
+            UidRange(ref range) => range.buffer_len(),
+            SourcePortRange(ref range) | DestinationPortRange(ref range) => range.buffer_len(),
+            Priority(_) | FwMark(_) | FwMask(_) | Flow(_) | Goto(_) | SuppressIfGroup(_)
+            | SuppressPrefixLen(_) | Table(_) => 4,
+            TunId(_) => 8,
             L3MDev(_) | Protocol(_) | IpProto(_) => 1,
             Other(attr) => attr.value_len(),
         }
@@ -117,24 +127,25 @@ impl nlas::Nla for Nla {
     fn emit_value(&self, buffer: &mut [u8]) {
         use self::Nla::*;
         match self {
-            Unspec(ref bytes)
-            | Destination(ref bytes)
-            | Source(ref bytes)
-            | Pad(ref bytes)
-            | UidRange(ref bytes)
-            | SourcePortRange(ref bytes)
-            | DestinationPortRange(ref bytes) => buffer.copy_from_slice(bytes.as_slice()),
-            Iifname(ref s) | OifName(ref s) => buffer.copy_from_slice(s.as_bytes()),
+            Unspec(ref bytes) | Destination(ref bytes) | Source(ref bytes) | Pad(ref bytes) => {
+                buffer.copy_from_slice(bytes.as_slice())
+            }
+            Iifname(ref s) | OifName(ref s) => {
+                buffer[..s.len()].copy_from_slice(s.as_bytes());
+                buffer[s.len()] = 0;
+            }
+            UidRange(ref range) => range.emit(buffer),
+            SourcePortRange(ref range) | DestinationPortRange(ref range) => range.emit(buffer),
 
             Priority(value)
             | FwMark(value)
             | FwMask(value)
             | Flow(value)
-            | TunId(value)
             | Goto(value)
             | SuppressIfGroup(value)
             | SuppressPrefixLen(value)
             | Table(value) => NativeEndian::write_u32(buffer, *value),
+            TunId(value) => BigEndian::write_u64(buffer, *value),
             L3MDev(value) | Protocol(value) | IpProto(value) => buffer[0] = *value,
             Other(attr) => attr.emit_value(buffer),
         }
@@ -156,7 +167,9 @@ impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>> for Nla {
             FRA_PRIORITY => Priority(parse_u32(payload).context("invalid FRA_PRIORITY value")?),
             FRA_FWMARK => FwMark(parse_u32(payload).context("invalid FRA_FWMARK value")?),
             FRA_FLOW => Flow(parse_u32(payload).context("invalid FRA_FLOW value")?),
-            FRA_TUN_ID => TunId(parse_u32(payload).context("invalid FRA_TUN_ID value")?),
+            FRA_TUN_ID => TunId(u64::from_be(
+                parse_u64(payload).context("invalid FRA_TUN_ID value")?,
+            )),
             FRA_SUPPRESS_IFGROUP => {
                 SuppressIfGroup(parse_u32(payload).context("invalid FRA_SUPPRESS_IFGROUP value")?)
             }
@@ -168,12 +181,120 @@ impl<'a, T: AsRef<[u8]> + ?Sized> Parseable<NlaBuffer<&'a T>> for Nla {
             FRA_OIFNAME => OifName(parse_string(payload).context("invalid FRA_OIFNAME value")?),
             FRA_PAD => Pad(payload.to_vec()),
             FRA_L3MDEV => L3MDev(parse_u8(payload).context("invalid FRA_L3MDEV value")?),
-            FRA_UID_RANGE => UidRange(payload.to_vec()),
+            FRA_UID_RANGE => UidRange(
+                uid_range::UidRange::parse(
+                    &UidRangeBuffer::new_checked(payload).context("invalid FRA_UID_RANGE value")?,
+                )
+                .context("invalid FRA_UID_RANGE value")?,
+            ),
             FRA_PROTOCOL => Protocol(parse_u8(payload).context("invalid FRA_PROTOCOL value")?),
             FRA_IP_PROTO => IpProto(parse_u8(payload).context("invalid FRA_IP_PROTO value")?),
-            FRA_SPORT_RANGE => SourcePortRange(payload.to_vec()),
-            FRA_DPORT_RANGE => DestinationPortRange(payload.to_vec()),
+            FRA_SPORT_RANGE => SourcePortRange(
+                PortRange::parse(
+                    &PortRangeBuffer::new_checked(payload)
+                        .context("invalid FRA_SPORT_RANGE value")?,
+                )
+                .context("invalid FRA_SPORT_RANGE value")?,
+            ),
+            FRA_DPORT_RANGE => DestinationPortRange(
+                PortRange::parse(
+                    &PortRangeBuffer::new_checked(payload)
+                        .context("invalid FRA_DPORT_RANGE value")?,
+                )
+                .context("invalid FRA_DPORT_RANGE value")?,
+            ),
             _ => Other(DefaultNla::parse(buf).context("invalid NLA (unknown kind)")?),
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[rustfmt::skip]
+    static UID_RANGE: [u8; 12] = [
+        0x0c, 0x00, // length = 12
+        0x14, 0x00, // type = 20 = FRA_UID_RANGE
+        0xe8, 0x03, 0x00, 0x00, // start = 1000
+        0xd0, 0x07, 0x00, 0x00, // end = 2000
+    ];
+
+    #[rustfmt::skip]
+    static DPORT_RANGE: [u8; 8] = [
+        0x08, 0x00, // length = 8
+        0x18, 0x00, // type = 24 = FRA_DPORT_RANGE
+        0x50, 0x00, // start = 80
+        0xbb, 0x01, // end = 443
+    ];
+
+    #[rustfmt::skip]
+    static IIFNAME: [u8; 12] = [
+        0x09, 0x00, // length = 9
+        0x03, 0x00, // type = 3 = FRA_IIFNAME
+        0x65, 0x74, 0x68, 0x30, 0x00, // "eth0\0"
+        0x00, 0x00, 0x00, // padding
+    ];
+
+    #[test]
+    fn parse_uid_range() {
+        let nla = NlaBuffer::new_checked(&UID_RANGE[..]).unwrap();
+        let parsed = Nla::parse(&nla).unwrap();
+        assert_eq!(
+            parsed,
+            Nla::UidRange(UidRange {
+                start: 1000,
+                end: 2000
+            })
+        );
+    }
+
+    #[test]
+    fn emit_uid_range() {
+        let nla = Nla::UidRange(UidRange {
+            start: 1000,
+            end: 2000,
+        });
+        assert_eq!(nla.buffer_len(), 12);
+
+        let mut buf = [0xff; 12];
+        nla.emit(&mut buf);
+        assert_eq!(&buf[..], &UID_RANGE[..]);
+    }
+
+    #[test]
+    fn parse_port_range() {
+        let nla = NlaBuffer::new_checked(&DPORT_RANGE[..]).unwrap();
+        let parsed = Nla::parse(&nla).unwrap();
+        assert_eq!(
+            parsed,
+            Nla::DestinationPortRange(PortRange {
+                start: 80,
+                end: 443
+            })
+        );
+    }
+
+    #[test]
+    fn emit_port_range() {
+        let nla = Nla::DestinationPortRange(PortRange {
+            start: 80,
+            end: 443,
+        });
+        assert_eq!(nla.buffer_len(), 8);
+
+        let mut buf = [0xff; 8];
+        nla.emit(&mut buf);
+        assert_eq!(&buf[..], &DPORT_RANGE[..]);
+    }
+
+    #[test]
+    fn emit_iifname() {
+        let nla = Nla::Iifname("eth0".into());
+        assert_eq!(nla.buffer_len(), 12);
+
+        let mut buf = [0xff; 12];
+        nla.emit(&mut buf);
+        assert_eq!(&buf[..], &IIFNAME[..]);
+    }
+}
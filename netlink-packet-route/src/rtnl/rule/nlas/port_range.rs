@@ -0,0 +1,39 @@
+use crate::{
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+/// Range of layer 4 ports matched by a rule (`struct fib_rule_port_range`). Both bounds are
+/// inclusive and in host byte order.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub struct PortRange {
+    pub start: u16,
+    pub end: u16,
+}
+
+pub const RULE_PORT_RANGE_LEN: usize = 4;
+buffer!(PortRangeBuffer(RULE_PORT_RANGE_LEN) {
+    start: (u16, 0..2),
+    end: (u16, 2..4),
+});
+
+impl<T: AsRef<[u8]>> Parseable<PortRangeBuffer<T>> for PortRange {
+    fn parse(buf: &PortRangeBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(PortRange {
+            start: buf.start(),
+            end: buf.end(),
+        })
+    }
+}
+
+impl Emitable for PortRange {
+    fn buffer_len(&self) -> usize {
+        RULE_PORT_RANGE_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut buffer = PortRangeBuffer::new(buffer);
+        buffer.set_start(self.start);
+        buffer.set_end(self.end);
+    }
+}
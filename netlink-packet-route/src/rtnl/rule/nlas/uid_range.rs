@@ -0,0 +1,38 @@
+use crate::{
+    traits::{Emitable, Parseable},
+    DecodeError,
+};
+
+/// Range of user ids matched by a rule (`struct fib_rule_uid_range`). Both bounds are inclusive.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub struct UidRange {
+    pub start: u32,
+    pub end: u32,
+}
+
+pub const RULE_UID_RANGE_LEN: usize = 8;
+buffer!(UidRangeBuffer(RULE_UID_RANGE_LEN) {
+    start: (u32, 0..4),
+    end: (u32, 4..8),
+});
+
+impl<T: AsRef<[u8]>> Parseable<UidRangeBuffer<T>> for UidRange {
+    fn parse(buf: &UidRangeBuffer<T>) -> Result<Self, DecodeError> {
+        Ok(UidRange {
+            start: buf.start(),
+            end: buf.end(),
+        })
+    }
+}
+
+impl Emitable for UidRange {
+    fn buffer_len(&self) -> usize {
+        RULE_UID_RANGE_LEN
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        let mut buffer = UidRangeBuffer::new(buffer);
+        buffer.set_start(self.start);
+        buffer.set_end(self.end);
+    }
+}
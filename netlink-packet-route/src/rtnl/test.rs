@@ -89,8 +89,8 @@ fn test_attach_to_bridge() {
             interface_family: 0,
             index: 6,
             link_layer_type: 0,
-            flags: 0,
-            change_mask: 0,
+            flags: LinkFlags::empty(),
+            change_mask: LinkFlags::empty(),
         },
         nlas: vec![Nla::Master(5)],
     });
@@ -7,10 +7,10 @@
 // Compilation:
 // ------------
 //
-// cargo build --example audit_events --features="workaround-audit-bug"
+// cargo build --example audit_events
 //
 // Note that the audit protocol has a bug that we have to workaround,
-// hence the custom --features flag for that protocol
+// hence the decoder quirks set on the connection
 //
 // Usage:
 // ------
@@ -35,6 +35,7 @@ use std::process;
 use netlink_proto::{
     new_connection,
     sys::{protocols::NETLINK_AUDIT, SocketAddr},
+    DecoderQuirks,
 };
 
 const AUDIT_STATUS_ENABLED: u32 = 1;
@@ -55,8 +56,9 @@ async fn main() -> Result<(), String> {
     //   messages that we have not sollicated, ie that are not
     //   response to a request we made. In this example, we'll receive
     //   the audit event through that channel.
-    let (conn, mut handle, mut messages) = new_connection(NETLINK_AUDIT)
+    let (mut conn, mut handle, mut messages) = new_connection(NETLINK_AUDIT)
         .map_err(|e| format!("Failed to create a new netlink connection: {}", e))?;
+    conn.set_decoder_quirks(DecoderQuirks::audit());
 
     // Spawn the `Connection` so that it starts polling the netlink
     // socket in the background.
@@ -85,9 +87,17 @@ async fn main() -> Result<(), String> {
         };
 
         while let Some(message) = response.next().await {
-            if let NetlinkPayload::Error(err_message) = message.payload {
-                eprintln!("Received an error message: {:?}", err_message);
-                return;
+            match message {
+                Ok(message) => {
+                    if let NetlinkPayload::Error(err_message) = message.payload {
+                        eprintln!("Received an error message: {:?}", err_message);
+                        return;
+                    }
+                }
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return;
+                }
             }
         }
     });
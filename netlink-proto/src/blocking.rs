@@ -0,0 +1,584 @@
+//! A blocking netlink connection, for programs that do not run an async runtime.
+//!
+//! [`Connection`] owns a plain [`netlink_sys::Socket`], or any other [`BlockingSocket`], and
+//! reads from it only when a response is expected. Requests can be sent directly, the responses
+//! being returned as an iterator, or through [`ConnectionHandle`]s, like with the async
+//! connection, and driven with [`Connection::block_on`].
+//!
+//! # Example: dumping all the machine's links
+//!
+//! ```rust,no_run
+//! use netlink_packet_route::{
+//!     LinkMessage, NetlinkMessage, RtnlMessage, NLM_F_DUMP, NLM_F_REQUEST,
+//! };
+//! use netlink_proto::{
+//!     blocking::Connection,
+//!     sys::{protocols::NETLINK_ROUTE, SocketAddr},
+//! };
+//!
+//! let mut connection = Connection::<RtnlMessage>::new(NETLINK_ROUTE).unwrap();
+//!
+//! let mut request = NetlinkMessage::from(RtnlMessage::GetLink(LinkMessage::default()));
+//! request.header.flags = NLM_F_DUMP | NLM_F_REQUEST;
+//!
+//! for message in connection.request(request, SocketAddr::new(0, 0)).unwrap() {
+//!     println!("<<< {:?}", message.unwrap());
+//! }
+//! ```
+use std::{
+    collections::VecDeque,
+    fmt::Debug,
+    future::Future,
+    io,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    thread::{self, Thread},
+};
+
+use bytes::BytesMut;
+use futures::{
+    channel::mpsc::{unbounded, UnboundedReceiver},
+    pin_mut,
+    task::{noop_waker_ref, waker, ArcWake},
+    Stream,
+};
+use netlink_packet_core::{NetlinkDeserializable, NetlinkMessage, NetlinkSerializable};
+use netlink_sys::{MemorySocket, Socket};
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::{
+    channel::{channel, Receiver, Sender},
+    codecs::{DecoderQuirks, NetlinkCodec},
+    connection::{datagram_len, forward_response, DatagramLimits},
+    errors::{Error, ErrorKind},
+    handle::{Cancellations, Response},
+    sys::{AsyncNetlinkSocket, SocketAddr},
+    Batch,
+    ConnectionHandle,
+    Protocol,
+    Request,
+    Responder,
+};
+
+/// A netlink socket that the blocking [`Connection`] can use: a [`netlink_sys::Socket`], or a
+/// [`MemorySocket`](crate::sys::MemorySocket) to test code without a kernel.
+pub trait BlockingSocket {
+    /// Send the datagram `buf` to `addr`, and return the number of bytes sent.
+    fn send_to(&mut self, buf: &[u8], addr: &SocketAddr) -> io::Result<usize>;
+
+    /// Wait for a datagram, and return it with the address of its sender.
+    fn recv_from_full(&mut self) -> io::Result<(Vec<u8>, SocketAddr)>;
+
+    /// Size of the send buffer of the socket, which bounds the size of the datagrams.
+    fn get_send_buffer_size(&self) -> io::Result<usize>;
+
+    /// Size of the receive buffer of the socket, which bounds the size of the datagrams that can
+    /// be queued before they are read.
+    fn get_receive_buffer_size(&self) -> io::Result<usize>;
+}
+
+impl BlockingSocket for Socket {
+    fn send_to(&mut self, buf: &[u8], addr: &SocketAddr) -> io::Result<usize> {
+        Socket::send_to(self, buf, addr, 0)
+    }
+
+    fn recv_from_full(&mut self) -> io::Result<(Vec<u8>, SocketAddr)> {
+        Socket::recv_from_full(self)
+    }
+
+    fn get_send_buffer_size(&self) -> io::Result<usize> {
+        Socket::get_send_buffer_size(self)
+    }
+
+    fn get_receive_buffer_size(&self) -> io::Result<usize> {
+        Socket::get_receive_buffer_size(self)
+    }
+}
+
+impl BlockingSocket for MemorySocket {
+    fn send_to(&mut self, buf: &[u8], _addr: &SocketAddr) -> io::Result<usize> {
+        self.send(buf)
+    }
+
+    fn recv_from_full(&mut self) -> io::Result<(Vec<u8>, SocketAddr)> {
+        let waker = waker(Arc::new(ThreadWaker(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            if let Poll::Ready(result) = self.poll_recv(&mut cx) {
+                return result;
+            }
+            thread::park();
+        }
+    }
+
+    fn get_send_buffer_size(&self) -> io::Result<usize> {
+        AsyncNetlinkSocket::get_send_buffer_size(self)
+    }
+
+    fn get_receive_buffer_size(&self) -> io::Result<usize> {
+        AsyncNetlinkSocket::get_receive_buffer_size(self)
+    }
+}
+
+/// A netlink connection that blocks the current thread while waiting for responses.
+///
+/// The messages received that are not responses to a request (unsolicited messages) are queued,
+/// and can be retrieved with [`next_unsolicited_message`](#method.next_unsolicited_message).
+///
+/// Unlike with the async [`Connection`](../struct.Connection.html), requests never time out: the
+/// deadline of the requests sent with
+/// [`ConnectionHandle::request_with_timeout`](../struct.ConnectionHandle.html#method.request_with_timeout)
+/// is ignored.
+pub struct Connection<T, S = Socket>
+where
+    T: Debug + PartialEq + Eq + Clone + NetlinkSerializable<T> + NetlinkDeserializable<T>,
+{
+    socket: S,
+    codec: NetlinkCodec<NetlinkMessage<T>>,
+    writer: BytesMut,
+    protocol: Protocol<T, Responder<T>>,
+    /// Channel of the requests sent through the handles
+    requests_tx: Sender<Batch<T>>,
+    requests_rx: Receiver<Batch<T>>,
+    /// Wakes up the connection when a response stream of a handle is dropped
+    cancellations: Arc<Cancellations>,
+    unsolicited_messages: VecDeque<(NetlinkMessage<T>, SocketAddr)>,
+}
+
+impl<T> Connection<T>
+where
+    T: Debug + PartialEq + Eq + Clone + NetlinkSerializable<T> + NetlinkDeserializable<T>,
+{
+    /// Open a socket for the given netlink protocol. `protocol` must be one of the
+    /// [`crate::sys::protocols`][protos] constants.
+    ///
+    /// [protos]: crate::sys::protocols
+    pub fn new(protocol: isize) -> io::Result<Self> {
+        Ok(Self::with_socket(Socket::new(protocol)?))
+    }
+}
+
+impl<T, S> Connection<T, S>
+where
+    T: Debug + PartialEq + Eq + Clone + NetlinkSerializable<T> + NetlinkDeserializable<T>,
+    S: BlockingSocket,
+{
+    /// Create a connection that uses the given socket.
+    pub fn with_socket(socket: S) -> Self {
+        let (requests_tx, requests_rx) = channel(None);
+        Connection {
+            socket,
+            codec: NetlinkCodec::new(),
+            writer: BytesMut::new(),
+            protocol: Protocol::new(),
+            requests_tx,
+            requests_rx,
+            cancellations: Arc::new(Cancellations::default()),
+            unsolicited_messages: VecDeque::new(),
+        }
+    }
+
+    pub fn socket_mut(&mut self) -> &mut S {
+        &mut self.socket
+    }
+
+    /// Set the workarounds for the malformed messages of the protocol, and what to do with the
+    /// datagrams that cannot be decoded. With [`MalformedPolicy::Fail`], the malformed datagrams
+    /// make the requests fail with an [`ErrorKind::SocketIo`] error.
+    ///
+    /// [`MalformedPolicy::Fail`]: crate::MalformedPolicy::Fail
+    pub fn set_decoder_quirks(&mut self, quirks: DecoderQuirks) {
+        self.codec.set_quirks(quirks);
+    }
+
+    /// Send a request and iterate over the responses, reading from the socket as needed. Like
+    /// for [`ConnectionHandle::request`](../struct.ConnectionHandle.html#method.request), the
+    /// acknowledgements and "end of dump" messages end the iteration instead of being returned,
+    /// while error messages are returned.
+    ///
+    /// Dropping the iterator before the end cancels the request.
+    pub fn request(
+        &mut self,
+        message: NetlinkMessage<T>,
+        destination: SocketAddr,
+    ) -> Result<Responses<'_, T, S>, Error<T>> {
+        let (tx, rx) = unbounded::<Response<T>>();
+        let responder = Responder {
+            tx,
+            deadline: None,
+            token: self.cancellations.next_token(),
+        };
+        self.send(vec![Request::from((message, destination, responder))])?;
+        Ok(Responses {
+            connection: self,
+            rx,
+        })
+    }
+
+    /// Send a message without waiting for a response.
+    pub fn notify(
+        &mut self,
+        message: NetlinkMessage<T>,
+        destination: SocketAddr,
+    ) -> Result<(), Error<T>> {
+        let (tx, _rx) = unbounded::<Response<T>>();
+        let responder = Responder {
+            tx,
+            deadline: None,
+            token: self.cancellations.next_token(),
+        };
+        self.send(vec![Request::from((message, destination, responder))])
+    }
+
+    /// Return the next unsolicited message, waiting for one if none was received yet.
+    pub fn next_unsolicited_message(
+        &mut self,
+    ) -> Result<(NetlinkMessage<T>, SocketAddr), Error<T>> {
+        loop {
+            if let Some(message) = self.unsolicited_messages.pop_front() {
+                return Ok(message);
+            }
+            self.receive()?;
+        }
+    }
+
+    /// Create a handle to send requests through this connection. The requests are only sent,
+    /// and their responses only received, while [`block_on`](#method.block_on) runs.
+    pub fn handle(&self) -> ConnectionHandle<T> {
+        ConnectionHandle::new(self.requests_tx.clone(), self.cancellations.clone())
+    }
+
+    /// Run a future on the current thread, typically a request sent with a handle of this
+    /// connection (see [`handle`](#method.handle)), and return its output. Meanwhile, the
+    /// requests sent by the handles are processed.
+    ///
+    /// This fails if reading from or writing to the socket fails.
+    pub fn block_on<F: Future>(&mut self, future: F) -> Result<F::Output, Error<T>> {
+        pin_mut!(future);
+        let waker = waker(Arc::new(ThreadWaker(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return Ok(output);
+            }
+
+            while let Poll::Ready(Some(requests)) =
+                Pin::new(&mut self.requests_rx).poll_next(&mut cx)
+            {
+                self.send(requests)?;
+            }
+            self.cancellations.register(cx.waker());
+            // Only a few requests are pending at a time, so the ones whose response stream was
+            // dropped are found by going through all of them.
+            self.cancellations.take_dropped();
+            self.protocol
+                .cancel_requests(|responder| responder.tx.is_closed());
+
+            if self.protocol.pending_requests().next().is_some() {
+                self.receive()?;
+            } else {
+                // The future is waiting for something else than a response: wait until it is
+                // woken up by another thread.
+                thread::park();
+            }
+        }
+    }
+
+    fn send(&mut self, requests: Batch<T>) -> Result<(), Error<T>> {
+        self.protocol.request_batch(requests);
+        while let Some((mut messages, destination)) = self.protocol.outgoing_messages.pop_front() {
+            let limits = if messages.len() > 1 {
+                DatagramLimits::new(
+                    self.socket.get_send_buffer_size(),
+                    self.socket.get_receive_buffer_size(),
+                )
+            } else {
+                DatagramLimits::default()
+            };
+            while !messages.is_empty() {
+                let count = datagram_len(&mut messages, limits);
+                let datagram: Vec<_> = messages.drain(..count).collect();
+                let awaited_ack = if count > 1 {
+                    self.protocol.last_awaited_ack(&datagram, &destination)
+                } else {
+                    None
+                };
+                self.writer.clear();
+                for message in datagram {
+                    self.codec
+                        .encode(message, &mut self.writer)
+                        .map_err(ErrorKind::SocketIo)?;
+                }
+                self.socket
+                    .send_to(&self.writer, &destination)
+                    .map_err(ErrorKind::SocketIo)?;
+                // Read the responses to this datagram before sending the next one, so that they
+                // do not overflow the receive buffer
+                if let Some(sequence_number) = awaited_ack {
+                    while self.protocol.is_awaiting_ack(sequence_number, &destination) {
+                        self.receive()?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Read one datagram from the socket, and dispatch the messages it contains.
+    fn receive(&mut self) -> Result<(), Error<T>> {
+        let (bytes, source) = self.socket.recv_from_full().map_err(ErrorKind::SocketIo)?;
+        let mut reader = BytesMut::from(&bytes[..]);
+        while let Some(message) = self
+            .codec
+            .decode(&mut reader)
+            .map_err(ErrorKind::SocketIo)?
+        {
+            self.protocol.handle_message(message, source);
+        }
+        while let Some(response) = self.protocol.incoming_responses.pop_front() {
+            forward_response(response);
+        }
+        self.unsolicited_messages
+            .extend(self.protocol.incoming_requests.drain(..));
+        Ok(())
+    }
+}
+
+/// Iterator over the responses to a request sent with [`Connection::request`].
+pub struct Responses<'a, T, S = Socket>
+where
+    T: Debug + PartialEq + Eq + Clone + NetlinkSerializable<T> + NetlinkDeserializable<T>,
+    S: BlockingSocket,
+{
+    connection: &'a mut Connection<T, S>,
+    rx: UnboundedReceiver<Response<T>>,
+}
+
+impl<'a, T, S> Iterator for Responses<'a, T, S>
+where
+    T: Debug + PartialEq + Eq + Clone + NetlinkSerializable<T> + NetlinkDeserializable<T>,
+    S: BlockingSocket,
+{
+    type Item = Response<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // The responses are received on this thread, so there is no need to be woken up
+        let mut cx = Context::from_waker(noop_waker_ref());
+        loop {
+            match Pin::new(&mut self.rx).poll_next(&mut cx) {
+                Poll::Ready(Some(message)) => return Some(message),
+                // The last response was received
+                Poll::Ready(None) => return None,
+                Poll::Pending => {}
+            }
+            if let Err(e) = self.connection.receive() {
+                // Give up on the request, the iteration ends after the error
+                self.rx.close();
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+impl<'a, T, S> Drop for Responses<'a, T, S>
+where
+    T: Debug + PartialEq + Eq + Clone + NetlinkSerializable<T> + NetlinkDeserializable<T>,
+    S: BlockingSocket,
+{
+    fn drop(&mut self) {
+        self.rx.close();
+        self.connection
+            .protocol
+            .cancel_requests(|responder| responder.tx.is_closed());
+    }
+}
+
+struct ThreadWaker(Thread);
+
+impl ArcWake for ThreadWaker {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.0.unpark();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::thread::{self, JoinHandle};
+
+    use netlink_packet_core::{NetlinkHeader, NetlinkPayload};
+    use netlink_packet_route::{
+        ErrorMessage,
+        LinkMessage,
+        RtnlMessage,
+        NLM_F_ACK,
+        NLM_F_DUMP,
+        NLM_F_MULTIPART,
+        NLM_F_REQUEST,
+    };
+
+    use super::*;
+
+    type Message = NetlinkMessage<RtnlMessage>;
+
+    /// Answer the requests received by `kernel` with the datagrams returned by `script`, until
+    /// the other end of the socket is dropped.
+    fn spawn_kernel<F>(mut kernel: MemorySocket, mut script: F) -> JoinHandle<()>
+    where
+        F: FnMut(Message) -> Vec<Vec<Message>> + Send + 'static,
+    {
+        thread::spawn(move || {
+            while let Ok((datagram, _)) = kernel.recv_from_full() {
+                let request = Message::deserialize(&datagram).unwrap();
+                for messages in script(request) {
+                    let mut datagram = vec![];
+                    for mut message in messages {
+                        message.finalize();
+                        let start = datagram.len();
+                        datagram.resize(start + message.buffer_len(), 0);
+                        message.serialize(&mut datagram[start..]);
+                    }
+                    kernel.send(&datagram).unwrap();
+                }
+            }
+        })
+    }
+
+    fn connect<F>(script: F) -> (Connection<RtnlMessage, MemorySocket>, JoinHandle<()>)
+    where
+        F: FnMut(Message) -> Vec<Vec<Message>> + Send + 'static,
+    {
+        let (user, kernel) = MemorySocket::pair(SocketAddr::new(1, 0), SocketAddr::new(0, 0));
+        (Connection::with_socket(user), spawn_kernel(kernel, script))
+    }
+
+    /// Build a response to `request`
+    fn response(request: &Message, payload: NetlinkPayload<RtnlMessage>, flags: u16) -> Message {
+        let mut header = NetlinkHeader::default();
+        header.sequence_number = request.header.sequence_number;
+        header.flags = flags;
+        NetlinkMessage::new(header, payload)
+    }
+
+    fn error(request: &Message, code: i32) -> Message {
+        let payload = NetlinkPayload::Error(ErrorMessage {
+            code,
+            header: vec![],
+        });
+        response(request, payload, 0)
+    }
+
+    fn link(index: u32) -> NetlinkPayload<RtnlMessage> {
+        let mut link = LinkMessage::default();
+        link.header.index = index;
+        NetlinkPayload::InnerMessage(RtnlMessage::NewLink(link))
+    }
+
+    fn request(flags: u16) -> Message {
+        let mut request = Message::from(RtnlMessage::GetLink(LinkMessage::default()));
+        request.header.flags = NLM_F_REQUEST | flags;
+        request
+    }
+
+    #[test]
+    fn ack_and_error() {
+        let mut code = 0;
+        let (mut connection, kernel) = connect(move |request| {
+            let response = error(&request, code);
+            code = -libc::ENODEV;
+            vec![vec![response]]
+        });
+
+        // Acks end the responses without being returned
+        let responses: Vec<_> = connection
+            .request(request(NLM_F_ACK), SocketAddr::new(0, 0))
+            .unwrap()
+            .collect();
+        assert!(responses.is_empty());
+
+        let responses: Vec<_> = connection
+            .request(request(NLM_F_ACK), SocketAddr::new(0, 0))
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(responses.len(), 1);
+        match responses[0].payload {
+            NetlinkPayload::Error(ref err) => assert_eq!(err.code, -libc::ENODEV),
+            ref payload => panic!("unexpected response: {:?}", payload),
+        }
+
+        drop(connection);
+        kernel.join().unwrap();
+    }
+
+    #[test]
+    fn multipart_dump() {
+        let (mut connection, kernel) = connect(|request| {
+            let part = |payload| response(&request, payload, NLM_F_MULTIPART);
+            vec![
+                vec![part(link(1)), part(link(2))],
+                vec![part(link(3)), part(NetlinkPayload::Done)],
+            ]
+        });
+
+        let indexes: Vec<_> = connection
+            .request(request(NLM_F_DUMP), SocketAddr::new(0, 0))
+            .unwrap()
+            .map(|message| match message.unwrap().payload {
+                NetlinkPayload::InnerMessage(RtnlMessage::NewLink(link)) => link.header.index,
+                payload => panic!("unexpected response: {:?}", payload),
+            })
+            .collect();
+        assert_eq!(indexes, vec![1, 2, 3]);
+
+        drop(connection);
+        kernel.join().unwrap();
+    }
+
+    #[test]
+    fn sequence_matching() {
+        let (mut connection, kernel) = connect(|request| {
+            // A message with another sequence number, like a notification, is received first
+            let mut other = response(&request, link(1), 0);
+            other.header.sequence_number += 100;
+            vec![vec![other], vec![response(&request, link(2), 0)]]
+        });
+
+        let responses: Vec<_> = connection
+            .request(request(0), SocketAddr::new(0, 0))
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0].payload, link(2));
+
+        let (unsolicited, source) = connection.next_unsolicited_message().unwrap();
+        assert_eq!(unsolicited.payload, link(1));
+        assert_eq!(source, SocketAddr::new(0, 0));
+
+        drop(connection);
+        kernel.join().unwrap();
+    }
+
+    #[test]
+    fn overrun_fails_the_request() {
+        let (mut connection, kernel) =
+            connect(|request| vec![vec![response(&request, NetlinkPayload::Overrun(vec![]), 0)]]);
+
+        let responses: Vec<_> = connection
+            .request(request(NLM_F_DUMP), SocketAddr::new(0, 0))
+            .unwrap()
+            .collect();
+        assert_eq!(responses.len(), 1);
+        match responses[0] {
+            Err(ref err) => match err.kind() {
+                ErrorKind::SocketIo(e) => assert_eq!(e.raw_os_error(), Some(libc::ENOBUFS)),
+                kind => panic!("unexpected error: {:?}", kind),
+            },
+            Ok(ref message) => panic!("unexpected response: {:?}", message),
+        }
+
+        drop(connection);
+        kernel.join().unwrap();
+    }
+}
@@ -0,0 +1,364 @@
+use std::{
+    fmt::Debug,
+    io,
+    marker::PhantomData,
+    path::PathBuf,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use futures::Stream;
+use netlink_packet_core::{NetlinkDeserializable, NetlinkMessage, NetlinkSerializable};
+
+use crate::{
+    channel::{channel, Receiver},
+    handle::Cancellations,
+    sys::{AsyncNetlinkSocket, SocketAddr},
+    Batch,
+    Connection,
+    ConnectionHandle,
+    DecoderQuirks,
+    OverflowPolicy,
+};
+
+/// Builder for a [`Connection`](struct.Connection.html), for the cases
+/// [`new_connection`](fn.new_connection.html) does not cover.
+///
+/// The builder opens the socket in a network namespace, binds it, joins multicast groups and
+/// sets socket options before the connection is created. The options that are not set keep
+/// the default of the kernel.
+///
+/// By default, the channels between the connection and its handles are unbounded, like with
+/// `new_connection`. A slow consumer of the unsolicited messages (multicast notifications for
+/// instance) can then make the process grow without limit, which bounded channels prevent:
+///
+/// ```rust,no_run
+/// use netlink_packet_route::RtnlMessage;
+/// use netlink_proto::{sys::protocols::NETLINK_ROUTE, ConnectionBuilder, OverflowPolicy};
+///
+/// // RTNLGRP_LINK
+/// const LINK_GROUP: u32 = 1;
+///
+/// let (connection, handle, messages) = ConnectionBuilder::<RtnlMessage>::new(NETLINK_ROUTE)
+///     .membership(LINK_GROUP)
+///     .receive_buffer_size(4 * 1024 * 1024, false)
+///     .no_enobufs(true)
+///     .requests_capacity(64)
+///     .unsolicited_messages_capacity(1024, OverflowPolicy::DropAndReport)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConnectionBuilder<T> {
+    protocol: isize,
+    requests_capacity: Option<usize>,
+    unsolicited_messages_limit: Option<(usize, OverflowPolicy)>,
+    decoder_quirks: DecoderQuirks,
+    reader_capacity: Option<usize>,
+    network_namespace: Option<PathBuf>,
+    bind_address: Option<SocketAddr>,
+    groups: Vec<u32>,
+    /// Size of the send buffer, and whether to exceed the limit of the system
+    send_buffer_size: Option<(usize, bool)>,
+    receive_buffer_size: Option<(usize, bool)>,
+    extended_ack: Option<bool>,
+    strict_checking: Option<bool>,
+    no_enobufs: Option<bool>,
+    cap_ack: Option<bool>,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> ConnectionBuilder<T>
+where
+    T: Debug + PartialEq + Eq + Clone + NetlinkSerializable<T> + NetlinkDeserializable<T> + Unpin,
+{
+    /// `protocol` must be one of the [`crate::sys::protocols`][protos] constants.
+    ///
+    /// [protos]: crate::sys::protocols
+    pub fn new(protocol: isize) -> Self {
+        ConnectionBuilder {
+            protocol,
+            requests_capacity: None,
+            unsolicited_messages_limit: None,
+            decoder_quirks: DecoderQuirks::default(),
+            reader_capacity: None,
+            network_namespace: None,
+            bind_address: None,
+            groups: vec![],
+            send_buffer_size: None,
+            receive_buffer_size: None,
+            extended_ack: None,
+            strict_checking: None,
+            no_enobufs: None,
+            cap_ack: None,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Bound the number of requests waiting to be sent. When the queue is full,
+    /// [`ConnectionHandle::request`](struct.ConnectionHandle.html#method.request) fails, while
+    /// [`ConnectionHandle::request_when_ready`](struct.ConnectionHandle.html#method.request_when_ready)
+    /// waits for room in the queue. Note that each clone of the handle can queue one more request, and that a batch
+    /// of requests (see
+    /// [`ConnectionHandle::batch_request`](struct.ConnectionHandle.html#method.batch_request))
+    /// counts as one request.
+    pub fn requests_capacity(mut self, capacity: usize) -> Self {
+        self.requests_capacity = Some(capacity);
+        self
+    }
+
+    /// Bound the number of unsolicited messages waiting to be consumed, and choose what happens
+    /// to the messages beyond that. Up to `capacity` messages are queued, on top of the one the
+    /// consumer of [`UnsolicitedMessages`] receives next.
+    pub fn unsolicited_messages_capacity(
+        mut self,
+        capacity: usize,
+        policy: OverflowPolicy,
+    ) -> Self {
+        self.unsolicited_messages_limit = Some((capacity, policy));
+        self
+    }
+
+    /// Set the workarounds for the malformed messages of the protocol, and what to do with the
+    /// datagrams that cannot be decoded (see
+    /// [`Connection::set_decoder_quirks`](struct.Connection.html#method.set_decoder_quirks)).
+    pub fn decoder_quirks(mut self, quirks: DecoderQuirks) -> Self {
+        self.decoder_quirks = quirks;
+        self
+    }
+
+    /// Set the size of the buffer the datagrams are read into (see
+    /// [`Connection::set_reader_capacity`](struct.Connection.html#method.set_reader_capacity)).
+    pub fn reader_capacity(mut self, capacity: usize) -> Self {
+        self.reader_capacity = Some(capacity);
+        self
+    }
+
+    /// Open the socket in the network namespace of the given file, like `/run/netns/<name>` (see
+    /// [`Socket::new_in_namespace`](netlink_sys::Socket::new_in_namespace)).
+    pub fn network_namespace<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.network_namespace = Some(path.into());
+        self
+    }
+
+    /// Bind the socket to the given address. Otherwise, the kernel assigns an address to the
+    /// socket when it sends its first message.
+    pub fn bind_address(mut self, address: SocketAddr) -> Self {
+        self.bind_address = Some(address);
+        self
+    }
+
+    /// Join the multicast group with the given number, whose messages are received as
+    /// unsolicited messages. Unlike the groups of the bind address, which are a bitmask of the
+    /// first 32 groups, any group can be joined this way. Without a bind address, the socket is
+    /// bound to an address assigned by the kernel.
+    pub fn membership(mut self, group: u32) -> Self {
+        self.groups.push(group);
+        self
+    }
+
+    /// Set `SO_SNDBUF`, or `SO_SNDBUFFORCE` when `force` is true to exceed the limit of the
+    /// system, which requires the `CAP_NET_ADMIN` capability. The connection sends batches in
+    /// datagrams that fit in this buffer.
+    pub fn send_buffer_size(mut self, size: usize, force: bool) -> Self {
+        self.send_buffer_size = Some((size, force));
+        self
+    }
+
+    /// Set `SO_RCVBUF`, or `SO_RCVBUFFORCE` when `force` is true to exceed the limit of the
+    /// system, which requires the `CAP_NET_ADMIN` capability. The messages that do not fit are
+    /// dropped by the kernel, so a large buffer avoids losing notifications when they come in
+    /// bursts.
+    pub fn receive_buffer_size(mut self, size: usize, force: bool) -> Self {
+        self.receive_buffer_size = Some((size, force));
+        self
+    }
+
+    /// Set `NETLINK_EXT_ACK`, for the errors sent by the kernel to carry a message.
+    pub fn extended_ack(mut self, value: bool) -> Self {
+        self.extended_ack = Some(value);
+        self
+    }
+
+    /// Set `NETLINK_GET_STRICT_CHK`, for the kernel to check the dump requests strictly and
+    /// apply the filters they carry.
+    pub fn strict_checking(mut self, value: bool) -> Self {
+        self.strict_checking = Some(value);
+        self
+    }
+
+    /// Set `NETLINK_NO_ENOBUFS`, for the reads not to fail when the kernel dropped messages
+    /// because the receive buffer was full.
+    pub fn no_enobufs(mut self, value: bool) -> Self {
+        self.no_enobufs = Some(value);
+        self
+    }
+
+    /// Set `NETLINK_CAP_ACK`, for the errors sent by the kernel to carry the header of the
+    /// request only instead of the whole request.
+    pub fn cap_ack(mut self, value: bool) -> Self {
+        self.cap_ack = Some(value);
+        self
+    }
+
+    /// Open a socket in the network namespace, with the address, multicast groups and options of
+    /// the builder. This is what [`build`](#method.build) uses, and it can be used to configure
+    /// the socket of a [blocking connection](blocking/struct.Connection.html) for instance.
+    pub fn open_socket(&self) -> io::Result<netlink_sys::Socket> {
+        let mut socket = match self.network_namespace {
+            Some(ref path) => netlink_sys::Socket::new_in_namespace(self.protocol, path)?,
+            None => netlink_sys::Socket::new(self.protocol)?,
+        };
+        match self.send_buffer_size {
+            Some((size, true)) => socket.set_send_buffer_size_force(size)?,
+            Some((size, false)) => socket.set_send_buffer_size(size)?,
+            None => {}
+        }
+        match self.receive_buffer_size {
+            Some((size, true)) => socket.set_receive_buffer_size_force(size)?,
+            Some((size, false)) => socket.set_receive_buffer_size(size)?,
+            None => {}
+        }
+        if let Some(value) = self.extended_ack {
+            socket.set_ext_ack(value)?;
+        }
+        if let Some(value) = self.strict_checking {
+            socket.set_strict_checking(value)?;
+        }
+        if let Some(value) = self.no_enobufs {
+            socket.set_no_enobufs(value)?;
+        }
+        if let Some(value) = self.cap_ack {
+            socket.set_cap_ack(value)?;
+        }
+        match self.bind_address {
+            Some(ref address) => socket.bind(address)?,
+            // The kernel only assigns an address when the socket sends a message. Until then,
+            // the socket has the address of the kernel, and doesn't receive its notifications.
+            None if !self.groups.is_empty() => {
+                socket.bind_auto()?;
+            }
+            None => {}
+        }
+        for group in &self.groups {
+            socket.add_membership(*group)?;
+        }
+        Ok(socket)
+    }
+
+    /// Create the connection, a handle to that connection, and the stream of unsolicited messages
+    /// received by that connection. This requires the `tokio_socket` or `smol_socket` feature.
+    #[cfg(any(feature = "tokio_socket", feature = "smol_socket"))]
+    #[allow(clippy::type_complexity)]
+    pub fn build(self) -> io::Result<(Connection<T>, ConnectionHandle<T>, UnsolicitedMessages<T>)> {
+        let socket = crate::sys::Socket::from_socket(self.open_socket()?)?;
+        Ok(self.build_with_socket(socket))
+    }
+
+    /// Like [`build`](#method.build), but the connection uses the given socket instead of opening
+    /// one (see [`new_connection_with_socket`](fn.new_connection_with_socket.html)). The network
+    /// namespace, address, multicast groups and socket options of the builder are ignored.
+    #[allow(clippy::type_complexity)]
+    pub fn build_with_socket<S>(
+        self,
+        socket: S,
+    ) -> (
+        Connection<T, S>,
+        ConnectionHandle<T>,
+        UnsolicitedMessages<T>,
+    )
+    where
+        S: AsyncNetlinkSocket,
+    {
+        let (requests_tx, requests_rx) = channel::<Batch<T>>(self.requests_capacity);
+        // A bounded channel has room for one message per sender on top of its capacity, and the
+        // connection holds the only sender, so this channel holds a single message. The other
+        // messages wait in the connection, which applies the overflow policy: at most
+        // `capacity + 1` messages wait to be consumed.
+        let (messages_tx, messages_rx) =
+            channel::<(NetlinkMessage<T>, SocketAddr)>(self.unsolicited_messages_limit.map(|_| 0));
+        let cancellations = Arc::new(Cancellations::default());
+        let mut connection = Connection::new(
+            requests_rx,
+            messages_tx,
+            self.unsolicited_messages_limit,
+            cancellations.clone(),
+            socket,
+        );
+        connection.set_decoder_quirks(self.decoder_quirks);
+        if let Some(capacity) = self.reader_capacity {
+            connection.set_reader_capacity(capacity);
+        }
+        (
+            connection,
+            ConnectionHandle::new(requests_tx, cancellations),
+            UnsolicitedMessages(messages_rx),
+        )
+    }
+}
+
+/// Stream of the unsolicited messages received by a connection created with a
+/// [`ConnectionBuilder`].
+#[derive(Debug)]
+pub struct UnsolicitedMessages<T>(Receiver<(NetlinkMessage<T>, SocketAddr)>)
+where
+    T: Debug + PartialEq + Eq + Clone;
+
+impl<T> Stream for UnsolicitedMessages<T>
+where
+    T: Debug + PartialEq + Eq + Clone,
+{
+    type Item = (NetlinkMessage<T>, SocketAddr);
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.0).poll_next(cx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use netlink_packet_route::RtnlMessage;
+
+    use super::*;
+    use crate::sys::protocols::NETLINK_ROUTE;
+
+    /// `RTNLGRP_LINK`
+    const LINK_GROUP: u32 = 1;
+
+    #[test]
+    fn open_socket_with_options() {
+        let socket = ConnectionBuilder::<RtnlMessage>::new(NETLINK_ROUTE)
+            .network_namespace("/proc/self/ns/net")
+            .bind_address(SocketAddr::new(0, 0))
+            .membership(LINK_GROUP)
+            .send_buffer_size(32 * 1024, false)
+            .receive_buffer_size(48 * 1024, false)
+            .extended_ack(true)
+            .strict_checking(true)
+            .no_enobufs(true)
+            .cap_ack(true)
+            .open_socket()
+            .unwrap();
+
+        // The kernel doubles the buffer sizes
+        assert_eq!(socket.get_send_buffer_size().unwrap(), 64 * 1024);
+        assert_eq!(socket.get_receive_buffer_size().unwrap(), 96 * 1024);
+        assert!(socket.get_ext_ack().unwrap());
+        assert!(socket.get_strict_checking().unwrap());
+        assert!(socket.get_no_enobufs().unwrap());
+        assert!(socket.get_cap_ack().unwrap());
+        let mut address = SocketAddr::new(0, 0);
+        socket.get_address(&mut address).unwrap();
+        assert_ne!(address.port_number(), 0);
+    }
+
+    #[test]
+    fn open_socket_in_missing_namespace() {
+        let err = ConnectionBuilder::<RtnlMessage>::new(NETLINK_ROUTE)
+            .network_namespace("/run/netns/does-not-exist")
+            .open_socket()
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+}
@@ -0,0 +1,76 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::{
+    channel::mpsc::{self, TrySendError, UnboundedReceiver, UnboundedSender},
+    Stream,
+};
+
+/// Sending half of a channel that is either bounded or unbounded.
+#[derive(Debug)]
+pub(crate) enum Sender<M> {
+    Unbounded(UnboundedSender<M>),
+    Bounded(mpsc::Sender<M>),
+}
+
+// #[derive(Clone)] would require `M: Clone`
+impl<M> Clone for Sender<M> {
+    fn clone(&self) -> Self {
+        match self {
+            Sender::Unbounded(tx) => Sender::Unbounded(tx.clone()),
+            Sender::Bounded(tx) => Sender::Bounded(tx.clone()),
+        }
+    }
+}
+
+impl<M> Sender<M> {
+    /// Check whether a message can be sent. An unbounded channel is always ready, unless it is
+    /// closed.
+    pub fn poll_ready(&mut self, cx: &mut Context) -> Poll<Result<(), ()>> {
+        match self {
+            Sender::Unbounded(tx) => tx.poll_ready(cx).map_err(|_| ()),
+            Sender::Bounded(tx) => tx.poll_ready(cx).map_err(|_| ()),
+        }
+    }
+
+    pub fn try_send(&mut self, message: M) -> Result<(), TrySendError<M>> {
+        match self {
+            Sender::Unbounded(tx) => tx.unbounded_send(message),
+            Sender::Bounded(tx) => tx.try_send(message),
+        }
+    }
+}
+
+/// Receiving half of a channel that is either bounded or unbounded.
+#[derive(Debug)]
+pub(crate) enum Receiver<M> {
+    Unbounded(UnboundedReceiver<M>),
+    Bounded(mpsc::Receiver<M>),
+}
+
+impl<M> Stream for Receiver<M> {
+    type Item = M;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<M>> {
+        match self.get_mut() {
+            Receiver::Unbounded(rx) => Pin::new(rx).poll_next(cx),
+            Receiver::Bounded(rx) => Pin::new(rx).poll_next(cx),
+        }
+    }
+}
+
+/// Create a channel with the given capacity, or an unbounded channel.
+pub(crate) fn channel<M>(capacity: Option<usize>) -> (Sender<M>, Receiver<M>) {
+    match capacity {
+        Some(capacity) => {
+            let (tx, rx) = mpsc::channel(capacity);
+            (Sender::Bounded(tx), Receiver::Bounded(rx))
+        }
+        None => {
+            let (tx, rx) = mpsc::unbounded();
+            (Sender::Unbounded(tx), Receiver::Unbounded(rx))
+        }
+    }
+}
@@ -6,10 +6,55 @@ use netlink_packet_core::{
     NetlinkDeserializable,
     NetlinkMessage,
     NetlinkSerializable,
+    NETLINK_HEADER_LEN,
 };
 use tokio_util::codec::{Decoder, Encoder};
 
+/// What a [`NetlinkCodec`] does with the datagrams it cannot decode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MalformedPolicy {
+    /// Log an error and drop the datagram, or the message if only the message is malformed. The
+    /// responses it carried are lost, so the requests they answer may never complete.
+    Discard,
+    /// Fail with an [`io::ErrorKind::InvalidData`] error. The
+    /// [`Connection`](struct.Connection.html) then closes, and its pending requests fail with
+    /// that error.
+    Fail,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for MalformedPolicy {
+    fn default() -> Self {
+        MalformedPolicy::Discard
+    }
+}
+
+/// Workarounds for the malformed messages sent by some netlink protocols, set per codec so
+/// that the connections of the other protocols keep a strict decoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DecoderQuirks {
+    /// Fix the length of the messages that are shorter than what is left of the datagram by at
+    /// most 16 bytes, and take the rest of the datagram instead. The audit protocol is known to
+    /// send such messages: their length does not include the header (see
+    /// <https://github.com/mozilla/libaudit-go/issues/24>), or the padding of some rule messages
+    /// (see <https://github.com/linux-audit/audit-userspace/issues/78>).
+    pub fix_truncated_lengths: bool,
+    /// What to do with malformed datagrams
+    pub malformed: MalformedPolicy,
+}
+
+impl DecoderQuirks {
+    /// Quirks of the `NETLINK_AUDIT` protocol
+    pub fn audit() -> Self {
+        DecoderQuirks {
+            fix_truncated_lengths: true,
+            malformed: MalformedPolicy::Discard,
+        }
+    }
+}
+
 pub struct NetlinkCodec<T> {
+    quirks: DecoderQuirks,
     phantom: PhantomData<T>,
 }
 
@@ -21,14 +66,36 @@ impl<T> Default for NetlinkCodec<T> {
 
 impl<T> NetlinkCodec<T> {
     pub fn new() -> Self {
+        Self::with_quirks(DecoderQuirks::default())
+    }
+
+    pub fn with_quirks(quirks: DecoderQuirks) -> Self {
         NetlinkCodec {
+            quirks,
             phantom: PhantomData,
         }
     }
+
+    pub fn quirks(&self) -> DecoderQuirks {
+        self.quirks
+    }
+
+    pub fn set_quirks(&mut self, quirks: DecoderQuirks) {
+        self.quirks = quirks;
+    }
+
+    /// Apply the malformed datagram policy to a decoding error
+    fn malformed(&self, message: String) -> Result<(), io::Error> {
+        match self.quirks.malformed {
+            MalformedPolicy::Discard => {
+                error!("{}", message);
+                Ok(())
+            }
+            MalformedPolicy::Fail => Err(io::Error::new(io::ErrorKind::InvalidData, message)),
+        }
+    }
 }
 
-// FIXME: it seems that for audit, we're receiving malformed packets.
-// See https://github.com/mozilla/libaudit-go/issues/24
 impl<T> Decoder for NetlinkCodec<NetlinkMessage<T>>
 where
     T: NetlinkDeserializable<T> + Debug + Eq + PartialEq + Clone,
@@ -50,29 +117,24 @@ where
             // This is a bit hacky because we don't want to keep `src`
             // borrowed, since we need to mutate it later.
             let len_res = match NetlinkBuffer::new_checked(src.as_ref()) {
-                #[cfg(not(feature = "workaround-audit-bug"))]
-                Ok(buf) => Ok(buf.length() as usize),
-                #[cfg(feature = "workaround-audit-bug")]
-                Ok(buf) => {
-                    if (src.as_ref().len() as isize - buf.length() as isize) <= 16 {
-                        // The audit messages are sometimes truncated,
-                        // because the length specified in the header,
-                        // does not take the header itself into
-                        // account. To workaround this, we tweak the
-                        // length. We've noticed two occurences of
-                        // truncated packets:
-                        //
-                        // - the length of the header is not included (see also:
-                        //   https://github.com/mozilla/libaudit-go/issues/24)
-                        // - some rule message have some padding for alignment (see
-                        //   https://github.com/linux-audit/audit-userspace/issues/78) which is not
-                        //   taken into account in the buffer length.
-                        warn!("found what looks like a truncated audit packet");
-                        Ok(src.as_ref().len())
-                    } else {
-                        Ok(buf.length() as usize)
-                    }
+                Ok(buf) if (buf.length() as usize) < NETLINK_HEADER_LEN => {
+                    Err(format!("invalid message length {}", buf.length()))
                 }
+                Ok(buf)
+                    if self.quirks.fix_truncated_lengths
+                        && (src.as_ref().len() as isize - buf.length() as isize) <= 16 =>
+                {
+                    // The message looks truncated (see `DecoderQuirks::fix_truncated_lengths`),
+                    // so we tweak the length.
+                    warn!("found what looks like a truncated audit packet");
+                    Ok(src.as_ref().len())
+                }
+                Ok(buf) => Ok(buf.length() as usize),
+                Err(e) => Err(e.to_string()),
+            };
+
+            let len = match len_res {
+                Ok(len) => len,
                 Err(e) => {
                     // We either received a truncated packet, or the
                     // packet if malformed (invalid length field). In
@@ -80,49 +142,41 @@ where
                     // cannot find the start of the next one (if
                     // any). The only solution is to clear the buffer
                     // and potentially lose some datagrams.
-                    error!("failed to decode datagram: {:?}: {:#x?}.", e, src.as_ref());
-                    Err(())
+                    let message = format!(
+                        "failed to decode datagram: {}: {:#x?}. Datagrams may have been lost",
+                        e,
+                        src.as_ref()
+                    );
+                    src.clear();
+                    self.malformed(message)?;
+                    return Ok(None);
                 }
             };
 
-            if len_res.is_err() {
-                error!("clearing the whole socket buffer. Datagrams may have been lost");
-                src.clear();
-                return Ok(None);
-            }
-
-            let len = len_res.unwrap();
-
-            #[cfg(feature = "workaround-audit-bug")]
-            let bytes = {
-                let mut bytes = src.split_to(len);
-                {
-                    let mut buf = NetlinkBuffer::new(bytes.as_mut());
-                    // If the buffer contains more bytes than what the header says the length is, it
-                    // means we ran into a malformed packet (see comment above), and we just set the
-                    // "right" length ourself, so that parsing does not fail.
-                    //
-                    // How do we know that's the right length? Due to an implementation detail and to
-                    // the fact that netlink is a datagram protocol.
-                    //
-                    // - our implementation of Stream always calls the codec with at most 1 message in
-                    //   the buffer, so we know the extra bytes do not belong to another message.
-                    // - because netlink is a datagram protocol, we receive entire messages, so we know
-                    //   that if those extra bytes do not belong to another message, they belong to
-                    //   this one.
-                    if len != buf.length() as usize {
-                        warn!(
-                            "setting packet length to {} instead of {}",
-                            len,
-                            buf.length()
-                        );
-                        buf.set_length(len as u32);
-                    }
+            let mut bytes = src.split_to(len);
+            {
+                let mut buf = NetlinkBuffer::new(bytes.as_mut());
+                // If the buffer contains more bytes than what the header says the length is, it
+                // means we ran into a malformed packet (see comment above), and we just set the
+                // "right" length ourself, so that parsing does not fail.
+                //
+                // How do we know that's the right length? Due to an implementation detail and to
+                // the fact that netlink is a datagram protocol.
+                //
+                // - our implementation of Stream always calls the codec with at most 1 message in
+                //   the buffer, so we know the extra bytes do not belong to another message.
+                // - because netlink is a datagram protocol, we receive entire messages, so we know
+                //   that if those extra bytes do not belong to another message, they belong to
+                //   this one.
+                if len != buf.length() as usize {
+                    warn!(
+                        "setting packet length to {} instead of {}",
+                        len,
+                        buf.length()
+                    );
+                    buf.set_length(len as u32);
                 }
-                bytes
-            };
-            #[cfg(not(feature = "workaround-audit-bug"))]
-            let bytes = src.split_to(len);
+            }
 
             let parsed = NetlinkMessage::<T>::deserialize(&bytes);
             match parsed {
@@ -131,7 +185,7 @@ where
                     return Ok(Some(packet));
                 }
                 Err(e) => {
-                    error!("failed to decode packet {:#x?}: {}", &bytes, e);
+                    self.malformed(format!("failed to decode packet {:#x?}: {}", &bytes, e))?;
                     // continue looping, there may be more datagrams in the buffer
                 }
             }
@@ -146,13 +200,13 @@ where
     type Error = io::Error;
 
     fn encode(&mut self, msg: NetlinkMessage<T>, buf: &mut BytesMut) -> Result<(), Self::Error> {
-        let msg_len = msg.buffer_len();
-        // FIXME: we should have a max length for the buffer
-        while buf.remaining_mut() < msg_len {
-            let new_len = buf.len() + 2048;
-            buf.resize(new_len, 0);
-        }
+        // The messages sent in the same datagram must be aligned on 4 bytes
+        let padding = (4 - buf.len() % 4) % 4;
+        buf.extend_from_slice(&[0; 3][..padding]);
+
         let size = msg.buffer_len();
+        // FIXME: we should have a max length for the buffer
+        buf.reserve(size);
         if buf.remaining_mut() < size {
             return Err(io::Error::new(
                 io::ErrorKind::Other,
@@ -187,3 +241,113 @@ where
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use netlink_packet_route::{LinkMessage, RtnlMessage, RTM_NEWLINK};
+
+    use super::*;
+
+    type Message = NetlinkMessage<RtnlMessage>;
+
+    fn link(index: u32) -> Message {
+        let mut link = LinkMessage::default();
+        link.header.index = index;
+        let mut message = Message::from(RtnlMessage::NewLink(link));
+        message.finalize();
+        message
+    }
+
+    fn serialize(message: &Message) -> Vec<u8> {
+        let mut bytes = vec![0; message.buffer_len()];
+        message.serialize(&mut bytes);
+        bytes
+    }
+
+    /// A `RTM_NEWLINK` message whose payload is too short to be parsed
+    fn unparsable() -> Vec<u8> {
+        let mut bytes = vec![0; NETLINK_HEADER_LEN + 4];
+        let mut buffer = NetlinkBuffer::new(&mut bytes);
+        buffer.set_length((NETLINK_HEADER_LEN + 4) as u32);
+        buffer.set_message_type(RTM_NEWLINK);
+        bytes
+    }
+
+    fn codec(fix_truncated_lengths: bool, malformed: MalformedPolicy) -> NetlinkCodec<Message> {
+        NetlinkCodec::with_quirks(DecoderQuirks {
+            fix_truncated_lengths,
+            malformed,
+        })
+    }
+
+    #[test]
+    fn decode_messages() {
+        let mut codec = NetlinkCodec::<Message>::new();
+        let mut src = BytesMut::new();
+        src.extend_from_slice(&serialize(&link(1)));
+        src.extend_from_slice(&serialize(&link(2)));
+
+        assert_eq!(codec.decode(&mut src).unwrap(), Some(link(1)));
+        assert_eq!(codec.decode(&mut src).unwrap(), Some(link(2)));
+        assert_eq!(codec.decode(&mut src).unwrap(), None);
+    }
+
+    #[test]
+    fn fix_truncated_lengths() {
+        let mut codec = codec(true, MalformedPolicy::Fail);
+        for missing in &[4, 16] {
+            let message = link(1);
+            let mut bytes = serialize(&message);
+            let len = bytes.len() - missing;
+            NetlinkBuffer::new(&mut bytes).set_length(len as u32);
+            let mut src = BytesMut::from(&bytes[..]);
+
+            assert_eq!(codec.decode(&mut src).unwrap(), Some(message));
+            assert!(src.is_empty());
+        }
+    }
+
+    #[test]
+    fn truncated_lengths_are_not_fixed_by_default() {
+        let mut codec = codec(false, MalformedPolicy::Fail);
+        let mut bytes = serialize(&link(1));
+        NetlinkBuffer::new(&mut bytes).set_length(NETLINK_HEADER_LEN as u32 + 4);
+        let mut src = BytesMut::from(&bytes[..]);
+        assert!(codec.decode(&mut src).is_err());
+    }
+
+    #[test]
+    fn discard_malformed() {
+        let mut codec = codec(false, MalformedPolicy::Discard);
+
+        // The message cannot be parsed: only this message is dropped
+        let mut src = BytesMut::from(&unparsable()[..]);
+        src.extend_from_slice(&serialize(&link(1)));
+        assert_eq!(codec.decode(&mut src).unwrap(), Some(link(1)));
+
+        // The length is invalid: the rest of the datagram is dropped
+        let mut bytes = serialize(&link(1));
+        NetlinkBuffer::new(&mut bytes).set_length(4);
+        let mut src = BytesMut::from(&bytes[..]);
+        src.extend_from_slice(&serialize(&link(2)));
+        assert_eq!(codec.decode(&mut src).unwrap(), None);
+        assert!(src.is_empty());
+    }
+
+    #[test]
+    fn fail_on_malformed() {
+        let mut codec = codec(false, MalformedPolicy::Fail);
+
+        let mut src = BytesMut::from(&unparsable()[..]);
+        src.extend_from_slice(&serialize(&link(1)));
+        let error = codec.decode(&mut src).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        let mut bytes = serialize(&link(1));
+        NetlinkBuffer::new(&mut bytes).set_length(4);
+        let mut src = BytesMut::from(&bytes[..]);
+        let error = codec.decode(&mut src).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(src.is_empty());
+    }
+}
@@ -1,105 +1,234 @@
 use std::{
+    collections::{BTreeSet, HashMap},
     fmt::Debug,
     io,
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
+    time::{Duration, Instant},
 };
 
-use futures::{
-    channel::mpsc::{UnboundedReceiver, UnboundedSender},
-    Future,
-    Sink,
-    Stream,
-};
+use futures::{Future, Sink, Stream};
+use futures_timer::Delay;
 use log::{error, warn};
 use netlink_packet_core::{
     NetlinkDeserializable,
+    NetlinkHeader,
     NetlinkMessage,
     NetlinkPayload,
     NetlinkSerializable,
+    NLM_F_DUMP_INTR,
 };
 
 use crate::{
-    codecs::NetlinkCodec,
+    channel::{Receiver, Sender},
+    codecs::{DecoderQuirks, NetlinkCodec},
+    errors::{Error, ErrorKind},
     framed::NetlinkFramed,
-    sys::{Socket, SocketAddr},
+    handle::Cancellations,
+    sys::{AsyncNetlinkSocket, SocketAddr},
+    Batch,
     Protocol,
-    Request,
+    RequestId,
+    Responder,
     Response,
 };
 
+/// What a [`Connection`] does with the unsolicited messages it receives when the channel that
+/// forwards them is full (see
+/// [`ConnectionBuilder::unsolicited_messages_capacity`](struct.ConnectionBuilder.html#method.unsolicited_messages_capacity)).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Stop reading from the socket until there is room in the channel. Note that the responses
+    /// to the requests are not read either in the meantime, and that the kernel drops the
+    /// messages that do not fit in the socket receive buffer.
+    Block,
+    /// Drop the oldest messages that have not been forwarded yet.
+    DropOldest,
+    /// Drop the new messages, and forward a message with a
+    /// [`NetlinkPayload::Overrun`](../netlink_packet_core/enum.NetlinkPayload.html) payload in
+    /// their place, so that the receiver knows that messages were lost.
+    DropAndReport,
+}
+
 /// Connection to a Netlink socket, running in the background.
 ///
 /// [`ConnectionHandle`](struct.ConnectionHandle.html) are used to pass new requests to the
 /// `Connection`, that in turn, sends them through the netlink socket.
-pub struct Connection<T>
-where
+///
+/// `S` is the type of the socket. By default, this is the socket of the async runtime selected
+/// with the `tokio_socket` or `smol_socket` feature, but any [`AsyncNetlinkSocket`] can be used
+/// (see [`new_connection_with_socket`](fn.new_connection_with_socket.html)), for instance a
+/// [`MemorySocket`](sys/struct.MemorySocket.html) in tests. Without these features, there is
+/// no default.
+pub struct Connection<
+    T,
+    #[cfg(any(feature = "tokio_socket", feature = "smol_socket"))] S = crate::sys::Socket,
+    #[cfg(not(any(feature = "tokio_socket", feature = "smol_socket")))] S,
+> where
     T: Debug + Clone + PartialEq + Eq + NetlinkSerializable<T> + NetlinkDeserializable<T>,
 {
-    socket: NetlinkFramed<NetlinkCodec<NetlinkMessage<T>>>,
+    socket: NetlinkFramed<NetlinkCodec<NetlinkMessage<T>>, S>,
 
-    protocol: Protocol<T, UnboundedSender<NetlinkMessage<T>>>,
+    protocol: Protocol<T, Responder<T>>,
 
     /// Channel used by the user to pass requests to the connection.
-    requests_rx: Option<UnboundedReceiver<Request<T>>>,
+    requests_rx: Option<Receiver<Batch<T>>>,
 
     /// Channel used to transmit to the ConnectionHandle the unsolicited messages received from the
     /// socket (multicast messages for instance).
-    unsolicited_messages_tx: Option<UnboundedSender<(NetlinkMessage<T>, SocketAddr)>>,
+    unsolicited_messages_tx: Option<Sender<(NetlinkMessage<T>, SocketAddr)>>,
+
+    /// Maximum number of unsolicited messages waiting to be forwarded, and what to do with the
+    /// messages beyond that. `None` if the channel is unbounded.
+    unsolicited_messages_limit: Option<(usize, OverflowPolicy)>,
 
     socket_closed: bool,
+
+    /// Timeout of the requests sent without an explicit timeout.
+    request_timeout: Option<Duration>,
+
+    /// Timer that fires when the earliest pending request times out.
+    timer: Option<(Instant, Delay)>,
+
+    /// Reports the requests whose response stream is dropped, so that they are cancelled.
+    cancellations: Arc<Cancellations>,
+
+    /// Ids of the pending requests, by the token of their responder.
+    requests: HashMap<u64, RequestId>,
+
+    /// Deadlines of the pending requests that can time out, with the token of their responder.
+    deadlines: BTreeSet<(Instant, u64)>,
+
+    /// Request that acknowledges the last datagram of a batch that was sent. The next datagram
+    /// is only sent once it is acknowledged, so that the responses do not overflow the socket
+    /// receive buffer.
+    awaited_ack: Option<(u32, SocketAddr)>,
 }
 
-impl<T> Connection<T>
+impl<T, S> Connection<T, S>
 where
     T: Debug + Clone + PartialEq + Eq + NetlinkSerializable<T> + NetlinkDeserializable<T> + Unpin,
+    S: AsyncNetlinkSocket,
 {
     pub(crate) fn new(
-        requests_rx: UnboundedReceiver<Request<T>>,
-        unsolicited_messages_tx: UnboundedSender<(NetlinkMessage<T>, SocketAddr)>,
-        protocol: isize,
-    ) -> io::Result<Self> {
-        let socket = Socket::new(protocol)?;
-        Ok(Connection {
+        requests_rx: Receiver<Batch<T>>,
+        unsolicited_messages_tx: Sender<(NetlinkMessage<T>, SocketAddr)>,
+        unsolicited_messages_limit: Option<(usize, OverflowPolicy)>,
+        cancellations: Arc<Cancellations>,
+        socket: S,
+    ) -> Self {
+        Connection {
             socket: NetlinkFramed::new(socket, NetlinkCodec::<NetlinkMessage<T>>::new()),
             protocol: Protocol::new(),
             requests_rx: Some(requests_rx),
             unsolicited_messages_tx: Some(unsolicited_messages_tx),
+            unsolicited_messages_limit,
             socket_closed: false,
-        })
+            request_timeout: None,
+            timer: None,
+            cancellations,
+            requests: HashMap::new(),
+            deadlines: BTreeSet::new(),
+            awaited_ack: None,
+        }
     }
 
-    pub fn socket_mut(&mut self) -> &mut Socket {
+    pub fn socket_mut(&mut self) -> &mut S {
         self.socket.get_mut()
     }
 
+    /// Set the workarounds for the malformed messages of the protocol, and what to do with the
+    /// datagrams that cannot be decoded. By default, decoding is strict, and the malformed
+    /// datagrams are logged and dropped.
+    pub fn set_decoder_quirks(&mut self, quirks: DecoderQuirks) {
+        self.socket.codec_mut().set_quirks(quirks);
+    }
+
+    /// Set the size of the buffer the datagrams are read into, 64kB by default. The datagrams
+    /// that are longer are truncated, and fail to be decoded. The datagrams of dumps are at most
+    /// 32kB long, but some messages can be longer, like the link messages of the devices with
+    /// many virtual functions.
+    pub fn set_reader_capacity(&mut self, capacity: usize) {
+        self.socket.set_reader_capacity(capacity);
+    }
+
+    /// Set the timeout of the requests sent with
+    /// [`ConnectionHandle::request`](struct.ConnectionHandle.html#method.request). When no
+    /// response is received in time, the request is dropped and the response stream ends with an
+    /// [`ErrorKind::Timeout`](enum.ErrorKind.html#variant.Timeout) error. By default, requests
+    /// never time out.
+    pub fn set_request_timeout(&mut self, timeout: Option<Duration>) {
+        self.request_timeout = timeout;
+    }
+
     pub fn poll_send_messages(&mut self, cx: &mut Context) {
         trace!("poll_send_messages called");
         let Connection {
             ref mut socket,
             ref mut protocol,
+            ref mut awaited_ack,
             ..
         } = self;
         let mut socket = Pin::new(socket);
 
-        while !protocol.outgoing_messages.is_empty() {
+        if let Some((sequence_number, addr)) = awaited_ack {
+            if protocol.is_awaiting_ack(*sequence_number, addr) {
+                trace!("waiting for the responses to the previous datagram of the batch");
+                return self.poll_flush(cx);
+            }
+            *awaited_ack = None;
+        }
+
+        while let Some((messages, addr)) = protocol.outgoing_messages.front_mut() {
             trace!("found outgoing message to send checking if socket is ready");
-            if let Poll::Ready(Err(e)) = Pin::as_mut(&mut socket).poll_ready(cx) {
-                // Sink errors are usually not recoverable. The socket
-                // probably shut down.
-                warn!("netlink socket shut down: {:?}", e);
-                self.socket_closed = true;
-                return;
+            match Pin::as_mut(&mut socket).poll_ready(cx) {
+                Poll::Ready(Ok(())) => {}
+                Poll::Ready(Err(e)) => {
+                    // Sink errors are usually not recoverable. The socket
+                    // probably shut down.
+                    warn!("netlink socket shut down: {:?}", e);
+                    self.socket_closed = true;
+                    return;
+                }
+                // The previous datagram is not sent yet
+                Poll::Pending => return,
             }
 
-            let (mut message, addr) = protocol.outgoing_messages.pop_front().unwrap();
-            message.finalize();
+            let limits = if messages.len() > 1 {
+                let socket = socket.get_ref();
+                DatagramLimits::new(
+                    socket.get_send_buffer_size(),
+                    socket.get_receive_buffer_size(),
+                )
+            } else {
+                DatagramLimits::default()
+            };
+            let count = datagram_len(messages, limits);
+            let addr = *addr;
+            let datagram: Vec<_> = messages.drain(..count).collect();
+            if messages.is_empty() {
+                protocol.outgoing_messages.pop_front();
+            }
 
-            trace!("sending outgoing message");
-            if let Err(e) = Pin::as_mut(&mut socket).start_send((message, addr)) {
-                error!("failed to send message: {:?}", e);
-                self.socket_closed = true;
-                return;
+            if count > 1 {
+                *awaited_ack = protocol
+                    .last_awaited_ack(&datagram, &addr)
+                    .map(|sequence_number| (sequence_number, addr));
+            }
+
+            trace!("sending {} outgoing message(s)", datagram.len());
+            for message in datagram {
+                if let Err(e) = Pin::as_mut(&mut socket).start_send((message, addr)) {
+                    error!("failed to send message: {:?}", e);
+                    self.socket_closed = true;
+                    return;
+                }
+            }
+
+            if awaited_ack.is_some() {
+                trace!("waiting for the responses to the datagram before sending more");
+                break;
             }
         }
 
@@ -117,18 +246,41 @@ where
 
     pub fn poll_read_messages(&mut self, cx: &mut Context) {
         trace!("poll_read_messages called");
-        let mut socket = Pin::new(&mut self.socket);
 
         loop {
+            if self.is_unsolicited_messages_queue_full() {
+                self.forward_unsolicited_messages(cx);
+                if self.is_unsolicited_messages_queue_full() {
+                    trace!("unsolicited messages queue full, not reading from the socket");
+                    return;
+                }
+            }
+
             trace!("polling socket");
-            match socket.as_mut().poll_next(cx) {
+            match Pin::new(&mut self.socket).poll_next(cx) {
                 Poll::Ready(Some((message, addr))) => {
                     trace!("read datagram from socket");
                     self.protocol.handle_message(message, addr);
+                    if self.is_unsolicited_messages_queue_over_limit() {
+                        // Only drop messages if the channel has no room for them either
+                        self.forward_unsolicited_messages(cx);
+                        self.drop_unsolicited_messages();
+                    }
                 }
                 Poll::Ready(None) => {
                     warn!("netlink socket stream shut down");
                     self.socket_closed = true;
+                    // Without this, the pending requests would look like they succeeded
+                    let error = self.socket.take_error();
+                    self.requests.clear();
+                    self.deadlines.clear();
+                    for responder in self.protocol.cancel_requests(|_| true) {
+                        let error = match error {
+                            Some(ref e) => copy_io_error(e),
+                            None => io::Error::from_raw_os_error(libc::EIO),
+                        };
+                        fail_request(responder, ErrorKind::SocketIo(error).into());
+                    }
                     return;
                 }
                 Poll::Pending => {
@@ -141,20 +293,81 @@ where
 
     pub fn poll_requests(&mut self, cx: &mut Context) {
         trace!("poll_requests called");
-        if let Some(mut stream) = self.requests_rx.as_mut() {
-            loop {
-                match Pin::new(&mut stream).poll_next(cx) {
-                    Poll::Ready(Some(request)) => self.protocol.request(request),
-                    Poll::Ready(None) => break,
-                    Poll::Pending => return,
+        while let Some(stream) = self.requests_rx.as_mut() {
+            match Pin::new(stream).poll_next(cx) {
+                Poll::Ready(Some(mut batch)) => {
+                    for request in batch.iter_mut() {
+                        if request.metadata.deadline.is_none() {
+                            request.metadata.deadline =
+                                self.request_timeout.map(|timeout| Instant::now() + timeout);
+                        }
+                    }
+                    for id in self.protocol.request_batch(batch) {
+                        self.track_request(id);
+                    }
+                }
+                Poll::Ready(None) => {
+                    let _ = self.requests_rx.take();
+                    trace!("no new requests to handle poll_requests done");
+                }
+                Poll::Pending => return,
+            }
+        }
+    }
+
+    /// Whether reading from the socket must wait until unsolicited messages are forwarded. One
+    /// message can always wait, so that a limit of 0 does not stop the reads for good.
+    fn is_unsolicited_messages_queue_full(&self) -> bool {
+        match self.unsolicited_messages_limit {
+            Some((limit, OverflowPolicy::Block)) => {
+                let len = self.protocol.incoming_requests.len();
+                self.unsolicited_messages_tx.is_some() && len > 0 && len >= limit
+            }
+            _ => false,
+        }
+    }
+
+    fn is_unsolicited_messages_queue_over_limit(&self) -> bool {
+        match self.unsolicited_messages_limit {
+            Some((limit, _)) => self.protocol.incoming_requests.len() > limit,
+            None => false,
+        }
+    }
+
+    /// Apply the overflow policy to the unsolicited messages that can't be forwarded. This is
+    /// called each time a message is read, so only the last message can exceed the limit.
+    fn drop_unsolicited_messages(&mut self) {
+        let queue = &mut self.protocol.incoming_requests;
+        match self.unsolicited_messages_limit {
+            Some((limit, OverflowPolicy::DropOldest)) if queue.len() > limit => {
+                warn!("unsolicited messages queue full, dropping the oldest message");
+                queue.pop_front();
+            }
+            Some((limit, OverflowPolicy::DropAndReport)) if queue.len() > limit => {
+                let is_marker = |message: Option<&(NetlinkMessage<T>, SocketAddr)>| match message {
+                    Some((message, _)) => matches!(message.payload, NetlinkPayload::Overrun(_)),
+                    None => false,
+                };
+                // The marker is allowed to exceed the limit.
+                if queue.len() == limit + 1 && is_marker(queue.back()) {
+                    return;
+                }
+                warn!("unsolicited messages queue full, dropping the new message");
+                queue.pop_back();
+                if !is_marker(queue.back()) {
+                    let mut marker = NetlinkMessage::new(
+                        NetlinkHeader::default(),
+                        NetlinkPayload::Overrun(vec![]),
+                    );
+                    marker.finalize();
+                    queue.push_back((marker, SocketAddr::new(0, 0)));
                 }
             }
-            let _ = self.requests_rx.take();
-            trace!("no new requests to handle poll_requests done");
+            _ => {}
         }
     }
 
-    pub fn forward_unsolicited_messages(&mut self) {
+    pub fn forward_unsolicited_messages(&mut self, cx: &mut Context) {
         if self.unsolicited_messages_tx.is_none() {
             while let Some((message, source)) = self.protocol.incoming_requests.pop_front() {
                 warn!(
@@ -166,35 +379,45 @@ where
         }
 
         trace!("forward_unsolicited_messages called");
-        let mut ready = false;
+        let mut closed = false;
 
         let Connection {
             ref mut protocol,
             ref mut unsolicited_messages_tx,
             ..
         } = self;
+        let tx = unsolicited_messages_tx.as_mut().unwrap();
 
-        while let Some((message, source)) = protocol.incoming_requests.pop_front() {
-            if unsolicited_messages_tx
-                .as_mut()
-                .unwrap()
-                .unbounded_send((message, source))
-                .is_err()
-            {
-                // The channel is unbounded so the only error that can
-                // occur is that the channel is closed because the
-                // receiver was dropped
-                warn!("failed to forward message to connection handle: channel closed");
-                ready = true;
+        while !protocol.incoming_requests.is_empty() {
+            match tx.poll_ready(cx) {
+                Poll::Ready(Ok(())) => {}
+                Poll::Ready(Err(())) => {
+                    closed = true;
+                    break;
+                }
+                Poll::Pending => {
+                    trace!("unsolicited messages channel full");
+                    break;
+                }
+            }
+            let message = protocol.incoming_requests.pop_front().unwrap();
+            if let Err(e) = tx.try_send(message) {
+                if e.is_disconnected() {
+                    closed = true;
+                    break;
+                }
+                // The channel was ready, so it can't be full
+                protocol.incoming_requests.push_front(e.into_inner());
                 break;
             }
         }
 
-        if ready {
-            // The channel is closed so we can drop the sender.
+        if closed {
+            // The channel is closed because the receiver was dropped, so we can drop the sender.
+            warn!("failed to forward message to connection handle: channel closed");
             let _ = self.unsolicited_messages_tx.take();
             // purge `protocol.incoming_requests`
-            self.forward_unsolicited_messages();
+            self.forward_unsolicited_messages(cx);
         }
 
         trace!("forward_unsolicited_messages done");
@@ -202,47 +425,83 @@ where
 
     pub fn forward_responses(&mut self) {
         trace!("forward_responses called");
-        let protocol = &mut self.protocol;
-
-        while let Some(response) = protocol.incoming_responses.pop_front() {
-            let Response {
-                message,
-                done,
-                metadata: tx,
-            } = response;
-            if done {
-                use NetlinkPayload::*;
-                match &message.payload {
-                    // Since `self.protocol` set the `done` flag here,
-                    // we know it has already dropped the request and
-                    // its associated metadata, ie the UnboundedSender
-                    // used to forward messages back to the
-                    // ConnectionHandle. By just continuing we're
-                    // dropping the last instance of that sender,
-                    // hence closing the channel and signaling the
-                    // handle that no more messages are expected.
-                    Noop | Done | Ack(_) => {
-                        trace!("not forwarding Noop/Ack/Done message to the handle");
-                        continue;
-                    }
-                    // I'm not sure how we should handle overrun messages
-                    Overrun(_) => unimplemented!("overrun is not handled yet"),
-                    // We need to forward error messages and messages
-                    // that are part of the netlink subprotocol,
-                    // because only the user knows how they want to
-                    // handle them.
-                    Error(_) | InnerMessage(_) => {}
-                }
+        while let Some(response) = self.protocol.incoming_responses.pop_front() {
+            if response.done {
+                self.untrack_request(&response.metadata);
+            }
+            forward_response(response);
+        }
+        trace!("forward_responses done");
+    }
+
+    /// Keep track of a request that was just registered, so that it can be cancelled when its
+    /// response stream is dropped or when it times out.
+    fn track_request(&mut self, id: RequestId) {
+        let responder = match self.protocol.pending_request(&id) {
+            Some(responder) => responder,
+            None => return,
+        };
+        // The stream may have been dropped before the request was registered, in which case
+        // its token was already reported.
+        if responder.tx.is_closed() {
+            self.protocol.cancel_request(&id);
+            return;
+        }
+        if let Some(deadline) = responder.deadline {
+            self.deadlines.insert((deadline, responder.token));
+        }
+        self.requests.insert(responder.token, id);
+    }
+
+    fn untrack_request(&mut self, responder: &Responder<T>) {
+        self.requests.remove(&responder.token);
+        if let Some(deadline) = responder.deadline {
+            self.deadlines.remove(&(deadline, responder.token));
+        }
+    }
+
+    /// Cancel the pending request of the given responder token, and return its responder.
+    fn cancel_request(&mut self, token: u64) -> Option<Responder<T>> {
+        let id = self.requests.remove(&token)?;
+        let responder = self.protocol.cancel_request(&id)?;
+        self.untrack_request(&responder);
+        Some(responder)
+    }
+
+    /// Drop the requests whose response stream was dropped, and the requests that timed out.
+    pub fn poll_expired_requests(&mut self, cx: &mut Context) {
+        trace!("poll_expired_requests called");
+        for token in self.cancellations.take_dropped() {
+            self.cancel_request(token);
+        }
+
+        let now = Instant::now();
+        while let Some(&(deadline, token)) = self.deadlines.first() {
+            if deadline > now {
+                break;
             }
+            self.deadlines.pop_first();
+            if let Some(responder) = self.cancel_request(token) {
+                warn!("netlink request timed out");
+                fail_request(responder, ErrorKind::Timeout.into());
+            }
+        }
 
-            trace!("forwarding response to the handle");
-            if tx.unbounded_send(message).is_err() {
-                // With an unboundedsender, an error can
-                // only happen if the receiver is closed.
-                warn!("failed to forward response back to the handle");
+        match self.deadlines.first() {
+            Some(&(deadline, _)) => {
+                if self.timer.as_ref().map(|(d, _)| *d) != Some(deadline) {
+                    let delay = Delay::new(deadline.saturating_duration_since(now));
+                    self.timer = Some((deadline, delay));
+                }
+                let (_, delay) = self.timer.as_mut().unwrap();
+                if Pin::new(delay).poll(cx).is_ready() {
+                    // Some requests expired in the meantime
+                    cx.waker().wake_by_ref();
+                }
             }
+            None => self.timer = None,
         }
-        trace!("forward_responses done");
+        trace!("poll_expired_requests done");
     }
 
     pub fn should_shut_down(&self) -> bool {
@@ -250,21 +509,23 @@ where
     }
 }
 
-impl<T> Future for Connection<T>
+impl<T, S> Future for Connection<T, S>
 where
     T: Debug + Clone + PartialEq + Eq + NetlinkSerializable<T> + NetlinkDeserializable<T> + Unpin,
+    S: AsyncNetlinkSocket,
 {
     type Output = ();
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
         trace!("polling Connection");
         let pinned = self.get_mut();
+        pinned.cancellations.register(cx.waker());
 
         debug!("reading incoming messages");
         pinned.poll_read_messages(cx);
 
         debug!("forwarding unsolicited messages to the connection handle");
-        pinned.forward_unsolicited_messages();
+        pinned.forward_unsolicited_messages(cx);
 
         debug!("forwaring responses to previous requests to the connection handle");
         pinned.forward_responses();
@@ -272,6 +533,9 @@ where
         debug!("handling requests");
         pinned.poll_requests(cx);
 
+        debug!("dropping cancelled and expired requests");
+        pinned.poll_expired_requests(cx);
+
         debug!("sending messages");
         pinned.poll_send_messages(cx);
 
@@ -284,3 +548,547 @@ where
         }
     }
 }
+
+/// Forward a response to the handle that sent the request. The messages that mark the end of the
+/// response are not forwarded, except errors and the end of interrupted dumps.
+pub(crate) fn forward_response<T>(response: Response<T, Responder<T>>)
+where
+    T: Debug + PartialEq + Eq + Clone + NetlinkSerializable<T> + NetlinkDeserializable<T>,
+{
+    let Response {
+        message,
+        done,
+        metadata,
+    } = response;
+    if done {
+        use NetlinkPayload::*;
+        match &message.payload {
+            // Since `self.protocol` set the `done` flag here,
+            // we know it has already dropped the request and
+            // its associated metadata, ie the UnboundedSender
+            // used to forward messages back to the
+            // ConnectionHandle. By just returning we're
+            // dropping the last instance of that sender,
+            // hence closing the channel and signaling the
+            // handle that no more messages are expected.
+            //
+            // The end of an interrupted dump is the exception: the
+            // handle needs to know that the dump is inconsistent.
+            Done if message.header.flags & NLM_F_DUMP_INTR == NLM_F_DUMP_INTR => {
+                trace!("forwarding the end of an interrupted dump to the handle");
+            }
+            Noop | Done | Ack(_) => {
+                trace!("not forwarding Noop/Ack/Done message to the handle");
+                return;
+            }
+            // Part of the response was lost: the request fails as if the socket overran.
+            Overrun(_) => {
+                warn!("overrun while receiving a response, failing the request");
+                let error = io::Error::from_raw_os_error(libc::ENOBUFS);
+                fail_request(metadata, ErrorKind::SocketIo(error).into());
+                return;
+            }
+            // We need to forward error messages and messages
+            // that are part of the netlink subprotocol,
+            // because only the user knows how they want to
+            // handle them.
+            Error(_) | InnerMessage(_) => {}
+        }
+    }
+
+    trace!("forwarding response to the handle");
+    if metadata.tx.unbounded_send(Ok(message)).is_err() {
+        // With an unboundedsender, an error can
+        // only happen if the receiver is closed.
+        warn!("failed to forward response back to the handle");
+    }
+}
+
+/// Rough size that a response takes in the socket receive buffer, on top of the request message
+/// that error messages echo back.
+const RESPONSE_OVERHEAD: usize = 1024;
+
+/// How many messages of a batch can be sent in a single datagram: the datagram must be accepted
+/// by the kernel, and the responses to its messages must fit in the socket receive buffer, since
+/// the kernel processes the whole datagram before they can be read. The default limits send one
+/// message per datagram.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct DatagramLimits {
+    len: usize,
+    responses_len: usize,
+}
+
+impl DatagramLimits {
+    /// Limits for the given sizes of the socket send and receive buffers. If they are unknown,
+    /// the messages are sent one per datagram.
+    pub(crate) fn new(
+        send_buffer_size: io::Result<usize>,
+        receive_buffer_size: io::Result<usize>,
+    ) -> Self {
+        match (send_buffer_size, receive_buffer_size) {
+            (Ok(send_buffer_size), Ok(receive_buffer_size)) => DatagramLimits {
+                // See netlink_sendmsg() in net/netlink/af_netlink.c
+                len: send_buffer_size.saturating_sub(32),
+                // Leave room for the other messages received in the meantime
+                responses_len: receive_buffer_size / 2,
+            },
+            (Err(e), _) | (_, Err(e)) => {
+                warn!("failed to get the size of the socket buffers: {}", e);
+                DatagramLimits::default()
+            }
+        }
+    }
+}
+
+/// Finalize the first messages and return how many of them fit in a datagram, given `limits`.
+/// This is at least one, so that a message that is too big fails to be sent instead of being
+/// stuck.
+pub(crate) fn datagram_len<T>(messages: &mut [NetlinkMessage<T>], limits: DatagramLimits) -> usize
+where
+    T: Debug + PartialEq + Eq + Clone + NetlinkSerializable<T>,
+{
+    let mut len = 0;
+    let mut responses_len = 0;
+    for (i, message) in messages.iter_mut().enumerate() {
+        message.finalize();
+        // Each message is aligned on 4 bytes
+        len = ((len + 3) & !3) + message.buffer_len();
+        responses_len += message.buffer_len() + RESPONSE_OVERHEAD;
+        if i > 0 && (len > limits.len || responses_len > limits.responses_len) {
+            return i;
+        }
+    }
+    messages.len()
+}
+
+/// End the response stream of a request with an error.
+fn fail_request<T>(responder: Responder<T>, error: Error<T>)
+where
+    T: Debug + PartialEq + Eq + Clone + NetlinkSerializable<T> + NetlinkDeserializable<T>,
+{
+    // Dropping the sender right after closes the response stream.
+    let _ = responder.tx.unbounded_send(Err(error));
+}
+
+/// `io::Error` is not `Clone`: copy the error code, or the kind and description.
+fn copy_io_error(error: &io::Error) -> io::Error {
+    match error.raw_os_error() {
+        Some(code) => io::Error::from_raw_os_error(code),
+        None => io::Error::new(error.kind(), error.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::thread;
+
+    use futures::{channel::mpsc::UnboundedReceiver, task::noop_waker_ref, FutureExt, StreamExt};
+    use netlink_packet_core::{ErrorMessage, NetlinkBuffer, NLM_F_ACK, NLM_F_MULTIPART};
+    use netlink_packet_route::{LinkMessage, RtnlMessage, NLM_F_REQUEST};
+    use netlink_sys::MemorySocket;
+
+    use super::*;
+    use crate::{
+        new_connection_with_socket,
+        sys::protocols::NETLINK_ROUTE,
+        ConnectionBuilder,
+        ConnectionHandle,
+        UnsolicitedMessages,
+    };
+
+    type Message = NetlinkMessage<RtnlMessage>;
+
+    #[allow(clippy::type_complexity)]
+    fn connect() -> (
+        Connection<RtnlMessage, MemorySocket>,
+        ConnectionHandle<RtnlMessage>,
+        UnboundedReceiver<(Message, SocketAddr)>,
+        MemorySocket,
+    ) {
+        let (user, kernel) = MemorySocket::pair(SocketAddr::new(1, 0), SocketAddr::new(0, 0));
+        let (connection, handle, messages) = new_connection_with_socket(user);
+        (connection, handle, messages, kernel)
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn build(
+        builder: ConnectionBuilder<RtnlMessage>,
+    ) -> (
+        Connection<RtnlMessage, MemorySocket>,
+        ConnectionHandle<RtnlMessage>,
+        UnsolicitedMessages<RtnlMessage>,
+        MemorySocket,
+    ) {
+        let (user, kernel) = MemorySocket::pair(SocketAddr::new(1, 0), SocketAddr::new(0, 0));
+        let (connection, handle, messages) = builder.build_with_socket(user);
+        (connection, handle, messages, kernel)
+    }
+
+    /// Poll the connection once: the requests of the handles and the datagrams of the kernel
+    /// are all available, so this handles all of them.
+    fn poll(connection: &mut Connection<RtnlMessage, MemorySocket>) {
+        let mut cx = Context::from_waker(noop_waker_ref());
+        assert!(Pin::new(connection).poll(&mut cx).is_pending());
+    }
+
+    fn request() -> Message {
+        let mut request = Message::from(RtnlMessage::GetLink(LinkMessage::default()));
+        request.header.flags = NLM_F_REQUEST;
+        request
+    }
+
+    /// Return the request received by the kernel.
+    fn receive(kernel: &mut MemorySocket) -> Message {
+        let (datagram, _) = kernel.try_recv().unwrap().expect("no request was sent");
+        Message::deserialize(&datagram).unwrap()
+    }
+
+    /// Return the messages of the next datagram received by the kernel, if any.
+    fn receive_datagram(kernel: &mut MemorySocket) -> Option<Vec<Message>> {
+        let (datagram, _) = kernel.try_recv().unwrap()?;
+        let mut messages = vec![];
+        let mut offset = 0;
+        while offset < datagram.len() {
+            let len = NetlinkBuffer::new_checked(&datagram[offset..])
+                .unwrap()
+                .length() as usize;
+            messages.push(Message::deserialize(&datagram[offset..offset + len]).unwrap());
+            offset += (len + 3) & !3;
+        }
+        Some(messages)
+    }
+
+    /// Acknowledge `request`.
+    fn ack(kernel: &mut MemorySocket, request: &Message) {
+        let mut header = NetlinkHeader::default();
+        header.sequence_number = request.header.sequence_number;
+        let payload = NetlinkPayload::Ack(ErrorMessage {
+            code: 0,
+            header: vec![0; 16],
+        });
+        let mut message = Message::new(header, payload);
+        message.finalize();
+        let mut datagram = vec![0; message.buffer_len()];
+        message.serialize(&mut datagram);
+        kernel.send(&datagram).unwrap();
+    }
+
+    /// Send a part of a multipart response to `request`.
+    fn respond(kernel: &mut MemorySocket, request: &Message) {
+        let mut header = NetlinkHeader::default();
+        header.sequence_number = request.header.sequence_number;
+        header.flags = NLM_F_MULTIPART;
+        let payload = NetlinkPayload::InnerMessage(RtnlMessage::NewLink(LinkMessage::default()));
+        let mut message = NetlinkMessage::new(header, payload);
+        message.finalize();
+        let mut datagram = vec![0; message.buffer_len()];
+        message.serialize(&mut datagram);
+        kernel.send(&datagram).unwrap();
+    }
+
+    /// Send a link message with each of the given indexes, one per datagram, that are not
+    /// responses to a request.
+    fn notify(kernel: &mut MemorySocket, indexes: impl Iterator<Item = u32>) {
+        for index in indexes {
+            let mut link = LinkMessage::default();
+            link.header.index = index;
+            let mut message = Message::from(RtnlMessage::NewLink(link));
+            message.finalize();
+            let mut datagram = vec![0; message.buffer_len()];
+            message.serialize(&mut datagram);
+            kernel.send(&datagram).unwrap();
+        }
+    }
+
+    /// Receive the unsolicited messages until there is none left, polling the connection in
+    /// between, and return their link index, or `None` for the overrun markers.
+    fn receive_unsolicited(
+        connection: &mut Connection<RtnlMessage, MemorySocket>,
+        messages: &mut UnsolicitedMessages<RtnlMessage>,
+    ) -> Vec<Option<u32>> {
+        let mut indexes = vec![];
+        loop {
+            poll(connection);
+            match messages.next().now_or_never() {
+                Some(Some((message, _))) => indexes.push(match message.payload {
+                    NetlinkPayload::InnerMessage(RtnlMessage::NewLink(link)) => {
+                        Some(link.header.index)
+                    }
+                    NetlinkPayload::Overrun(_) => None,
+                    payload => panic!("unexpected message: {:?}", payload),
+                }),
+                _ => return indexes,
+            }
+        }
+    }
+
+    fn assert_timed_out<S>(responses: &mut S)
+    where
+        S: Stream<Item = Result<Message, Error<RtnlMessage>>> + Unpin,
+    {
+        match responses.next().now_or_never() {
+            Some(Some(Err(e))) => assert!(matches!(e.kind(), ErrorKind::Timeout)),
+            other => panic!("expected a timeout, got {:?}", other),
+        }
+        assert!(matches!(responses.next().now_or_never(), Some(None)));
+    }
+
+    #[test]
+    fn request_times_out() {
+        let (mut connection, mut handle, mut messages, mut kernel) = connect();
+        connection.set_request_timeout(Some(Duration::from_millis(10)));
+
+        let mut responses = handle.request(request(), SocketAddr::new(0, 0)).unwrap();
+        poll(&mut connection);
+        let request = receive(&mut kernel);
+        assert!(responses.next().now_or_never().is_none());
+
+        thread::sleep(Duration::from_millis(20));
+        poll(&mut connection);
+        assert_timed_out(&mut responses);
+        assert!(connection.requests.is_empty());
+        assert!(connection.deadlines.is_empty());
+
+        // The responses that arrive late are discarded
+        respond(&mut kernel, &request);
+        poll(&mut connection);
+        assert!(messages.try_recv().is_err());
+    }
+
+    #[test]
+    fn request_with_timeout_overrides_default() {
+        let (mut connection, mut handle, _messages, mut kernel) = connect();
+        connection.set_request_timeout(Some(Duration::from_secs(3600)));
+
+        let mut responses = handle.request(request(), SocketAddr::new(0, 0)).unwrap();
+        let mut short = handle
+            .request_with_timeout(request(), SocketAddr::new(0, 0), Duration::from_millis(10))
+            .unwrap();
+        poll(&mut connection);
+        let first = receive(&mut kernel);
+        receive(&mut kernel);
+
+        thread::sleep(Duration::from_millis(20));
+        poll(&mut connection);
+        assert_timed_out(&mut short);
+        assert_eq!(connection.deadlines.len(), 1);
+
+        respond(&mut kernel, &first);
+        poll(&mut connection);
+        assert!(matches!(responses.next().now_or_never(), Some(Some(Ok(_)))));
+    }
+
+    #[test]
+    fn dropped_stream_cancels_request() {
+        let (mut connection, mut handle, mut messages, mut kernel) = connect();
+        connection.set_request_timeout(Some(Duration::from_secs(3600)));
+
+        let responses = handle.request(request(), SocketAddr::new(0, 0)).unwrap();
+        poll(&mut connection);
+        let request = receive(&mut kernel);
+        assert_eq!(connection.requests.len(), 1);
+
+        drop(responses);
+        poll(&mut connection);
+        assert!(connection.requests.is_empty());
+        assert!(connection.deadlines.is_empty());
+        assert_eq!(connection.protocol.pending_requests().count(), 0);
+
+        // The responses that arrive after the stream was dropped are discarded
+        respond(&mut kernel, &request);
+        poll(&mut connection);
+        assert!(messages.try_recv().is_err());
+    }
+
+    #[test]
+    fn stream_dropped_before_the_request_is_sent() {
+        let (mut connection, mut handle, _messages, mut kernel) = connect();
+
+        drop(handle.request(request(), SocketAddr::new(0, 0)).unwrap());
+        poll(&mut connection);
+        receive(&mut kernel);
+        assert!(connection.requests.is_empty());
+        assert_eq!(connection.protocol.pending_requests().count(), 0);
+    }
+
+    #[test]
+    fn block_stops_reading() {
+        let builder = ConnectionBuilder::new(NETLINK_ROUTE)
+            .unsolicited_messages_capacity(2, OverflowPolicy::Block);
+        let (mut connection, _handle, mut messages, mut kernel) = build(builder);
+
+        notify(&mut kernel, 1..=6);
+        poll(&mut connection);
+        // One message is in the channel, two wait in the connection, and the other ones are not
+        // read yet
+        assert_eq!(connection.protocol.incoming_requests.len(), 2);
+
+        let expected: Vec<_> = (1..=6).map(Some).collect();
+        assert_eq!(
+            receive_unsolicited(&mut connection, &mut messages),
+            expected
+        );
+    }
+
+    #[test]
+    fn block_with_no_capacity() {
+        let builder = ConnectionBuilder::new(NETLINK_ROUTE)
+            .unsolicited_messages_capacity(0, OverflowPolicy::Block);
+        let (mut connection, _handle, mut messages, mut kernel) = build(builder);
+
+        notify(&mut kernel, 1..=3);
+        poll(&mut connection);
+        assert_eq!(connection.protocol.incoming_requests.len(), 1);
+
+        let expected: Vec<_> = (1..=3).map(Some).collect();
+        assert_eq!(
+            receive_unsolicited(&mut connection, &mut messages),
+            expected
+        );
+    }
+
+    #[test]
+    fn drop_oldest() {
+        let builder = ConnectionBuilder::new(NETLINK_ROUTE)
+            .unsolicited_messages_capacity(2, OverflowPolicy::DropOldest);
+        let (mut connection, _handle, mut messages, mut kernel) = build(builder);
+
+        notify(&mut kernel, 1..=6);
+        // The first message went to the channel, and only the last two others were kept
+        assert_eq!(
+            receive_unsolicited(&mut connection, &mut messages),
+            vec![Some(1), Some(5), Some(6)]
+        );
+    }
+
+    #[test]
+    fn drop_and_report() {
+        let builder = ConnectionBuilder::new(NETLINK_ROUTE)
+            .unsolicited_messages_capacity(2, OverflowPolicy::DropAndReport);
+        let (mut connection, _handle, mut messages, mut kernel) = build(builder);
+
+        notify(&mut kernel, 1..=6);
+        // The loss is reported once, after the messages that were kept
+        assert_eq!(
+            receive_unsolicited(&mut connection, &mut messages),
+            vec![Some(1), Some(2), Some(3), None]
+        );
+
+        // Once there is room again, the new messages are kept
+        notify(&mut kernel, 7..=8);
+        assert_eq!(
+            receive_unsolicited(&mut connection, &mut messages),
+            vec![Some(7), Some(8)]
+        );
+    }
+
+    #[test]
+    fn request_waits_for_room_in_the_queue() {
+        let builder = ConnectionBuilder::new(NETLINK_ROUTE).requests_capacity(1);
+        let (mut connection, mut handle, _messages, mut kernel) = build(builder);
+        let kernel_address = SocketAddr::new(0, 0);
+
+        // The handle can queue one request on top of the capacity
+        let _first = handle.request(request(), kernel_address).unwrap();
+        let _second = handle.request(request(), kernel_address).unwrap();
+        match handle.request(request(), kernel_address) {
+            Err(e) => assert!(matches!(e.kind(), ErrorKind::RequestQueueFull)),
+            Ok(_) => panic!("the requests queue should be full"),
+        }
+        assert!(handle
+            .request_when_ready(request(), kernel_address)
+            .now_or_never()
+            .is_none());
+
+        poll(&mut connection);
+        let _third = handle
+            .request_when_ready(request(), kernel_address)
+            .now_or_never()
+            .expect("the requests queue should have room")
+            .unwrap();
+        poll(&mut connection);
+        for _ in 0..3 {
+            receive(&mut kernel);
+        }
+    }
+
+    #[test]
+    fn datagram_limits() {
+        let limits = DatagramLimits::new(Ok(1000), Ok(8000));
+        assert_eq!((limits.len, limits.responses_len), (968, 4000));
+
+        // Unknown buffer sizes send one message per datagram
+        let error = || Err(io::Error::from_raw_os_error(libc::EBADF));
+        let limits = DatagramLimits::new(error(), Ok(8000));
+        assert_eq!((limits.len, limits.responses_len), (0, 0));
+        let limits = DatagramLimits::new(Ok(1000), error());
+        assert_eq!((limits.len, limits.responses_len), (0, 0));
+    }
+
+    #[test]
+    fn datagram_len_packs_messages() {
+        let mut messages = vec![request(); 5];
+        let message_len = {
+            messages[0].finalize();
+            messages[0].buffer_len()
+        };
+        let unlimited = usize::MAX;
+
+        assert_eq!(datagram_len(&mut messages, DatagramLimits::default()), 1);
+        // Limited by the length of the datagram
+        let limits = DatagramLimits {
+            len: 3 * message_len,
+            responses_len: unlimited,
+        };
+        assert_eq!(datagram_len(&mut messages, limits), 3);
+        // Limited by the room the responses take
+        let limits = DatagramLimits {
+            len: unlimited,
+            responses_len: 2 * (message_len + RESPONSE_OVERHEAD),
+        };
+        assert_eq!(datagram_len(&mut messages, limits), 2);
+        let limits = DatagramLimits {
+            len: unlimited,
+            responses_len: unlimited,
+        };
+        assert_eq!(datagram_len(&mut messages, limits), 5);
+        // A message that is too big is sent on its own
+        let limits = DatagramLimits {
+            len: 1,
+            responses_len: 1,
+        };
+        assert_eq!(datagram_len(&mut messages, limits), 1);
+    }
+
+    #[test]
+    fn batch_waits_for_acks() {
+        let (mut connection, mut handle, _messages, mut kernel) = connect();
+        let mut acked = request();
+        acked.header.flags |= NLM_F_ACK;
+        let count = 250;
+        let _responses = handle
+            .batch_request(vec![acked; count], SocketAddr::new(0, 0))
+            .unwrap();
+
+        poll(&mut connection);
+        let first = receive_datagram(&mut kernel).unwrap();
+        assert!(first.len() > 1 && first.len() < count);
+        assert!(receive_datagram(&mut kernel).is_none());
+
+        // The next datagram waits for the ack of the last message of the first one
+        for message in &first[..first.len() - 1] {
+            ack(&mut kernel, message);
+        }
+        poll(&mut connection);
+        assert!(receive_datagram(&mut kernel).is_none());
+
+        ack(&mut kernel, first.last().unwrap());
+        poll(&mut connection);
+        let second = receive_datagram(&mut kernel).unwrap();
+        assert_eq!(
+            second[0].header.sequence_number,
+            first.last().unwrap().header.sequence_number + 1
+        );
+        assert!(receive_datagram(&mut kernel).is_none());
+    }
+}
@@ -35,6 +35,12 @@ where
     /// The netlink connection is closed
     ConnectionClosed,
 
+    /// The requests queue of the netlink connection is full
+    RequestQueueFull,
+
+    /// No response was received before the deadline of the request
+    Timeout,
+
     /// Received an error message as a response
     NetlinkError(NetlinkMessage<T>),
 
@@ -58,9 +64,17 @@ where
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use crate::ErrorKind::*;
         match self.kind() {
-            SocketIo(ref e) => write!(f, "{}: {}", self, e),
-            ConnectionClosed => write!(f, "{}", self),
-            NetlinkError(ref message) => write!(f, "{}: {:?}", self, message),
+            SocketIo(ref e) => write!(
+                f,
+                "Error while reading from or writing to the netlink socket: {}",
+                e
+            ),
+            ConnectionClosed => write!(f, "The netlink connection is closed"),
+            RequestQueueFull => write!(f, "The requests queue of the netlink connection is full"),
+            Timeout => write!(f, "The netlink request timed out"),
+            NetlinkError(ref message) => {
+                write!(f, "Received an error message as a response: {:?}", message)
+            }
         }
     }
 }
@@ -74,6 +88,8 @@ where
         match self.kind() {
             SocketIo(_) => "Error while reading from or writing to the netlink socket",
             ConnectionClosed => "The netlink connection is closed",
+            RequestQueueFull => "The requests queue of the netlink connection is full",
+            Timeout => "The netlink request timed out",
             NetlinkError(_) => "Received an error message as a response",
         }
     }
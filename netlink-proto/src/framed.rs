@@ -10,21 +10,26 @@ use futures::{Sink, Stream};
 use log::error;
 use tokio_util::codec::{Decoder, Encoder};
 
-use crate::sys::{Socket, SocketAddr};
+use crate::sys::{AsyncNetlinkSocket, SocketAddr};
 
-pub struct NetlinkFramed<C> {
-    socket: Socket,
+pub struct NetlinkFramed<C, S> {
+    socket: S,
     codec: C,
     reader: BytesMut,
+    /// Room made in `reader` for each datagram, which bounds the size of the datagrams
+    reader_capacity: usize,
     writer: BytesMut,
     in_addr: SocketAddr,
     out_addr: SocketAddr,
     flushed: bool,
+    /// Error that ended the stream
+    error: Option<io::Error>,
 }
 
-impl<C> Stream for NetlinkFramed<C>
+impl<C, S> Stream for NetlinkFramed<C, S>
 where
     C: Decoder + Unpin,
+    S: AsyncNetlinkSocket,
     C::Error: std::error::Error,
 {
     type Item = (C::Item, SocketAddr);
@@ -35,6 +40,8 @@ where
             ref mut socket,
             ref mut in_addr,
             ref mut reader,
+            ref reader_capacity,
+            ref mut error,
             ..
         } = Pin::get_mut(self);
 
@@ -44,12 +51,13 @@ where
                 Ok(None) => {}
                 Err(e) => {
                     error!("unrecoverable error in decoder: {:?}", e);
+                    *error = Some(io::Error::new(io::ErrorKind::InvalidData, e.to_string()));
                     return Poll::Ready(None);
                 }
             }
 
             reader.clear();
-            reader.reserve(INITIAL_READER_CAPACITY);
+            reader.reserve(*reader_capacity);
 
             *in_addr = unsafe {
                 // Read into the buffer without having to initialize the memory.
@@ -66,6 +74,7 @@ where
                     }
                     Err(e) => {
                         error!("failed to read from netlink socket: {:?}", e);
+                        *error = Some(e);
                         return Poll::Ready(None);
                     }
                 }
@@ -74,7 +83,11 @@ where
     }
 }
 
-impl<C: Encoder<Item> + Unpin, Item> Sink<(Item, SocketAddr)> for NetlinkFramed<C> {
+impl<C, S, Item> Sink<(Item, SocketAddr)> for NetlinkFramed<C, S>
+where
+    C: Encoder<Item> + Unpin,
+    S: AsyncNetlinkSocket,
+{
     type Error = C::Error;
 
     fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
@@ -144,22 +157,42 @@ impl<C: Encoder<Item> + Unpin, Item> Sink<(Item, SocketAddr)> for NetlinkFramed<
 const INITIAL_READER_CAPACITY: usize = 64 * 1024;
 const INITIAL_WRITER_CAPACITY: usize = 8 * 1024;
 
-impl<C> NetlinkFramed<C> {
+impl<C, S> NetlinkFramed<C, S> {
     /// Create a new `NetlinkFramed` backed by the given socket and codec.
     ///
     /// See struct level documentation for more details.
-    pub fn new(socket: Socket, codec: C) -> NetlinkFramed<C> {
+    pub fn new(socket: S, codec: C) -> NetlinkFramed<C, S> {
         NetlinkFramed {
             socket,
             codec,
             out_addr: SocketAddr::new(0, 0),
             in_addr: SocketAddr::new(0, 0),
             reader: BytesMut::with_capacity(INITIAL_READER_CAPACITY),
+            reader_capacity: INITIAL_READER_CAPACITY,
             writer: BytesMut::with_capacity(INITIAL_WRITER_CAPACITY),
             flushed: true,
+            error: None,
         }
     }
 
+    /// Set the size of the buffer the datagrams are read into. The datagrams that are longer
+    /// are truncated, and fail to be decoded.
+    pub fn set_reader_capacity(&mut self, capacity: usize) {
+        self.reader_capacity = capacity;
+        self.reader.reserve(capacity);
+    }
+
+    /// Returns a mutable reference to the codec.
+    pub fn codec_mut(&mut self) -> &mut C {
+        &mut self.codec
+    }
+
+    /// Take the error that ended the stream: the error of the socket, or the error of the
+    /// codec, as an [`io::ErrorKind::InvalidData`] error.
+    pub fn take_error(&mut self) -> Option<io::Error> {
+        self.error.take()
+    }
+
     /// Returns a reference to the underlying I/O stream wrapped by `Framed`.
     ///
     /// # Note
@@ -167,7 +200,7 @@ impl<C> NetlinkFramed<C> {
     /// Care should be taken to not tamper with the underlying stream of data
     /// coming in as it may corrupt the stream of frames otherwise being worked
     /// with.
-    pub fn get_ref(&self) -> &Socket {
+    pub fn get_ref(&self) -> &S {
         &self.socket
     }
 
@@ -179,12 +212,12 @@ impl<C> NetlinkFramed<C> {
     /// Care should be taken to not tamper with the underlying stream of data
     /// coming in as it may corrupt the stream of frames otherwise being worked
     /// with.
-    pub fn get_mut(&mut self) -> &mut Socket {
+    pub fn get_mut(&mut self) -> &mut S {
         &mut self.socket
     }
 
     /// Consumes the `Framed`, returning its underlying I/O stream.
-    pub fn into_inner(self) -> Socket {
+    pub fn into_inner(self) -> S {
         self.socket
     }
 }
@@ -1,31 +1,116 @@
 use futures::{
-    channel::mpsc::{unbounded, UnboundedSender},
+    channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+    future::poll_fn,
+    task::AtomicWaker,
     Stream,
 };
 use netlink_packet_core::NetlinkMessage;
-use std::fmt::Debug;
+use std::{
+    fmt::Debug,
+    mem,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+        Mutex,
+    },
+    task::{Context, Poll, Waker},
+    time::{Duration, Instant},
+};
 
 use crate::{
+    channel::Sender,
     errors::{Error, ErrorKind},
     sys::SocketAddr,
+    Batch,
     Request,
 };
 
+/// An item of a response stream: a message from the kernel, or the error that ended the stream.
+pub(crate) type Response<T> = Result<NetlinkMessage<T>, Error<T>>;
+
+/// Metadata associated by the [`Connection`](struct.Connection.html) to each pending request:
+/// the channel used to forward the responses to the handle, the time after which the request
+/// times out, and the token the response stream reports when it is dropped.
+#[derive(Clone, Debug)]
+pub(crate) struct Responder<T>
+where
+    T: Debug + Clone + Eq + PartialEq,
+{
+    pub tx: UnboundedSender<Response<T>>,
+    pub deadline: Option<Instant>,
+    pub token: u64,
+}
+
+/// State shared by a connection and its handles, through which the response streams that are
+/// dropped report the token of their request, so that the connection cancels it without going
+/// through all the pending requests.
+#[derive(Debug, Default)]
+pub(crate) struct Cancellations {
+    /// Woken up when a response stream is dropped
+    waker: AtomicWaker,
+    /// Tokens of the requests whose response stream was dropped
+    dropped: Mutex<Vec<u64>>,
+    /// Token of the next request
+    next_token: AtomicU64,
+}
+
+impl Cancellations {
+    pub fn next_token(&self) -> u64 {
+        self.next_token.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Register the waker of the task that runs the connection.
+    pub fn register(&self, waker: &Waker) {
+        self.waker.register(waker);
+    }
+
+    /// Take the tokens of the requests whose response stream was dropped since the last call.
+    pub fn take_dropped(&self) -> Vec<u64> {
+        mem::take(&mut *self.dropped.lock().unwrap())
+    }
+
+    fn report_dropped(&self, token: u64) {
+        self.dropped.lock().unwrap().push(token);
+        self.waker.wake();
+    }
+}
+
 /// A handle to pass requests to a [`Connection`](struct.Connection.html).
 #[derive(Clone, Debug)]
 pub struct ConnectionHandle<T>
 where
     T: Debug + Clone + Eq + PartialEq,
 {
-    requests_tx: UnboundedSender<Request<T>>,
+    requests_tx: Sender<Batch<T>>,
+    /// Used to cancel the requests whose response stream is dropped
+    cancellations: Arc<Cancellations>,
 }
 
 impl<T> ConnectionHandle<T>
 where
     T: Debug + Clone + Eq + PartialEq,
 {
-    pub(crate) fn new(requests_tx: UnboundedSender<Request<T>>) -> Self {
-        ConnectionHandle { requests_tx }
+    pub(crate) fn new(requests_tx: Sender<Batch<T>>, cancellations: Arc<Cancellations>) -> Self {
+        ConnectionHandle {
+            requests_tx,
+            cancellations,
+        }
+    }
+
+    /// Wait until the requests queue of the connection has room for a new request. This is only
+    /// needed if the queue is bounded (see
+    /// [`ConnectionBuilder::requests_capacity`](struct.ConnectionBuilder.html#method.requests_capacity)):
+    /// otherwise, the queue is always ready.
+    pub fn poll_ready(&mut self, cx: &mut Context) -> Poll<Result<(), Error<T>>> {
+        self.requests_tx
+            .poll_ready(cx)
+            .map_err(|_| ErrorKind::ConnectionClosed.into())
+    }
+
+    /// Like [`poll_ready`](#method.poll_ready), as a future.
+    pub async fn ready(&mut self) -> Result<(), Error<T>> {
+        poll_fn(|cx| self.poll_ready(cx)).await
     }
 
     /// Send a new request and get the response as a stream of messages. Note that some messages
@@ -33,27 +118,117 @@ where
     ///
     /// - **acknowledgements**: when an acknowledgement is received, the stream is closed
     /// - **end of dump messages**: similarly, upon receiving an "end of dump" message, the stream is
-    /// closed
+    ///   closed. If the dump was interrupted by a concurrent change, the "end of dump" message has
+    ///   the `NLM_F_DUMP_INTR` flag and is forwarded before closing the stream, so that the caller
+    ///   knows the dump may be inconsistent.
+    ///
+    /// If the [`Connection`](struct.Connection.html) has a request timeout (see
+    /// [`Connection::set_request_timeout`](struct.Connection.html#method.set_request_timeout))
+    /// and no response is received in time, the stream ends with an
+    /// [`ErrorKind::Timeout`](enum.ErrorKind.html#variant.Timeout) error. If the socket fails, it
+    /// ends with an [`ErrorKind::SocketIo`](enum.ErrorKind.html#variant.SocketIo) error. Dropping
+    /// the stream cancels the request: the responses that are still received for it are
+    /// discarded.
+    ///
+    /// If the requests queue of the connection is bounded and full, this fails with
+    /// [`ErrorKind::RequestQueueFull`](enum.ErrorKind.html#variant.RequestQueueFull). Use
+    /// [`request_when_ready`](#method.request_when_ready) to wait for room in the queue instead.
     pub fn request(
         &mut self,
         message: NetlinkMessage<T>,
         destination: SocketAddr,
-    ) -> Result<impl Stream<Item = NetlinkMessage<T>>, Error<T>> {
-        let (tx, rx) = unbounded::<NetlinkMessage<T>>();
-        let request = Request::from((message, destination, tx));
-        debug!("handle: forwarding new request to connection");
-        UnboundedSender::unbounded_send(&self.requests_tx, request).map_err(|e| {
-            // the channel is unbounded, so it can't be full. If this
-            // failed, it means the Connection shut down.
-            if e.is_full() {
-                panic!("internal error: unbounded channel full?!");
-            } else if e.is_disconnected() {
-                Error::from(ErrorKind::ConnectionClosed)
-            } else {
-                panic!("unknown error: {:?}", e);
-            }
-        })?;
-        Ok(rx)
+    ) -> Result<impl Stream<Item = Response<T>>, Error<T>> {
+        self.send_request(message, destination, None)
+    }
+
+    /// Like [`request`](#method.request), but if the requests queue of the connection is full,
+    /// wait for room in the queue instead of failing.
+    pub async fn request_when_ready(
+        &mut self,
+        message: NetlinkMessage<T>,
+        destination: SocketAddr,
+    ) -> Result<impl Stream<Item = Response<T>>, Error<T>> {
+        self.ready().await?;
+        self.send_request(message, destination, None)
+    }
+
+    /// Like [`request`](#method.request), but the request times out after `timeout` instead of
+    /// the default timeout of the connection.
+    pub fn request_with_timeout(
+        &mut self,
+        message: NetlinkMessage<T>,
+        destination: SocketAddr,
+        timeout: Duration,
+    ) -> Result<impl Stream<Item = Response<T>>, Error<T>> {
+        self.send_request(message, destination, Some(Instant::now() + timeout))
+    }
+
+    fn send_request(
+        &mut self,
+        message: NetlinkMessage<T>,
+        destination: SocketAddr,
+        deadline: Option<Instant>,
+    ) -> Result<ResponseStream<T>, Error<T>> {
+        let (tx, rx) = unbounded::<Response<T>>();
+        let token = self.cancellations.next_token();
+        let responder = Responder {
+            tx,
+            deadline,
+            token,
+        };
+        self.send(vec![Request::from((message, destination, responder))])?;
+        Ok(ResponseStream {
+            rx,
+            token,
+            cancellations: self.cancellations.clone(),
+        })
+    }
+
+    /// Send several requests at once, and get the response to each of them as a stream of
+    /// messages, like with [`request`](#method.request). The messages are packed in as few
+    /// datagrams as the size of the socket send buffer allows, which saves system calls when
+    /// sending many requests.
+    ///
+    /// The kernel processes the messages in order, and keeps going when one of them fails, so
+    /// each request gets its own response. A datagram is only sent once the last message of the
+    /// previous one that asks for an acknowledgement (`NLM_F_ACK`) is acknowledged, so that the
+    /// responses do not overflow the socket receive buffer. The requests that return more than
+    /// an acknowledgement, like dumps, are better sent on their own.
+    pub fn batch_request(
+        &mut self,
+        messages: Vec<NetlinkMessage<T>>,
+        destination: SocketAddr,
+    ) -> Result<Vec<impl Stream<Item = Response<T>>>, Error<T>> {
+        let mut requests = Vec::with_capacity(messages.len());
+        let mut responses = Vec::with_capacity(messages.len());
+        for message in messages {
+            let (tx, rx) = unbounded::<Response<T>>();
+            let token = self.cancellations.next_token();
+            let responder = Responder {
+                tx,
+                deadline: None,
+                token,
+            };
+            requests.push(Request::from((message, destination, responder)));
+            responses.push(ResponseStream {
+                rx,
+                token,
+                cancellations: self.cancellations.clone(),
+            });
+        }
+        self.send(requests)?;
+        Ok(responses)
+    }
+
+    /// Like [`batch_request`](#method.batch_request), but if the requests queue of the
+    /// connection is full, wait for room in the queue instead of failing.
+    pub async fn batch_request_when_ready(
+        &mut self,
+        messages: Vec<NetlinkMessage<T>>,
+        destination: SocketAddr,
+    ) -> Result<Vec<impl Stream<Item = Response<T>>>, Error<T>> {
+        self.ready().await?;
+        self.batch_request(messages, destination)
     }
 
     pub fn notify(
@@ -61,10 +236,57 @@ where
         message: NetlinkMessage<T>,
         destination: SocketAddr,
     ) -> Result<(), Error<T>> {
-        let (tx, _rx) = unbounded::<NetlinkMessage<T>>();
-        let request = Request::from((message, destination, tx));
-        debug!("handle: forwarding new request to connection");
-        UnboundedSender::unbounded_send(&self.requests_tx, request)
-            .map_err(|_| ErrorKind::ConnectionClosed.into())
+        // The response channel is closed right away, so the connection discards the responses.
+        let (tx, _rx) = unbounded::<Response<T>>();
+        let responder = Responder {
+            tx,
+            deadline: None,
+            token: self.cancellations.next_token(),
+        };
+        self.send(vec![Request::from((message, destination, responder))])
+    }
+
+    fn send(&mut self, requests: Batch<T>) -> Result<(), Error<T>> {
+        debug!("handle: forwarding new request(s) to connection");
+        self.requests_tx.try_send(requests).map_err(|e| {
+            if e.is_full() {
+                Error::from(ErrorKind::RequestQueueFull)
+            } else {
+                // If this failed, it means the Connection shut down.
+                Error::from(ErrorKind::ConnectionClosed)
+            }
+        })
+    }
+}
+
+/// Stream of the responses to a request. Dropping it closes the channel, and reports the request
+/// to the connection so that it stops waiting for the responses.
+struct ResponseStream<T>
+where
+    T: Debug + Clone + Eq + PartialEq,
+{
+    rx: UnboundedReceiver<Response<T>>,
+    token: u64,
+    cancellations: Arc<Cancellations>,
+}
+
+impl<T> Stream for ResponseStream<T>
+where
+    T: Debug + Clone + Eq + PartialEq,
+{
+    type Item = Response<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.rx).poll_next(cx)
+    }
+}
+
+impl<T> Drop for ResponseStream<T>
+where
+    T: Debug + Clone + Eq + PartialEq,
+{
+    fn drop(&mut self) {
+        self.rx.close();
+        self.cancellations.report_dropped(self.token);
     }
 }
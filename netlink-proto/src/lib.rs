@@ -72,9 +72,17 @@
 //!         };
 //!
 //!         while let Some(message) = response.next().await {
-//!             if let NetlinkPayload::Error(err_message) = message.payload {
-//!                 eprintln!("Received an error message: {:?}", err_message);
-//!                 return;
+//!             match message {
+//!                 Ok(message) => {
+//!                     if let NetlinkPayload::Error(err_message) = message.payload {
+//!                         eprintln!("Received an error message: {:?}", err_message);
+//!                         return;
+//!                     }
+//!                 }
+//!                 Err(e) => {
+//!                     eprintln!("{}", e);
+//!                     return;
+//!                 }
 //!             }
 //!         }
 //!     });
@@ -152,11 +160,19 @@
 //!     Ok(())
 //! }
 //! ```
+
 #[macro_use]
 extern crate futures;
 #[macro_use]
 extern crate log;
 
+pub mod blocking;
+
+mod builder;
+pub use crate::builder::*;
+
+mod channel;
+
 mod codecs;
 pub use crate::codecs::*;
 
@@ -164,9 +180,10 @@ mod framed;
 pub use crate::framed::*;
 
 mod protocol;
-pub(crate) use self::protocol::{Protocol, Response};
-pub(crate) type Request<T> =
-    self::protocol::Request<T, UnboundedSender<crate::packet::NetlinkMessage<T>>>;
+pub(crate) use self::protocol::{Protocol, RequestId, Response};
+pub(crate) type Request<T> = self::protocol::Request<T, self::handle::Responder<T>>;
+/// Requests that a handle sends to the connection at once, to be sent together
+pub(crate) type Batch<T> = Vec<Request<T>>;
 
 mod connection;
 pub use crate::connection::*;
@@ -177,19 +194,43 @@ pub use crate::errors::*;
 mod handle;
 pub use crate::handle::*;
 
-use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
-use std::{fmt::Debug, io};
+use futures::channel::mpsc::{unbounded, UnboundedReceiver};
+
+use crate::{
+    channel::{Receiver, Sender},
+    handle::Cancellations,
+};
+use std::{fmt::Debug, sync::Arc};
 
 pub use netlink_packet_core as packet;
 
 pub mod sys {
-    pub use netlink_sys::{protocols, SocketAddr};
+    pub use netlink_sys::{
+        protocols,
+        AsyncNetlinkSocket,
+        CaptureSocket,
+        Direction,
+        MemorySocket,
+        PcapReader,
+        PcapRecord,
+        PcapWriter,
+        ReplaySocket,
+        SocketAddr,
+    };
 
+    /// Socket used by default by the connections. When both the `tokio_socket` and `smol_socket`
+    /// features are enabled, this is the tokio socket, and the smol socket can be used with
+    /// [`new_connection_with_socket`](../fn.new_connection_with_socket.html).
     #[cfg(feature = "tokio_socket")]
     pub use netlink_sys::TokioSocket as Socket;
 
-    #[cfg(feature = "smol_socket")]
+    #[cfg(all(feature = "smol_socket", not(feature = "tokio_socket")))]
     pub use netlink_sys::SmolSocket as Socket;
+
+    #[cfg(feature = "smol_socket")]
+    pub use netlink_sys::SmolSocket;
+    #[cfg(feature = "tokio_socket")]
+    pub use netlink_sys::TokioSocket;
 }
 
 /// Create a new Netlink connection for the given Netlink protocol, and returns a handle to that
@@ -206,13 +247,15 @@ pub mod sys {
 /// details about the `NetlinkSerializable` and `NetlinkDeserializable` traits.
 ///
 /// Most of the time, users will want to spawn the `Connection` on an async runtime, and use the
-/// handle to send messages.
+/// handle to send messages. See [`ConnectionBuilder`] to bound the channels between the
+/// connection and its handles.
 ///
 /// [protos]: crate::sys::protocols
+#[cfg(any(feature = "tokio_socket", feature = "smol_socket"))]
 #[allow(clippy::type_complexity)]
 pub fn new_connection<T>(
     protocol: isize,
-) -> io::Result<(
+) -> std::io::Result<(
     Connection<T>,
     ConnectionHandle<T>,
     UnboundedReceiver<(packet::NetlinkMessage<T>, sys::SocketAddr)>,
@@ -226,11 +269,54 @@ where
         + packet::NetlinkDeserializable<T>
         + Unpin,
 {
-    let (requests_tx, requests_rx) = unbounded::<Request<T>>();
+    Ok(new_connection_with_socket(sys::Socket::new(protocol)?))
+}
+
+/// Like [`new_connection`], but the connection uses the given socket, which can come from
+/// another async runtime than the default one, or be one end of a
+/// [`MemorySocket`](sys/struct.MemorySocket.html) pair to test the code that uses the handle
+/// without a kernel.
+///
+/// ```rust
+/// use netlink_packet_route::RtnlMessage;
+/// use netlink_proto::{
+///     new_connection_with_socket,
+///     sys::{MemorySocket, SocketAddr},
+/// };
+///
+/// let (socket, fake_kernel) = MemorySocket::pair(SocketAddr::new(1, 0), SocketAddr::new(0, 0));
+/// let (connection, handle, messages) = new_connection_with_socket::<RtnlMessage, _>(socket);
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn new_connection_with_socket<T, S>(
+    socket: S,
+) -> (
+    Connection<T, S>,
+    ConnectionHandle<T>,
+    UnboundedReceiver<(packet::NetlinkMessage<T>, sys::SocketAddr)>,
+)
+where
+    T: Debug
+        + PartialEq
+        + Eq
+        + Clone
+        + packet::NetlinkSerializable<T>
+        + packet::NetlinkDeserializable<T>
+        + Unpin,
+    S: sys::AsyncNetlinkSocket,
+{
+    let (requests_tx, requests_rx) = unbounded::<Batch<T>>();
     let (messages_tx, messages_rx) = unbounded::<(packet::NetlinkMessage<T>, sys::SocketAddr)>();
-    Ok((
-        Connection::new(requests_rx, messages_tx, protocol)?,
-        ConnectionHandle::new(requests_tx),
+    let cancellations = Arc::new(Cancellations::default());
+    (
+        Connection::new(
+            Receiver::Unbounded(requests_rx),
+            Sender::Unbounded(messages_tx),
+            None,
+            cancellations.clone(),
+            socket,
+        ),
+        ConnectionHandle::new(Sender::Unbounded(requests_tx), cancellations),
         messages_rx,
-    ))
+    )
 }
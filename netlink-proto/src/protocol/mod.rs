@@ -2,5 +2,5 @@
 mod protocol;
 mod request;
 
-pub use protocol::{Protocol, Response};
+pub use protocol::{Protocol, RequestId, Response};
 pub use request::Request;
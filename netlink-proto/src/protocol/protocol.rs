@@ -14,8 +14,11 @@ use netlink_packet_core::{
 use super::Request;
 use crate::sys::SocketAddr;
 
-#[derive(Debug, Eq, PartialEq, Hash)]
-struct RequestId {
+/// Maximum number of cancelled requests for which late responses are discarded
+const MAX_CANCELLED_REQUESTS: usize = 1024;
+
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub struct RequestId {
     sequence_number: u32,
     port: u32,
 }
@@ -59,14 +62,19 @@ where
     /// associated with each request.
     pending_requests: HashMap<RequestId, PendingRequest<M>>,
 
+    /// Requests that were cancelled before being fully processed, and whether they expect an
+    /// ack. The responses that still arrive for them are discarded.
+    cancelled_requests: VecDeque<(RequestId, bool)>,
+
     /// Responses to pending requests
     pub incoming_responses: VecDeque<Response<T, M>>,
 
     /// Requests from remote peers
     pub incoming_requests: VecDeque<(NetlinkMessage<T>, SocketAddr)>,
 
-    /// The messages to be sent out
-    pub outgoing_messages: VecDeque<(NetlinkMessage<T>, SocketAddr)>,
+    /// The messages to be sent out. The messages of an entry were requested together, and are
+    /// sent in as few datagrams as possible.
+    pub outgoing_messages: VecDeque<(Vec<NetlinkMessage<T>>, SocketAddr)>,
 }
 
 impl<T, M> Protocol<T, M>
@@ -78,6 +86,7 @@ where
         Self {
             sequence_id: 0,
             pending_requests: HashMap::new(),
+            cancelled_requests: VecDeque::new(),
             incoming_responses: VecDeque::new(),
             incoming_requests: VecDeque::new(),
             outgoing_messages: VecDeque::new(),
@@ -89,11 +98,35 @@ where
         debug!("handling messages (request id = {:?})", request_id);
         if let hash_map::Entry::Occupied(entry) = self.pending_requests.entry(request_id) {
             Self::handle_response(&mut self.incoming_responses, entry, message);
+        } else if let Some(position) = self
+            .cancelled_requests
+            .iter()
+            .position(|(id, _)| *id == request_id)
+        {
+            debug!("discarding response to cancelled request {:?}", request_id);
+            if Self::is_last_response(&message, self.cancelled_requests[position].1) {
+                self.cancelled_requests.remove(position);
+            }
         } else {
             self.incoming_requests.push_back((message, source));
         }
     }
 
+    /// A request is processed if we receive an Ack, Error,
+    /// Done, Overrun, or InnerMessage without the
+    /// multipart flag and we were not expecting an Ack
+    fn is_last_response(message: &NetlinkMessage<T>, expecting_ack: bool) -> bool {
+        match message.payload {
+            NetlinkPayload::InnerMessage(_)
+                if message.header.flags & NLM_F_MULTIPART == NLM_F_MULTIPART =>
+            {
+                false
+            }
+            NetlinkPayload::InnerMessage(_) => !expecting_ack,
+            _ => true,
+        }
+    }
+
     fn handle_response(
         incoming_responses: &mut VecDeque<Response<T, M>>,
         entry: hash_map::OccupiedEntry<RequestId, PendingRequest<M>>,
@@ -103,18 +136,7 @@ where
         let mut request_id = entry.key();
         debug!("handling response to request {:?}", request_id);
 
-        // A request is processed if we receive an Ack, Error,
-        // Done, Overrun, or InnerMessage without the
-        // multipart flag and we were not expecting an Ack
-        let done = match message.payload {
-            NetlinkPayload::InnerMessage(_)
-                if message.header.flags & NLM_F_MULTIPART == NLM_F_MULTIPART =>
-            {
-                false
-            }
-            NetlinkPayload::InnerMessage(_) => !entry.get().expecting_ack,
-            _ => true,
-        };
+        let done = Self::is_last_response(&message, entry.get().expecting_ack);
 
         let metadata = if done {
             trace!("request {:?} fully processed", request_id);
@@ -136,7 +158,31 @@ where
         debug!("done handling response to request {:?}", request_id);
     }
 
-    pub fn request(&mut self, request: Request<T, M>) {
+    /// Queue the messages of the given requests, and return the ids of those that expect a
+    /// response. The consecutive messages that have the same destination are queued together, so
+    /// that they can be sent in the same datagram.
+    pub fn request_batch(&mut self, requests: Vec<Request<T, M>>) -> Vec<RequestId> {
+        let mut batches: Vec<(Vec<NetlinkMessage<T>>, SocketAddr)> = vec![];
+        let mut ids = vec![];
+        for request in requests {
+            let destination = request.destination;
+            let (message, id) = self.register_request(request);
+            ids.extend(id);
+            match batches.last_mut() {
+                Some((messages, addr)) if *addr == destination => messages.push(message),
+                _ => batches.push((vec![message], destination)),
+            }
+        }
+        self.outgoing_messages.extend(batches);
+        ids
+    }
+
+    /// Set the sequence number of the request message and return it. If a response is expected,
+    /// the request is added to the pending requests, and its id is returned too.
+    fn register_request(
+        &mut self,
+        request: Request<T, M>,
+    ) -> (NetlinkMessage<T>, Option<RequestId>) {
         let Request {
             mut message,
             metadata,
@@ -146,7 +192,6 @@ where
         self.set_sequence_id(&mut message);
         let request_id = RequestId::new(self.sequence_id, destination.port_number());
         let flags = message.header.flags;
-        self.outgoing_messages.push_back((message, destination));
 
         // If we expect a response, we store the request id so that we
         // can map the response to this specific request.
@@ -167,7 +212,77 @@ where
                     metadata,
                 },
             );
+            return (message, Some(request_id));
         }
+        (message, None)
+    }
+
+    /// Whether the request sent to `destination` with the given sequence number is still waiting
+    /// for its acknowledgement.
+    pub fn is_awaiting_ack(&self, sequence_number: u32, destination: &SocketAddr) -> bool {
+        let id = RequestId::new(sequence_number, destination.port_number());
+        self.pending_requests
+            .get(&id)
+            .is_some_and(|request| request.expecting_ack)
+    }
+
+    /// Return the sequence number of the last message of `datagram` whose acknowledgement is
+    /// awaited, if any.
+    pub fn last_awaited_ack(
+        &self,
+        datagram: &[NetlinkMessage<T>],
+        destination: &SocketAddr,
+    ) -> Option<u32> {
+        datagram
+            .iter()
+            .rev()
+            .map(|message| message.header.sequence_number)
+            .find(|sequence_number| self.is_awaiting_ack(*sequence_number, destination))
+    }
+
+    /// Iterate over the metadata of the requests awaiting a response.
+    pub fn pending_requests(&self) -> impl Iterator<Item = &M> {
+        self.pending_requests
+            .values()
+            .map(|request| &request.metadata)
+    }
+
+    /// Return the metadata of the given request, if it is still awaiting a response.
+    pub fn pending_request(&self, id: &RequestId) -> Option<&M> {
+        self.pending_requests
+            .get(id)
+            .map(|request| &request.metadata)
+    }
+
+    /// Stop waiting for a response to the given request, and return its metadata if it was still
+    /// pending. The responses that still arrive for it are discarded.
+    pub fn cancel_request(&mut self, id: &RequestId) -> Option<M> {
+        let request = self.pending_requests.remove(id)?;
+        debug!("cancelling request {:?}", id);
+        if self.cancelled_requests.len() == MAX_CANCELLED_REQUESTS {
+            self.cancelled_requests.pop_front();
+        }
+        self.cancelled_requests
+            .push_back((*id, request.expecting_ack));
+        Some(request.metadata)
+    }
+
+    /// Stop waiting for a response to the pending requests for which `cancel` returns `true`, and
+    /// return their metadata. The responses that still arrive for these requests are discarded.
+    pub fn cancel_requests<F>(&mut self, mut cancel: F) -> Vec<M>
+    where
+        F: FnMut(&M) -> bool,
+    {
+        let ids: Vec<RequestId> = self
+            .pending_requests
+            .iter()
+            .filter(|(_, request)| cancel(&request.metadata))
+            .map(|(id, _)| *id)
+            .collect();
+
+        ids.iter()
+            .filter_map(|id| self.cancel_request(id))
+            .collect()
     }
 
     fn set_sequence_id(&mut self, message: &mut NetlinkMessage<T>) {
@@ -175,3 +290,47 @@ where
         message.header.sequence_number = self.sequence_id;
     }
 }
+
+#[cfg(test)]
+mod test {
+    use netlink_packet_route::{LinkMessage, RtnlMessage};
+
+    use super::*;
+
+    fn response(sequence_number: u32) -> NetlinkMessage<RtnlMessage> {
+        let mut message = NetlinkMessage::from(RtnlMessage::NewLink(LinkMessage::default()));
+        message.header.sequence_number = sequence_number;
+        message.header.flags = NLM_F_MULTIPART;
+        message
+    }
+
+    #[test]
+    fn cancelled_requests_are_bounded() {
+        let mut protocol = Protocol::<RtnlMessage, ()>::new();
+        let kernel = SocketAddr::new(0, 0);
+        let requests = (0..=MAX_CANCELLED_REQUESTS)
+            .map(|_| {
+                let mut message =
+                    NetlinkMessage::from(RtnlMessage::GetLink(LinkMessage::default()));
+                message.header.flags = NLM_F_REQUEST;
+                Request::from((message, kernel, ()))
+            })
+            .collect();
+        let ids = protocol.request_batch(requests);
+        assert_eq!(ids.len(), MAX_CANCELLED_REQUESTS + 1);
+
+        for id in &ids {
+            assert!(protocol.cancel_request(id).is_some());
+        }
+        assert_eq!(protocol.cancelled_requests.len(), MAX_CANCELLED_REQUESTS);
+
+        // The oldest cancelled request was forgotten: its response looks unsolicited
+        protocol.handle_message(response(ids[0].sequence_number), kernel);
+        assert_eq!(protocol.incoming_requests.len(), 1);
+
+        let last = ids.last().unwrap().sequence_number;
+        protocol.handle_message(response(last), kernel);
+        assert_eq!(protocol.incoming_requests.len(), 1);
+        assert!(protocol.incoming_responses.is_empty());
+    }
+}
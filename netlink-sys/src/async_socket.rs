@@ -0,0 +1,42 @@
+use std::{
+    io,
+    task::{Context, Poll},
+};
+
+use crate::SocketAddr;
+
+/// A netlink socket that can be read from and written to asynchronously, like [`TokioSocket`],
+/// [`SmolSocket`] or [`MemorySocket`].
+///
+/// This is what connections are generic over, so that they can run on any runtime, or be
+/// tested without a kernel.
+///
+/// [`TokioSocket`]: crate::TokioSocket
+/// [`SmolSocket`]: crate::SmolSocket
+/// [`MemorySocket`]: crate::MemorySocket
+pub trait AsyncNetlinkSocket: Unpin {
+    /// Send the datagram `buf` to `addr`, and return the number of bytes sent. If the socket is
+    /// not ready, `cx` is woken up once it is.
+    fn poll_send_to(
+        &mut self,
+        cx: &mut Context,
+        buf: &[u8],
+        addr: &SocketAddr,
+    ) -> Poll<io::Result<usize>>;
+
+    /// Receive a datagram in `buf`, and return its length and the address of its sender. If
+    /// the datagram is longer than `buf`, the rest of it is discarded. If no datagram was
+    /// received yet, `cx` is woken up once one is.
+    fn poll_recv_from(
+        &mut self,
+        cx: &mut Context,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<(usize, SocketAddr)>>;
+
+    /// Size of the send buffer of the socket, which bounds the size of the datagrams.
+    fn get_send_buffer_size(&self) -> io::Result<usize>;
+
+    /// Size of the receive buffer of the socket, which bounds the size of the datagrams that can
+    /// be queued before they are read.
+    fn get_receive_buffer_size(&self) -> io::Result<usize>;
+}
@@ -0,0 +1,361 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    io::{self, Read, Write},
+    task::{Context, Poll, Waker},
+};
+
+use log::error;
+
+use crate::{
+    memory::BUFFER_SIZE,
+    AsyncNetlinkSocket,
+    Direction,
+    PcapReader,
+    PcapRecord,
+    PcapWriter,
+    SocketAddr,
+};
+
+/// A socket that writes the datagrams it sends and receives to a pcap file (see
+/// [`PcapWriter`]), and that can be used by a connection like the socket it wraps.
+///
+/// If writing to the file fails, an error is logged, and the capture stops: the socket keeps
+/// working.
+///
+/// ```rust,no_run
+/// use std::fs::File;
+///
+/// use netlink_sys::{protocols::NETLINK_ROUTE, AsyncNetlinkSocket, CaptureSocket, PcapWriter};
+///
+/// fn capture<S: AsyncNetlinkSocket>(socket: S) -> CaptureSocket<S, File> {
+///     let file = File::create("rtnetlink.pcap").unwrap();
+///     CaptureSocket::new(socket, PcapWriter::new(file, NETLINK_ROUTE).unwrap())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct CaptureSocket<S, W> {
+    socket: S,
+    writer: Option<PcapWriter<W>>,
+}
+
+impl<S, W: Write> CaptureSocket<S, W> {
+    pub fn new(socket: S, writer: PcapWriter<W>) -> Self {
+        CaptureSocket {
+            socket,
+            writer: Some(writer),
+        }
+    }
+
+    fn capture(&mut self, direction: Direction, datagram: &[u8]) {
+        if let Some(writer) = self.writer.as_mut() {
+            if let Err(e) = writer.write(direction, datagram) {
+                error!("failed to capture a datagram, stopping the capture: {}", e);
+                self.writer = None;
+            }
+        }
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.socket
+    }
+
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.socket
+    }
+
+    /// Return the socket, and the writer unless the capture stopped because of an error.
+    pub fn into_inner(self) -> (S, Option<W>) {
+        (self.socket, self.writer.map(PcapWriter::into_inner))
+    }
+}
+
+impl<S, W> AsyncNetlinkSocket for CaptureSocket<S, W>
+where
+    S: AsyncNetlinkSocket,
+    W: Write + Unpin,
+{
+    fn poll_send_to(
+        &mut self,
+        cx: &mut Context,
+        buf: &[u8],
+        addr: &SocketAddr,
+    ) -> Poll<io::Result<usize>> {
+        let poll = self.socket.poll_send_to(cx, buf, addr);
+        if let Poll::Ready(Ok(len)) = poll {
+            self.capture(Direction::Outgoing, &buf[..len]);
+        }
+        poll
+    }
+
+    fn poll_recv_from(
+        &mut self,
+        cx: &mut Context,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<(usize, SocketAddr)>> {
+        let poll = self.socket.poll_recv_from(cx, buf);
+        if let Poll::Ready(Ok((len, _))) = poll {
+            self.capture(Direction::Incoming, &buf[..len]);
+        }
+        poll
+    }
+
+    fn get_send_buffer_size(&self) -> io::Result<usize> {
+        self.socket.get_send_buffer_size()
+    }
+
+    fn get_receive_buffer_size(&self) -> io::Result<usize> {
+        self.socket.get_receive_buffer_size()
+    }
+}
+
+/// A socket that plays back the datagrams received in a capture, to reproduce the behavior of
+/// a connection offline.
+///
+/// The datagrams received in the capture are received in the same order, each one after the
+/// datagrams the capture sent before it were sent again: a connection that sends the same
+/// requests gets the same responses. The sequence numbers of the responses are changed to the
+/// ones of the requests sent, which may differ from the capture. The datagrams that are sent
+/// beyond the ones of the capture are dropped, and once all the datagrams were received, no more
+/// are ever received.
+///
+/// The datagrams are received from the kernel address: multicast messages are received as
+/// unicast messages.
+#[derive(Debug, Default)]
+pub struct ReplaySocket {
+    records: VecDeque<PcapRecord>,
+    /// Number of datagrams of `records` that were sent again
+    sent: usize,
+    /// Sequence numbers of the messages sent in the capture, and of the ones sent again
+    sequence_numbers: HashMap<u32, u32>,
+    /// Task waiting for a datagram to be sent
+    waker: Option<Waker>,
+}
+
+impl ReplaySocket {
+    /// Read a whole capture, like the ones made with a [`CaptureSocket`].
+    pub fn new<R: Read>(reader: PcapReader<R>) -> io::Result<Self> {
+        Ok(Self::from_records(reader.collect::<io::Result<Vec<_>>>()?))
+    }
+
+    pub fn from_records<I>(records: I) -> Self
+    where
+        I: IntoIterator<Item = PcapRecord>,
+    {
+        ReplaySocket {
+            records: records.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Whether all the datagrams of the capture were received, and all the datagrams it sent
+    /// were sent again.
+    pub fn is_finished(&self) -> bool {
+        self.records.len() == self.sent
+    }
+}
+
+impl AsyncNetlinkSocket for ReplaySocket {
+    fn poll_send_to(
+        &mut self,
+        _cx: &mut Context,
+        buf: &[u8],
+        _addr: &SocketAddr,
+    ) -> Poll<io::Result<usize>> {
+        let recorded = self
+            .records
+            .iter()
+            .filter(|record| record.direction == Direction::Outgoing)
+            .nth(self.sent);
+        if let Some(recorded) = recorded {
+            let pairs = sequence_numbers(&recorded.datagram).zip(sequence_numbers(buf));
+            self.sequence_numbers.extend(pairs);
+            self.sent += 1;
+            if let Some(waker) = self.waker.take() {
+                waker.wake();
+            }
+        }
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_recv_from(
+        &mut self,
+        cx: &mut Context,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<(usize, SocketAddr)>> {
+        loop {
+            match self.records.front() {
+                Some(record) if record.direction == Direction::Outgoing => {
+                    if self.sent == 0 {
+                        self.waker = Some(cx.waker().clone());
+                        return Poll::Pending;
+                    }
+                    self.records.pop_front();
+                    self.sent -= 1;
+                }
+                Some(_) => {
+                    let mut datagram = self.records.pop_front().unwrap().datagram;
+                    set_sequence_numbers(&mut datagram, &self.sequence_numbers);
+                    let len = datagram.len().min(buf.len());
+                    buf[..len].copy_from_slice(&datagram[..len]);
+                    return Poll::Ready(Ok((len, SocketAddr::new(0, 0))));
+                }
+                None => return Poll::Pending,
+            }
+        }
+    }
+
+    fn get_send_buffer_size(&self) -> io::Result<usize> {
+        Ok(BUFFER_SIZE)
+    }
+
+    fn get_receive_buffer_size(&self) -> io::Result<usize> {
+        Ok(BUFFER_SIZE)
+    }
+}
+
+/// Offsets of the headers of the messages of a datagram
+fn headers(datagram: &[u8]) -> impl Iterator<Item = usize> + '_ {
+    let mut offset = 0;
+    std::iter::from_fn(move || {
+        if datagram.len() < offset + 16 {
+            return None;
+        }
+        let header = offset;
+        let len = u32::from_ne_bytes([
+            datagram[offset],
+            datagram[offset + 1],
+            datagram[offset + 2],
+            datagram[offset + 3],
+        ]) as usize;
+        // Stop at the first malformed message
+        offset = if len < 16 {
+            datagram.len()
+        } else {
+            offset + ((len + 3) & !3)
+        };
+        Some(header)
+    })
+}
+
+fn sequence_numbers(datagram: &[u8]) -> impl Iterator<Item = u32> + '_ {
+    headers(datagram).map(move |header| {
+        u32::from_ne_bytes([
+            datagram[header + 8],
+            datagram[header + 9],
+            datagram[header + 10],
+            datagram[header + 11],
+        ])
+    })
+}
+
+fn set_sequence_numbers(datagram: &mut [u8], replacements: &HashMap<u32, u32>) {
+    let headers: Vec<usize> = headers(datagram).collect();
+    for header in headers {
+        let field = &mut datagram[header + 8..header + 12];
+        let sequence_number = u32::from_ne_bytes([field[0], field[1], field[2], field[3]]);
+        if let Some(replacement) = replacements.get(&sequence_number) {
+            field.copy_from_slice(&replacement.to_ne_bytes());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs::File, path::Path};
+
+    use futures::task::noop_waker_ref;
+
+    use super::*;
+    use crate::{pcap::NLM_F_REQUEST, protocols::NETLINK_ROUTE, MemorySocket};
+
+    /// A message with the given sequence number and flags, and a 4 bytes payload
+    fn message(sequence_number: u32, flags: u16) -> Vec<u8> {
+        let mut message = vec![];
+        message.extend_from_slice(&20u32.to_ne_bytes());
+        message.extend_from_slice(&16u16.to_ne_bytes());
+        message.extend_from_slice(&flags.to_ne_bytes());
+        message.extend_from_slice(&sequence_number.to_ne_bytes());
+        message.extend_from_slice(&[0; 8]);
+        message
+    }
+
+    #[test]
+    fn capture_and_replay() {
+        let kernel_addr = SocketAddr::new(0, 0);
+        let (user, mut kernel) = MemorySocket::pair(SocketAddr::new(1234, 0), kernel_addr);
+        let writer = PcapWriter::new(Vec::new(), NETLINK_ROUTE).unwrap();
+        let mut socket = CaptureSocket::new(user, writer);
+        let mut cx = Context::from_waker(noop_waker_ref());
+        let mut buf = [0; 64];
+
+        kernel.send(&message(0, 0)).unwrap();
+        assert!(socket.poll_recv_from(&mut cx, &mut buf).is_ready());
+        let request = message(7, NLM_F_REQUEST);
+        assert!(socket
+            .poll_send_to(&mut cx, &request, &kernel_addr)
+            .is_ready());
+        let mut responses = message(7, 2);
+        responses.extend(message(7, 2));
+        kernel.send(&responses).unwrap();
+        assert!(socket.poll_recv_from(&mut cx, &mut buf).is_ready());
+
+        let (_, capture) = socket.into_inner();
+        let records: Vec<_> = PcapReader::new(&capture.unwrap()[..])
+            .unwrap()
+            .collect::<io::Result<_>>()
+            .unwrap();
+        let directions: Vec<_> = records.iter().map(|record| record.direction).collect();
+        assert_eq!(
+            directions,
+            vec![
+                Direction::Incoming,
+                Direction::Outgoing,
+                Direction::Incoming
+            ]
+        );
+        assert!(records
+            .iter()
+            .all(|record| record.protocol == NETLINK_ROUTE as u16));
+        assert_eq!(records[2].datagram, responses);
+
+        let mut replay = ReplaySocket::from_records(records);
+        // The notification received before the request is received right away
+        match replay.poll_recv_from(&mut cx, &mut buf) {
+            Poll::Ready(Ok((20, _))) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        // The responses wait for the request
+        assert!(replay.poll_recv_from(&mut cx, &mut buf).is_pending());
+        let request = message(1, NLM_F_REQUEST);
+        assert!(replay
+            .poll_send_to(&mut cx, &request, &kernel_addr)
+            .is_ready());
+        match replay.poll_recv_from(&mut cx, &mut buf) {
+            Poll::Ready(Ok((40, _))) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        let mut expected = message(1, 2);
+        expected.extend(message(1, 2));
+        assert_eq!(&buf[..40], &expected[..]);
+        assert!(replay.is_finished());
+        assert!(replay.poll_recv_from(&mut cx, &mut buf).is_pending());
+    }
+
+    #[test]
+    fn read_nlmon_capture() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../netlink-packet-route/data/rtnetlink.pcap");
+        let file = File::open(path).unwrap();
+        let records: Vec<_> = PcapReader::new(file)
+            .unwrap()
+            .collect::<io::Result<_>>()
+            .unwrap();
+        assert!(!records.is_empty());
+        // tcpdump does not record the direction, which is guessed from the flags
+        let first = &records[0];
+        assert_eq!(first.direction, Direction::Outgoing);
+        assert!(records
+            .iter()
+            .any(|record| record.direction == Direction::Incoming));
+    }
+}
@@ -131,5 +131,20 @@ pub const NETLINK_LISTEN_ALL_NSID: int = 8;
 pub const NETLINK_LIST_MEMBERSHIPS: int = 9;
 pub const NETLINK_CAP_ACK: int = 10;
 pub const NETLINK_EXT_ACK: int = 11;
+pub const NETLINK_GET_STRICT_CHK: int = 12;
 pub const NL_MMAP_MSG_ALIGNMENT: int = 4;
 pub const NET_MAJOR: int = 36;
+
+// Socket options missing from libc for some targets
+#[cfg(not(any(target_arch = "mips", target_arch = "mips64", target_arch = "sparc64")))]
+pub const SO_SNDBUFFORCE: int = 32;
+#[cfg(not(any(target_arch = "mips", target_arch = "mips64", target_arch = "sparc64")))]
+pub const SO_RCVBUFFORCE: int = 33;
+#[cfg(any(target_arch = "mips", target_arch = "mips64"))]
+pub const SO_SNDBUFFORCE: int = 31;
+#[cfg(any(target_arch = "mips", target_arch = "mips64"))]
+pub const SO_RCVBUFFORCE: int = 33;
+#[cfg(target_arch = "sparc64")]
+pub const SO_SNDBUFFORCE: int = 0x100a;
+#[cfg(target_arch = "sparc64")]
+pub const SO_RCVBUFFORCE: int = 0x100b;
@@ -31,6 +31,18 @@ pub use self::socket::Socket;
 mod addr;
 pub use self::addr::SocketAddr;
 
+mod async_socket;
+pub use self::async_socket::AsyncNetlinkSocket;
+
+mod memory;
+pub use self::memory::MemorySocket;
+
+mod pcap;
+pub use self::pcap::{Direction, PcapReader, PcapRecord, PcapWriter};
+
+mod capture;
+pub use self::capture::{CaptureSocket, ReplaySocket};
+
 #[cfg(feature = "tokio_socket")]
 mod tokio;
 #[cfg(feature = "tokio_socket")]
@@ -0,0 +1,214 @@
+use std::{
+    collections::VecDeque,
+    io,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+use crate::{AsyncNetlinkSocket, SocketAddr};
+
+/// Size reported for the send and receive buffers of a [`MemorySocket`]: the default size of the
+/// buffers of netlink sockets.
+pub(crate) const BUFFER_SIZE: usize = 212_992;
+
+#[derive(Debug, Default)]
+struct Queue {
+    datagrams: VecDeque<(Vec<u8>, SocketAddr)>,
+    /// Task waiting for a datagram
+    waker: Option<Waker>,
+    /// Whether one of the ends was dropped
+    closed: bool,
+}
+
+impl Queue {
+    fn close(&mut self) {
+        self.closed = true;
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// One end of an in-memory pair of netlink sockets, created with [`MemorySocket::pair`]. The
+/// datagrams sent on one end are received on the other end, whatever their destination.
+///
+/// This is meant for tests: a connection can use one end, while the other end plays the part of
+/// the kernel, answering the requests with scripted messages.
+///
+/// ```rust
+/// use netlink_sys::{MemorySocket, SocketAddr};
+///
+/// let kernel_addr = SocketAddr::new(0, 0);
+/// let (mut user, mut kernel) = MemorySocket::pair(SocketAddr::new(1234, 0), kernel_addr);
+///
+/// user.send(&[1, 2, 3, 4]).unwrap();
+/// let (datagram, source) = kernel.try_recv().unwrap().unwrap();
+/// assert_eq!(datagram, vec![1, 2, 3, 4]);
+/// assert_eq!(source, SocketAddr::new(1234, 0));
+/// assert!(kernel.try_recv().unwrap().is_none());
+/// ```
+#[derive(Debug)]
+pub struct MemorySocket {
+    address: SocketAddr,
+    incoming: Arc<Mutex<Queue>>,
+    outgoing: Arc<Mutex<Queue>>,
+}
+
+impl MemorySocket {
+    /// Create a pair of connected sockets, with the given addresses. The peer of a socket sees
+    /// the datagrams it sends as coming from its address.
+    pub fn pair(first: SocketAddr, second: SocketAddr) -> (MemorySocket, MemorySocket) {
+        let first_to_second = Arc::new(Mutex::new(Queue::default()));
+        let second_to_first = Arc::new(Mutex::new(Queue::default()));
+        (
+            MemorySocket {
+                address: first,
+                incoming: second_to_first.clone(),
+                outgoing: first_to_second.clone(),
+            },
+            MemorySocket {
+                address: second,
+                incoming: first_to_second,
+                outgoing: second_to_first,
+            },
+        )
+    }
+
+    /// Address of this end of the pair.
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Send a datagram to the peer. This never blocks, the datagrams being queued until they are
+    /// read. This fails if the peer was dropped.
+    pub fn send(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let source = self.address;
+        self.send_from(buf, source)
+    }
+
+    /// Like [`send`](#method.send), but the peer sees the datagram as coming from `source`. This
+    /// is how the kernel side of a pair sends multicast messages, with the groups set in
+    /// `source`.
+    pub fn send_from(&mut self, buf: &[u8], source: SocketAddr) -> io::Result<usize> {
+        let mut queue = self.outgoing.lock().unwrap();
+        if queue.closed {
+            return Err(peer_dropped());
+        }
+        queue.datagrams.push_back((buf.to_vec(), source));
+        if let Some(waker) = queue.waker.take() {
+            waker.wake();
+        }
+        Ok(buf.len())
+    }
+
+    /// Return the next datagram sent by the peer and its source, or `None` if there is none yet.
+    /// This fails if the peer was dropped and all its datagrams were read.
+    pub fn try_recv(&mut self) -> io::Result<Option<(Vec<u8>, SocketAddr)>> {
+        let mut queue = self.incoming.lock().unwrap();
+        match queue.datagrams.pop_front() {
+            Some(datagram) => Ok(Some(datagram)),
+            None if queue.closed => Err(peer_dropped()),
+            None => Ok(None),
+        }
+    }
+
+    /// Like [`try_recv`](#method.try_recv), but if there is no datagram yet, `cx` is woken up
+    /// once there is one.
+    pub fn poll_recv(&mut self, cx: &mut Context) -> Poll<io::Result<(Vec<u8>, SocketAddr)>> {
+        let mut queue = self.incoming.lock().unwrap();
+        match queue.datagrams.pop_front() {
+            Some(datagram) => Poll::Ready(Ok(datagram)),
+            None if queue.closed => Poll::Ready(Err(peer_dropped())),
+            None => {
+                queue.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+fn peer_dropped() -> io::Error {
+    io::Error::new(io::ErrorKind::BrokenPipe, "the peer socket was dropped")
+}
+
+impl AsyncNetlinkSocket for MemorySocket {
+    /// The destination is ignored: the datagram is always sent to the peer.
+    fn poll_send_to(
+        &mut self,
+        _cx: &mut Context,
+        buf: &[u8],
+        _addr: &SocketAddr,
+    ) -> Poll<io::Result<usize>> {
+        Poll::Ready(self.send(buf))
+    }
+
+    fn poll_recv_from(
+        &mut self,
+        cx: &mut Context,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<(usize, SocketAddr)>> {
+        self.poll_recv(cx).map_ok(|(datagram, source)| {
+            let len = datagram.len().min(buf.len());
+            buf[..len].copy_from_slice(&datagram[..len]);
+            (len, source)
+        })
+    }
+
+    fn get_send_buffer_size(&self) -> io::Result<usize> {
+        Ok(BUFFER_SIZE)
+    }
+
+    fn get_receive_buffer_size(&self) -> io::Result<usize> {
+        Ok(BUFFER_SIZE)
+    }
+}
+
+impl Drop for MemorySocket {
+    fn drop(&mut self) {
+        self.incoming.lock().unwrap().close();
+        self.outgoing.lock().unwrap().close();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::task::Context;
+
+    use futures::task::noop_waker_ref;
+
+    use super::*;
+
+    #[test]
+    fn send_and_receive() {
+        let kernel_addr = SocketAddr::new(0, 0);
+        let user_addr = SocketAddr::new(1234, 0);
+        let (mut user, mut kernel) = MemorySocket::pair(user_addr, kernel_addr);
+        let mut cx = Context::from_waker(noop_waker_ref());
+
+        let mut buf = [0; 8];
+        assert!(user.poll_recv_from(&mut cx, &mut buf).is_pending());
+
+        kernel.send(&[1, 2, 3]).unwrap();
+        kernel.send_from(&[4], SocketAddr::new(0, 1)).unwrap();
+        match user.poll_recv_from(&mut cx, &mut buf) {
+            Poll::Ready(Ok((3, addr))) => assert_eq!(addr, kernel_addr),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert_eq!(&buf[..3], &[1, 2, 3]);
+        match user.poll_recv_from(&mut cx, &mut buf) {
+            Poll::Ready(Ok((1, addr))) => assert_eq!(addr, SocketAddr::new(0, 1)),
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        assert!(user.poll_send_to(&mut cx, &[5, 6], &kernel_addr).is_ready());
+        assert_eq!(kernel.try_recv().unwrap(), Some((vec![5, 6], user_addr)));
+    }
+
+    #[test]
+    fn dropped_peer() {
+        let (mut user, kernel) = MemorySocket::pair(SocketAddr::new(1, 0), SocketAddr::new(0, 0));
+        drop(kernel);
+        assert!(user.try_recv().is_err());
+        assert!(user.send(&[1]).is_err());
+    }
+}
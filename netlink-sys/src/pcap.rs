@@ -0,0 +1,289 @@
+use std::{
+    io::{self, Read, Write},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Magic number of the pcap files with microsecond timestamps
+const MAGIC_MICROS: u32 = 0xa1b2_c3d4;
+/// Magic number of the pcap files with nanosecond timestamps
+const MAGIC_NANOS: u32 = 0xa1b2_3c4d;
+
+/// Link type of the captures of an `nlmon` interface: each datagram is preceded by a
+/// [`COOKED_HEADER_LEN`] bytes header, like the one of the "Linux cooked" captures.
+const LINKTYPE_NETLINK: u32 = 253;
+const COOKED_HEADER_LEN: usize = 16;
+const ARPHRD_NETLINK: u16 = 824;
+
+/// Packet types set by the kernel on the datagrams captured by `nlmon`
+const PACKET_USER: u16 = 6;
+const PACKET_KERNEL: u16 = 7;
+
+/// Maximum length of the captured datagrams
+const SNAPLEN: u32 = 256 * 1024;
+
+/// Flag set on the messages sent to the kernel, which the kernel never sets on the messages it
+/// sends.
+pub(crate) const NLM_F_REQUEST: u16 = 1;
+
+/// Direction of a captured datagram
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// The datagram was received by the socket
+    Incoming,
+    /// The datagram was sent by the socket
+    Outgoing,
+}
+
+/// A datagram read from or written to a pcap file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PcapRecord {
+    /// Time the datagram was captured at, since the Unix epoch
+    pub timestamp: Duration,
+    pub direction: Direction,
+    /// Netlink protocol of the socket, one of the [`crate::protocols`] constants
+    pub protocol: u16,
+    pub datagram: Vec<u8>,
+}
+
+/// Writer of netlink datagrams to a pcap file with the `LINKTYPE_NETLINK` link type, the format
+/// of the captures of `nlmon` interfaces, that tools like `wireshark` can read.
+///
+/// The direction of the datagrams is stored like the kernel does: datagrams sent to the kernel
+/// have the `PACKET_KERNEL` packet type, and the others the `PACKET_USER` type.
+#[derive(Debug)]
+pub struct PcapWriter<W> {
+    writer: W,
+    protocol: u16,
+}
+
+impl<W: Write> PcapWriter<W> {
+    /// Write the header of the file. The datagrams are captured on sockets of the given
+    /// protocol, one of the [`crate::protocols`] constants.
+    pub fn new(mut writer: W, protocol: isize) -> io::Result<Self> {
+        let mut header = Vec::with_capacity(24);
+        header.extend_from_slice(&MAGIC_MICROS.to_ne_bytes());
+        // Version 2.4
+        header.extend_from_slice(&2u16.to_ne_bytes());
+        header.extend_from_slice(&4u16.to_ne_bytes());
+        // Time zone and accuracy of the timestamps
+        header.extend_from_slice(&[0; 8]);
+        header.extend_from_slice(&SNAPLEN.to_ne_bytes());
+        header.extend_from_slice(&LINKTYPE_NETLINK.to_ne_bytes());
+        writer.write_all(&header)?;
+        writer.flush()?;
+        Ok(PcapWriter {
+            writer,
+            protocol: protocol as u16,
+        })
+    }
+
+    /// Write a datagram captured now.
+    pub fn write(&mut self, direction: Direction, datagram: &[u8]) -> io::Result<()> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        self.write_datagram(timestamp, direction, self.protocol, datagram)
+    }
+
+    /// Write a record, for instance one read from another file.
+    pub fn write_record(&mut self, record: &PcapRecord) -> io::Result<()> {
+        self.write_datagram(
+            record.timestamp,
+            record.direction,
+            record.protocol,
+            &record.datagram,
+        )
+    }
+
+    fn write_datagram(
+        &mut self,
+        timestamp: Duration,
+        direction: Direction,
+        protocol: u16,
+        datagram: &[u8],
+    ) -> io::Result<()> {
+        let len = (COOKED_HEADER_LEN + datagram.len()) as u32;
+        let mut record = Vec::with_capacity(16 + len as usize);
+        record.extend_from_slice(&(timestamp.as_secs() as u32).to_ne_bytes());
+        record.extend_from_slice(&timestamp.subsec_micros().to_ne_bytes());
+        // Captured and original lengths
+        record.extend_from_slice(&len.to_ne_bytes());
+        record.extend_from_slice(&len.to_ne_bytes());
+
+        // Unlike the rest of the file, the cooked header is in network byte order
+        let packet_type = match direction {
+            Direction::Incoming => PACKET_USER,
+            Direction::Outgoing => PACKET_KERNEL,
+        };
+        record.extend_from_slice(&packet_type.to_be_bytes());
+        record.extend_from_slice(&ARPHRD_NETLINK.to_be_bytes());
+        // Length and value of the link layer address, that netlink does not have
+        record.extend_from_slice(&[0; 10]);
+        record.extend_from_slice(&protocol.to_be_bytes());
+
+        record.extend_from_slice(datagram);
+        // The file is flushed after each datagram, so that the capture is usable even if the
+        // process does not terminate cleanly.
+        self.writer.write_all(&record)?;
+        self.writer.flush()
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Reader of the netlink datagrams of a pcap file with the `LINKTYPE_NETLINK` link type, like
+/// the ones written by a [`PcapWriter`] or captured on an `nlmon` interface with `tcpdump`.
+///
+/// The captures made with `tcpdump` do not tell which datagrams were sent to the kernel. For
+/// these, the direction is guessed from the `NLM_F_REQUEST` flag of the first message of the
+/// datagram.
+///
+/// ```rust
+/// use netlink_sys::{protocols::NETLINK_ROUTE, Direction, PcapReader, PcapWriter};
+///
+/// let mut writer = PcapWriter::new(Vec::new(), NETLINK_ROUTE).unwrap();
+/// writer.write(Direction::Incoming, &[16, 0, 0, 0, 3, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0]).unwrap();
+///
+/// let mut reader = PcapReader::new(&writer.get_ref()[..]).unwrap();
+/// let record = reader.next().unwrap().unwrap();
+/// assert_eq!(record.direction, Direction::Incoming);
+/// assert_eq!(record.datagram.len(), 16);
+/// assert!(reader.next().is_none());
+/// ```
+#[derive(Debug)]
+pub struct PcapReader<R> {
+    reader: R,
+    /// Whether the file was written on a machine with the other byte order
+    swapped: bool,
+    nanos: bool,
+}
+
+impl<R: Read> PcapReader<R> {
+    /// Read the header of the file, and check that it is a capture of netlink datagrams.
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut header = [0; 24];
+        reader.read_exact(&mut header)?;
+        let magic = u32::from_ne_bytes([header[0], header[1], header[2], header[3]]);
+        let (swapped, nanos) = match magic {
+            MAGIC_MICROS => (false, false),
+            MAGIC_NANOS => (false, true),
+            _ if magic.swap_bytes() == MAGIC_MICROS => (true, false),
+            _ if magic.swap_bytes() == MAGIC_NANOS => (true, true),
+            _ => {
+                return Err(invalid_data(format!(
+                    "invalid pcap magic number {:#x}",
+                    magic
+                )))
+            }
+        };
+        let reader = PcapReader {
+            reader,
+            swapped,
+            nanos,
+        };
+        let link_type = reader.u32_at(&header, 20);
+        if link_type != LINKTYPE_NETLINK {
+            return Err(invalid_data(format!(
+                "not a netlink capture: link type {} instead of {}",
+                link_type, LINKTYPE_NETLINK
+            )));
+        }
+        Ok(reader)
+    }
+
+    fn u32_at(&self, buf: &[u8], offset: usize) -> u32 {
+        let value = u32::from_ne_bytes([
+            buf[offset],
+            buf[offset + 1],
+            buf[offset + 2],
+            buf[offset + 3],
+        ]);
+        if self.swapped {
+            value.swap_bytes()
+        } else {
+            value
+        }
+    }
+
+    fn read_record(&mut self) -> io::Result<Option<PcapRecord>> {
+        let mut header = [0; 16];
+        // The end of the file is only expected between two records
+        match self.reader.read(&mut header[..1])? {
+            0 => return Ok(None),
+            _ => self.reader.read_exact(&mut header[1..])?,
+        }
+        let seconds = self.u32_at(&header, 0);
+        let fraction = self.u32_at(&header, 4);
+        let timestamp = if self.nanos {
+            Duration::new(seconds.into(), fraction)
+        } else {
+            Duration::new(seconds.into(), fraction.saturating_mul(1000))
+        };
+        let captured_len = self.u32_at(&header, 8) as usize;
+        let original_len = self.u32_at(&header, 12) as usize;
+        if captured_len > SNAPLEN as usize {
+            return Err(invalid_data(format!(
+                "invalid record length {}",
+                captured_len
+            )));
+        }
+        let mut packet = vec![0; captured_len];
+        self.reader.read_exact(&mut packet)?;
+        if captured_len < original_len {
+            return Err(invalid_data(format!(
+                "truncated datagram: {} bytes captured out of {}",
+                captured_len, original_len
+            )));
+        }
+        if packet.len() < COOKED_HEADER_LEN {
+            return Err(invalid_data(format!(
+                "record of {} bytes is shorter than the netlink capture header",
+                packet.len()
+            )));
+        }
+
+        let datagram = packet.split_off(COOKED_HEADER_LEN);
+        let direction = match u16::from_be_bytes([packet[0], packet[1]]) {
+            PACKET_USER => Direction::Incoming,
+            PACKET_KERNEL => Direction::Outgoing,
+            _ if datagram.len() >= 8
+                && u16::from_ne_bytes([datagram[6], datagram[7]]) & NLM_F_REQUEST != 0 =>
+            {
+                Direction::Outgoing
+            }
+            _ => Direction::Incoming,
+        };
+        Ok(Some(PcapRecord {
+            timestamp,
+            direction,
+            protocol: u16::from_be_bytes([packet[14], packet[15]]),
+            datagram,
+        }))
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read> Iterator for PcapReader<R> {
+    type Item = io::Result<PcapRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
@@ -10,14 +10,18 @@ use futures::ready;
 
 use log::trace;
 
-use crate::{Socket, SocketAddr};
+use crate::{AsyncNetlinkSocket, Socket, SocketAddr};
 
 /// An I/O object representing a Netlink socket.
 pub struct SmolSocket(Async<Socket>);
 
 impl SmolSocket {
     pub fn new(protocol: isize) -> io::Result<Self> {
-        let socket = Socket::new(protocol)?;
+        Self::from_socket(Socket::new(protocol)?)
+    }
+
+    /// Register a socket, for instance one already configured, with the smol reactor.
+    pub fn from_socket(socket: Socket) -> io::Result<Self> {
         Ok(SmolSocket(Async::new(socket)?))
     }
 
@@ -157,6 +161,91 @@ impl SmolSocket {
     pub fn get_cap_ack(&self) -> io::Result<bool> {
         self.0.get_ref().get_cap_ack()
     }
+
+    /// `NETLINK_EXT_ACK` (since Linux 4.12). When set, the errors and acknowledgments sent by
+    /// the kernel may carry attributes, like a message explaining the error, or the offset of
+    /// the attribute of the request that caused it.
+    pub fn set_ext_ack(&mut self, value: bool) -> io::Result<()> {
+        self.0.get_mut().set_ext_ack(value)
+    }
+
+    pub fn get_ext_ack(&self) -> io::Result<bool> {
+        self.0.get_ref().get_ext_ack()
+    }
+
+    /// `NETLINK_GET_STRICT_CHK` (since Linux 4.20). When set, the kernel checks the headers
+    /// and attributes of the dump requests strictly, and applies the filters they carry,
+    /// instead of ignoring what it does not expect.
+    pub fn set_strict_checking(&mut self, value: bool) -> io::Result<()> {
+        self.0.get_mut().set_strict_checking(value)
+    }
+
+    pub fn get_strict_checking(&self) -> io::Result<bool> {
+        self.0.get_ref().get_strict_checking()
+    }
+
+    /// `SO_SNDBUF`: size of the send buffer of the socket. The kernel doubles the given value,
+    /// to leave room for its own bookkeeping, and rejects the datagrams that do not fit in the
+    /// buffer.
+    pub fn set_send_buffer_size(&mut self, size: usize) -> io::Result<()> {
+        self.0.get_mut().set_send_buffer_size(size)
+    }
+
+    pub fn get_send_buffer_size(&self) -> io::Result<usize> {
+        self.0.get_ref().get_send_buffer_size()
+    }
+
+    /// `SO_SNDBUFFORCE`: like [`set_send_buffer_size`](#method.set_send_buffer_size), but the
+    /// size can exceed the `net.core.wmem_max` limit. This requires the `CAP_NET_ADMIN`
+    /// capability.
+    pub fn set_send_buffer_size_force(&mut self, size: usize) -> io::Result<()> {
+        self.0.get_mut().set_send_buffer_size_force(size)
+    }
+
+    /// `SO_RCVBUF`: size of the receive buffer of the socket. The kernel doubles the given
+    /// value, and drops the messages that do not fit in the buffer, in which case the next read
+    /// fails with `ENOBUFS`.
+    pub fn set_receive_buffer_size(&mut self, size: usize) -> io::Result<()> {
+        self.0.get_mut().set_receive_buffer_size(size)
+    }
+
+    pub fn get_receive_buffer_size(&self) -> io::Result<usize> {
+        self.0.get_ref().get_receive_buffer_size()
+    }
+
+    /// `SO_RCVBUFFORCE`: like [`set_receive_buffer_size`](#method.set_receive_buffer_size), but
+    /// the size can exceed the `net.core.rmem_max` limit. This requires the `CAP_NET_ADMIN`
+    /// capability.
+    pub fn set_receive_buffer_size_force(&mut self, size: usize) -> io::Result<()> {
+        self.0.get_mut().set_receive_buffer_size_force(size)
+    }
+}
+
+impl AsyncNetlinkSocket for SmolSocket {
+    fn poll_send_to(
+        &mut self,
+        cx: &mut Context,
+        buf: &[u8],
+        addr: &SocketAddr,
+    ) -> Poll<io::Result<usize>> {
+        SmolSocket::poll_send_to(self, cx, buf, addr)
+    }
+
+    fn poll_recv_from(
+        &mut self,
+        cx: &mut Context,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<(usize, SocketAddr)>> {
+        SmolSocket::poll_recv_from(self, cx, buf)
+    }
+
+    fn get_send_buffer_size(&self) -> io::Result<usize> {
+        SmolSocket::get_send_buffer_size(self)
+    }
+
+    fn get_receive_buffer_size(&self) -> io::Result<usize> {
+        SmolSocket::get_receive_buffer_size(self)
+    }
 }
 
 impl FromRawFd for SmolSocket {
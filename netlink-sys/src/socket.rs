@@ -1,10 +1,13 @@
 use std::{
-    io::{Error, Result},
+    fs::File,
+    io::{Error, ErrorKind, Result},
     mem,
     os::unix::io::{AsRawFd, FromRawFd, RawFd},
+    path::Path,
+    thread,
 };
 
-use crate::SocketAddr;
+use crate::{constants, SocketAddr};
 
 /// A netlink socket.
 ///
@@ -84,6 +87,31 @@ impl Socket {
         Ok(Socket(res))
     }
 
+    /// Like [`new`](#method.new), but open the socket in the network namespace of the given
+    /// file, for instance `/run/netns/<name>` for the namespaces created with `ip netns add`, or
+    /// `/proc/<pid>/ns/net`. The socket stays in that namespace: it sends requests to and receives
+    /// notifications from the kernel of that namespace only.
+    ///
+    /// The namespace of the current thread is not changed: the socket is opened from another
+    /// thread. This requires the `CAP_SYS_ADMIN` capability.
+    pub fn new_in_namespace<P: AsRef<Path>>(protocol: isize, namespace: P) -> Result<Self> {
+        let namespace = File::open(namespace)?;
+        let fd = namespace.as_raw_fd();
+        thread::spawn(move || {
+            if unsafe { libc::setns(fd, libc::CLONE_NEWNET) } < 0 {
+                return Err(Error::last_os_error());
+            }
+            Socket::new(protocol)
+        })
+        .join()
+        .unwrap_or_else(|_| {
+            Err(Error::new(
+                ErrorKind::Other,
+                "the thread opening the socket panicked",
+            ))
+        })
+    }
+
     /// Bind the socket to the given address
     pub fn bind(&mut self, addr: &SocketAddr) -> Result<()> {
         let (addr_ptr, addr_len) = addr.as_raw();
@@ -418,6 +446,99 @@ impl Socket {
         let res = getsockopt::<libc::c_int>(self.0, libc::SOL_NETLINK, libc::NETLINK_CAP_ACK)?;
         Ok(res == 1)
     }
+
+    /// `NETLINK_EXT_ACK` (since Linux 4.12). When set, the errors and acknowledgments sent by
+    /// the kernel may carry attributes, like a message explaining the error, or the offset of
+    /// the attribute of the request that caused it.
+    pub fn set_ext_ack(&mut self, value: bool) -> Result<()> {
+        let value: libc::c_int = if value { 1 } else { 0 };
+        setsockopt(self.0, libc::SOL_NETLINK, constants::NETLINK_EXT_ACK, value)
+    }
+
+    pub fn get_ext_ack(&self) -> Result<bool> {
+        let res = getsockopt::<libc::c_int>(self.0, libc::SOL_NETLINK, constants::NETLINK_EXT_ACK)?;
+        Ok(res == 1)
+    }
+
+    /// `NETLINK_GET_STRICT_CHK` (since Linux 4.20). When set, the kernel checks the headers
+    /// and attributes of the dump requests strictly, and applies the filters they carry,
+    /// instead of ignoring what it does not expect.
+    pub fn set_strict_checking(&mut self, value: bool) -> Result<()> {
+        let value: libc::c_int = if value { 1 } else { 0 };
+        setsockopt(
+            self.0,
+            libc::SOL_NETLINK,
+            constants::NETLINK_GET_STRICT_CHK,
+            value,
+        )
+    }
+
+    pub fn get_strict_checking(&self) -> Result<bool> {
+        let res = getsockopt::<libc::c_int>(
+            self.0,
+            libc::SOL_NETLINK,
+            constants::NETLINK_GET_STRICT_CHK,
+        )?;
+        Ok(res == 1)
+    }
+
+    /// `SO_SNDBUF`: size of the send buffer of the socket. The kernel doubles the given value,
+    /// to leave room for its own bookkeeping, and rejects the datagrams that do not fit in the
+    /// buffer.
+    pub fn set_send_buffer_size(&mut self, size: usize) -> Result<()> {
+        setsockopt(
+            self.0,
+            libc::SOL_SOCKET,
+            libc::SO_SNDBUF,
+            size as libc::c_int,
+        )
+    }
+
+    pub fn get_send_buffer_size(&self) -> Result<usize> {
+        let res = getsockopt::<libc::c_int>(self.0, libc::SOL_SOCKET, libc::SO_SNDBUF)?;
+        Ok(res as usize)
+    }
+
+    /// `SO_SNDBUFFORCE`: like [`set_send_buffer_size`](#method.set_send_buffer_size), but the
+    /// size can exceed the `net.core.wmem_max` limit. This requires the `CAP_NET_ADMIN`
+    /// capability.
+    pub fn set_send_buffer_size_force(&mut self, size: usize) -> Result<()> {
+        setsockopt(
+            self.0,
+            libc::SOL_SOCKET,
+            constants::SO_SNDBUFFORCE,
+            size as libc::c_int,
+        )
+    }
+
+    /// `SO_RCVBUF`: size of the receive buffer of the socket. The kernel doubles the given
+    /// value, and drops the messages that do not fit in the buffer, in which case the next read
+    /// fails with `ENOBUFS`.
+    pub fn set_receive_buffer_size(&mut self, size: usize) -> Result<()> {
+        setsockopt(
+            self.0,
+            libc::SOL_SOCKET,
+            libc::SO_RCVBUF,
+            size as libc::c_int,
+        )
+    }
+
+    pub fn get_receive_buffer_size(&self) -> Result<usize> {
+        let res = getsockopt::<libc::c_int>(self.0, libc::SOL_SOCKET, libc::SO_RCVBUF)?;
+        Ok(res as usize)
+    }
+
+    /// `SO_RCVBUFFORCE`: like [`set_receive_buffer_size`](#method.set_receive_buffer_size), but
+    /// the size can exceed the `net.core.rmem_max` limit. This requires the `CAP_NET_ADMIN`
+    /// capability.
+    pub fn set_receive_buffer_size_force(&mut self, size: usize) -> Result<()> {
+        setsockopt(
+            self.0,
+            libc::SOL_SOCKET,
+            constants::SO_RCVBUFFORCE,
+            size as libc::c_int,
+        )
+    }
 }
 
 /// Wrapper around `getsockopt`:
@@ -524,10 +645,40 @@ mod test {
         sock.set_broadcast_error(false).unwrap();
         assert!(!sock.get_broadcast_error().unwrap());
 
+        sock.set_ext_ack(true).unwrap();
+        assert!(sock.get_ext_ack().unwrap());
+        sock.set_ext_ack(false).unwrap();
+        assert!(!sock.get_ext_ack().unwrap());
+
+        sock.set_strict_checking(true).unwrap();
+        assert!(sock.get_strict_checking().unwrap());
+        sock.set_strict_checking(false).unwrap();
+        assert!(!sock.get_strict_checking().unwrap());
+
         // FIXME: these require root permissions
         // sock.set_listen_all_namespaces(true).unwrap();
         // assert!(sock.get_listen_all_namespaces().unwrap());
         // sock.set_listen_all_namespaces(false).unwrap();
         // assert!(!sock.get_listen_all_namespaces().unwrap());
     }
+
+    #[test]
+    fn buffer_sizes() {
+        let mut sock = Socket::new(NETLINK_ROUTE).unwrap();
+
+        // The kernel doubles the sizes
+        sock.set_send_buffer_size(32 * 1024).unwrap();
+        assert_eq!(sock.get_send_buffer_size().unwrap(), 64 * 1024);
+        sock.set_receive_buffer_size(32 * 1024).unwrap();
+        assert_eq!(sock.get_receive_buffer_size().unwrap(), 64 * 1024);
+    }
+
+    #[test]
+    fn new_in_namespace() {
+        let mut sock = Socket::new_in_namespace(NETLINK_ROUTE, "/proc/self/ns/net").unwrap();
+        sock.bind_auto().unwrap();
+
+        let err = Socket::new_in_namespace(NETLINK_ROUTE, "/run/netns/does-not-exist").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+    }
 }
@@ -1,8 +1,9 @@
-use futures::stream::StreamExt;
+use futures::stream::TryStreamExt;
 use std::net::{IpAddr, Ipv4Addr};
 
 use netlink_packet_route::{
-    nlas::address::Nla,
+    nlas::address::{CacheInfo, Nla},
+    AddressHeaderFlags,
     AddressMessage,
     NetlinkMessage,
     RtnlMessage,
@@ -11,15 +12,17 @@ use netlink_packet_route::{
     NLM_F_ACK,
     NLM_F_CREATE,
     NLM_F_EXCL,
+    NLM_F_REPLACE,
     NLM_F_REQUEST,
 };
 
-use crate::{try_nl, Error, Handle};
+use crate::{try_nl, Batchable, Error, Handle};
 
 /// A request to create a new address. This is equivalent to the `ip address add` commands.
 pub struct AddressAddRequest {
     handle: Handle,
     message: AddressMessage,
+    replace: bool,
 }
 
 impl AddressAddRequest {
@@ -66,20 +69,18 @@ impl AddressAddRequest {
                 message.nlas.push(Nla::Broadcast(brd.octets().to_vec()));
             };
         }
-        AddressAddRequest { handle, message }
+        AddressAddRequest {
+            handle,
+            message,
+            replace: false,
+        }
     }
 
     /// Execute the request.
     pub async fn execute(self) -> Result<(), Error> {
-        let AddressAddRequest {
-            mut handle,
-            message,
-        } = self;
-        let mut req = NetlinkMessage::from(RtnlMessage::NewAddress(message));
-        req.header.flags = NLM_F_REQUEST | NLM_F_ACK | NLM_F_EXCL | NLM_F_CREATE;
-
-        let mut response = handle.request(req)?;
-        while let Some(message) = response.next().await {
+        let mut handle = self.handle.clone();
+        let mut response = handle.request(self.into_message())?;
+        while let Some(message) = response.try_next().await? {
             try_nl!(message);
         }
         Ok(())
@@ -89,4 +90,126 @@ impl AddressAddRequest {
     pub fn message_mut(&mut self) -> &mut AddressMessage {
         &mut self.message
     }
+
+    /// Replace the address if it already exists instead of failing (equivalent to `ip address
+    /// replace`). This is how the lifetimes and flags of an existing address are updated.
+    pub fn replace(self) -> Self {
+        Self {
+            replace: true,
+            ..self
+        }
+    }
+
+    /// Set the valid and preferred lifetimes of the address, in seconds (equivalent to `ip
+    /// address add ... valid_lft VALID preferred_lft PREFERRED`). Use `INFINITY_LIFE_TIME` for
+    /// an address that never expires. The preferred lifetime cannot exceed the valid lifetime.
+    pub fn lifetimes(mut self, valid: u32, preferred: u32) -> Self {
+        self.message
+            .nlas
+            .retain(|nla| !matches!(nla, Nla::CacheInfo(_)));
+        self.message.nlas.push(Nla::CacheInfo(CacheInfo {
+            ifa_preferred: preferred as i32,
+            ifa_valid: valid as i32,
+            ..Default::default()
+        }));
+        self
+    }
+
+    /// Add flags to the address. `flags` is a combination of the `IFA_F_*` constants, for
+    /// instance `IFA_F_NOPREFIXROUTE`, `IFA_F_NODAD`, `IFA_F_OPTIMISTIC`,
+    /// `IFA_F_MANAGETEMPADDR`, `IFA_F_HOMEADDRESS` or `IFA_F_MCAUTOJOIN`. They are sent in the
+    /// `IFA_FLAGS` attribute, since the header can only carry the first 8 bits.
+    pub fn flags(mut self, flags: u32) -> Self {
+        let mut flags = flags;
+        self.message.nlas.retain(|nla| match nla {
+            Nla::Flags(existing) => {
+                flags |= existing;
+                false
+            }
+            _ => true,
+        });
+        self.message.header.flags = AddressHeaderFlags::from(flags as u8);
+        self.message.nlas.push(Nla::Flags(flags));
+        self
+    }
+
+    /// Set the address of the remote end of a point-to-point link (equivalent to `ip address add
+    /// ADDRESS peer PEER`). The prefix length then applies to the peer address, and no broadcast
+    /// address is set.
+    pub fn peer(mut self, peer: IpAddr) -> Self {
+        let peer = match peer {
+            IpAddr::V4(ipv4) => ipv4.octets().to_vec(),
+            IpAddr::V6(ipv6) => ipv6.octets().to_vec(),
+        };
+
+        // The local address is carried by IFA_LOCAL, and IFA_ADDRESS holds the peer
+        let mut local = None;
+        let mut has_local = false;
+        self.message.nlas.retain(|nla| match nla {
+            Nla::Address(address) => {
+                local = Some(address.clone());
+                false
+            }
+            Nla::Local(_) => {
+                has_local = true;
+                true
+            }
+            Nla::Broadcast(_) => false,
+            _ => true,
+        });
+        if let (Some(local), false) = (local, has_local) {
+            self.message.nlas.push(Nla::Local(local));
+        }
+        self.message.nlas.push(Nla::Address(peer));
+        self
+    }
+
+    /// Set the label of the address (equivalent to `ip address add ... label LABEL`). For IPv4,
+    /// the label must start with the name of the interface, for instance `eth0:1`.
+    pub fn label(mut self, label: String) -> Self {
+        self.message
+            .nlas
+            .retain(|nla| !matches!(nla, Nla::Label(_)));
+        self.message.nlas.push(Nla::Label(label));
+        self
+    }
+
+    /// Set the broadcast address, instead of the one computed from the prefix length (equivalent
+    /// to `ip address add ... broadcast BROADCAST`)
+    pub fn broadcast(mut self, broadcast: Ipv4Addr) -> Self {
+        self.message
+            .nlas
+            .retain(|nla| !matches!(nla, Nla::Broadcast(_)));
+        self.message
+            .nlas
+            .push(Nla::Broadcast(broadcast.octets().to_vec()));
+        self
+    }
+
+    /// Do not set any broadcast address
+    pub fn no_broadcast(mut self) -> Self {
+        self.message
+            .nlas
+            .retain(|nla| !matches!(nla, Nla::Broadcast(_)));
+        self
+    }
+
+    /// Set the scope of the address (equivalent to `ip address add ... scope SCOPE`). `scope`
+    /// is one of the `RT_SCOPE_*` constants.
+    pub fn scope(mut self, scope: u8) -> Self {
+        self.message.header.scope = scope;
+        self
+    }
+}
+
+impl Batchable for AddressAddRequest {
+    fn into_message(self) -> NetlinkMessage<RtnlMessage> {
+        let AddressAddRequest {
+            message, replace, ..
+        } = self;
+        let mut req = NetlinkMessage::from(RtnlMessage::NewAddress(message));
+        let replace = if replace { NLM_F_REPLACE } else { NLM_F_EXCL };
+        req.header.flags = NLM_F_REQUEST | NLM_F_ACK | replace | NLM_F_CREATE;
+        req
+    }
 }
@@ -1,8 +1,9 @@
-use futures::stream::StreamExt;
+use futures::stream::TryStreamExt;
 
 use crate::{
     packet::{AddressMessage, NetlinkMessage, RtnlMessage, NLM_F_ACK, NLM_F_REQUEST},
     try_nl,
+    Batchable,
     Error,
     Handle,
 };
@@ -19,15 +20,9 @@ impl AddressDelRequest {
 
     /// Execute the request
     pub async fn execute(self) -> Result<(), Error> {
-        let AddressDelRequest {
-            mut handle,
-            message,
-        } = self;
-
-        let mut req = NetlinkMessage::from(RtnlMessage::DelAddress(message));
-        req.header.flags = NLM_F_REQUEST | NLM_F_ACK;
-        let mut response = handle.request(req)?;
-        while let Some(msg) = response.next().await {
+        let mut handle = self.handle.clone();
+        let mut response = handle.request(self.into_message())?;
+        while let Some(msg) = response.try_next().await? {
             try_nl!(msg);
         }
         Ok(())
@@ -37,3 +32,12 @@ impl AddressDelRequest {
         &mut self.message
     }
 }
+
+impl Batchable for AddressDelRequest {
+    fn into_message(self) -> NetlinkMessage<RtnlMessage> {
+        let AddressDelRequest { message, .. } = self;
+        let mut req = NetlinkMessage::from(RtnlMessage::DelAddress(message));
+        req.header.flags = NLM_F_REQUEST | NLM_F_ACK;
+        req
+    }
+}
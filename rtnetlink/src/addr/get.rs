@@ -1,7 +1,6 @@
 use futures::{
-    future::{self, Either},
+    future,
     stream::{StreamExt, TryStream, TryStreamExt},
-    FutureExt,
 };
 use std::net::IpAddr;
 
@@ -14,12 +13,13 @@ use netlink_packet_route::{
     NLM_F_REQUEST,
 };
 
-use crate::{try_rtnl, Error, Handle};
+use crate::{dump_request, try_rtnl, DumpInterruptedPolicy, Error, Handle};
 
 pub struct AddressGetRequest {
     handle: Handle,
     message: AddressMessage,
     filter_builder: AddressFilterBuilder,
+    dump_interrupted: DumpInterruptedPolicy,
 }
 
 impl AddressGetRequest {
@@ -28,6 +28,7 @@ impl AddressGetRequest {
             handle,
             message: AddressMessage::default(),
             filter_builder: AddressFilterBuilder::new(),
+            dump_interrupted: DumpInterruptedPolicy::default(),
         }
     }
 
@@ -35,25 +36,29 @@ impl AddressGetRequest {
         &mut self.message
     }
 
+    /// Set what to do if the dump is interrupted by a concurrent change (see
+    /// [`DumpInterruptedPolicy`](enum.DumpInterruptedPolicy.html)). By default, the interruption
+    /// is ignored.
+    pub fn on_dump_interrupted(mut self, policy: DumpInterruptedPolicy) -> Self {
+        self.dump_interrupted = policy;
+        self
+    }
+
     pub fn execute(self) -> impl TryStream<Ok = AddressMessage, Error = Error> {
         let AddressGetRequest {
-            mut handle,
+            handle,
             message,
             filter_builder,
+            dump_interrupted,
         } = self;
 
         let mut req = NetlinkMessage::from(RtnlMessage::GetAddress(message));
         req.header.flags = NLM_F_REQUEST | NLM_F_DUMP;
 
         let filter = filter_builder.build();
-        match handle.request(req) {
-            Ok(response) => Either::Left(
-                response
-                    .map(move |msg| Ok(try_rtnl!(msg, RtnlMessage::NewAddress)))
-                    .try_filter(move |msg| future::ready(filter(msg))),
-            ),
-            Err(e) => Either::Right(future::err::<AddressMessage, Error>(e).into_stream()),
-        }
+        dump_request(handle, req, dump_interrupted)
+            .map(move |msg| Ok(try_rtnl!(msg?, RtnlMessage::NewAddress)))
+            .try_filter(move |msg| future::ready(filter(msg)))
     }
 
     /// Return only the addresses of the given interface.
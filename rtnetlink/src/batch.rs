@@ -0,0 +1,88 @@
+use futures::{future::join_all, stream::StreamExt};
+
+use crate::{
+    packet::{NetlinkMessage, NetlinkPayload, RtnlMessage},
+    Error,
+    Handle,
+};
+
+/// A request that can be added to a [`BatchRequest`]. It is implemented by the requests that
+/// create, change or delete an object, and are only acknowledged by the kernel.
+pub trait Batchable {
+    /// Build the netlink message of the request, with its flags.
+    fn into_message(self) -> NetlinkMessage<RtnlMessage>;
+}
+
+/// Several requests sent at once, in as few datagrams as possible, which is much faster than
+/// sending them one after the other when there are many of them (adding thousands of routes for
+/// instance).
+///
+/// ```rust,no_run
+/// use std::net::Ipv4Addr;
+///
+/// use rtnetlink::{new_connection, Error};
+///
+/// async fn add_routes(index: u32) -> Result<(), Error> {
+///     let (connection, handle, _) = new_connection().unwrap();
+///     tokio::spawn(connection);
+///
+///     let mut batch = handle.batch();
+///     for i in 0..=255 {
+///         let route = handle
+///             .route()
+///             .add()
+///             .v4()
+///             .destination_prefix(Ipv4Addr::new(10, 0, i, 0), 24)
+///             .output_interface(index);
+///         batch = batch.add(route);
+///     }
+///     for result in batch.execute().await? {
+///         result?;
+///     }
+///     Ok(())
+/// }
+/// ```
+pub struct BatchRequest {
+    handle: Handle,
+    messages: Vec<NetlinkMessage<RtnlMessage>>,
+}
+
+impl BatchRequest {
+    pub(crate) fn new(handle: Handle) -> Self {
+        BatchRequest {
+            handle,
+            messages: vec![],
+        }
+    }
+
+    /// Add a request to the batch. The requests are processed by the kernel in the order they
+    /// were added.
+    #[allow(clippy::should_implement_trait)]
+    pub fn add<R: Batchable>(mut self, request: R) -> Self {
+        self.messages.push(request.into_message());
+        self
+    }
+
+    /// Execute the requests. The kernel keeps going when a request fails, so the result of each
+    /// request is returned, in the order they were added. This only fails if the requests could
+    /// not be sent.
+    pub async fn execute(self) -> Result<Vec<Result<(), Error>>, Error> {
+        let BatchRequest {
+            mut handle,
+            messages,
+        } = self;
+        if messages.is_empty() {
+            return Ok(vec![]);
+        }
+        let responses = handle.batch_request(messages)?;
+        let results = join_all(responses.into_iter().map(|mut response| async move {
+            while let Some(message) = response.next().await {
+                if let NetlinkPayload::Error(e) = message?.payload {
+                    return Err(Error::from(e));
+                }
+            }
+            Ok(())
+        }));
+        Ok(results.await)
+    }
+}
@@ -0,0 +1,73 @@
+use std::{future::Future, io};
+
+use futures::stream::{TryStream, TryStreamExt};
+
+use crate::{
+    packet::RtnlMessage,
+    proto::blocking::Connection,
+    sys::protocols::NETLINK_ROUTE,
+    Error,
+    Handle,
+};
+
+/// A netlink route connection that runs the requests on the current thread, without an async
+/// runtime.
+///
+/// The requests are built with a regular [`Handle`] (see [`handle`](#method.handle)), and run
+/// with [`block_on`](#method.block_on) or [`collect`](#method.collect):
+///
+/// ```rust,no_run
+/// use rtnetlink::BlockingHandle;
+///
+/// fn main() -> Result<(), rtnetlink::Error> {
+///     let mut rtnl = BlockingHandle::new().unwrap();
+///     let links = rtnl.collect(rtnl.handle().link().get().execute())?;
+///     for link in links {
+///         println!("{:?}", link);
+///     }
+///     rtnl.block_on(rtnl.handle().link().set(1).up().execute())
+/// }
+/// ```
+pub struct BlockingHandle {
+    connection: Connection<RtnlMessage>,
+    handle: Handle,
+}
+
+impl BlockingHandle {
+    pub fn new() -> io::Result<Self> {
+        let connection = Connection::new(NETLINK_ROUTE)?;
+        let handle = Handle::new(connection.handle());
+        Ok(BlockingHandle { connection, handle })
+    }
+
+    /// Return a handle to build requests. They must be run with [`block_on`](#method.block_on)
+    /// or [`collect`](#method.collect).
+    pub fn handle(&self) -> Handle {
+        self.handle.clone()
+    }
+
+    /// Run a request, like `handle.link().set(index).up().execute()`, and return its result.
+    pub fn block_on<F, R>(&mut self, request: F) -> Result<R, Error>
+    where
+        F: Future<Output = Result<R, Error>>,
+    {
+        self.connection
+            .block_on(request)
+            .map_err(|e| Error::SocketError(e.to_string()))?
+    }
+
+    /// Run a request that returns a stream, like `handle.link().get().execute()`, and collect the
+    /// results.
+    pub fn collect<S>(&mut self, request: S) -> Result<Vec<S::Ok>, Error>
+    where
+        S: TryStream<Error = Error>,
+    {
+        self.block_on(request.try_collect())
+    }
+
+    /// Return the underlying connection, for instance to configure the socket or to receive the
+    /// multicast messages.
+    pub fn connection_mut(&mut self) -> &mut Connection<RtnlMessage> {
+        &mut self.connection
+    }
+}
@@ -0,0 +1,225 @@
+use std::{
+    net::IpAddr,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+
+use crate::{
+    cache::state::{link_name, route_table, rule_table, CacheState},
+    packet::{
+        AddressMessage,
+        LinkMessage,
+        NeighbourMessage,
+        RouteMessage,
+        RtnlMessage,
+        RuleMessage,
+        AF_INET,
+        AF_INET6,
+    },
+};
+
+/// A change of the content of a [`Cache`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum CacheChange {
+    NewLink(LinkMessage),
+    DelLink(LinkMessage),
+    NewAddress(AddressMessage),
+    DelAddress(AddressMessage),
+    NewRoute(RouteMessage),
+    DelRoute(RouteMessage),
+    NewRule(RuleMessage),
+    DelRule(RuleMessage),
+    NewNeighbour(NeighbourMessage),
+    DelNeighbour(NeighbourMessage),
+    /// The cache has been synchronized with the kernel. This is sent once the initial dump is
+    /// complete, and after each resynchronization. The changes found while resynchronizing are
+    /// sent before this one.
+    Synced,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Shared {
+    state: CacheState,
+    synced: bool,
+    subscribers: Vec<UnboundedSender<CacheChange>>,
+}
+
+impl Shared {
+    /// Forward changes to the subscribers. Subscribers that went away are forgotten.
+    fn notify(&mut self, changes: Vec<CacheChange>) {
+        for change in changes {
+            self.subscribers
+                .retain(|tx| tx.unbounded_send(change.clone()).is_ok());
+        }
+    }
+
+    /// Mark the cache as being resynchronized. It keeps serving the current content in the
+    /// meantime.
+    pub(crate) fn unsync(&mut self) {
+        self.synced = false;
+    }
+
+    /// Replace the content of the cache by a fresh dump, and notify the differences.
+    pub(crate) fn replace(&mut self, state: CacheState) {
+        let mut changes = self.state.diff(&state);
+        changes.push(CacheChange::Synced);
+        self.state = state;
+        self.synced = true;
+        self.notify(changes);
+    }
+
+    pub(crate) fn apply(&mut self, message: RtnlMessage) {
+        let changes = self.state.apply(message);
+        self.notify(changes);
+    }
+}
+
+/// An in-memory mirror of the links, addresses, routes, rules and neighbours of the system.
+///
+/// The cache is kept up to date by a [`CacheManager`](crate::CacheManager), created along with
+/// it by [`new_cache`](crate::new_cache). Cloning a `Cache` is cheap: all the clones share the
+/// same content. Only IPv4 and IPv6 addresses, routes, rules and neighbours are tracked.
+#[derive(Clone, Debug)]
+pub struct Cache(pub(crate) Arc<Mutex<Shared>>);
+
+impl Cache {
+    fn lock(&self) -> MutexGuard<'_, Shared> {
+        self.0.lock().unwrap()
+    }
+
+    /// Whether the content of the cache reflects the state of the kernel. This is `false` until
+    /// the initial dump completes, and while the cache is being resynchronized.
+    pub fn is_synced(&self) -> bool {
+        self.lock().synced
+    }
+
+    /// Return a stream of the changes of the content of the cache. Only the changes that happen
+    /// after this call are sent: to get the full content, subscribe before spawning the
+    /// [`CacheManager`](crate::CacheManager) or read the cache after subscribing.
+    pub fn changes(&self) -> UnboundedReceiver<CacheChange> {
+        let (tx, rx) = unbounded();
+        self.lock().subscribers.push(tx);
+        rx
+    }
+
+    pub fn links(&self) -> Vec<LinkMessage> {
+        self.lock().state.links().cloned().collect()
+    }
+
+    /// Get a link by index.
+    pub fn link(&self, index: u32) -> Option<LinkMessage> {
+        self.lock().state.link(index).cloned()
+    }
+
+    /// Get a link by name.
+    pub fn link_by_name(&self, name: &str) -> Option<LinkMessage> {
+        self.lock()
+            .state
+            .links()
+            .find(|link| link_name(link) == Some(name))
+            .cloned()
+    }
+
+    pub fn addresses(&self) -> Vec<AddressMessage> {
+        self.lock().state.addresses().cloned().collect()
+    }
+
+    /// Get the addresses of the link with the given index.
+    pub fn addresses_by_index(&self, index: u32) -> Vec<AddressMessage> {
+        self.lock()
+            .state
+            .addresses()
+            .filter(|address| address.header.index == index)
+            .cloned()
+            .collect()
+    }
+
+    /// Get the addresses with the given prefix.
+    pub fn addresses_by_prefix(&self, address: IpAddr, prefix_len: u8) -> Vec<AddressMessage> {
+        self.lock()
+            .state
+            .addresses()
+            .filter(|msg| msg.prefix() == Some((address, prefix_len)))
+            .cloned()
+            .collect()
+    }
+
+    pub fn routes(&self) -> Vec<RouteMessage> {
+        self.lock().state.routes().cloned().collect()
+    }
+
+    /// Get the routes of the given table.
+    pub fn routes_by_table(&self, table: u32) -> Vec<RouteMessage> {
+        self.lock()
+            .state
+            .routes()
+            .filter(|route| route_table(route) == table)
+            .cloned()
+            .collect()
+    }
+
+    /// Get the routes going through the link with the given index.
+    pub fn routes_by_index(&self, index: u32) -> Vec<RouteMessage> {
+        self.lock()
+            .state
+            .routes()
+            .filter(|route| route.output_interface() == Some(index))
+            .cloned()
+            .collect()
+    }
+
+    /// Get the routes to the given destination prefix, in all the tables.
+    pub fn routes_by_prefix(&self, destination: IpAddr, prefix_length: u8) -> Vec<RouteMessage> {
+        self.lock()
+            .state
+            .routes()
+            .filter(|route| route_destination(route) == Some((destination, prefix_length)))
+            .cloned()
+            .collect()
+    }
+
+    pub fn rules(&self) -> Vec<RuleMessage> {
+        self.lock().state.rules().cloned().collect()
+    }
+
+    /// Get the rules pointing to the given table.
+    pub fn rules_by_table(&self, table: u32) -> Vec<RuleMessage> {
+        self.lock()
+            .state
+            .rules()
+            .filter(|rule| rule_table(rule) == table)
+            .cloned()
+            .collect()
+    }
+
+    pub fn neighbours(&self) -> Vec<NeighbourMessage> {
+        self.lock().state.neighbours().cloned().collect()
+    }
+
+    /// Get the neighbours of the link with the given index.
+    pub fn neighbours_by_index(&self, index: u32) -> Vec<NeighbourMessage> {
+        self.lock()
+            .state
+            .neighbours()
+            .filter(|neighbour| neighbour.header.ifindex == index)
+            .cloned()
+            .collect()
+    }
+}
+
+/// The kernel omits the destination of default routes.
+fn route_destination(route: &RouteMessage) -> Option<(IpAddr, u8)> {
+    route.destination_prefix().or_else(|| {
+        if route.header.destination_prefix_length != 0 {
+            return None;
+        }
+        if route.header.address_family == AF_INET as u8 {
+            Some((IpAddr::from([0u8; 4]), 0))
+        } else if route.header.address_family == AF_INET6 as u8 {
+            Some((IpAddr::from([0u8; 16]), 0))
+        } else {
+            None
+        }
+    })
+}
@@ -0,0 +1,214 @@
+use std::sync::{Arc, Mutex};
+
+use futures::{
+    future::{self, Either},
+    pin_mut,
+    Future,
+    Stream,
+    StreamExt,
+    TryStreamExt,
+};
+use log::{debug, warn};
+
+use crate::{
+    cache::{cache::Shared, state::CacheState},
+    packet::{
+        constants::*,
+        AddressMessage,
+        LinkMessage,
+        NeighbourMessage,
+        NetlinkMessage,
+        NetlinkPayload,
+        RouteMessage,
+        RtnlMessage,
+        RuleMessage,
+    },
+    sys::SocketAddr,
+    Cache,
+    Error,
+    Handle,
+    RtnlGroup,
+};
+
+/// Number of notifications the connections opened by [`CacheManager::run_with_builder`] queue
+/// before the manager falls behind and resynchronizes the cache.
+#[cfg(any(feature = "tokio_socket", feature = "smol_socket"))]
+const NOTIFICATIONS_CAPACITY: usize = 4096;
+
+/// Create a [`Cache`] and the [`CacheManager`] that keeps it up to date. The manager must be
+/// spawned for the cache to be populated:
+///
+/// ```rust,no_run
+/// use futures::stream::StreamExt;
+/// use rtnetlink::{new_cache, CacheChange};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let (manager, cache) = new_cache();
+///     let mut changes = cache.changes();
+///     tokio::spawn(manager.run());
+///
+///     while let Some(change) = changes.next().await {
+///         if change == CacheChange::Synced {
+///             break;
+///         }
+///     }
+///     println!("{:?}", cache.link_by_name("lo"));
+/// }
+/// ```
+pub fn new_cache() -> (CacheManager, Cache) {
+    let shared = Arc::new(Mutex::new(Shared::default()));
+    (CacheManager(shared.clone()), Cache(shared))
+}
+
+/// Background task keeping a [`Cache`] synchronized with the kernel.
+///
+/// The manager dumps the links, addresses, routes, rules and neighbours, and then applies the
+/// notifications sent by the kernel. If a dump is interrupted (`NLM_F_DUMP_INTR`) it is
+/// restarted. Notifications can be lost in two ways: the kernel drops them when the socket
+/// receive buffer is full, which makes the socket fail with `ENOBUFS` and closes the
+/// connection, and the connection drops them when the manager falls behind, which it reports
+/// with a [`NetlinkPayload::Overrun`] message. In both cases the manager opens a new connection
+/// and resynchronizes the cache.
+#[derive(Debug)]
+pub struct CacheManager(Arc<Mutex<Shared>>);
+
+impl CacheManager {
+    /// Multicast groups the cache listens to.
+    pub const GROUPS: &'static [RtnlGroup] = &[
+        RtnlGroup::Link,
+        RtnlGroup::Ipv4Ifaddr,
+        RtnlGroup::Ipv6Ifaddr,
+        RtnlGroup::Ipv4Route,
+        RtnlGroup::Ipv6Route,
+        RtnlGroup::Ipv4Rule,
+        RtnlGroup::Ipv6Rule,
+        RtnlGroup::Neighbour,
+    ];
+
+    /// Run the manager on connections to the network namespace of the calling thread. See
+    /// [`CacheManager::run_with`].
+    #[cfg(any(feature = "tokio_socket", feature = "smol_socket"))]
+    pub async fn run(self) -> Result<(), Error> {
+        self.run_with_builder(crate::ConnectionBuilder::new()).await
+    }
+
+    /// Run the manager on connections opened with the given builder, for instance to cache the
+    /// state of another network namespace. The builder joins the [`CacheManager::GROUPS`]
+    /// groups. See [`CacheManager::run_with`].
+    #[cfg(any(feature = "tokio_socket", feature = "smol_socket"))]
+    pub async fn run_with_builder(self, builder: crate::ConnectionBuilder) -> Result<(), Error> {
+        let builder = Self::GROUPS
+            .iter()
+            .fold(builder, |builder, group| builder.group(*group))
+            .unsolicited_messages_capacity(
+                NOTIFICATIONS_CAPACITY,
+                crate::proto::OverflowPolicy::DropAndReport,
+            );
+        self.run_with(|| {
+            builder
+                .clone()
+                .build()
+                .map_err(|e| Error::SocketError(e.to_string()))
+        })
+        .await
+    }
+
+    /// Run the manager on the connections returned by `connect`, for instance the ones of a
+    /// [`FakeKernel`](crate::FakeKernel). A new connection is opened for each
+    /// resynchronization, and it must have joined the [`CacheManager::GROUPS`] groups.
+    ///
+    /// This only returns if `connect` fails or if the kernel rejects a dump request.
+    pub async fn run_with<F, C, M>(self, mut connect: F) -> Result<(), Error>
+    where
+        F: FnMut() -> Result<(C, Handle, M), Error>,
+        C: Future<Output = ()>,
+        M: Stream<Item = (NetlinkMessage<RtnlMessage>, SocketAddr)> + Unpin,
+    {
+        loop {
+            let (connection, handle, messages) = connect()?;
+            pin_mut!(connection);
+            let session = self.session(handle, messages);
+            pin_mut!(session);
+            match future::select(connection, session).await {
+                Either::Left(_) => warn!("netlink socket closed"),
+                Either::Right((res, _)) => res?,
+            }
+            debug!("resynchronizing the cache");
+            self.0.lock().unwrap().unsync();
+        }
+    }
+
+    /// Synchronize the cache and apply notifications until some of them are lost.
+    async fn session<M>(&self, mut handle: Handle, mut messages: M) -> Result<(), Error>
+    where
+        M: Stream<Item = (NetlinkMessage<RtnlMessage>, SocketAddr)> + Unpin,
+    {
+        let state = loop {
+            if let Some(state) = dump_all(&mut handle).await? {
+                break state;
+            }
+            debug!("dump interrupted, starting over");
+        };
+        self.0.lock().unwrap().replace(state);
+
+        // Notifications received during the dump are applied afterwards. They are more recent
+        // than the state they describe, so replaying them brings the cache up to date.
+        while let Some((message, _)) = messages.next().await {
+            match message.payload {
+                NetlinkPayload::InnerMessage(msg) => self.0.lock().unwrap().apply(msg),
+                NetlinkPayload::Overrun(_) => {
+                    warn!("notifications queue full: notifications were lost");
+                    return Ok(());
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Dump all the objects tracked by the cache. `None` is returned if one of the dumps was
+/// interrupted.
+async fn dump_all(handle: &mut Handle) -> Result<Option<CacheState>, Error> {
+    let mut state = CacheState::default();
+    let requests = vec![
+        RtnlMessage::GetLink(LinkMessage::default()),
+        RtnlMessage::GetAddress(AddressMessage::default()),
+        RtnlMessage::GetRoute(RouteMessage::default()),
+        RtnlMessage::GetRule(RuleMessage::default()),
+        RtnlMessage::GetNeighbour(NeighbourMessage::default()),
+    ];
+    for request in requests {
+        if !dump(handle, request, &mut state).await? {
+            return Ok(None);
+        }
+    }
+    Ok(Some(state))
+}
+
+/// Dump objects into `state`. Return `false` if the dump was interrupted.
+async fn dump(
+    handle: &mut Handle,
+    request: RtnlMessage,
+    state: &mut CacheState,
+) -> Result<bool, Error> {
+    let mut req = NetlinkMessage::from(request);
+    req.header.flags = NLM_F_REQUEST | NLM_F_DUMP;
+
+    let mut interrupted = false;
+    let mut response = handle.request(req)?;
+    while let Some(message) = response.try_next().await? {
+        if message.header.flags & NLM_F_DUMP_INTR == NLM_F_DUMP_INTR {
+            interrupted = true;
+        }
+        match message.payload {
+            NetlinkPayload::InnerMessage(msg) => {
+                state.apply(msg);
+            }
+            NetlinkPayload::Error(err) => return Err(Error::from(err)),
+            _ => {}
+        }
+    }
+    Ok(!interrupted)
+}
@@ -0,0 +1,11 @@
+mod cache;
+pub use self::cache::*;
+
+mod manager;
+pub use self::manager::*;
+
+mod state;
+pub(crate) use self::state::{link_name, route_table, rule_table};
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,372 @@
+use std::collections::BTreeMap;
+
+use netlink_packet_route::{
+    nlas::{address, link, neighbour, route, rule},
+    AddressMessage,
+    LinkMessage,
+    NeighbourMessage,
+    RouteMessage,
+    RtnlMessage,
+    RuleMessage,
+    AF_INET,
+    AF_INET6,
+    AF_UNSPEC,
+};
+
+use crate::CacheChange;
+
+/// Addresses are identified by their link, their local address and their prefix length.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct AddressKey {
+    index: u32,
+    family: u8,
+    prefix_len: u8,
+    address: Vec<u8>,
+}
+
+impl AddressKey {
+    fn new(message: &AddressMessage) -> Self {
+        let mut address = vec![];
+        for nla in message.nlas.iter() {
+            match nla {
+                address::Nla::Local(bytes) => {
+                    address = bytes.clone();
+                    break;
+                }
+                address::Nla::Address(bytes) => address = bytes.clone(),
+                _ => {}
+            }
+        }
+        AddressKey {
+            index: message.header.index,
+            family: message.header.family,
+            prefix_len: message.header.prefix_len,
+            address,
+        }
+    }
+}
+
+/// Routes are identified by their table, prefixes, tos and metric. The output interface and
+/// the gateway are part of the key as well, so that the IPv6 routes that only differ by their
+/// next hop are kept separate.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct RouteKey {
+    family: u8,
+    table: u32,
+    destination: Vec<u8>,
+    destination_prefix_length: u8,
+    source: Vec<u8>,
+    source_prefix_length: u8,
+    tos: u8,
+    priority: Option<u32>,
+    output_interface: Option<u32>,
+    gateway: Vec<u8>,
+}
+
+impl RouteKey {
+    fn new(message: &RouteMessage) -> Self {
+        let mut key = RouteKey {
+            family: message.header.address_family,
+            table: route_table(message),
+            destination: vec![],
+            destination_prefix_length: message.header.destination_prefix_length,
+            source: vec![],
+            source_prefix_length: message.header.source_prefix_length,
+            tos: message.header.tos,
+            priority: None,
+            output_interface: None,
+            gateway: vec![],
+        };
+        for nla in message.nlas.iter() {
+            match nla {
+                route::Nla::Destination(bytes) => key.destination = bytes.clone(),
+                route::Nla::Source(bytes) => key.source = bytes.clone(),
+                route::Nla::Gateway(bytes) => key.gateway = bytes.clone(),
+                route::Nla::Priority(priority) => key.priority = Some(*priority),
+                route::Nla::Oif(index) => key.output_interface = Some(*index),
+                _ => {}
+            }
+        }
+        key
+    }
+}
+
+/// Neighbours are identified by their link and their destination address.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct NeighbourKey {
+    index: u32,
+    family: u8,
+    destination: Vec<u8>,
+}
+
+impl NeighbourKey {
+    fn new(message: &NeighbourMessage) -> Self {
+        let destination = message
+            .nlas
+            .iter()
+            .find_map(|nla| {
+                if let neighbour::Nla::Destination(bytes) = nla {
+                    Some(bytes.clone())
+                } else {
+                    None
+                }
+            })
+            .unwrap_or_default();
+        NeighbourKey {
+            index: message.header.ifindex,
+            family: message.header.family,
+            destination,
+        }
+    }
+}
+
+/// Return the table of a route: the `RTA_TABLE` attribute if present, the table of the header
+/// otherwise.
+pub(crate) fn route_table(message: &RouteMessage) -> u32 {
+    message
+        .nlas
+        .iter()
+        .find_map(|nla| {
+            if let route::Nla::Table(table) = nla {
+                Some(*table)
+            } else {
+                None
+            }
+        })
+        .unwrap_or(u8::from(message.header.table) as u32)
+}
+
+/// Return the table of a rule: the `FRA_TABLE` attribute if present, the table of the header
+/// otherwise.
+pub(crate) fn rule_table(message: &RuleMessage) -> u32 {
+    message
+        .nlas
+        .iter()
+        .find_map(|nla| {
+            if let rule::Nla::Table(table) = nla {
+                Some(*table)
+            } else {
+                None
+            }
+        })
+        .unwrap_or(message.header.table as u32)
+}
+
+pub(crate) fn link_name(message: &LinkMessage) -> Option<&str> {
+    message.nlas.iter().find_map(|nla| {
+        if let link::Nla::IfName(name) = nla {
+            Some(name.as_str())
+        } else {
+            None
+        }
+    })
+}
+
+fn is_ip_family(family: u8) -> bool {
+    family == AF_INET as u8 || family == AF_INET6 as u8
+}
+
+/// Content of the cache: the objects are indexed by their identity in the kernel.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CacheState {
+    links: BTreeMap<u32, LinkMessage>,
+    addresses: BTreeMap<AddressKey, AddressMessage>,
+    routes: BTreeMap<RouteKey, RouteMessage>,
+    rules: Vec<RuleMessage>,
+    neighbours: BTreeMap<NeighbourKey, NeighbourMessage>,
+}
+
+impl CacheState {
+    pub(crate) fn links(&self) -> impl Iterator<Item = &LinkMessage> {
+        self.links.values()
+    }
+
+    pub(crate) fn link(&self, index: u32) -> Option<&LinkMessage> {
+        self.links.get(&index)
+    }
+
+    pub(crate) fn addresses(&self) -> impl Iterator<Item = &AddressMessage> {
+        self.addresses.values()
+    }
+
+    pub(crate) fn routes(&self) -> impl Iterator<Item = &RouteMessage> {
+        self.routes.values()
+    }
+
+    pub(crate) fn rules(&self) -> impl Iterator<Item = &RuleMessage> {
+        self.rules.iter()
+    }
+
+    pub(crate) fn neighbours(&self) -> impl Iterator<Item = &NeighbourMessage> {
+        self.neighbours.values()
+    }
+
+    /// Apply a `New*` or `Del*` message to the cache, and return the changes it caused. Messages
+    /// that are not tracked by the cache, or that do not change its content, are ignored.
+    pub(crate) fn apply(&mut self, message: RtnlMessage) -> Vec<CacheChange> {
+        use RtnlMessage::*;
+
+        let mut changes = vec![];
+        match message {
+            // Link messages with a family (AF_BRIDGE for bridge ports for instance) only describe
+            // the link from the point of view of that family, and must not replace the link.
+            NewLink(msg) | DelLink(msg) if msg.header.interface_family != AF_UNSPEC as u8 => {}
+            NewLink(msg) if self.links.get(&msg.header.index) != Some(&msg) => {
+                self.links.insert(msg.header.index, msg.clone());
+                changes.push(CacheChange::NewLink(msg));
+            }
+            DelLink(msg) => {
+                let index = msg.header.index;
+                if let Some(link) = self.links.remove(&index) {
+                    // The kernel does not always send notifications for the objects that go
+                    // away with the link, so remove them explicitly.
+                    changes.extend(
+                        drain(&mut self.addresses, |a| a.header.index == index)
+                            .into_iter()
+                            .map(CacheChange::DelAddress),
+                    );
+                    changes.extend(
+                        drain(&mut self.routes, |r| r.output_interface() == Some(index))
+                            .into_iter()
+                            .map(CacheChange::DelRoute),
+                    );
+                    changes.extend(
+                        drain(&mut self.neighbours, |n| n.header.ifindex == index)
+                            .into_iter()
+                            .map(CacheChange::DelNeighbour),
+                    );
+                    changes.push(CacheChange::DelLink(link));
+                }
+            }
+            NewAddress(msg) if is_ip_family(msg.header.family) => {
+                let key = AddressKey::new(&msg);
+                if self.addresses.get(&key) != Some(&msg) {
+                    self.addresses.insert(key, msg.clone());
+                    changes.push(CacheChange::NewAddress(msg));
+                }
+            }
+            DelAddress(msg) if is_ip_family(msg.header.family) => {
+                if let Some(address) = self.addresses.remove(&AddressKey::new(&msg)) {
+                    changes.push(CacheChange::DelAddress(address));
+                }
+            }
+            NewRoute(msg) if is_ip_family(msg.header.address_family) => {
+                let key = RouteKey::new(&msg);
+                if self.routes.get(&key) != Some(&msg) {
+                    self.routes.insert(key, msg.clone());
+                    changes.push(CacheChange::NewRoute(msg));
+                }
+            }
+            DelRoute(msg) if is_ip_family(msg.header.address_family) => {
+                if let Some(route) = self.routes.remove(&RouteKey::new(&msg)) {
+                    changes.push(CacheChange::DelRoute(route));
+                }
+            }
+            NewRule(msg) if is_ip_family(msg.header.family) && !self.rules.contains(&msg) => {
+                self.rules.push(msg.clone());
+                changes.push(CacheChange::NewRule(msg));
+            }
+            DelRule(msg) if is_ip_family(msg.header.family) => {
+                if let Some(position) = self.rules.iter().position(|rule| *rule == msg) {
+                    changes.push(CacheChange::DelRule(self.rules.remove(position)));
+                }
+            }
+            NewNeighbour(msg) if is_ip_family(msg.header.family) => {
+                let key = NeighbourKey::new(&msg);
+                if self.neighbours.get(&key) != Some(&msg) {
+                    self.neighbours.insert(key, msg.clone());
+                    changes.push(CacheChange::NewNeighbour(msg));
+                }
+            }
+            DelNeighbour(msg) if is_ip_family(msg.header.family) => {
+                if let Some(neighbour) = self.neighbours.remove(&NeighbourKey::new(&msg)) {
+                    changes.push(CacheChange::DelNeighbour(neighbour));
+                }
+            }
+            _ => {}
+        }
+        changes
+    }
+
+    /// Return the changes that turn `self` into `other`.
+    pub(crate) fn diff(&self, other: &CacheState) -> Vec<CacheChange> {
+        let mut changes = vec![];
+        diff_maps(
+            &self.links,
+            &other.links,
+            CacheChange::NewLink,
+            CacheChange::DelLink,
+            &mut changes,
+        );
+        diff_maps(
+            &self.addresses,
+            &other.addresses,
+            CacheChange::NewAddress,
+            CacheChange::DelAddress,
+            &mut changes,
+        );
+        diff_maps(
+            &self.routes,
+            &other.routes,
+            CacheChange::NewRoute,
+            CacheChange::DelRoute,
+            &mut changes,
+        );
+        for rule in self.rules.iter() {
+            if !other.rules.contains(rule) {
+                changes.push(CacheChange::DelRule(rule.clone()));
+            }
+        }
+        for rule in other.rules.iter() {
+            if !self.rules.contains(rule) {
+                changes.push(CacheChange::NewRule(rule.clone()));
+            }
+        }
+        diff_maps(
+            &self.neighbours,
+            &other.neighbours,
+            CacheChange::NewNeighbour,
+            CacheChange::DelNeighbour,
+            &mut changes,
+        );
+        changes
+    }
+}
+
+/// Remove the entries matching `predicate` from `map`, and return them.
+fn drain<K, V, F>(map: &mut BTreeMap<K, V>, predicate: F) -> Vec<V>
+where
+    K: Ord + Clone,
+    F: Fn(&V) -> bool,
+{
+    let keys: Vec<K> = map
+        .iter()
+        .filter(|(_, value)| predicate(value))
+        .map(|(key, _)| key.clone())
+        .collect();
+    keys.into_iter()
+        .filter_map(|key| map.remove(&key))
+        .collect()
+}
+
+fn diff_maps<K, V>(
+    old: &BTreeMap<K, V>,
+    new: &BTreeMap<K, V>,
+    added: fn(V) -> CacheChange,
+    removed: fn(V) -> CacheChange,
+    changes: &mut Vec<CacheChange>,
+) where
+    K: Ord,
+    V: PartialEq + Clone,
+{
+    for (key, value) in old.iter() {
+        if !new.contains_key(key) {
+            changes.push(removed(value.clone()));
+        }
+    }
+    for (key, value) in new.iter() {
+        if old.get(key) != Some(value) {
+            changes.push(added(value.clone()));
+        }
+    }
+}
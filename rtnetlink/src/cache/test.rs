@@ -0,0 +1,205 @@
+use std::sync::{Arc, Mutex};
+
+use futures::{
+    channel::mpsc::{unbounded, UnboundedReceiver},
+    stream::{self, StreamExt},
+};
+use tokio::runtime::Runtime;
+
+use crate::{
+    cache::state::{link_name, CacheState},
+    new_cache,
+    packet::{
+        nlas::{address, link, route},
+        AddressMessage,
+        LinkMessage,
+        NetlinkHeader,
+        NetlinkMessage,
+        NetlinkPayload,
+        RouteMessage,
+        RtnlMessage,
+        AF_BRIDGE,
+        AF_INET,
+    },
+    sys::SocketAddr,
+    CacheChange,
+    CacheManager,
+    FakeKernel,
+};
+
+fn link(index: u32, name: &str) -> LinkMessage {
+    let mut message = LinkMessage::default();
+    message.header.index = index;
+    message.nlas.push(link::Nla::IfName(name.to_string()));
+    message
+}
+
+fn address(index: u32, octets: [u8; 4]) -> AddressMessage {
+    let mut message = AddressMessage::default();
+    message.header.family = AF_INET as u8;
+    message.header.index = index;
+    message.header.prefix_len = 24;
+    message.nlas.push(address::Nla::Local(octets.to_vec()));
+    message
+}
+
+fn route(index: u32, destination: [u8; 4]) -> RouteMessage {
+    let mut message = RouteMessage::default();
+    message.header.address_family = AF_INET as u8;
+    message.header.destination_prefix_length = 24;
+    message
+        .nlas
+        .push(route::Nla::Destination(destination.to_vec()));
+    message.nlas.push(route::Nla::Oif(index));
+    message
+}
+
+#[test]
+fn apply_new_and_del() {
+    let mut state = CacheState::default();
+
+    let changes = state.apply(RtnlMessage::NewLink(link(2, "eth0")));
+    assert_eq!(changes, vec![CacheChange::NewLink(link(2, "eth0"))]);
+    // The same message again does not change anything
+    assert!(state
+        .apply(RtnlMessage::NewLink(link(2, "eth0")))
+        .is_empty());
+
+    state.apply(RtnlMessage::NewAddress(address(2, [10, 0, 0, 1])));
+    state.apply(RtnlMessage::NewAddress(address(2, [10, 0, 0, 2])));
+    assert_eq!(state.addresses().count(), 2);
+
+    let changes = state.apply(RtnlMessage::DelAddress(address(2, [10, 0, 0, 1])));
+    assert_eq!(
+        changes,
+        vec![CacheChange::DelAddress(address(2, [10, 0, 0, 1]))]
+    );
+    assert_eq!(state.addresses().count(), 1);
+}
+
+#[test]
+fn del_link_removes_dependent_objects() {
+    let mut state = CacheState::default();
+    state.apply(RtnlMessage::NewLink(link(2, "eth0")));
+    state.apply(RtnlMessage::NewLink(link(3, "eth1")));
+    state.apply(RtnlMessage::NewAddress(address(2, [10, 0, 0, 1])));
+    state.apply(RtnlMessage::NewRoute(route(2, [10, 0, 0, 0])));
+    state.apply(RtnlMessage::NewRoute(route(3, [10, 0, 1, 0])));
+
+    let changes = state.apply(RtnlMessage::DelLink(link(2, "eth0")));
+    assert_eq!(
+        changes,
+        vec![
+            CacheChange::DelAddress(address(2, [10, 0, 0, 1])),
+            CacheChange::DelRoute(route(2, [10, 0, 0, 0])),
+            CacheChange::DelLink(link(2, "eth0")),
+        ]
+    );
+    assert_eq!(state.links().count(), 1);
+    assert_eq!(
+        state.routes().collect::<Vec<_>>(),
+        vec![&route(3, [10, 0, 1, 0])]
+    );
+}
+
+#[test]
+fn bridge_link_messages_are_ignored() {
+    let mut state = CacheState::default();
+    state.apply(RtnlMessage::NewLink(link(2, "eth0")));
+
+    let mut port = link(2, "eth0");
+    port.header.interface_family = AF_BRIDGE as u8;
+    assert!(state.apply(RtnlMessage::DelLink(port)).is_empty());
+    assert_eq!(state.link(2), Some(&link(2, "eth0")));
+}
+
+#[test]
+fn diff() {
+    let mut old = CacheState::default();
+    old.apply(RtnlMessage::NewLink(link(2, "eth0")));
+    old.apply(RtnlMessage::NewLink(link(3, "eth1")));
+
+    let mut new = CacheState::default();
+    new.apply(RtnlMessage::NewLink(link(2, "wan")));
+    new.apply(RtnlMessage::NewLink(link(4, "eth2")));
+
+    assert_eq!(
+        old.diff(&new),
+        vec![
+            CacheChange::DelLink(link(3, "eth1")),
+            CacheChange::NewLink(link(2, "wan")),
+            CacheChange::NewLink(link(4, "eth2")),
+        ]
+    );
+}
+
+async fn next_change(changes: &mut UnboundedReceiver<CacheChange>) -> CacheChange {
+    changes.next().await.expect("the cache manager stopped")
+}
+
+/// Return the changes up to the next [`CacheChange::Synced`].
+async fn sync(changes: &mut UnboundedReceiver<CacheChange>) -> Vec<CacheChange> {
+    let mut synced = vec![];
+    loop {
+        match next_change(changes).await {
+            CacheChange::Synced => return synced,
+            change => synced.push(change),
+        }
+    }
+}
+
+#[test]
+fn manager_resynchronizes_when_notifications_are_lost() {
+    let kernel = FakeKernel::new();
+    let (manager, cache) = new_cache();
+    let mut changes = cache.changes();
+    // Each connection of the manager gets a channel to inject messages into its notifications
+    let injectors = Arc::new(Mutex::new(Vec::new()));
+
+    let connect = {
+        let kernel = kernel.clone();
+        let injectors = injectors.clone();
+        move || {
+            let (mut connection, handle, messages) = kernel.connect();
+            for group in CacheManager::GROUPS {
+                connection.add_membership(*group);
+            }
+            let (tx, rx) = unbounded();
+            injectors.lock().unwrap().push(tx);
+            Ok((connection, handle, stream::select(messages, rx)))
+        }
+    };
+
+    Runtime::new().unwrap().block_on(async {
+        tokio::spawn(manager.run_with(connect));
+        assert!(!sync(&mut changes).await.is_empty());
+        assert!(cache.is_synced());
+        assert!(cache.link_by_name("lo").is_some());
+
+        let mut overrun =
+            NetlinkMessage::new(NetlinkHeader::default(), NetlinkPayload::Overrun(vec![]));
+        overrun.finalize();
+        injectors.lock().unwrap()[0]
+            .unbounded_send((overrun, SocketAddr::new(0, 0)))
+            .unwrap();
+
+        // The cache is dumped again on a new connection. Nothing changed in the meantime.
+        assert_eq!(sync(&mut changes).await, vec![]);
+        assert_eq!(injectors.lock().unwrap().len(), 2);
+
+        // The notifications are received on the new connection
+        let (connection, handle, _) = kernel.connect();
+        tokio::spawn(connection);
+        handle
+            .link()
+            .add()
+            .dummy("dummy0".into())
+            .execute()
+            .await
+            .unwrap();
+        match next_change(&mut changes).await {
+            CacheChange::NewLink(link) => assert_eq!(link_name(&link), Some("dummy0")),
+            other => panic!("unexpected change: {:?}", other),
+        }
+    });
+}
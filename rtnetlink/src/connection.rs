@@ -1,16 +1,18 @@
-use std::io;
+use std::path::PathBuf;
 
 use futures::channel::mpsc::UnboundedReceiver;
 
 use crate::{
     packet::{NetlinkMessage, RtnlMessage},
-    proto::Connection,
-    sys::{protocols::NETLINK_ROUTE, SocketAddr},
+    proto::{self, Connection, OverflowPolicy, UnsolicitedMessages},
+    sys::{protocols::NETLINK_ROUTE, AsyncNetlinkSocket, SocketAddr},
     Handle,
+    RtnlGroup,
 };
 
+#[cfg(any(feature = "tokio_socket", feature = "smol_socket"))]
 #[allow(clippy::type_complexity)]
-pub fn new_connection() -> io::Result<(
+pub fn new_connection() -> std::io::Result<(
     Connection<RtnlMessage>,
     Handle,
     UnboundedReceiver<(NetlinkMessage<RtnlMessage>, SocketAddr)>,
@@ -18,3 +20,168 @@ pub fn new_connection() -> io::Result<(
     let (conn, handle, messages) = netlink_proto::new_connection(NETLINK_ROUTE)?;
     Ok((conn, Handle::new(handle), messages))
 }
+
+/// Like [`new_connection`], but the connection uses the given socket, for instance one end of a
+/// [`MemorySocket`](crate::sys::MemorySocket) pair, the other end playing the part of the kernel.
+#[allow(clippy::type_complexity)]
+pub fn new_connection_with_socket<S>(
+    socket: S,
+) -> (
+    Connection<RtnlMessage, S>,
+    Handle,
+    UnboundedReceiver<(NetlinkMessage<RtnlMessage>, SocketAddr)>,
+)
+where
+    S: AsyncNetlinkSocket,
+{
+    let (conn, handle, messages) = netlink_proto::new_connection_with_socket(socket);
+    (conn, Handle::new(handle), messages)
+}
+
+/// Builder for a `NETLINK_ROUTE` connection, to open it in another network namespace, join
+/// multicast groups or set socket options (see [`proto::ConnectionBuilder`]).
+///
+/// ```rust,no_run
+/// use rtnetlink::{ConnectionBuilder, RtnlGroup};
+///
+/// # async fn example() {
+/// let (connection, handle, messages) = ConnectionBuilder::new()
+///     .network_namespace("/run/netns/blue")
+///     .group(RtnlGroup::Link)
+///     .receive_buffer_size(4 * 1024 * 1024, false)
+///     .build()
+///     .unwrap();
+/// tokio::spawn(connection);
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConnectionBuilder(proto::ConnectionBuilder<RtnlMessage>);
+
+impl Default for ConnectionBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConnectionBuilder {
+    pub fn new() -> Self {
+        ConnectionBuilder(proto::ConnectionBuilder::new(NETLINK_ROUTE))
+    }
+
+    /// Join a multicast group: its messages are received as unsolicited messages.
+    pub fn group(self, group: RtnlGroup) -> Self {
+        ConnectionBuilder(self.0.membership(u32::from(group)))
+    }
+
+    /// See [`proto::ConnectionBuilder::network_namespace`].
+    pub fn network_namespace<P: Into<PathBuf>>(self, path: P) -> Self {
+        ConnectionBuilder(self.0.network_namespace(path))
+    }
+
+    /// See [`proto::ConnectionBuilder::bind_address`].
+    pub fn bind_address(self, address: SocketAddr) -> Self {
+        ConnectionBuilder(self.0.bind_address(address))
+    }
+
+    /// See [`proto::ConnectionBuilder::send_buffer_size`].
+    pub fn send_buffer_size(self, size: usize, force: bool) -> Self {
+        ConnectionBuilder(self.0.send_buffer_size(size, force))
+    }
+
+    /// See [`proto::ConnectionBuilder::receive_buffer_size`].
+    pub fn receive_buffer_size(self, size: usize, force: bool) -> Self {
+        ConnectionBuilder(self.0.receive_buffer_size(size, force))
+    }
+
+    /// See [`proto::ConnectionBuilder::extended_ack`].
+    pub fn extended_ack(self, value: bool) -> Self {
+        ConnectionBuilder(self.0.extended_ack(value))
+    }
+
+    /// See [`proto::ConnectionBuilder::strict_checking`].
+    pub fn strict_checking(self, value: bool) -> Self {
+        ConnectionBuilder(self.0.strict_checking(value))
+    }
+
+    /// See [`proto::ConnectionBuilder::no_enobufs`].
+    pub fn no_enobufs(self, value: bool) -> Self {
+        ConnectionBuilder(self.0.no_enobufs(value))
+    }
+
+    /// See [`proto::ConnectionBuilder::cap_ack`].
+    pub fn cap_ack(self, value: bool) -> Self {
+        ConnectionBuilder(self.0.cap_ack(value))
+    }
+
+    /// See [`proto::ConnectionBuilder::reader_capacity`].
+    pub fn reader_capacity(self, capacity: usize) -> Self {
+        ConnectionBuilder(self.0.reader_capacity(capacity))
+    }
+
+    /// See [`proto::ConnectionBuilder::requests_capacity`].
+    pub fn requests_capacity(self, capacity: usize) -> Self {
+        ConnectionBuilder(self.0.requests_capacity(capacity))
+    }
+
+    /// See [`proto::ConnectionBuilder::unsolicited_messages_capacity`].
+    pub fn unsolicited_messages_capacity(self, capacity: usize, policy: OverflowPolicy) -> Self {
+        ConnectionBuilder(self.0.unsolicited_messages_capacity(capacity, policy))
+    }
+
+    /// Like [`new_connection`], but with the settings of the builder.
+    #[cfg(any(feature = "tokio_socket", feature = "smol_socket"))]
+    pub fn build(
+        self,
+    ) -> std::io::Result<(
+        Connection<RtnlMessage>,
+        Handle,
+        UnsolicitedMessages<RtnlMessage>,
+    )> {
+        let (conn, handle, messages) = self.0.build()?;
+        Ok((conn, Handle::new(handle), messages))
+    }
+
+    /// Open a connection that only receives the events sent to the multicast groups joined
+    /// with [`group`](#method.group), and return the stream of these events. The stream drives
+    /// the connection, so it does not need to be spawned.
+    ///
+    /// ```rust,no_run
+    /// use futures::stream::TryStreamExt;
+    /// use rtnetlink::{ConnectionBuilder, Error, RtnlEvent, RtnlGroup};
+    ///
+    /// async fn print_link_changes() -> Result<(), Error> {
+    ///     let mut events = ConnectionBuilder::new()
+    ///         .group(RtnlGroup::Link)
+    ///         .subscribe()
+    ///         .map_err(|e| Error::SocketError(e.to_string()))?;
+    ///     while let Some(event) = events.try_next().await? {
+    ///         if let RtnlEvent::LinkAdded(link) = event {
+    ///             println!("{:?}", link);
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(any(feature = "tokio_socket", feature = "smol_socket"))]
+    pub fn subscribe(self) -> std::io::Result<crate::RtnlEventStream> {
+        let (connection, _, messages) = self.build()?;
+        Ok(crate::RtnlEventStream::new(connection, messages))
+    }
+
+    /// Like [`new_connection_with_socket`]: the network namespace, address, multicast groups and
+    /// socket options of the builder are ignored.
+    pub fn build_with_socket<S>(
+        self,
+        socket: S,
+    ) -> (
+        Connection<RtnlMessage, S>,
+        Handle,
+        UnsolicitedMessages<RtnlMessage>,
+    )
+    where
+        S: AsyncNetlinkSocket,
+    {
+        let (conn, handle, messages) = self.0.build_with_socket(socket);
+        (conn, Handle::new(handle), messages)
+    }
+}
@@ -0,0 +1,107 @@
+use futures::{
+    future::{self, Either},
+    stream::{self, Stream, StreamExt},
+    FutureExt,
+};
+use log::debug;
+
+use crate::{
+    packet::{NetlinkMessage, NetlinkPayload, RtnlMessage, NLM_F_DUMP_INTR},
+    Error,
+    Handle,
+};
+
+/// What a get request does when the kernel reports that its dump was interrupted
+/// (`NLM_F_DUMP_INTR`): the objects changed while they were being dumped, so the result may be
+/// inconsistent, with objects missing or reported twice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DumpInterruptedPolicy {
+    /// Return the result anyway.
+    Ignore,
+    /// Fail with [`Error::DumpInterrupted`] when the interruption is detected. The objects
+    /// received before are returned.
+    Fail,
+    /// Restart the dump, up to the given number of times, and then fail with
+    /// [`Error::DumpInterrupted`]. The objects are only returned once the dump completed without
+    /// interruption, so they are buffered in the meantime.
+    Retry(u32),
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for DumpInterruptedPolicy {
+    fn default() -> Self {
+        DumpInterruptedPolicy::Ignore
+    }
+}
+
+fn is_interrupted(message: &NetlinkMessage<RtnlMessage>) -> bool {
+    message.header.flags & NLM_F_DUMP_INTR == NLM_F_DUMP_INTR
+}
+
+/// Send a request and return the responses, applying `policy` if it is a dump that gets
+/// interrupted. The `NLMSG_DONE` messages that `netlink-proto` forwards for the interrupted dumps
+/// are not returned.
+pub(crate) fn dump_request(
+    mut handle: Handle,
+    request: NetlinkMessage<RtnlMessage>,
+    policy: DumpInterruptedPolicy,
+) -> impl Stream<Item = Result<NetlinkMessage<RtnlMessage>, Error>> {
+    if let DumpInterruptedPolicy::Retry(retries) = policy {
+        let responses = async move {
+            for _ in 0..=retries {
+                let mut response = match handle.request(request.clone()) {
+                    Ok(response) => response,
+                    Err(e) => return vec![Err(e)],
+                };
+                let mut messages = vec![];
+                let mut interrupted = false;
+                while let Some(message) = response.next().await {
+                    let message = match message {
+                        Ok(message) => message,
+                        Err(e) => return vec![Err(e)],
+                    };
+                    interrupted |= is_interrupted(&message);
+                    if !matches!(message.payload, NetlinkPayload::Done) {
+                        messages.push(Ok(message));
+                    }
+                }
+                if !interrupted {
+                    return messages;
+                }
+                debug!("dump interrupted, restarting it");
+            }
+            vec![Err(Error::DumpInterrupted)]
+        };
+        // Boxed so that the stream is `Unpin`, which callers of `try_next()` rely on
+        return Either::Left(Box::pin(responses).map(stream::iter).flatten_stream());
+    }
+
+    let response = match handle.request(request) {
+        Ok(response) => response,
+        Err(e) => return Either::Right(Either::Left(future::err(e).into_stream())),
+    };
+    Either::Right(Either::Right(
+        response
+            .scan(false, move |failed, message| {
+                if *failed {
+                    return future::ready(None);
+                }
+                let message = match message {
+                    Ok(message) => message,
+                    Err(e) => {
+                        *failed = true;
+                        return future::ready(Some(Some(Err(e))));
+                    }
+                };
+                if policy == DumpInterruptedPolicy::Fail && is_interrupted(&message) {
+                    *failed = true;
+                    return future::ready(Some(Some(Err(Error::DumpInterrupted))));
+                }
+                if let NetlinkPayload::Done = message.payload {
+                    return future::ready(Some(None));
+                }
+                future::ready(Some(Some(Ok(message))))
+            })
+            .filter_map(future::ready),
+    ))
+}
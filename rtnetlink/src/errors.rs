@@ -1,6 +1,9 @@
 use thiserror::Error;
 
-use crate::packet::{ErrorMessage, NetlinkMessage, RtnlMessage};
+use crate::{
+    packet::{ErrorMessage, NetlinkMessage, RtnlMessage},
+    proto,
+};
 
 #[derive(Clone, Eq, PartialEq, Debug, Error)]
 pub enum Error {
@@ -13,9 +16,21 @@ pub enum Error {
     #[error("A netlink request failed")]
     RequestFailed,
 
+    #[error("A netlink request timed out")]
+    Timeout,
+
+    #[error("A dump was interrupted by a concurrent change")]
+    DumpInterrupted,
+
     #[error("Namespace error {0}")]
     NamespaceError(String),
 
+    #[error("Failed to open or configure the netlink socket: {0}")]
+    SocketError(String),
+
+    #[error("Invalid network state: {0}")]
+    InvalidNetworkState(String),
+
     #[error(
         "Received a link message (RTM_GETLINK, RTM_NEWLINK, RTM_SETLINK or RTMGETLINK) with an invalid hardware address attribute: {0:?}."
     )]
@@ -27,3 +42,20 @@ pub enum Error {
     #[error("Failed to parse a network address (IP and mask): {0:?}/{1:?}")]
     InvalidAddress(Vec<u8>, Vec<u8>),
 }
+
+impl From<ErrorMessage> for Error {
+    fn from(err: ErrorMessage) -> Self {
+        Error::NetlinkError(err)
+    }
+}
+
+impl From<proto::Error<RtnlMessage>> for Error {
+    /// Convert an error that ended a response stream.
+    fn from(err: proto::Error<RtnlMessage>) -> Self {
+        match err.into_inner() {
+            proto::ErrorKind::Timeout => Error::Timeout,
+            proto::ErrorKind::SocketIo(e) => Error::SocketError(e.to_string()),
+            _ => Error::RequestFailed,
+        }
+    }
+}
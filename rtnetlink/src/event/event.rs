@@ -0,0 +1,93 @@
+use crate::{
+    packet::{
+        AddressMessage,
+        LinkMessage,
+        NeighbourMessage,
+        NeighbourTableMessage,
+        NetlinkMessage,
+        NetlinkPayload,
+        NsidMessage,
+        RouteMessage,
+        RtnlMessage,
+        RuleMessage,
+        TcMessage,
+    },
+    Error,
+};
+
+/// A notification sent by the kernel to the multicast groups of the `NETLINK_ROUTE` protocol.
+///
+/// Note that the kernel does not distinguish creations from changes: `LinkAdded` for instance is
+/// also sent when an existing link changes.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum RtnlEvent {
+    LinkAdded(LinkMessage),
+    LinkRemoved(LinkMessage),
+    AddressAdded(AddressMessage),
+    AddressRemoved(AddressMessage),
+    RouteChanged(RouteMessage),
+    RouteRemoved(RouteMessage),
+    RuleAdded(RuleMessage),
+    RuleRemoved(RuleMessage),
+    NeighbourChanged(NeighbourMessage),
+    NeighbourRemoved(NeighbourMessage),
+    NeighbourTableChanged(NeighbourTableMessage),
+    QueueDisciplineChanged(TcMessage),
+    QueueDisciplineRemoved(TcMessage),
+    TrafficClassChanged(TcMessage),
+    TrafficClassRemoved(TcMessage),
+    TrafficFilterChanged(TcMessage),
+    TrafficFilterRemoved(TcMessage),
+    TrafficChainChanged(TcMessage),
+    TrafficChainRemoved(TcMessage),
+    NsidAdded(NsidMessage),
+    NsidRemoved(NsidMessage),
+    /// Any other message
+    Other(RtnlMessage),
+}
+
+impl From<RtnlMessage> for RtnlEvent {
+    fn from(message: RtnlMessage) -> Self {
+        use RtnlMessage::*;
+        match message {
+            NewLink(msg) => RtnlEvent::LinkAdded(msg),
+            DelLink(msg) => RtnlEvent::LinkRemoved(msg),
+            NewAddress(msg) => RtnlEvent::AddressAdded(msg),
+            DelAddress(msg) => RtnlEvent::AddressRemoved(msg),
+            NewRoute(msg) => RtnlEvent::RouteChanged(msg),
+            DelRoute(msg) => RtnlEvent::RouteRemoved(msg),
+            NewRule(msg) => RtnlEvent::RuleAdded(msg),
+            DelRule(msg) => RtnlEvent::RuleRemoved(msg),
+            NewNeighbour(msg) => RtnlEvent::NeighbourChanged(msg),
+            DelNeighbour(msg) => RtnlEvent::NeighbourRemoved(msg),
+            NewNeighbourTable(msg) => RtnlEvent::NeighbourTableChanged(msg),
+            NewQueueDiscipline(msg) => RtnlEvent::QueueDisciplineChanged(msg),
+            DelQueueDiscipline(msg) => RtnlEvent::QueueDisciplineRemoved(msg),
+            NewTrafficClass(msg) => RtnlEvent::TrafficClassChanged(msg),
+            DelTrafficClass(msg) => RtnlEvent::TrafficClassRemoved(msg),
+            NewTrafficFilter(msg) => RtnlEvent::TrafficFilterChanged(msg),
+            DelTrafficFilter(msg) => RtnlEvent::TrafficFilterRemoved(msg),
+            NewTrafficChain(msg) => RtnlEvent::TrafficChainChanged(msg),
+            DelTrafficChain(msg) => RtnlEvent::TrafficChainRemoved(msg),
+            NewNsId(msg) => RtnlEvent::NsidAdded(msg),
+            DelNsId(msg) => RtnlEvent::NsidRemoved(msg),
+            msg => RtnlEvent::Other(msg),
+        }
+    }
+}
+
+impl RtnlEvent {
+    /// Convert a message received from a multicast group into an event. Error messages are
+    /// turned into [`Error::NetlinkError`], and the other messages that are not part of the
+    /// `NETLINK_ROUTE` protocol into [`Error::UnexpectedMessage`].
+    pub(crate) fn from_message(message: NetlinkMessage<RtnlMessage>) -> Result<Self, Error> {
+        let (header, payload) = message.into_parts();
+        match payload {
+            NetlinkPayload::InnerMessage(msg) => Ok(RtnlEvent::from(msg)),
+            NetlinkPayload::Error(err) => Err(Error::NetlinkError(err)),
+            payload => Err(Error::UnexpectedMessage(NetlinkMessage::new(
+                header, payload,
+            ))),
+        }
+    }
+}
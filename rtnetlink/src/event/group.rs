@@ -0,0 +1,94 @@
+use crate::packet::constants::*;
+
+/// Multicast groups of the `NETLINK_ROUTE` protocol, to be passed to
+/// [`ConnectionBuilder::group`](crate::ConnectionBuilder::group).
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum RtnlGroup {
+    /// Link creation, deletion and changes
+    Link,
+    Notify,
+    /// Neighbour cache entries
+    Neighbour,
+    /// Traffic control objects
+    Tc,
+    /// IPv4 addresses
+    Ipv4Ifaddr,
+    Ipv4Mroute,
+    /// IPv4 routes
+    Ipv4Route,
+    /// IPv4 routing rules
+    Ipv4Rule,
+    /// IPv6 addresses
+    Ipv6Ifaddr,
+    Ipv6Mroute,
+    /// IPv6 routes
+    Ipv6Route,
+    /// IPv6 link information (`RTM_NEWLINK` messages with the `AF_INET6` family)
+    Ipv6Ifinfo,
+    Ipv6Prefix,
+    /// IPv6 routing rules
+    Ipv6Rule,
+    NdUseropt,
+    PhonetIfaddr,
+    PhonetRoute,
+    Dcb,
+    Ipv4Netconf,
+    Ipv6Netconf,
+    /// Bridge multicast database entries
+    Mdb,
+    MplsRoute,
+    /// Network namespace ids
+    Nsid,
+    MplsNetconf,
+    Ipv4MrouteR,
+    Ipv6MrouteR,
+    /// Next hop objects
+    Nexthop,
+    /// Bridge VLANs
+    Brvlan,
+    MctpIfaddr,
+    Tunnel,
+    Stats,
+    /// Any other group, by `RTNLGRP_*` number
+    Other(u32),
+}
+
+impl From<RtnlGroup> for u32 {
+    fn from(group: RtnlGroup) -> u32 {
+        use self::RtnlGroup::*;
+        match group {
+            Link => RTNLGRP_LINK,
+            Notify => RTNLGRP_NOTIFY,
+            Neighbour => RTNLGRP_NEIGH,
+            Tc => RTNLGRP_TC,
+            Ipv4Ifaddr => RTNLGRP_IPV4_IFADDR,
+            Ipv4Mroute => RTNLGRP_IPV4_MROUTE,
+            Ipv4Route => RTNLGRP_IPV4_ROUTE,
+            Ipv4Rule => RTNLGRP_IPV4_RULE,
+            Ipv6Ifaddr => RTNLGRP_IPV6_IFADDR,
+            Ipv6Mroute => RTNLGRP_IPV6_MROUTE,
+            Ipv6Route => RTNLGRP_IPV6_ROUTE,
+            Ipv6Ifinfo => RTNLGRP_IPV6_IFINFO,
+            Ipv6Prefix => RTNLGRP_IPV6_PREFIX,
+            Ipv6Rule => RTNLGRP_IPV6_RULE,
+            NdUseropt => RTNLGRP_ND_USEROPT,
+            PhonetIfaddr => RTNLGRP_PHONET_IFADDR,
+            PhonetRoute => RTNLGRP_PHONET_ROUTE,
+            Dcb => RTNLGRP_DCB,
+            Ipv4Netconf => RTNLGRP_IPV4_NETCONF,
+            Ipv6Netconf => RTNLGRP_IPV6_NETCONF,
+            Mdb => RTNLGRP_MDB,
+            MplsRoute => RTNLGRP_MPLS_ROUTE,
+            Nsid => RTNLGRP_NSID,
+            MplsNetconf => RTNLGRP_MPLS_NETCONF,
+            Ipv4MrouteR => RTNLGRP_IPV4_MROUTE_R,
+            Ipv6MrouteR => RTNLGRP_IPV6_MROUTE_R,
+            Nexthop => RTNLGRP_NEXTHOP,
+            Brvlan => RTNLGRP_BRVLAN,
+            MctpIfaddr => RTNLGRP_MCTP_IFADDR,
+            Tunnel => RTNLGRP_TUNNEL,
+            Stats => RTNLGRP_STATS,
+            Other(group) => group,
+        }
+    }
+}
@@ -0,0 +1,11 @@
+mod event;
+pub use self::event::*;
+
+mod group;
+pub use self::group::*;
+
+mod stream;
+pub use self::stream::*;
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,71 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::{Future, Stream};
+
+use crate::{
+    packet::{NetlinkMessage, RtnlMessage},
+    sys::SocketAddr,
+    Error,
+    RtnlEvent,
+};
+
+/// Stream of the events sent to multicast groups, returned by
+/// [`Handle::subscribe`](crate::Handle::subscribe) and
+/// [`ConnectionBuilder::subscribe`](crate::ConnectionBuilder::subscribe).
+///
+/// The stream owns its connection and drives it when polled, so it does not need to be spawned.
+/// The connection only stops if its socket fails, for instance when the kernel drops
+/// notifications because they were not read fast enough (`ENOBUFS`). The stream then yields
+/// an [`Error::SocketError`] after the events received before the failure, and ends.
+pub struct RtnlEventStream {
+    connection: Option<Pin<Box<dyn Future<Output = ()> + Send>>>,
+    messages: Pin<Box<dyn Stream<Item = (NetlinkMessage<RtnlMessage>, SocketAddr)> + Send>>,
+    /// Whether the failure of the connection has been reported
+    failed: bool,
+}
+
+impl RtnlEventStream {
+    /// Create a stream from a connection that joined the multicast groups, and the stream of the
+    /// unsolicited messages it receives. This is mostly useful for connections that are not
+    /// opened with a [`ConnectionBuilder`](crate::ConnectionBuilder), like the ones of a
+    /// [`FakeKernel`](crate::FakeKernel).
+    pub fn new<C, M>(connection: C, messages: M) -> Self
+    where
+        C: Future<Output = ()> + Send + 'static,
+        M: Stream<Item = (NetlinkMessage<RtnlMessage>, SocketAddr)> + Send + 'static,
+    {
+        RtnlEventStream {
+            connection: Some(Box::pin(connection)),
+            messages: Box::pin(messages),
+            failed: false,
+        }
+    }
+}
+
+impl Stream for RtnlEventStream {
+    type Item = Result<RtnlEvent, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        if let Some(connection) = self.connection.as_mut() {
+            if connection.as_mut().poll(cx).is_ready() {
+                // The socket failed: the remaining messages are still delivered, after which the
+                // stream of messages ends.
+                self.connection = None;
+            }
+        }
+        match self.messages.as_mut().poll_next(cx) {
+            Poll::Ready(Some((message, _))) => Poll::Ready(Some(RtnlEvent::from_message(message))),
+            Poll::Ready(None) if !self.failed => {
+                self.failed = true;
+                Poll::Ready(Some(Err(Error::SocketError(
+                    "netlink socket closed, events may have been lost".into(),
+                ))))
+            }
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
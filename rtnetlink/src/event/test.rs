@@ -0,0 +1,136 @@
+use std::thread;
+
+use futures::stream::StreamExt;
+use nix::{
+    errno::Errno,
+    sched::{unshare, CloneFlags},
+};
+use tokio::runtime::Runtime;
+
+use crate::{
+    new_connection,
+    new_connection_with_socket,
+    packet::{
+        ErrorMessage,
+        nlas::link::Nla,
+        LinkMessage,
+        NetlinkHeader,
+        NetlinkMessage,
+        NetlinkPayload,
+        NsidMessage,
+        RouteMessage,
+        RtnlMessage,
+        RuleMessage,
+    },
+    sys::{MemorySocket, SocketAddr},
+    Error,
+    RtnlEvent,
+    RtnlEventStream,
+    RtnlGroup,
+};
+
+fn message(payload: NetlinkPayload<RtnlMessage>) -> NetlinkMessage<RtnlMessage> {
+    let mut message = NetlinkMessage::new(NetlinkHeader::default(), payload);
+    message.finalize();
+    message
+}
+
+#[test]
+fn dispatch() {
+    let event = |msg| RtnlEvent::from_message(message(NetlinkPayload::InnerMessage(msg))).unwrap();
+    assert_eq!(
+        event(RtnlMessage::NewLink(LinkMessage::default())),
+        RtnlEvent::LinkAdded(LinkMessage::default())
+    );
+    assert_eq!(
+        event(RtnlMessage::DelLink(LinkMessage::default())),
+        RtnlEvent::LinkRemoved(LinkMessage::default())
+    );
+    assert_eq!(
+        event(RtnlMessage::NewRoute(RouteMessage::default())),
+        RtnlEvent::RouteChanged(RouteMessage::default())
+    );
+    assert_eq!(
+        event(RtnlMessage::DelRule(RuleMessage::default())),
+        RtnlEvent::RuleRemoved(RuleMessage::default())
+    );
+    assert_eq!(
+        event(RtnlMessage::NewNsId(NsidMessage::default())),
+        RtnlEvent::NsidAdded(NsidMessage::default())
+    );
+    // Requests are not notifications, but they are part of the protocol
+    assert_eq!(
+        event(RtnlMessage::GetLink(LinkMessage::default())),
+        RtnlEvent::Other(RtnlMessage::GetLink(LinkMessage::default()))
+    );
+}
+
+#[test]
+fn dispatch_errors() {
+    let error = ErrorMessage {
+        code: -(Errno::ENOBUFS as i32),
+        header: vec![],
+    };
+    match RtnlEvent::from_message(message(NetlinkPayload::Error(error.clone()))) {
+        Err(Error::NetlinkError(err)) => assert_eq!(err, error),
+        other => panic!("unexpected result: {:?}", other),
+    }
+    match RtnlEvent::from_message(message(NetlinkPayload::Overrun(vec![]))) {
+        Err(Error::UnexpectedMessage(msg)) => {
+            assert_eq!(msg.payload, NetlinkPayload::Overrun(vec![]))
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn stream_reports_socket_failure() {
+    let (user, mut kernel) = MemorySocket::pair(SocketAddr::new(1, 0), SocketAddr::new(0, 0));
+    let (connection, _, messages) = new_connection_with_socket(user);
+    let mut events = RtnlEventStream::new(connection, messages);
+
+    let notification = message(NetlinkPayload::InnerMessage(RtnlMessage::NewLink(
+        LinkMessage::default(),
+    )));
+    let mut buf = vec![0; notification.header.length as usize];
+    notification.serialize(&mut buf);
+    kernel.send(&buf).unwrap();
+    drop(kernel);
+
+    Runtime::new().unwrap().block_on(async {
+        match events.next().await {
+            Some(Ok(RtnlEvent::LinkAdded(link))) => assert_eq!(link, LinkMessage::default()),
+            other => panic!("unexpected event: {:?}", other),
+        }
+        assert!(matches!(
+            events.next().await,
+            Some(Err(Error::SocketError(_)))
+        ));
+        assert!(events.next().await.is_none());
+    });
+}
+
+#[test]
+#[cfg_attr(not(feature = "test_as_root"), ignore)]
+fn subscribe_to_link_events() {
+    // Work in a namespace of our own, where the loopback interface is down
+    thread::spawn(|| {
+        unshare(CloneFlags::CLONE_NEWNET).unwrap();
+        Runtime::new().unwrap().block_on(async {
+            let (connection, handle, _) = new_connection().unwrap();
+            tokio::spawn(connection);
+            let mut events = handle.subscribe(&[RtnlGroup::Link]).unwrap();
+
+            handle.link().set(1).up().execute().await.unwrap();
+            match events.next().await {
+                Some(Ok(RtnlEvent::LinkAdded(link))) => {
+                    assert_eq!(link.header.index, 1);
+                    assert!(link.nlas.contains(&Nla::IfName("lo".to_string())));
+                }
+                other => panic!("unexpected event: {:?}", other),
+            }
+        });
+    })
+    .join()
+    .unwrap();
+}
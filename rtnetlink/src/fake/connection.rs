@@ -0,0 +1,62 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::Future;
+
+use crate::{
+    packet::RtnlMessage,
+    proto::Connection,
+    sys::MemorySocket,
+    FakeKernel,
+    RtnlGroup,
+};
+
+/// Connection to a [`FakeKernel`], returned by [`FakeKernel::connect`]. Like a
+/// [`Connection`], it must be spawned for the requests of its handle to be processed.
+pub struct FakeConnection {
+    connection: Connection<RtnlMessage, MemorySocket>,
+    kernel: FakeKernel,
+    port: u32,
+}
+
+impl FakeConnection {
+    pub(crate) fn new(
+        connection: Connection<RtnlMessage, MemorySocket>,
+        kernel: FakeKernel,
+        port: u32,
+    ) -> Self {
+        FakeConnection {
+            connection,
+            kernel,
+            port,
+        }
+    }
+
+    /// Join a multicast group: the changes of the kernel notified to this group are received
+    /// from the channel returned with the connection.
+    pub fn add_membership(&mut self, group: RtnlGroup) {
+        self.kernel.lock().join(self.port, u32::from(group));
+    }
+}
+
+impl Future for FakeConnection {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        if Pin::new(&mut self.connection).poll(cx).is_ready() {
+            return Poll::Ready(());
+        }
+        // The responses wake up the connection, which is polled again
+        let port = self.port;
+        self.kernel.lock().poll_requests(port, cx);
+        Poll::Pending
+    }
+}
+
+impl Drop for FakeConnection {
+    fn drop(&mut self) {
+        self.kernel.lock().leave(self.port);
+    }
+}
@@ -0,0 +1,376 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    sync::{Arc, Mutex, MutexGuard},
+    task::{Context, Poll},
+};
+
+use futures::channel::mpsc::UnboundedReceiver;
+use nix::errno::Errno;
+
+use crate::{
+    new_connection_with_socket,
+    packet::{
+        AddressMessage,
+        ErrorMessage,
+        LinkMessage,
+        NetlinkBuffer,
+        NetlinkHeader,
+        NetlinkMessage,
+        NetlinkPayload,
+        RouteMessage,
+        RtnlMessage,
+        RuleMessage,
+        NLM_F_ACK,
+        NLM_F_CREATE,
+        NLM_F_DUMP,
+        NLM_F_DUMP_INTR,
+        NLM_F_MULTIPART,
+        NLM_F_REPLACE,
+        NLM_F_REQUEST,
+    },
+    sys::{MemorySocket, SocketAddr},
+    Error,
+    FakeConnection,
+    Handle,
+    KernelState,
+};
+
+/// Length of the netlink header, which is what the acks carry of the request
+const HEADER_LEN: usize = 16;
+
+/// Maximum length of the datagrams of a dump: the messages of a dump are sent in as few
+/// datagrams as possible, like the kernel does.
+const DUMP_DATAGRAM_LEN: usize = 32 * 1024;
+
+/// A socket connected to the kernel
+#[derive(Debug)]
+struct Member {
+    /// Kernel end of the socket pair
+    socket: MemorySocket,
+    /// Multicast groups the socket joined, by `RTNLGRP_*` number
+    groups: BTreeSet<u32>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Shared {
+    state: KernelState,
+    members: BTreeMap<u32, Member>,
+    last_port: u32,
+    /// Scripted failures: the next request of the given type fails with the given errno
+    failures: VecDeque<(u16, i32)>,
+    /// Scripted interruptions: the next dump of the given type is interrupted
+    interruptions: VecDeque<u16>,
+}
+
+impl Shared {
+    pub(crate) fn join(&mut self, port: u32, group: u32) {
+        if let Some(member) = self.members.get_mut(&port) {
+            member.groups.insert(group);
+        }
+    }
+
+    pub(crate) fn leave(&mut self, port: u32) {
+        self.members.remove(&port);
+    }
+
+    /// Process the requests sent by the socket with the given port, until there are none left.
+    /// `cx` is woken up when more are sent.
+    pub(crate) fn poll_requests(&mut self, port: u32, cx: &mut Context) {
+        while let Some(member) = self.members.get_mut(&port) {
+            match member.socket.poll_recv(cx) {
+                Poll::Ready(Ok((datagram, _))) => self.handle_datagram(port, &datagram),
+                Poll::Ready(Err(_)) | Poll::Pending => return,
+            }
+        }
+    }
+
+    fn handle_datagram(&mut self, port: u32, datagram: &[u8]) {
+        let mut offset = 0;
+        while offset < datagram.len() {
+            let len = match NetlinkBuffer::new_checked(&datagram[offset..]) {
+                Ok(buffer) if buffer.length() as usize >= HEADER_LEN => buffer.length() as usize,
+                // Like the kernel, ignore the rest of a datagram that is malformed
+                _ => return,
+            };
+            let replies = self.handle_message(port, &datagram[offset..offset + len]);
+            if let Some(member) = self.members.get_mut(&port) {
+                for reply in replies {
+                    let _ = member.socket.send(&reply);
+                }
+            }
+            offset += (len + 3) & !3;
+        }
+    }
+
+    /// Process a request, and return the datagrams of the replies
+    fn handle_message(&mut self, port: u32, bytes: &[u8]) -> Vec<Vec<u8>> {
+        let buffer = NetlinkBuffer::new(bytes);
+        let (message_type, flags) = (buffer.message_type(), buffer.flags());
+        if flags & NLM_F_REQUEST == 0 {
+            return vec![];
+        }
+        let mut header = NetlinkHeader {
+            sequence_number: buffer.sequence_number(),
+            port_number: port,
+            ..Default::default()
+        };
+
+        let failure = self
+            .failures
+            .iter()
+            .position(|(failing_type, _)| *failing_type == message_type)
+            .and_then(|position| self.failures.remove(position));
+        let result = match failure {
+            Some((_, errno)) => Err(Errno::from_i32(errno)),
+            None => match NetlinkMessage::<RtnlMessage>::deserialize(bytes) {
+                Ok(NetlinkMessage {
+                    payload: NetlinkPayload::InnerMessage(message),
+                    ..
+                }) => self.state.handle(message, flags),
+                // Control messages are not answered
+                Ok(_) => return vec![],
+                Err(_) => Err(Errno::EINVAL),
+            },
+        };
+        let outcome = match result {
+            Ok(outcome) => outcome,
+            Err(errno) => {
+                let error = ErrorMessage {
+                    code: -(errno as i32),
+                    header: bytes.to_vec(),
+                };
+                return vec![serialize(header, NetlinkPayload::Error(error))];
+            }
+        };
+        for (group, notification) in outcome.notifications {
+            self.notify(group, notification);
+        }
+
+        if flags & NLM_F_DUMP == NLM_F_DUMP {
+            header.flags = NLM_F_MULTIPART;
+            // Like the kernel, flag the messages dumped after the objects changed, and the end
+            // of the dump
+            let interrupted_header = NetlinkHeader {
+                flags: NLM_F_MULTIPART | NLM_F_DUMP_INTR,
+                ..header
+            };
+            let end = outcome.responses.len();
+            let interrupted_from = self
+                .interruptions
+                .iter()
+                .position(|interrupted_type| *interrupted_type == message_type)
+                .and_then(|position| self.interruptions.remove(position))
+                .map(|_| end / 2);
+            let header_of = |i: usize| match interrupted_from {
+                Some(from) if i >= from => interrupted_header,
+                _ => header,
+            };
+            let mut datagrams: Vec<Vec<u8>> = vec![];
+            let messages = outcome
+                .responses
+                .into_iter()
+                .enumerate()
+                .map(|(i, response)| {
+                    serialize(header_of(i), NetlinkPayload::InnerMessage(response))
+                })
+                .chain(Some(done(header_of(end))));
+            for message in messages {
+                match datagrams.last_mut() {
+                    Some(datagram) if datagram.len() + message.len() <= DUMP_DATAGRAM_LEN => {
+                        datagram.extend(message)
+                    }
+                    _ => datagrams.push(message),
+                }
+            }
+            datagrams
+        } else {
+            let mut datagrams: Vec<Vec<u8>> = outcome
+                .responses
+                .into_iter()
+                .map(|response| serialize(header, NetlinkPayload::InnerMessage(response)))
+                .collect();
+            if flags & NLM_F_ACK != 0 {
+                let ack = ErrorMessage {
+                    code: 0,
+                    header: bytes[..HEADER_LEN].to_vec(),
+                };
+                datagrams.push(serialize(header, NetlinkPayload::Ack(ack)));
+            }
+            datagrams
+        }
+    }
+
+    /// Send a notification to the sockets that joined `group`
+    fn notify(&mut self, group: u32, message: RtnlMessage) {
+        let bytes = serialize(
+            NetlinkHeader::default(),
+            NetlinkPayload::InnerMessage(message),
+        );
+        // Only the first 32 groups can be represented in an address
+        let groups = if (1..=32).contains(&group) {
+            1 << (group - 1)
+        } else {
+            0
+        };
+        let source = SocketAddr::new(0, groups);
+        for member in self.members.values_mut() {
+            if member.groups.contains(&group) {
+                let _ = member.socket.send_from(&bytes, source);
+            }
+        }
+    }
+}
+
+fn serialize(header: NetlinkHeader, payload: NetlinkPayload<RtnlMessage>) -> Vec<u8> {
+    let mut message = NetlinkMessage::new(header, payload);
+    message.finalize();
+    let mut bytes = vec![0; message.buffer_len()];
+    message.serialize(&mut bytes);
+    bytes
+}
+
+/// Message that ends a dump. Like the kernel, it carries a 4 bytes payload.
+fn done(header: NetlinkHeader) -> Vec<u8> {
+    let mut bytes = serialize(header, NetlinkPayload::Done);
+    bytes.extend_from_slice(&[0; 4]);
+    let len = bytes.len() as u32;
+    bytes[..4].copy_from_slice(&len.to_ne_bytes());
+    bytes
+}
+
+/// An in-process model of the `NETLINK_ROUTE` side of the kernel, to test code that uses a
+/// [`Handle`] without privileges nor network namespaces.
+///
+/// The kernel keeps links, addresses, routes and rules, that the handles returned by
+/// [`connect`](#method.connect) get, dump, create, change and delete like they would on Linux:
+/// the requests are acknowledged or fail with the same error codes, and the changes are
+/// notified to the sockets that joined the multicast groups. It starts like a new network
+/// namespace, with a loopback link that is down and the default rules.
+///
+/// The model is simple: routes are not derived from addresses, the links have no
+/// kind-specific behavior (the peer of a veth link is not created, for instance), and the
+/// other objects, like neighbours and queue disciplines, are not supported (neighbour dumps
+/// are empty).
+///
+/// This is only available with the `fake_kernel` feature, meant to be enabled in the
+/// `dev-dependencies` of the crates under test.
+///
+/// ```rust
+/// use futures::stream::TryStreamExt;
+/// use rtnetlink::{packet::rtnl::link::nlas::InfoKind, FakeKernel};
+///
+/// # async fn example() {
+/// let kernel = FakeKernel::new();
+/// let (connection, handle, _) = kernel.connect();
+/// tokio::spawn(connection);
+///
+/// handle
+///     .link()
+///     .add()
+///     .dummy("dummy0".into())
+///     .execute()
+///     .await
+///     .unwrap();
+/// let links: Vec<_> = handle.link().get().execute().try_collect().await.unwrap();
+/// assert_eq!(links.len(), 2);
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FakeKernel(Arc<Mutex<Shared>>);
+
+impl FakeKernel {
+    /// Create a kernel with a loopback link and the default rules.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn lock(&self) -> MutexGuard<'_, Shared> {
+        self.0.lock().unwrap()
+    }
+
+    /// Open a connection to the kernel. Like [`new_connection`](crate::new_connection), this
+    /// returns the connection, that must be spawned, a handle to send requests, and the
+    /// messages sent to the multicast groups joined with
+    /// [`FakeConnection::add_membership`].
+    #[allow(clippy::type_complexity)]
+    pub fn connect(
+        &self,
+    ) -> (
+        FakeConnection,
+        Handle,
+        UnboundedReceiver<(NetlinkMessage<RtnlMessage>, SocketAddr)>,
+    ) {
+        let port = {
+            let mut shared = self.lock();
+            shared.last_port += 1;
+            shared.last_port
+        };
+        let (user, kernel) = MemorySocket::pair(SocketAddr::new(port, 0), SocketAddr::new(0, 0));
+        self.lock().members.insert(
+            port,
+            Member {
+                socket: kernel,
+                groups: BTreeSet::new(),
+            },
+        );
+        let (connection, handle, messages) = new_connection_with_socket(user);
+        (
+            FakeConnection::new(connection, self.clone(), port),
+            handle,
+            messages,
+        )
+    }
+
+    /// Make the next request with the given `RTM_*` message type fail with `errno`, whatever
+    /// the state of the kernel.
+    pub fn fail_next(&self, message_type: u16, errno: i32) {
+        self.lock().failures.push_back((message_type, errno));
+    }
+
+    /// Make the next dump with the given `RTM_GET*` message type look interrupted by a
+    /// concurrent change: the second half of its messages and its end carry the
+    /// `NLM_F_DUMP_INTR` flag.
+    pub fn interrupt_next_dump(&self, message_type: u16) {
+        self.lock().interruptions.push_back(message_type);
+    }
+
+    /// Create, change or delete an object like another process would, for instance to
+    /// simulate a link going down. The change is notified to the multicast groups, and it
+    /// replaces the existing object if there is one.
+    pub fn apply(&self, message: RtnlMessage) -> Result<(), Error> {
+        let mut shared = self.lock();
+        let outcome = shared
+            .state
+            .handle(message, NLM_F_REQUEST | NLM_F_CREATE | NLM_F_REPLACE)
+            .map_err(|errno| {
+                Error::NetlinkError(ErrorMessage {
+                    code: -(errno as i32),
+                    header: vec![],
+                })
+            })?;
+        for (group, notification) in outcome.notifications {
+            shared.notify(group, notification);
+        }
+        Ok(())
+    }
+
+    /// Links of the kernel, by index
+    pub fn links(&self) -> Vec<LinkMessage> {
+        self.lock().state.links().cloned().collect()
+    }
+
+    /// Addresses of the kernel, in the order they were added
+    pub fn addresses(&self) -> Vec<AddressMessage> {
+        self.lock().state.addresses().cloned().collect()
+    }
+
+    /// Routes of the kernel, in the order they were added
+    pub fn routes(&self) -> Vec<RouteMessage> {
+        self.lock().state.routes().cloned().collect()
+    }
+
+    /// Rules of the kernel, by priority
+    pub fn rules(&self) -> Vec<RuleMessage> {
+        self.lock().state.rules().cloned().collect()
+    }
+}
@@ -0,0 +1,11 @@
+mod connection;
+pub use self::connection::*;
+
+mod kernel;
+pub use self::kernel::*;
+
+mod state;
+pub(crate) use self::state::KernelState;
+
+#[cfg(test)]
+mod test;
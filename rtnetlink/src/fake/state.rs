@@ -0,0 +1,789 @@
+use std::{collections::BTreeMap, mem};
+
+use nix::errno::Errno;
+
+use crate::{
+    cache::{link_name, route_table, rule_table},
+    packet::{
+        constants::*,
+        nlas::{
+            address,
+            link::{self, Info, State},
+            route,
+            rule,
+        },
+        AddressHeaderFlags,
+        AddressMessage,
+        LinkFlags,
+        LinkMessage,
+        RouteMessage,
+        RtnlMessage,
+        RuleMessage,
+        NLM_F_CREATE,
+        NLM_F_DUMP,
+        NLM_F_EXCL,
+        NLM_F_REPLACE,
+    },
+};
+
+/// Maximum length of a link name, including the terminating null byte
+const IFNAMSIZ: usize = 16;
+
+/// Maximum length of an alternative link name, including the terminating null byte
+const ALTIFNAMSIZ: usize = 128;
+
+/// Priority of the IPv6 routes added without one
+const IP6_RT_PRIO_USER: u32 = 1024;
+
+/// Flags that can be changed with `RTM_NEWLINK` and `RTM_SETLINK`. The others, like
+/// `IFF_RUNNING`, reflect the state of the link.
+const CHANGEABLE_FLAGS: LinkFlags = LinkFlags::from_bits_truncate(
+    LinkFlags::IFF_UP.bits()
+        | LinkFlags::IFF_DEBUG.bits()
+        | LinkFlags::IFF_NOARP.bits()
+        | LinkFlags::IFF_PROMISC.bits()
+        | LinkFlags::IFF_ALLMULTI.bits()
+        | LinkFlags::IFF_MULTICAST.bits()
+        | LinkFlags::IFF_AUTOMEDIA.bits(),
+);
+
+/// What the kernel sends back for a request: the responses to the requester, and the
+/// notifications to the multicast groups, by `RTNLGRP_*` number.
+#[derive(Debug, Default)]
+pub(crate) struct Outcome {
+    pub(crate) responses: Vec<RtnlMessage>,
+    pub(crate) notifications: Vec<(u32, RtnlMessage)>,
+}
+
+impl Outcome {
+    fn responses<I: IntoIterator<Item = RtnlMessage>>(responses: I) -> Self {
+        Outcome {
+            responses: responses.into_iter().collect(),
+            notifications: vec![],
+        }
+    }
+
+    fn notify(&mut self, group: u32, message: RtnlMessage) {
+        self.notifications.push((group, message));
+    }
+}
+
+/// Model of the links, addresses, routes and rules of a network namespace, that processes the
+/// requests the way the kernel does.
+#[derive(Debug, Clone)]
+pub(crate) struct KernelState {
+    links: BTreeMap<u32, LinkMessage>,
+    addresses: Vec<AddressMessage>,
+    routes: Vec<RouteMessage>,
+    /// Rules, sorted by priority
+    rules: Vec<RuleMessage>,
+    /// Index of the last link created. Like in the kernel, the indexes of the deleted links are
+    /// not reused.
+    last_index: u32,
+}
+
+impl Default for KernelState {
+    /// State of a new network namespace: a loopback link that is down, and the default rules.
+    fn default() -> Self {
+        let mut lo = LinkMessage::default();
+        lo.header.index = 1;
+        lo.header.link_layer_type = ARPHRD_LOOPBACK;
+        lo.header.flags = LinkFlags::IFF_LOOPBACK;
+        lo.nlas = vec![
+            link::Nla::IfName("lo".to_string()),
+            link::Nla::Mtu(65536),
+            link::Nla::Address(vec![0; 6]),
+            link::Nla::Broadcast(vec![0; 6]),
+            link::Nla::OperState(State::Down),
+        ];
+        let rules = vec![
+            default_rule(AF_INET, 0, RT_TABLE_LOCAL),
+            default_rule(AF_INET6, 0, RT_TABLE_LOCAL),
+            default_rule(AF_INET, 32766, RT_TABLE_MAIN),
+            default_rule(AF_INET6, 32766, RT_TABLE_MAIN),
+            default_rule(AF_INET, 32767, RT_TABLE_DEFAULT),
+        ];
+        KernelState {
+            links: vec![(1, lo)].into_iter().collect(),
+            addresses: vec![],
+            routes: vec![],
+            rules,
+            last_index: 1,
+        }
+    }
+}
+
+fn default_rule(family: u16, priority: u32, table: u8) -> RuleMessage {
+    let mut rule = RuleMessage::default();
+    rule.header.family = family as u8;
+    rule.header.table = table;
+    rule.header.action = FR_ACT_TO_TBL;
+    rule.nlas.push(rule::Nla::Table(table as u32));
+    if priority != 0 {
+        rule.nlas.push(rule::Nla::Priority(priority));
+    }
+    rule
+}
+
+impl KernelState {
+    pub(crate) fn links(&self) -> impl Iterator<Item = &LinkMessage> {
+        self.links.values()
+    }
+
+    pub(crate) fn addresses(&self) -> impl Iterator<Item = &AddressMessage> {
+        self.addresses.iter()
+    }
+
+    pub(crate) fn routes(&self) -> impl Iterator<Item = &RouteMessage> {
+        self.routes.iter()
+    }
+
+    pub(crate) fn rules(&self) -> impl Iterator<Item = &RuleMessage> {
+        self.rules.iter()
+    }
+
+    /// Process a request with the given `NLM_F_*` flags.
+    pub(crate) fn handle(&mut self, message: RtnlMessage, flags: u16) -> Result<Outcome, Errno> {
+        use RtnlMessage::*;
+        let dump = flags & NLM_F_DUMP == NLM_F_DUMP;
+        match message {
+            NewLink(msg) => self.new_link(msg, flags),
+            SetLink(msg) => {
+                let index = self.find_link(&msg).ok_or(Errno::ENODEV)?;
+                self.change_link(index, msg)
+            }
+            DelLink(msg) => self.del_link(msg),
+            GetLink(_) if dump => Ok(Outcome::responses(
+                self.links.values().cloned().map(NewLink),
+            )),
+            GetLink(msg) => {
+                let index = self.find_link(&msg).ok_or(Errno::ENODEV)?;
+                Ok(Outcome::responses(vec![NewLink(
+                    self.links[&index].clone(),
+                )]))
+            }
+            NewLinkProp(msg) => self.new_link_prop(msg),
+            DelLinkProp(msg) => self.del_link_prop(msg),
+            NewAddress(msg) => self.new_address(msg, flags),
+            DelAddress(msg) => self.del_address(msg),
+            GetAddress(msg) if dump => Ok(Outcome::responses(
+                self.addresses
+                    .iter()
+                    .filter(|address| has_family(address.header.family, msg.header.family))
+                    .cloned()
+                    .map(NewAddress),
+            )),
+            NewRoute(msg) => self.new_route(msg, flags),
+            DelRoute(msg) => self.del_route(msg),
+            GetRoute(msg) if dump => Ok(Outcome::responses(
+                self.routes
+                    .iter()
+                    .filter(|route| {
+                        has_family(route.header.address_family, msg.header.address_family)
+                    })
+                    .cloned()
+                    .map(NewRoute),
+            )),
+            NewRule(msg) => self.new_rule(msg, flags),
+            DelRule(msg) => self.del_rule(msg),
+            GetRule(msg) if dump => Ok(Outcome::responses(
+                self.rules
+                    .iter()
+                    .filter(|rule| has_family(rule.header.family, msg.header.family))
+                    .cloned()
+                    .map(NewRule),
+            )),
+            // Neighbours are not supported, so there are none to dump
+            GetNeighbour(_) if dump => Ok(Outcome::responses(vec![])),
+            _ => Err(Errno::EOPNOTSUPP),
+        }
+    }
+
+    /// Find a link by index if the index of the message is set, by name or alternative name
+    /// otherwise.
+    fn find_link(&self, message: &LinkMessage) -> Option<u32> {
+        let index = message.header.index;
+        if index != 0 {
+            return if self.links.contains_key(&index) {
+                Some(index)
+            } else {
+                None
+            };
+        }
+        let name = message.nlas.iter().find_map(|nla| match nla {
+            link::Nla::IfName(name) | link::Nla::AltIfName(name) => Some(name.as_str()),
+            _ => None,
+        })?;
+        self.links
+            .values()
+            .find(|link| has_name(link, name))
+            .map(|link| link.header.index)
+    }
+
+    fn new_link(&mut self, message: LinkMessage, flags: u16) -> Result<Outcome, Errno> {
+        match self.find_link(&message) {
+            Some(_) if flags & NLM_F_EXCL != 0 => Err(Errno::EEXIST),
+            Some(index) => self.change_link(index, message),
+            None if flags & NLM_F_CREATE == 0 => Err(Errno::ENODEV),
+            None => self.create_link(message),
+        }
+    }
+
+    fn create_link(&mut self, message: LinkMessage) -> Result<Outcome, Errno> {
+        // Without a kind, the kernel does not know which driver creates the link
+        if link_kind(&message).is_none() {
+            return Err(Errno::EOPNOTSUPP);
+        }
+        // The kernel would pick a name from the kind of the link
+        if link_name(&message).is_none() {
+            return Err(Errno::EINVAL);
+        }
+        let index = match message.header.index {
+            0 => self.last_index + 1,
+            index => index,
+        };
+        self.last_index = self.last_index.max(index);
+
+        let [high, low] = (index as u16).to_be_bytes();
+        let mut link = LinkMessage::default();
+        link.header.index = index;
+        link.header.link_layer_type = ARPHRD_ETHER;
+        link.header.flags = LinkFlags::IFF_BROADCAST | LinkFlags::IFF_MULTICAST;
+        link.nlas = vec![
+            link::Nla::Mtu(1500),
+            link::Nla::Address(vec![0x02, 0, 0, 0, high, low]),
+            link::Nla::Broadcast(vec![0xff; 6]),
+            link::Nla::OperState(State::Down),
+        ];
+        self.links.insert(index, link);
+        let outcome = self.change_link(index, message);
+        if outcome.is_err() {
+            self.links.remove(&index);
+        }
+        outcome
+    }
+
+    fn change_link(&mut self, index: u32, message: LinkMessage) -> Result<Outcome, Errno> {
+        let mut link = self.links[&index].clone();
+        for nla in message.nlas {
+            match nla {
+                link::Nla::IfName(ref name) => {
+                    if name.is_empty() || name.len() >= IFNAMSIZ {
+                        return Err(Errno::EINVAL);
+                    }
+                    if self
+                        .links
+                        .values()
+                        .any(|other| other.header.index != index && has_name(other, name))
+                    {
+                        return Err(Errno::EEXIST);
+                    }
+                    replace_nla(&mut link.nlas, nla);
+                }
+                link::Nla::Master(0) => {
+                    link.nlas.retain(|nla| !matches!(nla, link::Nla::Master(_)));
+                }
+                link::Nla::Master(master) => {
+                    if master == index {
+                        return Err(Errno::EINVAL);
+                    }
+                    if !self.links.contains_key(&master) {
+                        return Err(Errno::ENODEV);
+                    }
+                    replace_nla(&mut link.nlas, nla);
+                }
+                // Attributes of the request that are not properties of the link
+                link::Nla::ExtMask(_) | link::Nla::NetNsPid(_) | link::Nla::NetNsFd(_) => {}
+                nla => replace_nla(&mut link.nlas, nla),
+            }
+        }
+        let change_mask = message.header.change_mask;
+        if !message.header.flags.is_empty() || !change_mask.is_empty() {
+            let change = if change_mask.is_empty() {
+                CHANGEABLE_FLAGS
+            } else {
+                change_mask & CHANGEABLE_FLAGS
+            };
+            let flags = (link.header.flags & !change) | (message.header.flags & change);
+            set_link_flags(&mut link, flags);
+        }
+        self.links.insert(index, link.clone());
+        let mut outcome = Outcome::default();
+        outcome.notify(RTNLGRP_LINK, RtnlMessage::NewLink(link));
+        Ok(outcome)
+    }
+
+    fn del_link(&mut self, message: LinkMessage) -> Result<Outcome, Errno> {
+        let index = self.find_link(&message).ok_or(Errno::ENODEV)?;
+        // Links that were not created with a kind, like the loopback, cannot be deleted
+        if link_kind(&self.links[&index]).is_none() {
+            return Err(Errno::EOPNOTSUPP);
+        }
+        let link = self.links.remove(&index).unwrap();
+        let mut outcome = Outcome::default();
+        for address in drain(&mut self.addresses, |address| address.header.index == index) {
+            outcome.notify(
+                address_group(address.header.family),
+                RtnlMessage::DelAddress(address),
+            );
+        }
+        // Like for IPv4 in the kernel, the routes going through the link are removed without
+        // notifications.
+        self.routes.retain(|route| route_oif(route) != Some(index));
+        for other in self.links.values_mut() {
+            let len = other.nlas.len();
+            other
+                .nlas
+                .retain(|nla| !matches!(nla, link::Nla::Master(master) if *master == index));
+            if other.nlas.len() != len {
+                outcome.notify(RTNLGRP_LINK, RtnlMessage::NewLink(other.clone()));
+            }
+        }
+        outcome.notify(RTNLGRP_LINK, RtnlMessage::DelLink(link));
+        Ok(outcome)
+    }
+
+    fn new_link_prop(&mut self, message: LinkMessage) -> Result<Outcome, Errno> {
+        let index = self.find_link(&message).ok_or(Errno::ENODEV)?;
+        let mut link = self.links[&index].clone();
+        for name in alt_names(&message) {
+            if name.is_empty() || name.len() >= ALTIFNAMSIZ {
+                return Err(Errno::EINVAL);
+            }
+            if self.links.values().any(|other| has_name(other, name)) || has_name(&link, name) {
+                return Err(Errno::EEXIST);
+            }
+            let prop = link::Prop::AltIfName(name.to_string());
+            match link.nlas.iter_mut().find_map(|nla| match nla {
+                link::Nla::PropList(props) => Some(props),
+                _ => None,
+            }) {
+                Some(props) => props.push(prop),
+                None => link.nlas.push(link::Nla::PropList(vec![prop])),
+            }
+        }
+        self.links.insert(index, link.clone());
+        let mut outcome = Outcome::default();
+        outcome.notify(RTNLGRP_LINK, RtnlMessage::NewLink(link));
+        Ok(outcome)
+    }
+
+    fn del_link_prop(&mut self, message: LinkMessage) -> Result<Outcome, Errno> {
+        let index = self.find_link(&message).ok_or(Errno::ENODEV)?;
+        let mut link = self.links[&index].clone();
+        for name in alt_names(&message) {
+            let props = link.nlas.iter_mut().find_map(|nla| match nla {
+                link::Nla::PropList(props) => Some(props),
+                _ => None,
+            });
+            let position = props.as_ref().and_then(|props| {
+                props
+                    .iter()
+                    .position(|prop| matches!(prop, link::Prop::AltIfName(alt) if alt == name))
+            });
+            match (props, position) {
+                (Some(props), Some(position)) => {
+                    props.remove(position);
+                }
+                _ => return Err(Errno::ENOENT),
+            }
+        }
+        link.nlas
+            .retain(|nla| !matches!(nla, link::Nla::PropList(props) if props.is_empty()));
+        self.links.insert(index, link.clone());
+        let mut outcome = Outcome::default();
+        outcome.notify(RTNLGRP_LINK, RtnlMessage::NewLink(link));
+        Ok(outcome)
+    }
+
+    fn new_address(&mut self, message: AddressMessage, flags: u16) -> Result<Outcome, Errno> {
+        let family = message.header.family as u16;
+        if family != AF_INET && family != AF_INET6 {
+            return Err(Errno::EOPNOTSUPP);
+        }
+        let name = match self.links.get(&message.header.index) {
+            Some(link) => link_name(link).unwrap_or_default().to_string(),
+            None => return Err(Errno::ENODEV),
+        };
+        if address_local(&message).is_none() {
+            return Err(Errno::EINVAL);
+        }
+        let mut address = message;
+        address.header.flags |= AddressHeaderFlags::IFA_F_PERMANENT;
+        if family == AF_INET
+            && !address
+                .nlas
+                .iter()
+                .any(|nla| matches!(nla, address::Nla::Label(_)))
+        {
+            address.nlas.push(address::Nla::Label(name));
+        }
+        match self
+            .addresses
+            .iter()
+            .position(|other| same_address(other, &address))
+        {
+            Some(position) if flags & NLM_F_REPLACE != 0 && flags & NLM_F_EXCL == 0 => {
+                self.addresses[position] = address.clone();
+            }
+            Some(_) => return Err(Errno::EEXIST),
+            None => self.addresses.push(address.clone()),
+        }
+        let mut outcome = Outcome::default();
+        outcome.notify(
+            address_group(address.header.family),
+            RtnlMessage::NewAddress(address),
+        );
+        Ok(outcome)
+    }
+
+    fn del_address(&mut self, message: AddressMessage) -> Result<Outcome, Errno> {
+        let local = address_local(&message);
+        let position = self
+            .addresses
+            .iter()
+            .position(|address| {
+                address.header.family == message.header.family
+                    && address.header.index == message.header.index
+                    && (local.is_none()
+                        || address.header.prefix_len == message.header.prefix_len
+                            && address_local(address) == local)
+            })
+            .ok_or(Errno::EADDRNOTAVAIL)?;
+        let address = self.addresses.remove(position);
+        let mut outcome = Outcome::default();
+        outcome.notify(
+            address_group(address.header.family),
+            RtnlMessage::DelAddress(address),
+        );
+        Ok(outcome)
+    }
+
+    fn new_route(&mut self, message: RouteMessage, flags: u16) -> Result<Outcome, Errno> {
+        let family = message.header.address_family as u16;
+        if family != AF_INET && family != AF_INET6 {
+            return Err(Errno::EOPNOTSUPP);
+        }
+        if let Some(index) = route_oif(&message) {
+            if !self.links.contains_key(&index) {
+                return Err(Errno::ENODEV);
+            }
+        }
+        let mut route = message;
+        let table = match route_table(&route) {
+            0 => RT_TABLE_MAIN as u32,
+            table => table,
+        };
+        route.header.table = if table > 255 {
+            RT_TABLE_COMPAT.into()
+        } else {
+            (table as u8).into()
+        };
+        replace_nla(&mut route.nlas, route::Nla::Table(table));
+        if family == AF_INET6 && route_priority(&route).is_none() {
+            route.nlas.push(route::Nla::Priority(IP6_RT_PRIO_USER));
+        }
+
+        let existing = self
+            .routes
+            .iter()
+            .position(|other| same_route_key(other, &route));
+        match existing {
+            Some(_) if flags & NLM_F_EXCL != 0 => return Err(Errno::EEXIST),
+            Some(position) if flags & NLM_F_REPLACE != 0 => self.routes[position] = route.clone(),
+            Some(_)
+                if self.routes.iter().any(|other| {
+                    same_route_key(other, &route)
+                        && route_oif(other) == route_oif(&route)
+                        && route_gateway(other) == route_gateway(&route)
+                }) =>
+            {
+                return Err(Errno::EEXIST)
+            }
+            _ => self.routes.push(route.clone()),
+        }
+        let mut outcome = Outcome::default();
+        outcome.notify(
+            route_group(route.header.address_family),
+            RtnlMessage::NewRoute(route),
+        );
+        Ok(outcome)
+    }
+
+    fn del_route(&mut self, message: RouteMessage) -> Result<Outcome, Errno> {
+        let table = match route_table(&message) {
+            0 => RT_TABLE_MAIN as u32,
+            table => table,
+        };
+        let position = self
+            .routes
+            .iter()
+            .position(|route| {
+                route.header.address_family == message.header.address_family
+                    && route_table(route) == table
+                    && route.header.destination_prefix_length
+                        == message.header.destination_prefix_length
+                    && route_destination(route) == route_destination(&message)
+                    && (message.header.tos == 0 || route.header.tos == message.header.tos)
+                    && matches_if_set(route_priority(&message), route_priority(route))
+                    && matches_if_set(route_oif(&message), route_oif(route))
+                    && matches_if_set(route_gateway(&message), route_gateway(route))
+            })
+            .ok_or(Errno::ESRCH)?;
+        let route = self.routes.remove(position);
+        let mut outcome = Outcome::default();
+        outcome.notify(
+            route_group(route.header.address_family),
+            RtnlMessage::DelRoute(route),
+        );
+        Ok(outcome)
+    }
+
+    fn new_rule(&mut self, message: RuleMessage, flags: u16) -> Result<Outcome, Errno> {
+        let family = message.header.family as u16;
+        if family != AF_INET && family != AF_INET6 {
+            return Err(Errno::EAFNOSUPPORT);
+        }
+        let table = rule_table(&message);
+        if message.header.action == FR_ACT_TO_TBL && table == 0 {
+            return Err(Errno::EINVAL);
+        }
+        let mut rule = message;
+        let priority = match rule_priority(&rule) {
+            Some(priority) => priority,
+            // Like the kernel, put the rule just before the first rule that follows the rule
+            // with priority 0
+            None => self
+                .rules
+                .iter()
+                .filter(|other| other.header.family == rule.header.family)
+                .map(rule_priority_or_zero)
+                .find(|priority| *priority != 0)
+                .map_or(0, |priority| priority - 1),
+        };
+        rule.nlas
+            .retain(|nla| !matches!(nla, rule::Nla::Priority(_) | rule::Nla::Table(_)));
+        if table != 0 {
+            rule.header.table = if table > 255 {
+                RT_TABLE_COMPAT
+            } else {
+                table as u8
+            };
+            rule.nlas.push(rule::Nla::Table(table));
+        }
+        if priority != 0 {
+            rule.nlas.push(rule::Nla::Priority(priority));
+        }
+        if flags & NLM_F_EXCL != 0 && self.rules.iter().any(|other| same_rule(other, &rule)) {
+            return Err(Errno::EEXIST);
+        }
+        let position = self
+            .rules
+            .iter()
+            .position(|other| rule_priority_or_zero(other) > priority)
+            .unwrap_or(self.rules.len());
+        self.rules.insert(position, rule.clone());
+        let mut outcome = Outcome::default();
+        outcome.notify(rule_group(rule.header.family), RtnlMessage::NewRule(rule));
+        Ok(outcome)
+    }
+
+    fn del_rule(&mut self, message: RuleMessage) -> Result<Outcome, Errno> {
+        let table = rule_table(&message);
+        let position = self
+            .rules
+            .iter()
+            .position(|rule| {
+                rule.header.family == message.header.family
+                    && (table == 0 || rule_table(rule) == table)
+                    && (message.header.action == 0 || rule.header.action == message.header.action)
+                    && matches_if_set(rule_priority(&message), Some(rule_priority_or_zero(rule)))
+                    && message.nlas.iter().all(|nla| {
+                        matches!(nla, rule::Nla::Priority(_) | rule::Nla::Table(_))
+                            || rule.nlas.contains(nla)
+                    })
+            })
+            .ok_or(Errno::ENOENT)?;
+        let rule = self.rules.remove(position);
+        let mut outcome = Outcome::default();
+        outcome.notify(rule_group(rule.header.family), RtnlMessage::DelRule(rule));
+        Ok(outcome)
+    }
+}
+
+/// Whether an object of the family `family` is part of a dump for `requested`
+fn has_family(family: u8, requested: u8) -> bool {
+    requested == AF_UNSPEC as u8 || family == requested
+}
+
+/// Whether `value` matches the `requested` value, if any
+fn matches_if_set<T: PartialEq>(requested: Option<T>, value: Option<T>) -> bool {
+    requested.is_none() || requested == value
+}
+
+fn replace_nla<T>(nlas: &mut Vec<T>, nla: T) {
+    match nlas
+        .iter_mut()
+        .find(|other| mem::discriminant(*other) == mem::discriminant(&nla))
+    {
+        Some(other) => *other = nla,
+        None => nlas.push(nla),
+    }
+}
+
+/// Remove and return the elements that match `predicate`
+fn drain<T, F>(items: &mut Vec<T>, predicate: F) -> Vec<T>
+where
+    F: Fn(&T) -> bool,
+{
+    let (drained, kept) = mem::take(items).into_iter().partition(predicate);
+    *items = kept;
+    drained
+}
+
+/// Whether `name` is the name or one of the alternative names of the link
+fn has_name(link: &LinkMessage, name: &str) -> bool {
+    link_name(link) == Some(name) || alt_names(link).any(|alt| alt == name)
+}
+
+/// Alternative names of a link, or in a property request
+fn alt_names(message: &LinkMessage) -> impl Iterator<Item = &str> {
+    message
+        .nlas
+        .iter()
+        .filter_map(|nla| match nla {
+            link::Nla::PropList(props) => Some(props),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|prop| match prop {
+            link::Prop::AltIfName(name) => Some(name.as_str()),
+            _ => None,
+        })
+}
+
+fn link_kind(message: &LinkMessage) -> Option<&link::InfoKind> {
+    message.nlas.iter().find_map(|nla| match nla {
+        link::Nla::Info(infos) => infos.iter().find_map(|info| match info {
+            Info::Kind(kind) => Some(kind),
+            _ => None,
+        }),
+        _ => None,
+    })
+}
+
+/// Set the flags of a link, and the flags and the operational state that follow from whether
+/// it is up: the modelled links always have a carrier.
+fn set_link_flags(link: &mut LinkMessage, mut flags: LinkFlags) {
+    let running = LinkFlags::IFF_RUNNING | LinkFlags::IFF_LOWER_UP;
+    let state = if flags.contains(LinkFlags::IFF_UP) {
+        flags |= running;
+        State::Up
+    } else {
+        flags.remove(running);
+        State::Down
+    };
+    link.header.flags = flags;
+    replace_nla(&mut link.nlas, link::Nla::OperState(state));
+}
+
+/// Local address of an address message, which is its only address for IPv6
+fn address_local(message: &AddressMessage) -> Option<&[u8]> {
+    let mut local = None;
+    for nla in message.nlas.iter() {
+        match nla {
+            address::Nla::Local(bytes) => return Some(bytes),
+            address::Nla::Address(bytes) => local = Some(bytes.as_slice()),
+            _ => {}
+        }
+    }
+    local
+}
+
+fn same_address(first: &AddressMessage, second: &AddressMessage) -> bool {
+    first.header.family == second.header.family
+        && first.header.index == second.header.index
+        && first.header.prefix_len == second.header.prefix_len
+        && address_local(first) == address_local(second)
+}
+
+fn route_destination(message: &RouteMessage) -> Option<&[u8]> {
+    message.nlas.iter().find_map(|nla| match nla {
+        route::Nla::Destination(bytes) => Some(bytes.as_slice()),
+        _ => None,
+    })
+}
+
+fn route_gateway(message: &RouteMessage) -> Option<&[u8]> {
+    message.nlas.iter().find_map(|nla| match nla {
+        route::Nla::Gateway(bytes) => Some(bytes.as_slice()),
+        _ => None,
+    })
+}
+
+fn route_oif(message: &RouteMessage) -> Option<u32> {
+    message.nlas.iter().find_map(|nla| match nla {
+        route::Nla::Oif(index) => Some(*index),
+        _ => None,
+    })
+}
+
+fn route_priority(message: &RouteMessage) -> Option<u32> {
+    message.nlas.iter().find_map(|nla| match nla {
+        route::Nla::Priority(priority) => Some(*priority),
+        _ => None,
+    })
+}
+
+/// Whether two routes have the same key in their table: the kernel keeps at most one route per
+/// key, unless they have different next hops.
+fn same_route_key(first: &RouteMessage, second: &RouteMessage) -> bool {
+    first.header.address_family == second.header.address_family
+        && route_table(first) == route_table(second)
+        && first.header.destination_prefix_length == second.header.destination_prefix_length
+        && route_destination(first) == route_destination(second)
+        && first.header.tos == second.header.tos
+        && route_priority(first).unwrap_or(0) == route_priority(second).unwrap_or(0)
+}
+
+fn rule_priority(message: &RuleMessage) -> Option<u32> {
+    message.nlas.iter().find_map(|nla| match nla {
+        rule::Nla::Priority(priority) => Some(*priority),
+        _ => None,
+    })
+}
+
+/// Priority of a rule of the model, where a priority of 0 has no attribute
+fn rule_priority_or_zero(message: &RuleMessage) -> u32 {
+    rule_priority(message).unwrap_or(0)
+}
+
+fn same_rule(first: &RuleMessage, second: &RuleMessage) -> bool {
+    first.header == second.header
+        && first.nlas.len() == second.nlas.len()
+        && first.nlas.iter().all(|nla| second.nlas.contains(nla))
+}
+
+fn address_group(family: u8) -> u32 {
+    if family as u16 == AF_INET6 {
+        RTNLGRP_IPV6_IFADDR
+    } else {
+        RTNLGRP_IPV4_IFADDR
+    }
+}
+
+fn route_group(family: u8) -> u32 {
+    if family as u16 == AF_INET6 {
+        RTNLGRP_IPV6_ROUTE
+    } else {
+        RTNLGRP_IPV4_ROUTE
+    }
+}
+
+fn rule_group(family: u8) -> u32 {
+    if family as u16 == AF_INET6 {
+        RTNLGRP_IPV6_RULE
+    } else {
+        RTNLGRP_IPV4_RULE
+    }
+}
@@ -0,0 +1,488 @@
+use std::net::{IpAddr, Ipv4Addr};
+
+use futures::{
+    stream::{StreamExt, TryStreamExt},
+    Future,
+};
+use nix::errno::Errno;
+use tokio::runtime::Runtime;
+
+use crate::{
+    packet::{
+        constants::{RTM_DELLINK, RTM_GETLINK, RTM_NEWLINK},
+        nlas::{
+            link::{Info, InfoKind, Nla},
+            rule,
+        },
+        LinkFlags,
+        LinkMessage,
+        RtnlMessage,
+    },
+    DumpInterruptedPolicy,
+    Error,
+    FakeKernel,
+    Handle,
+    IpVersion,
+    LinkConfig,
+    LinkKind,
+    LinkState,
+    NetworkState,
+    RtnlEvent,
+    RtnlEventStream,
+    RtnlGroup,
+};
+
+fn run<F: Future>(future: F) -> F::Output {
+    Runtime::new().unwrap().block_on(future)
+}
+
+fn connect(kernel: &FakeKernel) -> Handle {
+    let (connection, handle, _) = kernel.connect();
+    tokio::spawn(connection);
+    handle
+}
+
+fn errno<T: std::fmt::Debug>(result: Result<T, Error>) -> Errno {
+    match result {
+        Err(Error::NetlinkError(message)) => Errno::from_i32(-message.code),
+        other => panic!("expected a netlink error, got {:?}", other),
+    }
+}
+
+async fn link_by_name(handle: &Handle, name: &str) -> Result<Option<LinkMessage>, Error> {
+    handle
+        .link()
+        .get()
+        .set_name_filter(name.to_string())
+        .execute()
+        .try_next()
+        .await
+}
+
+#[test]
+fn link_lifecycle() {
+    let kernel = FakeKernel::new();
+    run(async {
+        let handle = connect(&kernel);
+        handle
+            .link()
+            .add()
+            .dummy("dummy0".into())
+            .execute()
+            .await
+            .unwrap();
+        let link = link_by_name(&handle, "dummy0").await.unwrap().unwrap();
+        let index = link.header.index;
+        assert_eq!(index, 2);
+        assert!(link
+            .header
+            .flags
+            .contains(LinkFlags::IFF_UP | LinkFlags::IFF_RUNNING));
+
+        handle.link().set(index).down().execute().await.unwrap();
+        let link = handle
+            .link()
+            .get()
+            .match_index(index)
+            .execute()
+            .try_next()
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!link
+            .header
+            .flags
+            .intersects(LinkFlags::IFF_UP | LinkFlags::IFF_RUNNING));
+
+        handle.link().del(index).execute().await.unwrap();
+        let result = handle
+            .link()
+            .get()
+            .match_index(index)
+            .execute()
+            .try_next()
+            .await;
+        assert_eq!(errno(result), Errno::ENODEV);
+    });
+    assert_eq!(kernel.links().len(), 1);
+}
+
+#[test]
+fn link_errors() {
+    let kernel = FakeKernel::new();
+    run(async {
+        let handle = connect(&kernel);
+        let add = || handle.link().add().dummy("dummy0".into()).execute();
+        add().await.unwrap();
+        assert_eq!(errno(add().await), Errno::EEXIST);
+        // The loopback link cannot be deleted
+        assert_eq!(
+            errno(handle.link().del(1).execute().await),
+            Errno::EOPNOTSUPP
+        );
+        assert_eq!(
+            errno(handle.link().set(42).up().execute().await),
+            Errno::ENODEV
+        );
+    });
+}
+
+#[test]
+fn link_properties() {
+    let kernel = FakeKernel::new();
+    run(async {
+        let handle = connect(&kernel);
+        handle
+            .link()
+            .add()
+            .dummy("dummy0".into())
+            .execute()
+            .await
+            .unwrap();
+        let long_name = "an-alternative-name-longer-than-ifnamsiz";
+        handle
+            .link()
+            .property_add(2)
+            .alt_ifname(&["uplink", long_name])
+            .execute()
+            .await
+            .unwrap();
+        for name in &["dummy0", "uplink", long_name] {
+            let link = link_by_name(&handle, name).await.unwrap().unwrap();
+            assert_eq!(link.header.index, 2);
+        }
+        let add = || {
+            handle
+                .link()
+                .property_add(2)
+                .alt_ifname(&["uplink"])
+                .execute()
+        };
+        assert_eq!(errno(add().await), Errno::EEXIST);
+
+        let del = || {
+            handle
+                .link()
+                .property_del(2)
+                .alt_ifname(&["uplink"])
+                .execute()
+        };
+        del().await.unwrap();
+        assert!(link_by_name(&handle, "uplink").await.unwrap().is_none());
+        assert_eq!(errno(del().await), Errno::ENOENT);
+        let link = link_by_name(&handle, long_name).await.unwrap().unwrap();
+        assert_eq!(link.header.index, 2);
+    });
+}
+
+#[test]
+fn addresses_and_routes() {
+    let kernel = FakeKernel::new();
+    run(async {
+        let handle = connect(&kernel);
+        handle
+            .link()
+            .add()
+            .dummy("dummy0".into())
+            .execute()
+            .await
+            .unwrap();
+        let address = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let add_address = || handle.address().add(2, address, 24).execute();
+        add_address().await.unwrap();
+        assert_eq!(errno(add_address().await), Errno::EEXIST);
+        assert_eq!(
+            errno(handle.address().add(42, address, 24).execute().await),
+            Errno::ENODEV
+        );
+        let addresses: Vec<_> = handle
+            .address()
+            .get()
+            .execute()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(addresses.len(), 1);
+
+        let add_route = || {
+            handle
+                .route()
+                .add()
+                .v4()
+                .destination_prefix(Ipv4Addr::new(10, 1, 0, 0), 16)
+                .output_interface(2)
+                .execute()
+        };
+        add_route().await.unwrap();
+        assert_eq!(errno(add_route().await), Errno::EEXIST);
+        let routes: Vec<_> = handle
+            .route()
+            .get(IpVersion::V4)
+            .execute()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(routes.len(), 1);
+        handle
+            .route()
+            .del(routes[0].clone())
+            .execute()
+            .await
+            .unwrap();
+        assert_eq!(
+            errno(handle.route().del(routes[0].clone()).execute().await),
+            Errno::ESRCH
+        );
+
+        // Deleting a link removes its addresses and routes
+        add_route().await.unwrap();
+        handle.link().del(2).execute().await.unwrap();
+    });
+    assert!(kernel.addresses().is_empty());
+    assert!(kernel.routes().is_empty());
+}
+
+#[test]
+fn rules() {
+    let kernel = FakeKernel::new();
+    run(async {
+        let handle = connect(&kernel);
+        let dump = || async {
+            handle
+                .rule()
+                .get(IpVersion::V4)
+                .execute()
+                .try_collect::<Vec<_>>()
+                .await
+                .unwrap()
+        };
+        assert_eq!(dump().await.len(), 3);
+
+        handle.rule().add().v4().table(100).execute().await.unwrap();
+        let rules = dump().await;
+        assert_eq!(rules.len(), 4);
+        // Without a priority, the rule goes right after the rule with priority 0
+        assert_eq!(rules[1].header.table, 100);
+        assert!(rules[1].nlas.contains(&rule::Nla::Priority(32765)));
+
+        let del = || handle.rule().del(rules[1].clone()).execute();
+        del().await.unwrap();
+        assert_eq!(errno(del().await), Errno::ENOENT);
+    });
+    assert_eq!(kernel.rules().len(), 5);
+}
+
+#[test]
+fn rule_prefixes() {
+    let kernel = FakeKernel::new();
+    run(async {
+        let handle = connect(&kernel);
+        let source = Ipv4Addr::new(10, 0, 0, 0);
+        let mut request = handle
+            .rule()
+            .del_matching()
+            .v4()
+            .source_prefix(Ipv4Addr::new(192, 168, 0, 0), 16)
+            .source_prefix(source, 8);
+        let message = request.message_mut();
+        assert_eq!(message.source_prefix(), Some((IpAddr::V4(source), 8)));
+        let sources = message
+            .nlas
+            .iter()
+            .filter(|nla| matches!(nla, rule::Nla::Source(_)))
+            .count();
+        assert_eq!(sources, 1);
+    });
+}
+
+#[test]
+#[should_panic(expected = "invalid prefix length 33")]
+fn rule_invalid_prefix() {
+    let kernel = FakeKernel::new();
+    run(async {
+        let handle = connect(&kernel);
+        handle
+            .rule()
+            .add()
+            .v4()
+            .destination_prefix(Ipv4Addr::new(10, 0, 0, 0), 33);
+    });
+}
+
+#[test]
+fn notifications() {
+    let kernel = FakeKernel::new();
+    run(async {
+        let handle = connect(&kernel);
+        let (mut connection, _, messages) = kernel.connect();
+        connection.add_membership(RtnlGroup::Link);
+        let mut events = RtnlEventStream::new(connection, messages);
+        handle
+            .link()
+            .add()
+            .dummy("dummy0".into())
+            .execute()
+            .await
+            .unwrap();
+        match events.try_next().await.unwrap() {
+            Some(RtnlEvent::LinkAdded(link)) => {
+                assert!(link.nlas.contains(&Nla::IfName("dummy0".into())))
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        // Changes made by other processes are notified as well
+        let mut link = LinkMessage::default();
+        link.header.index = 2;
+        kernel.apply(RtnlMessage::DelLink(link)).unwrap();
+        match events.try_next().await.unwrap() {
+            Some(RtnlEvent::LinkRemoved(link)) => assert_eq!(link.header.index, 2),
+            other => panic!("unexpected event: {:?}", other),
+        }
+    });
+}
+
+#[test]
+fn batch_with_scripted_failure() {
+    let kernel = FakeKernel::new();
+    kernel.fail_next(RTM_NEWLINK, Errno::EBUSY as i32);
+    let results = run(async {
+        let handle = connect(&kernel);
+        handle
+            .batch()
+            .add(handle.link().add().dummy("dummy0".into()))
+            .add(handle.link().add().dummy("dummy1".into()))
+            .add(handle.link().del(42))
+            .execute()
+            .await
+            .unwrap()
+    });
+    let mut results = results.into_iter();
+    assert_eq!(errno(results.next().unwrap()), Errno::EBUSY);
+    results.next().unwrap().unwrap();
+    assert_eq!(errno(results.next().unwrap()), Errno::ENODEV);
+
+    let link = kernel.links().pop().unwrap();
+    assert!(link
+        .nlas
+        .contains(&Nla::Info(vec![Info::Kind(InfoKind::Dummy)])));
+    assert!(link.nlas.contains(&Nla::IfName("dummy1".into())));
+}
+
+#[test]
+fn reconcile_ignores_missing_objects() {
+    let kernel = kernel_with_links();
+    let mut config = LinkConfig {
+        name: "dummy0".to_string(),
+        kind: LinkKind::Dummy,
+        state: LinkState::Absent,
+        mtu: None,
+        master: None,
+    };
+    let desired = NetworkState {
+        links: vec![config.clone()],
+        ..Default::default()
+    };
+
+    // The link disappears between the dump and its deletion
+    kernel.fail_next(RTM_DELLINK, Errno::ENODEV as i32);
+    run(async { connect(&kernel).reconcile(desired).execute().await }).unwrap();
+
+    config.name = "dummy1".to_string();
+    let desired = NetworkState {
+        links: vec![config],
+        ..Default::default()
+    };
+    kernel.fail_next(RTM_DELLINK, Errno::EPERM as i32);
+    let result = run(async { connect(&kernel).reconcile(desired).execute().await });
+    assert_eq!(errno(result), Errno::EPERM);
+}
+
+/// Create a kernel with three links
+fn kernel_with_links() -> FakeKernel {
+    let kernel = FakeKernel::new();
+    run(async {
+        let handle = connect(&kernel);
+        for name in &["dummy0", "dummy1"] {
+            handle
+                .link()
+                .add()
+                .dummy(name.to_string())
+                .execute()
+                .await
+                .unwrap();
+        }
+    });
+    kernel
+}
+
+/// Dump the links, and return their index, or `None` for the [`Error::DumpInterrupted`] errors.
+fn dump_links(kernel: &FakeKernel, policy: DumpInterruptedPolicy) -> Vec<Option<u32>> {
+    run(async {
+        connect(kernel)
+            .link()
+            .get()
+            .on_dump_interrupted(policy)
+            .execute()
+            .into_stream()
+            .map(|result| match result {
+                Ok(link) => Some(link.header.index),
+                Err(Error::DumpInterrupted) => None,
+                Err(e) => panic!("unexpected error: {:?}", e),
+            })
+            .collect()
+            .await
+    })
+}
+
+#[test]
+fn dump_interrupted() {
+    let kernel = kernel_with_links();
+    kernel.interrupt_next_dump(RTM_GETLINK);
+    assert_eq!(
+        dump_links(&kernel, DumpInterruptedPolicy::Ignore),
+        vec![Some(1), Some(2), Some(3)]
+    );
+
+    // The links received before the interruption are returned
+    kernel.interrupt_next_dump(RTM_GETLINK);
+    assert_eq!(
+        dump_links(&kernel, DumpInterruptedPolicy::Fail),
+        vec![Some(1), None]
+    );
+    assert_eq!(
+        dump_links(&kernel, DumpInterruptedPolicy::Fail),
+        vec![Some(1), Some(2), Some(3)]
+    );
+}
+
+#[test]
+fn dump_interrupted_retry() {
+    let kernel = kernel_with_links();
+    kernel.interrupt_next_dump(RTM_GETLINK);
+    kernel.interrupt_next_dump(RTM_GETLINK);
+    // The third dump completes, and only its links are returned
+    assert_eq!(
+        dump_links(&kernel, DumpInterruptedPolicy::Retry(2)),
+        vec![Some(1), Some(2), Some(3)]
+    );
+    // No interruption is left
+    assert_eq!(
+        dump_links(&kernel, DumpInterruptedPolicy::Fail),
+        vec![Some(1), Some(2), Some(3)]
+    );
+
+    for _ in 0..3 {
+        kernel.interrupt_next_dump(RTM_GETLINK);
+    }
+    // No link is returned when all the attempts are interrupted
+    assert_eq!(
+        dump_links(&kernel, DumpInterruptedPolicy::Retry(1)),
+        vec![None]
+    );
+    // Only two dumps were made
+    assert_eq!(
+        dump_links(&kernel, DumpInterruptedPolicy::Fail),
+        vec![Some(1), None]
+    );
+}
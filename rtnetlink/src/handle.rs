@@ -1,12 +1,17 @@
-use futures::Stream;
+use futures::{Stream, TryStreamExt};
 
 use crate::{
     packet::{NetlinkMessage, RtnlMessage},
     AddressHandle,
+    BatchRequest,
     Error,
     LinkHandle,
     NeighbourHandle,
+    NeighbourTableHandle,
+    NetworkState,
+    NsidHandle,
     QDiscHandle,
+    ReconcileRequest,
     RouteHandle,
     RuleHandle,
     TrafficChainHandle,
@@ -26,12 +31,30 @@ impl Handle {
     pub fn request(
         &mut self,
         message: NetlinkMessage<RtnlMessage>,
-    ) -> Result<impl Stream<Item = NetlinkMessage<RtnlMessage>>, Error> {
+    ) -> Result<impl Stream<Item = Result<NetlinkMessage<RtnlMessage>, Error>>, Error> {
         self.0
             .request(message, SocketAddr::new(0, 0))
+            .map(|response| response.map_err(Error::from))
             .map_err(|_| Error::RequestFailed)
     }
 
+    /// Send several requests at once. They are sent in as few datagrams as possible, and a stream
+    /// of responses is returned for each of them, in the same order. This is meant for requests
+    /// that are only acknowledged, not for dumps. See also [`batch`](#method.batch).
+    pub fn batch_request(
+        &mut self,
+        messages: Vec<NetlinkMessage<RtnlMessage>>,
+    ) -> Result<Vec<impl Stream<Item = Result<NetlinkMessage<RtnlMessage>, Error>>>, Error> {
+        let responses = self
+            .0
+            .batch_request(messages, SocketAddr::new(0, 0))
+            .map_err(|_| Error::RequestFailed)?;
+        Ok(responses
+            .into_iter()
+            .map(|response| response.map_err(Error::from))
+            .collect())
+    }
+
     pub fn notify(&mut self, msg: NetlinkMessage<RtnlMessage>) -> Result<(), Error> {
         self.0
             .notify(msg, SocketAddr::new(0, 0))
@@ -39,6 +62,73 @@ impl Handle {
         Ok(())
     }
 
+    /// Subscribe to the given multicast groups, and return the stream of the events sent to
+    /// these groups. The events are received on a dedicated socket, so they are not mixed with
+    /// the responses to the requests made with this handle. To open this socket with other
+    /// settings, for instance in another network namespace, see
+    /// [`ConnectionBuilder::subscribe`](crate::ConnectionBuilder::subscribe).
+    ///
+    /// ```rust,no_run
+    /// use futures::stream::TryStreamExt;
+    /// use rtnetlink::{new_connection, Error, RtnlEvent, RtnlGroup};
+    ///
+    /// async fn print_link_changes() -> Result<(), Error> {
+    ///     let (connection, handle, _) = new_connection().unwrap();
+    ///     tokio::spawn(connection);
+    ///
+    ///     let mut events = handle.subscribe(&[RtnlGroup::Link])?;
+    ///     while let Some(event) = events.try_next().await? {
+    ///         if let RtnlEvent::LinkAdded(link) = event {
+    ///             println!("{:?}", link);
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(any(feature = "tokio_socket", feature = "smol_socket"))]
+    pub fn subscribe(&self, groups: &[crate::RtnlGroup]) -> Result<crate::RtnlEventStream, Error> {
+        groups
+            .iter()
+            .fold(crate::ConnectionBuilder::new(), |builder, group| {
+                builder.group(*group)
+            })
+            .subscribe()
+            .map_err(|e| Error::SocketError(e.to_string()))
+    }
+
+    /// Create a new batch of requests, that are sent together. See [`BatchRequest`].
+    pub fn batch(&self) -> BatchRequest {
+        BatchRequest::new(self.clone())
+    }
+
+    /// Bring the links, addresses, routes and rules to the given state. See [`ReconcileRequest`].
+    ///
+    /// ```no_run
+    /// use rtnetlink::{new_connection, Error, LinkConfig, LinkKind, LinkState, NetworkState};
+    ///
+    /// async fn run() -> Result<(), Error> {
+    ///     let (connection, handle, _) = new_connection().unwrap();
+    ///     tokio::spawn(connection);
+    ///
+    ///     let desired = NetworkState {
+    ///         links: vec![LinkConfig {
+    ///             name: "dummy0".to_string(),
+    ///             kind: LinkKind::Dummy,
+    ///             state: LinkState::Up,
+    ///             mtu: Some(1400),
+    ///             master: None,
+    ///         }],
+    ///         ..Default::default()
+    ///     };
+    ///     let plan = handle.reconcile(desired).execute().await?;
+    ///     print!("{}", plan);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn reconcile(&self, desired: NetworkState) -> ReconcileRequest {
+        ReconcileRequest::new(self.clone(), desired)
+    }
+
     /// Create a new handle, specifically for link requests (equivalent to `ip link` commands)
     pub fn link(&self) -> LinkHandle {
         LinkHandle::new(self.clone())
@@ -64,6 +154,18 @@ impl Handle {
         NeighbourHandle::new(self.clone())
     }
 
+    /// Create a new handle, specifically for neighbour table requests (equivalent to `ip ntable`
+    /// commands)
+    pub fn neighbour_table(&self) -> NeighbourTableHandle {
+        NeighbourTableHandle::new(self.clone())
+    }
+
+    /// Create a new handle, specifically for network namespace id requests (equivalent to `ip
+    /// netns set` and `ip netns list-id` commands)
+    pub fn nsid(&self) -> NsidHandle {
+        NsidHandle::new(self.clone())
+    }
+
     /// Create a new handle, specifically for traffic control qdisc requests
     /// (equivalent to `tc qdisc show` commands)
     pub fn qdisc(&self) -> QDiscHandle {
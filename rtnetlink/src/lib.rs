@@ -12,6 +12,12 @@ pub use crate::ns::*;
 mod errors;
 pub use crate::errors::*;
 
+mod dump;
+pub use crate::dump::*;
+
+mod batch;
+pub use crate::batch::*;
+
 mod link;
 pub use crate::link::*;
 
@@ -27,17 +33,51 @@ pub use crate::rule::*;
 mod connection;
 pub use crate::connection::*;
 
+mod blocking;
+pub use crate::blocking::*;
+
 mod traffic_control;
 pub use crate::traffic_control::*;
 
 mod neighbour;
 pub use crate::neighbour::*;
 
+mod neighbour_table;
+pub use crate::neighbour_table::*;
+
+mod nsid;
+pub use crate::nsid::*;
+
+mod cache;
+pub use crate::cache::*;
+
+mod event;
+pub use crate::event::*;
+
+mod reconcile;
+pub use crate::reconcile::*;
+
+#[cfg(any(test, feature = "fake_kernel"))]
+mod fake;
+#[cfg(any(test, feature = "fake_kernel"))]
+pub use crate::fake::*;
+
 pub mod constants;
 
 pub use netlink_packet_route as packet;
 pub mod proto {
-    pub use netlink_proto::{Connection, ConnectionHandle, Error, ErrorKind};
+    pub use netlink_proto::{
+        blocking,
+        Connection,
+        ConnectionBuilder,
+        ConnectionHandle,
+        DecoderQuirks,
+        Error,
+        ErrorKind,
+        MalformedPolicy,
+        OverflowPolicy,
+        UnsolicitedMessages,
+    };
 }
 pub use netlink_proto::sys;
 
@@ -1,18 +1,19 @@
-use futures::stream::StreamExt;
+use futures::stream::TryStreamExt;
 
 use crate::{
     packet::{
-        nlas::link::{Info, InfoData, InfoKind, InfoVlan, InfoVxlan, Nla, VethInfo},
+        nlas::link::{Info, InfoData, InfoKind, InfoVlan, InfoVrf, InfoVxlan, Nla, VethInfo},
+        LinkFlags,
         LinkMessage,
         NetlinkMessage,
         RtnlMessage,
-        IFF_UP,
         NLM_F_ACK,
         NLM_F_CREATE,
         NLM_F_EXCL,
         NLM_F_REQUEST,
     },
     try_nl,
+    Batchable,
     Error,
     Handle,
 };
@@ -236,6 +237,14 @@ impl VxlanAddRequest {
     }
 }
 
+impl Batchable for VxlanAddRequest {
+    fn into_message(self) -> NetlinkMessage<RtnlMessage> {
+        self.request
+            .link_info(InfoKind::Vxlan, Some(InfoData::Vxlan(self.info_data)))
+            .into_message()
+    }
+}
+
 /// A request to create a new link. This is equivalent to the `ip link add` commands.
 ///
 /// A few methods for common actions (creating a veth pair, creating a vlan interface, etc.) are
@@ -256,15 +265,9 @@ impl LinkAddRequest {
 
     /// Execute the request.
     pub async fn execute(self) -> Result<(), Error> {
-        let LinkAddRequest {
-            mut handle,
-            message,
-        } = self;
-        let mut req = NetlinkMessage::from(RtnlMessage::NewLink(message));
-        req.header.flags = NLM_F_REQUEST | NLM_F_ACK | NLM_F_EXCL | NLM_F_CREATE;
-
-        let mut response = handle.request(req)?;
-        while let Some(message) = response.next().await {
+        let mut handle = self.handle.clone();
+        let mut response = handle.request(self.into_message())?;
+        while let Some(message) = response.try_next().await? {
             try_nl!(message);
         }
         Ok(())
@@ -281,15 +284,15 @@ impl LinkAddRequest {
     ///
     /// ```rust,no_run
     /// use futures::Future;
-    /// use rtnetlink::{Handle, new_connection, packet::IFF_UP};
+    /// use rtnetlink::{Handle, new_connection, packet::LinkFlags};
     ///
     /// async fn run(handle: Handle) -> Result<(), String> {
     ///     let vlan_id = 100;
     ///     let link_id = 6;
     ///     let mut request = handle.link().add().vlan("my-vlan-itf".into(), link_id, vlan_id);
     ///     // unset the IFF_UP flag before sending the request
-    ///     request.message_mut().header.flags &= !IFF_UP;
-    ///     request.message_mut().header.change_mask &= !IFF_UP;
+    ///     request.message_mut().header.flags.remove(LinkFlags::IFF_UP);
+    ///     request.message_mut().header.change_mask.remove(LinkFlags::IFF_UP);
     ///     // send the request
     ///     request.execute().await.map_err(|e| format!("{}", e))
     /// }
@@ -312,8 +315,8 @@ impl LinkAddRequest {
 
         let mut peer = LinkMessage::default();
         // FIXME: we get a -107 (ENOTCONN) (???) when trying to set `name` up.
-        // peer.header.flags = LinkFlags::from(IFF_UP);
-        // peer.header.change_mask = LinkFlags::from(IFF_UP);
+        // peer.header.flags = LinkFlags::IFF_UP;
+        // peer.header.change_mask = LinkFlags::IFF_UP;
         peer.nlas.push(Nla::IfName(name));
         let link_info_data = InfoData::Veth(VethInfo::Peer(peer));
         self.name(peer_name)
@@ -354,9 +357,18 @@ impl LinkAddRequest {
             .append_nla(Nla::IfName(name))
     }
 
+    /// Create a new VRF device bound to a routing table.
+    /// This is equivalent to `ip link add NAME type vrf table TABLE`.
+    pub fn vrf(self, name: String, table: u32) -> Self {
+        self.name(name).link_info(
+            InfoKind::Vrf,
+            Some(InfoData::Vrf(vec![InfoVrf::TableId(table)])),
+        )
+    }
+
     fn up(mut self) -> Self {
-        self.message.header.flags = IFF_UP;
-        self.message.header.change_mask = IFF_UP;
+        self.message.header.flags = LinkFlags::IFF_UP;
+        self.message.header.change_mask = LinkFlags::IFF_UP;
         self
     }
 
@@ -378,3 +390,12 @@ impl LinkAddRequest {
         self
     }
 }
+
+impl Batchable for LinkAddRequest {
+    fn into_message(self) -> NetlinkMessage<RtnlMessage> {
+        let LinkAddRequest { message, .. } = self;
+        let mut req = NetlinkMessage::from(RtnlMessage::NewLink(message));
+        req.header.flags = NLM_F_REQUEST | NLM_F_ACK | NLM_F_EXCL | NLM_F_CREATE;
+        req
+    }
+}
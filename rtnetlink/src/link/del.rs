@@ -1,4 +1,4 @@
-use futures::stream::StreamExt;
+use futures::stream::TryStreamExt;
 
 use crate::{
     packet::{
@@ -11,6 +11,7 @@ use crate::{
         NLM_F_REQUEST,
     },
     try_nl,
+    Batchable,
     Error,
     Handle,
 };
@@ -29,15 +30,9 @@ impl LinkDelRequest {
 
     /// Execute the request
     pub async fn execute(self) -> Result<(), Error> {
-        let LinkDelRequest {
-            mut handle,
-            message,
-        } = self;
-        let mut req = NetlinkMessage::from(RtnlMessage::DelLink(message));
-        req.header.flags = NLM_F_REQUEST | NLM_F_ACK | NLM_F_EXCL | NLM_F_CREATE;
-
-        let mut response = handle.request(req)?;
-        while let Some(message) = response.next().await {
+        let mut handle = self.handle.clone();
+        let mut response = handle.request(self.into_message())?;
+        while let Some(message) = response.try_next().await? {
             try_nl!(message)
         }
         Ok(())
@@ -48,3 +43,12 @@ impl LinkDelRequest {
         &mut self.message
     }
 }
+
+impl Batchable for LinkDelRequest {
+    fn into_message(self) -> NetlinkMessage<RtnlMessage> {
+        let LinkDelRequest { message, .. } = self;
+        let mut req = NetlinkMessage::from(RtnlMessage::DelLink(message));
+        req.header.flags = NLM_F_REQUEST | NLM_F_ACK | NLM_F_EXCL | NLM_F_CREATE;
+        req
+    }
+}
@@ -1,26 +1,41 @@
 use futures::{
-    future::{self, Either},
-    stream::{StreamExt, TryStream, TryStreamExt},
-    FutureExt,
+    future,
+    stream::{StreamExt, TryStream},
 };
+use nix::errno::Errno;
 
 use crate::{
-    packet::{constants::*, nlas::link::Nla, LinkMessage, NetlinkMessage, RtnlMessage},
+    packet::{
+        constants::*,
+        nlas::link::Nla,
+        LinkMessage,
+        NetlinkMessage,
+        RtnlMessage,
+    },
+    dump_request,
     try_rtnl,
+    DumpInterruptedPolicy,
     Error,
     Handle,
 };
 
+/// Maximum length of a link name, including the terminating null byte
+const IFNAMSIZ: usize = 16;
+
 pub struct LinkGetRequest {
     handle: Handle,
     message: LinkMessage,
     // There are two ways to retrieve links: we can either dump them
-    // all and filter the result, or if we already know the index of
-    // the link we're looking for, we can just retrieve that one. If
-    // `dump` is `true`, all the links are fetched. Otherwise, only
-    // the link that match the given index is fetched.
+    // all, or if we already know the index or the name of the link
+    // we're looking for, we can just retrieve that one. If `dump` is
+    // `true`, all the links are fetched. Otherwise, only the link that
+    // match the given index or name is fetched.
     dump: bool,
-    filter_builder: LinkFilterBuilder,
+    // Whether the link is looked up by name. The kernel then fails with `ENODEV` if there is no
+    // such link, but the stream ends without error instead, as when the dump used to be filtered
+    // by name.
+    by_name: bool,
+    dump_interrupted: DumpInterruptedPolicy,
 }
 
 impl LinkGetRequest {
@@ -29,7 +44,8 @@ impl LinkGetRequest {
             handle,
             message: LinkMessage::default(),
             dump: true,
-            filter_builder: LinkFilterBuilder::new(),
+            by_name: false,
+            dump_interrupted: DumpInterruptedPolicy::default(),
         }
     }
 
@@ -43,10 +59,11 @@ impl LinkGetRequest {
     /// Execute the request
     pub fn execute(self) -> impl TryStream<Ok = LinkMessage, Error = Error> {
         let LinkGetRequest {
-            mut handle,
+            handle,
             message,
             dump,
-            filter_builder,
+            by_name,
+            dump_interrupted,
         } = self;
 
         let mut req = NetlinkMessage::from(RtnlMessage::GetLink(message));
@@ -57,15 +74,9 @@ impl LinkGetRequest {
             req.header.flags = NLM_F_REQUEST;
         }
 
-        let filter = filter_builder.build();
-        match handle.request(req) {
-            Ok(response) => Either::Left(
-                response
-                    .map(move |msg| Ok(try_rtnl!(msg, RtnlMessage::NewLink)))
-                    .try_filter(move |msg| future::ready(filter(msg))),
-            ),
-            Err(e) => Either::Right(future::err::<LinkMessage, Error>(e).into_stream()),
-        }
+        dump_request(handle, req, dump_interrupted)
+            .map(move |msg| Ok(try_rtnl!(msg?, RtnlMessage::NewLink)))
+            .filter(move |result| future::ready(!(by_name && is_no_such_device(result))))
     }
 
     /// Return a mutable reference to the request
@@ -73,42 +84,36 @@ impl LinkGetRequest {
         &mut self.message
     }
 
+    /// Set what to do if the dump is interrupted by a concurrent change (see
+    /// [`DumpInterruptedPolicy`](enum.DumpInterruptedPolicy.html)). By default, the interruption
+    /// is ignored.
+    pub fn on_dump_interrupted(mut self, policy: DumpInterruptedPolicy) -> Self {
+        self.dump_interrupted = policy;
+        self
+    }
+
     pub fn match_index(mut self, index: u32) -> Self {
         self.dump = false;
         self.message.header.index = index;
         self
     }
 
+    /// Only retrieve the link whose name, or one of whose alternative names (see
+    /// [`LinkHandle::property_add`](struct.LinkHandle.html#method.property_add)), is `name`. The
+    /// kernel looks the link up, and the stream is empty if there is none.
     pub fn set_name_filter(mut self, name: String) -> Self {
-        self.filter_builder.name = Some(name);
+        self.dump = false;
+        self.by_name = true;
+        if name.len() < IFNAMSIZ {
+            self.message.nlas.push(Nla::IfName(name));
+        } else {
+            // Longer names can only be alternative names
+            self.message.nlas.push(Nla::AltIfName(name));
+        }
         self
     }
 }
 
-#[derive(Default)]
-struct LinkFilterBuilder {
-    name: Option<String>,
-}
-
-impl LinkFilterBuilder {
-    fn new() -> Self {
-        Default::default()
-    }
-
-    fn build(self) -> impl Fn(&LinkMessage) -> bool {
-        move |msg: &LinkMessage| {
-            if let Some(name) = &self.name {
-                for nla in msg.nlas.iter() {
-                    if let Nla::IfName(s) = nla {
-                        if s == name {
-                            return true;
-                        }
-                    }
-                }
-                false
-            } else {
-                true
-            }
-        }
-    }
+fn is_no_such_device(result: &Result<LinkMessage, Error>) -> bool {
+    matches!(result, Err(Error::NetlinkError(err)) if err.code == -(Errno::ENODEV as i32))
 }
@@ -1,4 +1,11 @@
-use super::{LinkAddRequest, LinkDelRequest, LinkGetRequest, LinkSetRequest};
+use super::{
+    LinkAddRequest,
+    LinkDelPropRequest,
+    LinkDelRequest,
+    LinkGetRequest,
+    LinkNewPropRequest,
+    LinkSetRequest,
+};
 use crate::Handle;
 
 pub struct LinkHandle(Handle);
@@ -20,6 +27,18 @@ impl LinkHandle {
         LinkDelRequest::new(self.0.clone(), index)
     }
 
+    /// Add properties (such as alternative names) to a link (equivalent to `ip link property
+    /// add`)
+    pub fn property_add(&self, index: u32) -> LinkNewPropRequest {
+        LinkNewPropRequest::new(self.0.clone(), index)
+    }
+
+    /// Remove properties (such as alternative names) from a link (equivalent to `ip link
+    /// property del`)
+    pub fn property_del(&self, index: u32) -> LinkDelPropRequest {
+        LinkDelPropRequest::new(self.0.clone(), index)
+    }
+
     /// Retrieve the list of links (equivalent to `ip link show`)
     pub fn get(&mut self) -> LinkGetRequest {
         LinkGetRequest::new(self.0.clone())
@@ -0,0 +1,66 @@
+use futures::stream::TryStreamExt;
+
+use crate::{
+    packet::{
+        nlas::link::{Nla, Prop},
+        LinkMessage,
+        NetlinkMessage,
+        RtnlMessage,
+        NLM_F_ACK,
+        NLM_F_REQUEST,
+    },
+    try_nl,
+    Batchable,
+    Error,
+    Handle,
+};
+
+/// A request to remove properties from a link. This is equivalent to the `ip link property del`
+/// commands.
+pub struct LinkDelPropRequest {
+    handle: Handle,
+    message: LinkMessage,
+}
+
+impl LinkDelPropRequest {
+    pub(crate) fn new(handle: Handle, index: u32) -> Self {
+        let mut message = LinkMessage::default();
+        message.header.index = index;
+        LinkDelPropRequest { handle, message }
+    }
+
+    /// Execute the request
+    pub async fn execute(self) -> Result<(), Error> {
+        let mut handle = self.handle.clone();
+        let mut response = handle.request(self.into_message())?;
+        while let Some(message) = response.try_next().await? {
+            try_nl!(message);
+        }
+        Ok(())
+    }
+
+    /// Return a mutable reference to the request
+    pub fn message_mut(&mut self) -> &mut LinkMessage {
+        &mut self.message
+    }
+
+    /// Remove alternative names from the link. This is equivalent to `ip link property del DEV
+    /// altname NAME [altname NAME2 ...]`.
+    pub fn alt_ifname(mut self, alt_ifnames: &[&str]) -> Self {
+        let mut props = Vec::new();
+        for alt_ifname in alt_ifnames {
+            props.push(Prop::AltIfName(alt_ifname.to_string()));
+        }
+        self.message.nlas.push(Nla::PropList(props));
+        self
+    }
+}
+
+impl Batchable for LinkDelPropRequest {
+    fn into_message(self) -> NetlinkMessage<RtnlMessage> {
+        let LinkDelPropRequest { message, .. } = self;
+        let mut req = NetlinkMessage::from(RtnlMessage::DelLinkProp(message));
+        req.header.flags = NLM_F_REQUEST | NLM_F_ACK;
+        req
+    }
+}
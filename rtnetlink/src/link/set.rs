@@ -1,29 +1,60 @@
 use crate::{
     packet::{
-        nlas::link::Nla,
+        nlas::link::{AfSpecInet, Inet, Inet6, InetDevConfValue, Nla, ProtoDownReason, Xdp},
+        LinkFlags,
         LinkMessage,
         NetlinkMessage,
         RtnlMessage,
-        IFF_NOARP,
-        IFF_PROMISC,
-        IFF_UP,
+        IPV4_DEVCONF_ACCEPT_LOCAL,
+        IPV4_DEVCONF_ARP_ANNOUNCE,
+        IPV4_DEVCONF_ARP_IGNORE,
+        IPV4_DEVCONF_FORWARDING,
+        IPV4_DEVCONF_PROXY_ARP,
+        IPV4_DEVCONF_RP_FILTER,
         NLM_F_ACK,
         NLM_F_CREATE,
         NLM_F_EXCL,
         NLM_F_REQUEST,
+        XDP_FLAGS_DRV_MODE,
+        XDP_FLAGS_HW_MODE,
+        XDP_FLAGS_MODES,
+        XDP_FLAGS_SKB_MODE,
+        XDP_FLAGS_UPDATE_IF_NOEXIST,
     },
     try_nl,
+    Batchable,
     Error,
     Handle,
 };
-use futures::stream::StreamExt;
-use std::os::unix::io::RawFd;
+use futures::stream::TryStreamExt;
+use std::{net::Ipv6Addr, os::unix::io::RawFd};
 
 pub struct LinkSetRequest {
     handle: Handle,
     message: LinkMessage,
 }
 
+/// Where an XDP program runs.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum XdpMode {
+    /// In the generic network stack, for any driver (`xdpgeneric`)
+    Generic,
+    /// In the driver, which must support XDP (`xdpdrv`)
+    Driver,
+    /// In the network card, which must support XDP offload (`xdpoffload`)
+    Offload,
+}
+
+impl XdpMode {
+    pub(crate) fn flags(self) -> u32 {
+        match self {
+            XdpMode::Generic => XDP_FLAGS_SKB_MODE,
+            XdpMode::Driver => XDP_FLAGS_DRV_MODE,
+            XdpMode::Offload => XDP_FLAGS_HW_MODE,
+        }
+    }
+}
+
 impl LinkSetRequest {
     pub(crate) fn new(handle: Handle, index: u32) -> Self {
         let mut message = LinkMessage::default();
@@ -33,15 +64,9 @@ impl LinkSetRequest {
 
     /// Execute the request
     pub async fn execute(self) -> Result<(), Error> {
-        let LinkSetRequest {
-            mut handle,
-            message,
-        } = self;
-        let mut req = NetlinkMessage::from(RtnlMessage::SetLink(message));
-        req.header.flags = NLM_F_REQUEST | NLM_F_ACK | NLM_F_EXCL | NLM_F_CREATE;
-
-        let mut response = handle.request(req)?;
-        while let Some(message) = response.next().await {
+        let mut handle = self.handle.clone();
+        let mut response = handle.request(self.into_message())?;
+        while let Some(message) = response.try_next().await? {
             try_nl!(message);
         }
         Ok(())
@@ -71,37 +96,37 @@ impl LinkSetRequest {
 
     /// Set the link with the given index up (equivalent to `ip link set dev DEV up`)
     pub fn up(mut self) -> Self {
-        self.message.header.flags |= IFF_UP;
-        self.message.header.change_mask |= IFF_UP;
+        self.message.header.flags |= LinkFlags::IFF_UP;
+        self.message.header.change_mask |= LinkFlags::IFF_UP;
         self
     }
 
     /// Set the link with the given index down (equivalent to `ip link set dev DEV down`)
     pub fn down(mut self) -> Self {
-        self.message.header.flags &= !IFF_UP;
-        self.message.header.change_mask |= IFF_UP;
+        self.message.header.flags.remove(LinkFlags::IFF_UP);
+        self.message.header.change_mask |= LinkFlags::IFF_UP;
         self
     }
 
     /// Enable or disable promiscious mode of the link with the given index (equivalent to `ip link set dev DEV promisc on/off`)
     pub fn promiscuous(mut self, enable: bool) -> Self {
         if enable {
-            self.message.header.flags |= IFF_PROMISC;
+            self.message.header.flags |= LinkFlags::IFF_PROMISC;
         } else {
-            self.message.header.flags &= !IFF_PROMISC;
+            self.message.header.flags.remove(LinkFlags::IFF_PROMISC);
         }
-        self.message.header.change_mask |= IFF_PROMISC;
+        self.message.header.change_mask |= LinkFlags::IFF_PROMISC;
         self
     }
 
     /// Enable or disable the ARP protocol of the link with the given index (equivalent to `ip link set dev DEV arp on/off`)
     pub fn arp(mut self, enable: bool) -> Self {
         if enable {
-            self.message.header.flags &= !IFF_NOARP;
+            self.message.header.flags.remove(LinkFlags::IFF_NOARP);
         } else {
-            self.message.header.flags |= IFF_NOARP;
+            self.message.header.flags |= LinkFlags::IFF_NOARP;
         }
-        self.message.header.change_mask |= IFF_NOARP;
+        self.message.header.change_mask |= LinkFlags::IFF_NOARP;
         self
     }
 
@@ -135,4 +160,222 @@ impl LinkSetRequest {
         self.message.nlas.push(Nla::NetNsFd(fd));
         self
     }
+
+    /// Attach the XDP program referred to by the given file descriptor to the link, in the given
+    /// mode. A program already attached in this mode is replaced, unless
+    /// [`xdp_update_if_noexist`](#method.xdp_update_if_noexist) is called. This is equivalent to
+    /// `ip link set DEV xdpgeneric/xdpdrv/xdpoffload fd FD`.
+    pub fn xdp_fd(mut self, fd: RawFd, mode: XdpMode) -> Self {
+        let nlas = self.xdp_nlas();
+        nlas.retain(|nla| !matches!(nla, Xdp::Fd(_)));
+        nlas.push(Xdp::Fd(fd));
+        self.set_xdp_flags(XDP_FLAGS_MODES, mode.flags());
+        self
+    }
+
+    /// Make [`xdp_fd`](#method.xdp_fd) fail if a program is already attached to the link
+    /// (`XDP_FLAGS_UPDATE_IF_NOEXIST`).
+    pub fn xdp_update_if_noexist(mut self) -> Self {
+        self.set_xdp_flags(XDP_FLAGS_UPDATE_IF_NOEXIST, XDP_FLAGS_UPDATE_IF_NOEXIST);
+        self
+    }
+
+    /// Detach the XDP program attached to the link in the given mode (equivalent to `ip link set
+    /// DEV xdpgeneric/xdpdrv/xdpoffload off`). A program attached in another mode is left in
+    /// place.
+    pub fn xdp_detach(self, mode: XdpMode) -> Self {
+        self.xdp_fd(-1, mode)
+    }
+
+    /// The `IFLA_XDP` attributes of the request, added if needed
+    fn xdp_nlas(&mut self) -> &mut Vec<Xdp> {
+        let position = self
+            .message
+            .nlas
+            .iter()
+            .position(|nla| matches!(nla, Nla::Xdp(_)));
+        let index = match position {
+            Some(index) => index,
+            None => {
+                self.message.nlas.push(Nla::Xdp(vec![]));
+                self.message.nlas.len() - 1
+            }
+        };
+        match self.message.nlas[index] {
+            Nla::Xdp(ref mut nlas) => nlas,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Replace the bits of `mask` in the `IFLA_XDP_FLAGS` attribute of the request by `flags`
+    fn set_xdp_flags(&mut self, mask: u32, flags: u32) {
+        let nlas = self.xdp_nlas();
+        for nla in nlas.iter_mut() {
+            if let Xdp::Flags(ref mut value) = nla {
+                *value = (*value & !mask) | flags;
+                return;
+            }
+        }
+        nlas.push(Xdp::Flags(flags));
+    }
+
+    /// Hold the link down (or release it) on behalf of a protocol, independently of its
+    /// administrative state (equivalent to `ip link set DEV protodown on/off protodown_reason
+    /// REASON on/off`).
+    ///
+    /// `reason` is a bitmask of the reasons to set (when `enable` is `true`) or to clear (when it
+    /// is `false`). The other reasons already held on the link are left untouched. The kernel
+    /// refuses to release the link while some reasons remain set. Pass `0` to change the
+    /// `protodown` state without tracking a reason.
+    pub fn protodown(mut self, enable: bool, reason: u32) -> Self {
+        self.message.nlas.push(Nla::ProtoDown(enable as u8));
+        if reason != 0 {
+            let value = if enable { reason } else { 0 };
+            self.message.nlas.push(Nla::ProtoDownReason(vec![
+                ProtoDownReason::Mask(reason),
+                ProtoDownReason::Value(value),
+            ]));
+        }
+        self
+    }
+
+    /// Set the IPv6 address generation mode of the link (equivalent to `ip link set DEV
+    /// addrgenmode MODE`). `mode` is one of the `IN6_ADDR_GEN_MODE_*` constants.
+    ///
+    /// Note that the kernel only accepts the address generation mode and the token in the
+    /// `AF_INET6` part of `IFLA_AF_SPEC`: the other IPv6 settings of the link, like `accept_ra`,
+    /// `forwarding` or `disable_ipv6`, can only be changed through sysctl.
+    pub fn ipv6_addr_gen_mode(mut self, mode: u8) -> Self {
+        self.af_spec_inet6().push(Inet6::AddrGenMode(mode));
+        self
+    }
+
+    /// Set the IPv6 token of the link, used as interface identifier for the addresses
+    /// configured by SLAAC (equivalent to `ip token set TOKEN dev DEV`). Only the lower 64 bits
+    /// of the token are used.
+    pub fn ipv6_token(mut self, token: Ipv6Addr) -> Self {
+        self.af_spec_inet6().push(Inet6::Token(token.octets()));
+        self
+    }
+
+    /// Change an IPv4 setting of the link. `id` is one of the `IPV4_DEVCONF_*` constants. All
+    /// the settings changed by the same request are applied at once, and the others are left
+    /// untouched. This is equivalent to writing to `/proc/sys/net/ipv4/conf/DEV/`.
+    pub fn ipv4_dev_conf(mut self, id: u16, value: i32) -> Self {
+        let nlas = self.af_spec_inet();
+        let index = match nlas
+            .iter()
+            .position(|nla| matches!(nla, Inet::DevConfUpdate(_)))
+        {
+            Some(index) => index,
+            None => {
+                nlas.push(Inet::DevConfUpdate(vec![]));
+                nlas.len() - 1
+            }
+        };
+        if let Inet::DevConfUpdate(ref mut values) = nlas[index] {
+            values.retain(|v| v.id != id);
+            values.push(InetDevConfValue { id, value });
+        }
+        self
+    }
+
+    /// Enable or disable IPv4 forwarding on the link (`net.ipv4.conf.DEV.forwarding`)
+    pub fn ipv4_forwarding(self, enable: bool) -> Self {
+        self.ipv4_dev_conf(IPV4_DEVCONF_FORWARDING, enable as i32)
+    }
+
+    /// Set the reverse path filtering mode of the link (`net.ipv4.conf.DEV.rp_filter`): `0` for
+    /// no validation, `1` for strict mode and `2` for loose mode
+    pub fn ipv4_rp_filter(self, mode: i32) -> Self {
+        self.ipv4_dev_conf(IPV4_DEVCONF_RP_FILTER, mode)
+    }
+
+    /// Enable or disable proxy ARP on the link (`net.ipv4.conf.DEV.proxy_arp`)
+    pub fn ipv4_proxy_arp(self, enable: bool) -> Self {
+        self.ipv4_dev_conf(IPV4_DEVCONF_PROXY_ARP, enable as i32)
+    }
+
+    /// Set the mode used to reply to ARP requests on the link
+    /// (`net.ipv4.conf.DEV.arp_ignore`), from `0` to `8`
+    pub fn ipv4_arp_ignore(self, mode: i32) -> Self {
+        self.ipv4_dev_conf(IPV4_DEVCONF_ARP_IGNORE, mode)
+    }
+
+    /// Set the restriction level for the source address of the ARP requests sent on the link
+    /// (`net.ipv4.conf.DEV.arp_announce`), from `0` to `2`
+    pub fn ipv4_arp_announce(self, level: i32) -> Self {
+        self.ipv4_dev_conf(IPV4_DEVCONF_ARP_ANNOUNCE, level)
+    }
+
+    /// Accept or not packets with a local source address on the link
+    /// (`net.ipv4.conf.DEV.accept_local`)
+    pub fn ipv4_accept_local(self, enable: bool) -> Self {
+        self.ipv4_dev_conf(IPV4_DEVCONF_ACCEPT_LOCAL, enable as i32)
+    }
+
+    // The kernel only takes the last IFLA_AF_SPEC attribute into account, so all the settings
+    // must be sent in the same one.
+    fn af_spec(&mut self) -> &mut Vec<AfSpecInet> {
+        let nlas = &mut self.message.nlas;
+        let index = match nlas
+            .iter()
+            .position(|nla| matches!(nla, Nla::AfSpecInet(_)))
+        {
+            Some(index) => index,
+            None => {
+                nlas.push(Nla::AfSpecInet(vec![]));
+                nlas.len() - 1
+            }
+        };
+        match nlas[index] {
+            Nla::AfSpecInet(ref mut af_spec) => af_spec,
+            _ => unreachable!(),
+        }
+    }
+
+    fn af_spec_inet(&mut self) -> &mut Vec<Inet> {
+        let af_spec = self.af_spec();
+        let index = match af_spec
+            .iter()
+            .position(|nla| matches!(nla, AfSpecInet::Inet(_)))
+        {
+            Some(index) => index,
+            None => {
+                af_spec.push(AfSpecInet::Inet(vec![]));
+                af_spec.len() - 1
+            }
+        };
+        match af_spec[index] {
+            AfSpecInet::Inet(ref mut nlas) => nlas,
+            _ => unreachable!(),
+        }
+    }
+
+    fn af_spec_inet6(&mut self) -> &mut Vec<Inet6> {
+        let af_spec = self.af_spec();
+        let index = match af_spec
+            .iter()
+            .position(|nla| matches!(nla, AfSpecInet::Inet6(_)))
+        {
+            Some(index) => index,
+            None => {
+                af_spec.push(AfSpecInet::Inet6(vec![]));
+                af_spec.len() - 1
+            }
+        };
+        match af_spec[index] {
+            AfSpecInet::Inet6(ref mut nlas) => nlas,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl Batchable for LinkSetRequest {
+    fn into_message(self) -> NetlinkMessage<RtnlMessage> {
+        let LinkSetRequest { message, .. } = self;
+        let mut req = NetlinkMessage::from(RtnlMessage::SetLink(message));
+        req.header.flags = NLM_F_REQUEST | NLM_F_ACK | NLM_F_EXCL | NLM_F_CREATE;
+        req
+    }
 }
@@ -3,12 +3,26 @@ use tokio::runtime::Runtime;
 
 use crate::{
     new_connection,
-    packet::rtnl::link::{
-        nlas::{Info, InfoKind, Nla},
-        LinkMessage,
+    new_connection_with_socket,
+    packet::{
+        rtnl::link::{
+            nlas::{Info, InfoKind, Nla, Xdp},
+            LinkMessage,
+        },
+        NetlinkPayload,
+        RtnlMessage,
+        XDP_FLAGS_DRV_MODE,
+        XDP_FLAGS_SKB_MODE,
+        XDP_FLAGS_UPDATE_IF_NOEXIST,
     },
+    sys::{MemorySocket, SocketAddr},
+    Batchable,
+    BlockingHandle,
+    DumpInterruptedPolicy,
     Error,
     LinkHandle,
+    LinkSetRequest,
+    XdpMode,
 };
 
 const IFACE_NAME: &str = "wg142"; // rand?
@@ -29,6 +43,118 @@ fn create_get_delete_wg() {
     rt.block_on(_del_wg(&mut handle, msg.header.index)).unwrap();
 }
 
+#[test]
+fn dump_with_interrupted_policies() {
+    let rt = Runtime::new().unwrap();
+    let names = |policy| rt.block_on(_dump_link_names(policy));
+    // The links are not expected to change while the test runs, so no dump is interrupted.
+    let expected = names(DumpInterruptedPolicy::Ignore);
+    assert!(expected.iter().any(|name| name == "lo"));
+    assert_eq!(names(DumpInterruptedPolicy::Fail), expected);
+    assert_eq!(names(DumpInterruptedPolicy::Retry(3)), expected);
+}
+
+async fn _dump_link_names(policy: DumpInterruptedPolicy) -> Vec<String> {
+    let (connection, handle, _) = new_connection().unwrap();
+    tokio::spawn(connection);
+    let links: Vec<LinkMessage> = handle
+        .link()
+        .get()
+        .on_dump_interrupted(policy)
+        .execute()
+        .try_collect()
+        .await
+        .unwrap();
+    links
+        .iter()
+        .filter_map(|link| {
+            link.nlas.iter().find_map(|nla| match nla {
+                Nla::IfName(name) => Some(name.clone()),
+                _ => None,
+            })
+        })
+        .collect()
+}
+
+#[test]
+fn get_without_runtime() {
+    let mut rtnl = BlockingHandle::new().unwrap();
+    let links = rtnl.collect(rtnl.handle().link().get().execute()).unwrap();
+    assert!(!links.is_empty());
+    let lo = rtnl
+        .collect(rtnl.handle().link().get().match_index(1).execute())
+        .unwrap();
+    assert_eq!(lo.len(), 1);
+    assert!(has_nla(&lo[0], &Nla::IfName("lo".to_string())));
+}
+
+#[test]
+fn get_unknown_name() {
+    let mut rtnl = BlockingHandle::new().unwrap();
+    let request = rtnl
+        .handle()
+        .link()
+        .get()
+        .set_name_filter("does-not-exist".to_string());
+    let links = rtnl.collect(request.execute()).unwrap();
+    assert!(links.is_empty());
+}
+
+#[test]
+#[cfg_attr(not(feature = "test_as_root"), ignore)]
+fn batch_reports_each_failure() {
+    let mut rtnl = BlockingHandle::new().unwrap();
+    let handle = rtnl.handle();
+    // Neither link exists, so both deletions fail, but each gets its own response
+    let batch = handle
+        .batch()
+        .add(handle.link().del(0x7fff_fff0))
+        .add(handle.link().del(0x7fff_fff1));
+    let results = rtnl.block_on(batch.execute()).unwrap();
+    assert_eq!(results.len(), 2);
+    assert!(results.iter().all(|result| result.is_err()));
+}
+
+fn xdp_nlas(request: LinkSetRequest) -> Vec<Xdp> {
+    match request.into_message().payload {
+        NetlinkPayload::InnerMessage(RtnlMessage::SetLink(message)) => message
+            .nlas
+            .into_iter()
+            .find_map(|nla| match nla {
+                Nla::Xdp(nlas) => Some(nlas),
+                _ => None,
+            })
+            .unwrap(),
+        payload => panic!("unexpected payload: {:?}", payload),
+    }
+}
+
+#[test]
+fn xdp_modes() {
+    let (socket, _) = MemorySocket::pair(SocketAddr::new(1, 0), SocketAddr::new(0, 0));
+    let (_, handle, _) = new_connection_with_socket(socket);
+
+    let attach = handle
+        .link()
+        .set(1)
+        .xdp_fd(3, XdpMode::Generic)
+        .xdp_update_if_noexist()
+        .xdp_fd(4, XdpMode::Driver);
+    assert_eq!(
+        xdp_nlas(attach),
+        vec![
+            Xdp::Flags(XDP_FLAGS_UPDATE_IF_NOEXIST | XDP_FLAGS_DRV_MODE),
+            Xdp::Fd(4),
+        ]
+    );
+
+    let detach = handle.link().set(1).xdp_detach(XdpMode::Generic);
+    assert_eq!(
+        xdp_nlas(detach),
+        vec![Xdp::Fd(-1), Xdp::Flags(XDP_FLAGS_SKB_MODE)]
+    );
+}
+
 fn has_nla(msg: &LinkMessage, nla: &Nla) -> bool {
     msg.nlas.iter().any(|x| x == nla)
 }
@@ -7,7 +7,7 @@ macro_rules! try_rtnl {
         let (header, payload) = $msg.into_parts();
         match payload {
             NetlinkPayload::InnerMessage($message_type(msg)) => msg,
-            NetlinkPayload::Error(err) => return Err(Error::NetlinkError(err)),
+            NetlinkPayload::Error(err) => return Err(Error::from(err)),
             _ => {
                 return Err(Error::UnexpectedMessage(NetlinkMessage::new(
                     header, payload,
@@ -23,7 +23,7 @@ macro_rules! try_nl {
         use netlink_packet_route::NetlinkPayload;
         use $crate::Error;
         if let NetlinkPayload::Error(err) = $msg.payload {
-            return Err(Error::NetlinkError(err));
+            return Err(Error::from(err));
         }
     }};
 }
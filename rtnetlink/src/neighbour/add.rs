@@ -1,15 +1,17 @@
-use futures::stream::StreamExt;
+use futures::stream::TryStreamExt;
 
 use netlink_packet_route::{
     constants::*,
     neighbour::{NeighbourMessage, Nla},
+    NeighbourFlags,
+    NeighbourState,
     NetlinkPayload,
     RtnlMessage,
 };
 
 use netlink_proto::packet::NetlinkMessage;
 
-use crate::{Error, Handle};
+use crate::{Batchable, Error, Handle};
 use std::net::IpAddr;
 
 pub struct NeighbourAddRequest {
@@ -27,7 +29,7 @@ impl NeighbourAddRequest {
         };
 
         message.header.ifindex = index;
-        message.header.state = IFA_F_PERMANENT as u16;
+        message.header.state = NeighbourState::NUD_PERMANENT;
         message.header.ntype = NDA_UNSPEC as u8;
 
         message.nlas.push(Nla::Destination(match destination {
@@ -38,20 +40,47 @@ impl NeighbourAddRequest {
         NeighbourAddRequest { handle, message }
     }
 
-    /// Set a bitmask of states for the neighbor cache entry.
-    /// It should be a combination of `NUD_*` constants.
-    pub fn state(mut self, state: u16) -> Self {
+    /// Set the state of the neighbor cache entry. Default is [`NeighbourState::NUD_PERMANENT`].
+    pub fn state(mut self, state: NeighbourState) -> Self {
         self.message.header.state = state;
         self
     }
 
     /// Set flags for the neighbor cache entry.
-    /// It should be a combination of `NTF_*` constants.
-    pub fn flags(mut self, flags: u8) -> Self {
+    pub fn flags(mut self, flags: NeighbourFlags) -> Self {
         self.message.header.flags = flags;
         self
     }
 
+    /// Make the entry a proxy entry: the host answers neighbour solicitations for the
+    /// destination on the interface (equivalent to `ip neighbour add proxy`).
+    pub fn proxy(mut self) -> Self {
+        self.message.header.flags |= NeighbourFlags::NTF_PROXY;
+        self
+    }
+
+    /// Mark the entry as learned externally, for instance by a control plane protocol, rather
+    /// than through neighbour discovery (equivalent to `ip neighbour add ... extern_learn`).
+    pub fn extern_learn(mut self) -> Self {
+        self.message.header.flags |= NeighbourFlags::NTF_EXT_LEARNED;
+        self
+    }
+
+    /// Ask the kernel to keep the entry resolved by probing it periodically (equivalent to `ip
+    /// neighbour add ... managed`). Managed entries cannot be permanent, so this also resets the
+    /// state to `NUD_NONE` unless another state is set afterwards.
+    pub fn managed(mut self) -> Self {
+        self.message.header.state = NeighbourState::empty();
+        for nla in self.message.nlas.iter_mut() {
+            if let Nla::FlagsExt(flags) = nla {
+                *flags |= NTF_EXT_MANAGED;
+                return self;
+            }
+        }
+        self.message.nlas.push(Nla::FlagsExt(NTF_EXT_MANAGED));
+        self
+    }
+
     /// Set attributes applicable to the the neighbor cache entry.
     /// It should be one of `NDA_*` constants.
     pub fn ntype(mut self, ntype: u8) -> Self {
@@ -67,18 +96,11 @@ impl NeighbourAddRequest {
 
     /// Execute the request.
     pub async fn execute(self) -> Result<(), Error> {
-        let NeighbourAddRequest {
-            mut handle,
-            message,
-        } = self;
-
-        let mut req = NetlinkMessage::from(RtnlMessage::NewNeighbour(message));
-        req.header.flags = NLM_F_REQUEST | NLM_F_ACK | NLM_F_EXCL | NLM_F_CREATE;
-
-        let mut response = handle.request(req)?;
-        while let Some(message) = response.next().await {
+        let mut handle = self.handle.clone();
+        let mut response = handle.request(self.into_message())?;
+        while let Some(message) = response.try_next().await? {
             if let NetlinkPayload::Error(err) = message.payload {
-                return Err(Error::NetlinkError(err));
+                return Err(Error::from(err));
             }
         }
 
@@ -90,3 +112,12 @@ impl NeighbourAddRequest {
         &mut self.message
     }
 }
+
+impl Batchable for NeighbourAddRequest {
+    fn into_message(self) -> NetlinkMessage<RtnlMessage> {
+        let NeighbourAddRequest { message, .. } = self;
+        let mut req = NetlinkMessage::from(RtnlMessage::NewNeighbour(message));
+        req.header.flags = NLM_F_REQUEST | NLM_F_ACK | NLM_F_EXCL | NLM_F_CREATE;
+        req
+    }
+}
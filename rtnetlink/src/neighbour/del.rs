@@ -1,4 +1,4 @@
-use futures::stream::StreamExt;
+use futures::stream::TryStreamExt;
 
 use netlink_packet_route::{
     constants::*,
@@ -9,7 +9,7 @@ use netlink_packet_route::{
 
 use netlink_proto::packet::NetlinkMessage;
 
-use crate::{Error, Handle};
+use crate::{Batchable, Error, Handle};
 
 pub struct NeighbourDelRequest {
     handle: Handle,
@@ -23,17 +23,11 @@ impl NeighbourDelRequest {
 
     /// Execute the request
     pub async fn execute(self) -> Result<(), Error> {
-        let NeighbourDelRequest {
-            mut handle,
-            message,
-        } = self;
-
-        let mut req = NetlinkMessage::from(RtnlMessage::DelNeighbour(message));
-        req.header.flags = NLM_F_REQUEST | NLM_F_ACK;
-        let mut response = handle.request(req)?;
-        while let Some(msg) = response.next().await {
+        let mut handle = self.handle.clone();
+        let mut response = handle.request(self.into_message())?;
+        while let Some(msg) = response.try_next().await? {
             if let NetlinkPayload::Error(e) = msg.payload {
-                return Err(Error::NetlinkError(e));
+                return Err(Error::from(e));
             }
         }
         Ok(())
@@ -43,3 +37,12 @@ impl NeighbourDelRequest {
         &mut self.message
     }
 }
+
+impl Batchable for NeighbourDelRequest {
+    fn into_message(self) -> NetlinkMessage<RtnlMessage> {
+        let NeighbourDelRequest { message, .. } = self;
+        let mut req = NetlinkMessage::from(RtnlMessage::DelNeighbour(message));
+        req.header.flags = NLM_F_REQUEST | NLM_F_ACK;
+        req
+    }
+}
@@ -0,0 +1,101 @@
+use futures::{future, stream::TryStreamExt};
+
+use netlink_packet_route::{
+    neighbour::{NeighbourMessage, Nla},
+    NeighbourState,
+};
+
+use crate::{Error, Handle, IpVersion, NeighbourDelRequest, NeighbourGetRequest};
+
+/// A request to delete all the neighbour entries matching a set of filters. This is equivalent to
+/// the `ip neighbour flush` command.
+///
+/// The kernel has no flush operation for neighbours, so the entries are dumped and then deleted
+/// one by one. Entries that disappear in between are ignored.
+pub struct NeighbourFlushRequest {
+    handle: Handle,
+    family: Option<IpVersion>,
+    index: Option<u32>,
+    state: NeighbourState,
+}
+
+impl NeighbourFlushRequest {
+    pub(crate) fn new(handle: Handle) -> Self {
+        NeighbourFlushRequest {
+            handle,
+            family: None,
+            index: None,
+            state: NeighbourState::all()
+                - NeighbourState::NUD_PERMANENT
+                - NeighbourState::NUD_NOARP,
+        }
+    }
+
+    /// Only flush IPv4 or IPv6 entries.
+    pub fn set_family(mut self, ip_version: IpVersion) -> Self {
+        self.family = Some(ip_version);
+        self
+    }
+
+    /// Only flush the entries of the interface with the given index (equivalent to `ip neighbour
+    /// flush dev <interface>`).
+    pub fn set_link_index_filter(mut self, index: u32) -> Self {
+        self.index = Some(index);
+        self
+    }
+
+    /// Only flush the entries that are in one of the given states (equivalent to `ip neighbour
+    /// flush nud <state>`). Default is all the states but [`NeighbourState::NUD_PERMANENT`] and
+    /// [`NeighbourState::NUD_NOARP`].
+    pub fn state(mut self, state: NeighbourState) -> Self {
+        self.state = state;
+        self
+    }
+
+    /// Execute the request
+    pub async fn execute(self) -> Result<(), Error> {
+        let NeighbourFlushRequest {
+            handle,
+            family,
+            index,
+            state,
+        } = self;
+
+        let mut request = NeighbourGetRequest::new(handle.clone());
+        if let Some(ip_version) = family {
+            request = request.set_family(ip_version);
+        }
+        if let Some(index) = index {
+            request = request.set_link_index_filter(index);
+        }
+
+        // Collect the entries before deleting them, so that the dump is not interrupted by our
+        // own changes.
+        let entries: Vec<NeighbourMessage> = request
+            .execute()
+            .try_filter(|entry| future::ready(entry.header.state.intersects(state)))
+            .try_collect()
+            .await?;
+
+        for entry in entries {
+            let message = NeighbourMessage {
+                header: entry.header,
+                nlas: entry
+                    .nlas
+                    .into_iter()
+                    .filter(|nla| matches!(nla, Nla::Destination(_)))
+                    .collect(),
+            };
+
+            match NeighbourDelRequest::new(handle.clone(), message)
+                .execute()
+                .await
+            {
+                Err(Error::NetlinkError(err))
+                    if err.code == -(nix::errno::Errno::ENOENT as i32) => {}
+                result => result?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,34 +1,46 @@
 use futures::{
-    future::{self, Either},
-    stream::{StreamExt, TryStream},
-    FutureExt,
+    future,
+    stream::{StreamExt, TryStream, TryStreamExt},
 };
 
 use netlink_packet_route::{
     constants::*,
-    neighbour::NeighbourMessage,
+    neighbour::{NeighbourMessage, Nla},
+    NeighbourFlags,
     NetlinkPayload,
     RtnlMessage,
 };
 
 use netlink_proto::packet::NetlinkMessage;
 
-use crate::{Error, Handle, IpVersion};
+use crate::{dump_request, DumpInterruptedPolicy, Error, Handle, IpVersion};
+use std::net::IpAddr;
 
 pub struct NeighbourGetRequest {
     handle: Handle,
     message: NeighbourMessage,
+    // If `dump` is `true`, all the entries are fetched. Otherwise, only the entry matching the
+    // destination and interface set with `match_destination` is fetched.
+    dump: bool,
+    index_filter: Option<u32>,
+    dump_interrupted: DumpInterruptedPolicy,
 }
 
 impl NeighbourGetRequest {
     pub(crate) fn new(handle: Handle) -> Self {
         let message = NeighbourMessage::default();
-        NeighbourGetRequest { handle, message }
+        NeighbourGetRequest {
+            handle,
+            message,
+            dump: true,
+            index_filter: None,
+            dump_interrupted: DumpInterruptedPolicy::default(),
+        }
     }
 
     /// List neighbor proxies in the system (equivalent to: `ip neighbor show proxy`).
     pub fn proxies(mut self) -> Self {
-        self.message.header.flags |= NTF_PROXY;
+        self.message.header.flags |= NeighbourFlags::NTF_PROXY;
         self
     }
 
@@ -37,33 +49,78 @@ impl NeighbourGetRequest {
         self
     }
 
+    /// Only retrieve the entry for `destination` on the interface with the given index
+    /// (equivalent to `ip neighbour get <destination> dev <interface>`). The kernel returns an
+    /// error if there is no such entry.
+    pub fn match_destination(mut self, index: u32, destination: IpAddr) -> Self {
+        self.dump = false;
+        self.message.header.ifindex = index;
+        self.message.header.family = match destination {
+            IpAddr::V4(_) => AF_INET as u8,
+            IpAddr::V6(_) => AF_INET6 as u8,
+        };
+        self.message.nlas.push(Nla::Destination(match destination {
+            IpAddr::V4(v4) => v4.octets().to_vec(),
+            IpAddr::V6(v6) => v6.octets().to_vec(),
+        }));
+        self
+    }
+
+    /// Only retrieve the entries of the interface with the given index. Recent kernels do the
+    /// filtering themselves, older ones return all the entries which are then filtered here.
+    pub fn set_link_index_filter(mut self, index: u32) -> Self {
+        self.message.nlas.push(Nla::IfIndex(index));
+        self.index_filter = Some(index);
+        self
+    }
+
     /// Execute the request
     pub fn execute(self) -> impl TryStream<Ok = NeighbourMessage, Error = Error> {
         let NeighbourGetRequest {
-            mut handle,
+            handle,
             message,
+            dump,
+            index_filter,
+            dump_interrupted,
         } = self;
 
         let mut req = NetlinkMessage::from(RtnlMessage::GetNeighbour(message));
-        req.header.flags = NLM_F_REQUEST | NLM_F_DUMP;
+        if dump {
+            req.header.flags = NLM_F_REQUEST | NLM_F_DUMP;
+        } else {
+            req.header.flags = NLM_F_REQUEST;
+        }
 
-        match handle.request(req) {
-            Ok(response) => Either::Left(response.map(move |msg| {
-                let (header, payload) = msg.into_parts();
+        dump_request(handle, req, dump_interrupted)
+            .map(move |msg| {
+                let (header, payload) = msg?.into_parts();
                 match payload {
                     NetlinkPayload::InnerMessage(RtnlMessage::NewNeighbour(msg)) => Ok(msg),
-                    NetlinkPayload::Error(err) => Err(Error::NetlinkError(err)),
+                    NetlinkPayload::Error(err) => Err(Error::from(err)),
                     _ => Err(Error::UnexpectedMessage(NetlinkMessage::new(
                         header, payload,
                     ))),
                 }
-            })),
-            Err(e) => Either::Right(future::err::<NeighbourMessage, Error>(e).into_stream()),
-        }
+            })
+            .try_filter(move |msg| {
+                let keep = match index_filter {
+                    Some(index) => msg.header.ifindex == index,
+                    None => true,
+                };
+                future::ready(keep)
+            })
     }
 
     /// Return a mutable reference to the request
     pub fn message_mut(&mut self) -> &mut NeighbourMessage {
         &mut self.message
     }
+
+    /// Set what to do if the dump is interrupted by a concurrent change (see
+    /// [`DumpInterruptedPolicy`](enum.DumpInterruptedPolicy.html)). By default, the interruption
+    /// is ignored.
+    pub fn on_dump_interrupted(mut self, policy: DumpInterruptedPolicy) -> Self {
+        self.dump_interrupted = policy;
+        self
+    }
 }
@@ -1,4 +1,10 @@
-use crate::{Handle, NeighbourAddRequest, NeighbourDelRequest, NeighbourGetRequest};
+use crate::{
+    Handle,
+    NeighbourAddRequest,
+    NeighbourDelRequest,
+    NeighbourFlushRequest,
+    NeighbourGetRequest,
+};
 use netlink_packet_route::NeighbourMessage;
 use std::net::IpAddr;
 
@@ -23,4 +29,10 @@ impl NeighbourHandle {
     pub fn del(&self, message: NeighbourMessage) -> NeighbourDelRequest {
         NeighbourDelRequest::new(self.0.clone(), message)
     }
+
+    /// Delete all the neighbour entries matching the request filters (equivalent to `ip
+    /// neighbour flush`)
+    pub fn flush(&self) -> NeighbourFlushRequest {
+        NeighbourFlushRequest::new(self.0.clone())
+    }
 }
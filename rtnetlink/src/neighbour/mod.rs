@@ -9,3 +9,6 @@ pub use self::add::*;
 
 mod del;
 pub use self::del::*;
+
+mod flush;
+pub use self::flush::*;
@@ -0,0 +1,67 @@
+use futures::stream::{StreamExt, TryStream};
+
+use crate::{
+    packet::{
+        constants::*,
+        NeighbourTableMessage,
+        NetlinkMessage,
+        RtnlMessage,
+    },
+    dump_request,
+    try_rtnl,
+    DumpInterruptedPolicy,
+    Error,
+    Handle,
+    IpVersion,
+};
+
+pub struct NeighbourTableGetRequest {
+    handle: Handle,
+    message: NeighbourTableMessage,
+    dump_interrupted: DumpInterruptedPolicy,
+}
+
+impl NeighbourTableGetRequest {
+    pub(crate) fn new(handle: Handle) -> Self {
+        let message = NeighbourTableMessage::default();
+        NeighbourTableGetRequest {
+            handle,
+            message,
+            dump_interrupted: DumpInterruptedPolicy::default(),
+        }
+    }
+
+    /// Only dump the tables of the given address family
+    pub fn set_family(mut self, ip_version: IpVersion) -> Self {
+        self.message.header.family = ip_version.family();
+        self
+    }
+
+    /// Execute the request
+    pub fn execute(self) -> impl TryStream<Ok = NeighbourTableMessage, Error = Error> {
+        let NeighbourTableGetRequest {
+            handle,
+            message,
+            dump_interrupted,
+        } = self;
+
+        let mut req = NetlinkMessage::from(RtnlMessage::GetNeighbourTable(message));
+        req.header.flags = NLM_F_REQUEST | NLM_F_DUMP;
+
+        dump_request(handle, req, dump_interrupted)
+            .map(move |msg| Ok(try_rtnl!(msg?, RtnlMessage::NewNeighbourTable)))
+    }
+
+    /// Return a mutable reference to the request
+    pub fn message_mut(&mut self) -> &mut NeighbourTableMessage {
+        &mut self.message
+    }
+
+    /// Set what to do if the dump is interrupted by a concurrent change (see
+    /// [`DumpInterruptedPolicy`](enum.DumpInterruptedPolicy.html)). By default, the interruption
+    /// is ignored.
+    pub fn on_dump_interrupted(mut self, policy: DumpInterruptedPolicy) -> Self {
+        self.dump_interrupted = policy;
+        self
+    }
+}
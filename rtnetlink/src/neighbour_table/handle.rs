@@ -0,0 +1,21 @@
+use crate::{Handle, IpVersion, NeighbourTableGetRequest, NeighbourTableSetRequest};
+
+pub struct NeighbourTableHandle(Handle);
+
+impl NeighbourTableHandle {
+    pub fn new(handle: Handle) -> Self {
+        NeighbourTableHandle(handle)
+    }
+
+    /// List the neighbour tables and their per-interface parameters (equivalent to `ip ntable
+    /// show`)
+    pub fn get(&self) -> NeighbourTableGetRequest {
+        NeighbourTableGetRequest::new(self.0.clone())
+    }
+
+    /// Change the parameters of the ARP (`IpVersion::V4`) or neighbour discovery
+    /// (`IpVersion::V6`) table (equivalent to `ip ntable change`)
+    pub fn set(&self, ip_version: IpVersion) -> NeighbourTableSetRequest {
+        NeighbourTableSetRequest::new(self.0.clone(), ip_version)
+    }
+}
@@ -0,0 +1,8 @@
+mod handle;
+pub use self::handle::*;
+
+mod get;
+pub use self::get::*;
+
+mod set;
+pub use self::set::*;
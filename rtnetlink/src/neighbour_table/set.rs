@@ -0,0 +1,174 @@
+use futures::stream::TryStreamExt;
+
+use crate::{
+    packet::{
+        nlas::neighbour_table::{NeighbourTableParameter, Nla},
+        NeighbourTableMessage,
+        NetlinkMessage,
+        RtnlMessage,
+        NLM_F_ACK,
+        NLM_F_REQUEST,
+    },
+    try_nl,
+    Batchable,
+    Error,
+    Handle,
+    IpVersion,
+};
+
+/// A request to change the parameters of a neighbour table. The table-wide settings
+/// (thresholds, garbage collection interval) are sent as top-level attributes, while the
+/// parameters are sent nested in `NDTA_PARMS`. Without a call to
+/// [`index()`](NeighbourTableSetRequest::index), the parameters change the defaults of the
+/// table instead of the settings of a single interface.
+pub struct NeighbourTableSetRequest {
+    handle: Handle,
+    message: NeighbourTableMessage,
+    parameters: Vec<NeighbourTableParameter>,
+}
+
+impl NeighbourTableSetRequest {
+    pub(crate) fn new(handle: Handle, ip_version: IpVersion) -> Self {
+        let mut message = NeighbourTableMessage::default();
+        let name = match ip_version {
+            IpVersion::V4 => "arp_cache",
+            IpVersion::V6 => "ndisc_cache",
+        };
+        message.header.family = ip_version.family();
+        message.nlas.push(Nla::Name(name.into()));
+        NeighbourTableSetRequest {
+            handle,
+            message,
+            parameters: vec![],
+        }
+    }
+
+    /// Execute the request
+    pub async fn execute(self) -> Result<(), Error> {
+        let mut handle = self.handle.clone();
+        let mut response = handle.request(self.into_message())?;
+        while let Some(message) = response.try_next().await? {
+            try_nl!(message);
+        }
+        Ok(())
+    }
+
+    /// Return a mutable reference to the request
+    pub fn message_mut(&mut self) -> &mut NeighbourTableMessage {
+        &mut self.message
+    }
+
+    /// Add a parameter to the `NDTA_PARMS` attribute of the request
+    pub fn parameter(mut self, parameter: NeighbourTableParameter) -> Self {
+        self.parameters.push(parameter);
+        self
+    }
+
+    /// Only change the parameters of the interface with the given index
+    pub fn index(self, index: u32) -> Self {
+        self.parameter(NeighbourTableParameter::Ifindex(index))
+    }
+
+    /// Set the base reachable time, in milliseconds (equivalent to `ip ntable change ...
+    /// base_reachable MSEC`)
+    pub fn base_reachable_time(self, ms: u64) -> Self {
+        self.parameter(NeighbourTableParameter::BaseReachableTime(ms))
+    }
+
+    /// Set the retransmission time, in milliseconds
+    pub fn retrans_time(self, ms: u64) -> Self {
+        self.parameter(NeighbourTableParameter::RetransTime(ms))
+    }
+
+    /// Set the time after which a stale entry may be garbage collected, in milliseconds
+    pub fn gc_stale_time(self, ms: u64) -> Self {
+        self.parameter(NeighbourTableParameter::GcStaleTime(ms))
+    }
+
+    /// Set the delay before the first probe of a stale entry, in milliseconds
+    pub fn delay_probe_time(self, ms: u64) -> Self {
+        self.parameter(NeighbourTableParameter::DelayProbeTime(ms))
+    }
+
+    /// Set the maximum number of packets queued for an unresolved entry
+    pub fn queue_len(self, packets: u32) -> Self {
+        self.parameter(NeighbourTableParameter::QueueLen(packets))
+    }
+
+    /// Set the maximum number of bytes queued for an unresolved entry
+    pub fn queue_len_bytes(self, bytes: u32) -> Self {
+        self.parameter(NeighbourTableParameter::QueueLenBytes(bytes))
+    }
+
+    /// Set the number of probes sent to the user space resolver
+    pub fn app_probes(self, probes: u32) -> Self {
+        self.parameter(NeighbourTableParameter::AppProbes(probes))
+    }
+
+    /// Set the number of unicast probes sent before considering an entry failed
+    pub fn ucast_probes(self, probes: u32) -> Self {
+        self.parameter(NeighbourTableParameter::UcastProbes(probes))
+    }
+
+    /// Set the number of multicast probes sent before considering an entry failed
+    pub fn mcast_probes(self, probes: u32) -> Self {
+        self.parameter(NeighbourTableParameter::McastProbes(probes))
+    }
+
+    /// Set the number of multicast probes sent when re-validating a stale entry
+    pub fn mcast_reprobes(self, probes: u32) -> Self {
+        self.parameter(NeighbourTableParameter::McastReprobes(probes))
+    }
+
+    /// Set the maximum random delay before answering a neighbour solicitation, in milliseconds
+    pub fn anycast_delay(self, ms: u64) -> Self {
+        self.parameter(NeighbourTableParameter::AnycastDelay(ms))
+    }
+
+    /// Set the maximum random delay before answering a proxied request, in milliseconds
+    pub fn proxy_delay(self, ms: u64) -> Self {
+        self.parameter(NeighbourTableParameter::ProxyDelay(ms))
+    }
+
+    /// Set the maximum number of queued proxied requests
+    pub fn proxy_queue_len(self, packets: u32) -> Self {
+        self.parameter(NeighbourTableParameter::ProxyQueueLen(packets))
+    }
+
+    /// Set the minimum time before an entry may be replaced, in milliseconds
+    pub fn locktime(self, ms: u64) -> Self {
+        self.parameter(NeighbourTableParameter::Locktime(ms))
+    }
+
+    /// Set the garbage collector thresholds of the table (`gc_thresh1`, `gc_thresh2` and
+    /// `gc_thresh3`)
+    pub fn thresholds(mut self, threshold1: u32, threshold2: u32, threshold3: u32) -> Self {
+        self.message.nlas.push(Nla::Threshold1(threshold1));
+        self.message.nlas.push(Nla::Threshold2(threshold2));
+        self.message.nlas.push(Nla::Threshold3(threshold3));
+        self
+    }
+
+    /// Set the interval between two runs of the garbage collector, in milliseconds
+    pub fn gc_interval(mut self, ms: u64) -> Self {
+        self.message.nlas.push(Nla::GcInterval(ms));
+        self
+    }
+}
+
+impl Batchable for NeighbourTableSetRequest {
+    fn into_message(self) -> NetlinkMessage<RtnlMessage> {
+        let NeighbourTableSetRequest {
+            mut message,
+            parameters,
+            ..
+        } = self;
+        if !parameters.is_empty() {
+            message.nlas.push(Nla::Parms(parameters));
+        }
+
+        let mut req = NetlinkMessage::from(RtnlMessage::SetNeighbourTable(message));
+        req.header.flags = NLM_F_REQUEST | NLM_F_ACK;
+        req
+    }
+}
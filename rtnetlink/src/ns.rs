@@ -45,6 +45,9 @@ impl NetworkNamespace {
 
     /// Remove a network namespace
     /// This is equivalent to `ip netns del NS_NAME`.
+    ///
+    /// This requires the `tokio_socket` or `smol_socket` feature.
+    #[cfg(any(feature = "tokio_socket", feature = "smol_socket"))]
     pub async fn del(ns_name: String) -> Result<(), Error> {
         let res = task::spawn_blocking(move || {
             let mut netns_path = String::new();
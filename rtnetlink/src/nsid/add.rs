@@ -0,0 +1,71 @@
+use std::os::unix::io::RawFd;
+
+use futures::stream::TryStreamExt;
+
+use crate::{
+    packet::{
+        nlas::nsid::Nla,
+        NetlinkMessage,
+        NsidMessage,
+        RtnlMessage,
+        NLM_F_ACK,
+        NLM_F_REQUEST,
+    },
+    try_nl,
+    Batchable,
+    Error,
+    Handle,
+};
+
+/// A request to assign an id to a network namespace. This is equivalent to `ip netns set NAME
+/// NSID`, but the namespace is identified by a process id or a file descriptor instead of a
+/// name.
+pub struct NsidAddRequest {
+    handle: Handle,
+    message: NsidMessage,
+}
+
+impl NsidAddRequest {
+    pub(crate) fn new(handle: Handle, nsid: i32) -> Self {
+        let mut message = NsidMessage::default();
+        message.nlas.push(Nla::Id(nsid));
+        NsidAddRequest { handle, message }
+    }
+
+    /// Execute the request
+    pub async fn execute(self) -> Result<(), Error> {
+        let mut handle = self.handle.clone();
+        let mut response = handle.request(self.into_message())?;
+        while let Some(message) = response.try_next().await? {
+            try_nl!(message);
+        }
+        Ok(())
+    }
+
+    /// Return a mutable reference to the request
+    pub fn message_mut(&mut self) -> &mut NsidMessage {
+        &mut self.message
+    }
+
+    /// Assign the id to the network namespace of the process with the given `pid`
+    pub fn pid(mut self, pid: u32) -> Self {
+        self.message.nlas.push(Nla::Pid(pid));
+        self
+    }
+
+    /// Assign the id to the network namespace corresponding to the given file descriptor, for
+    /// instance a file opened under `/run/netns/`
+    pub fn fd(mut self, fd: RawFd) -> Self {
+        self.message.nlas.push(Nla::Fd(fd as u32));
+        self
+    }
+}
+
+impl Batchable for NsidAddRequest {
+    fn into_message(self) -> NetlinkMessage<RtnlMessage> {
+        let NsidAddRequest { message, .. } = self;
+        let mut req = NetlinkMessage::from(RtnlMessage::NewNsId(message));
+        req.header.flags = NLM_F_REQUEST | NLM_F_ACK;
+        req
+    }
+}
@@ -0,0 +1,94 @@
+use std::os::unix::io::RawFd;
+
+use futures::stream::{StreamExt, TryStream};
+
+use crate::{
+    packet::{
+        constants::*,
+        nlas::nsid::Nla,
+        NetlinkMessage,
+        NsidMessage,
+        RtnlMessage,
+    },
+    dump_request,
+    try_rtnl,
+    DumpInterruptedPolicy,
+    Error,
+    Handle,
+};
+
+pub struct NsidGetRequest {
+    handle: Handle,
+    message: NsidMessage,
+    // By default, all the ids known to the current namespace are dumped. As soon as a peer
+    // namespace is selected with `pid()` or `fd()`, only the id of that namespace is fetched.
+    dump: bool,
+    dump_interrupted: DumpInterruptedPolicy,
+}
+
+impl NsidGetRequest {
+    pub(crate) fn new(handle: Handle) -> Self {
+        NsidGetRequest {
+            handle,
+            message: NsidMessage::default(),
+            dump: true,
+            dump_interrupted: DumpInterruptedPolicy::default(),
+        }
+    }
+
+    /// Execute the request
+    pub fn execute(self) -> impl TryStream<Ok = NsidMessage, Error = Error> {
+        let NsidGetRequest {
+            handle,
+            message,
+            dump,
+            dump_interrupted,
+        } = self;
+
+        let mut req = NetlinkMessage::from(RtnlMessage::GetNsId(message));
+
+        if dump {
+            req.header.flags = NLM_F_REQUEST | NLM_F_DUMP;
+        } else {
+            req.header.flags = NLM_F_REQUEST;
+        }
+
+        dump_request(handle, req, dump_interrupted)
+            .map(move |msg| Ok(try_rtnl!(msg?, RtnlMessage::NewNsId)))
+    }
+
+    /// Return a mutable reference to the request
+    pub fn message_mut(&mut self) -> &mut NsidMessage {
+        &mut self.message
+    }
+
+    /// Set what to do if the dump is interrupted by a concurrent change (see
+    /// [`DumpInterruptedPolicy`](enum.DumpInterruptedPolicy.html)). By default, the interruption
+    /// is ignored.
+    pub fn on_dump_interrupted(mut self, policy: DumpInterruptedPolicy) -> Self {
+        self.dump_interrupted = policy;
+        self
+    }
+
+    /// Only retrieve the id of the network namespace of the process with the given `pid`
+    pub fn pid(mut self, pid: u32) -> Self {
+        self.dump = false;
+        self.message.nlas.push(Nla::Pid(pid));
+        self
+    }
+
+    /// Only retrieve the id of the network namespace corresponding to the given file descriptor
+    pub fn fd(mut self, fd: RawFd) -> Self {
+        self.dump = false;
+        self.message.nlas.push(Nla::Fd(fd as u32));
+        self
+    }
+
+    /// Return the ids as seen from the namespace with the given id, instead of the current
+    /// namespace. The id of the current namespace as seen from the target namespace is then
+    /// reported in the `CurrentNsid` attribute of the responses.
+    pub fn target_nsid(mut self, nsid: i32) -> Self {
+        self.message.nlas.push(Nla::TargetNsid(nsid));
+        self
+    }
+}
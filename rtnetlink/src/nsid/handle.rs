@@ -0,0 +1,116 @@
+#[cfg(feature = "tokio_socket")]
+use tokio::task;
+
+#[cfg(feature = "smol_socket")]
+use async_std::task;
+
+#[cfg(any(feature = "tokio_socket", feature = "smol_socket"))]
+use std::{
+    fs::File,
+    os::unix::io::AsRawFd,
+    path::{Path, PathBuf},
+};
+
+#[cfg(any(feature = "tokio_socket", feature = "smol_socket"))]
+use futures::stream::TryStreamExt;
+
+use super::{NsidAddRequest, NsidGetRequest};
+use crate::Handle;
+#[cfg(any(feature = "tokio_socket", feature = "smol_socket"))]
+use crate::{packet::nlas::nsid::Nla, Error, NETNS_PATH};
+
+pub struct NsidHandle(Handle);
+
+impl NsidHandle {
+    pub fn new(handle: Handle) -> Self {
+        NsidHandle(handle)
+    }
+
+    /// Assign an id to a peer network namespace (equivalent to `ip netns set NAME NSID`). Passing
+    /// `NETNSA_NSID_NOT_ASSIGNED` lets the kernel pick an id.
+    pub fn add(&self, nsid: i32) -> NsidAddRequest {
+        NsidAddRequest::new(self.0.clone(), nsid)
+    }
+
+    /// Retrieve the ids of the peer network namespaces (equivalent to `ip netns list-id`)
+    pub fn get(&self) -> NsidGetRequest {
+        NsidGetRequest::new(self.0.clone())
+    }
+
+    /// Find the namespace under `/run/netns/` that has the given id, as reported for instance by
+    /// the `NetnsId` (`IFLA_LINK_NETNSID`) or `IfNetnsId` attributes of a link. `None` is
+    /// returned if no named namespace has this id.
+    ///
+    /// This requires the `tokio_socket` or `smol_socket` feature.
+    #[cfg(any(feature = "tokio_socket", feature = "smol_socket"))]
+    pub async fn resolve_path(&self, nsid: i32) -> Result<Option<PathBuf>, Error> {
+        self.resolve_path_in(Path::new(NETNS_PATH), nsid).await
+    }
+
+    #[cfg(any(feature = "tokio_socket", feature = "smol_socket"))]
+    pub(crate) async fn resolve_path_in(
+        &self,
+        dir: &Path,
+        nsid: i32,
+    ) -> Result<Option<PathBuf>, Error> {
+        // All the namespaces without an id report NETNSA_NSID_NOT_ASSIGNED, so a negative id
+        // cannot designate one of them.
+        if nsid < 0 {
+            return Ok(None);
+        }
+        for (path, file) in open_files(dir.to_path_buf()).await? {
+            // The kernel rejects the files that are not namespaces, like a mount point whose
+            // namespace went away: skip them.
+            let ids: Vec<_> = match self
+                .get()
+                .fd(file.as_raw_fd())
+                .execute()
+                .try_collect()
+                .await
+            {
+                Ok(ids) => ids,
+                Err(Error::NetlinkError(_)) => continue,
+                Err(e) => return Err(e),
+            };
+            if ids.iter().any(|msg| msg.nlas.contains(&Nla::Id(nsid))) {
+                return Ok(Some(path));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Open the files of the given directory, without blocking the runtime. The files that cannot be
+/// opened are skipped: they are not namespaces we have access to.
+#[cfg(any(feature = "tokio_socket", feature = "smol_socket"))]
+async fn open_files(dir: PathBuf) -> Result<Vec<(PathBuf, File)>, Error> {
+    let res = task::spawn_blocking(move || {
+        let mut files = vec![];
+        if let Ok(entries) = std::fs::read_dir(&dir) {
+            for entry in entries {
+                let path = match entry {
+                    Ok(entry) => entry.path(),
+                    Err(e) => {
+                        return Err(Error::NamespaceError(format!("{}: {}", dir.display(), e)))
+                    }
+                };
+                if let Ok(file) = File::open(&path) {
+                    files.push((path, file));
+                }
+            }
+        }
+        #[cfg(feature = "tokio_socket")]
+        return Ok(files);
+
+        #[cfg(feature = "smol_socket")]
+        return Ok(Ok(files));
+    });
+
+    match res.await {
+        Ok(r) => r,
+        Err(e) => Err(Error::NamespaceError(format!(
+            "Failed to list the network namespaces: {}",
+            e
+        ))),
+    }
+}
@@ -0,0 +1,11 @@
+mod handle;
+pub use self::handle::*;
+
+mod add;
+pub use self::add::*;
+
+mod get;
+pub use self::get::*;
+
+#[cfg(test)]
+mod test;
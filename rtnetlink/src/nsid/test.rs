@@ -0,0 +1,101 @@
+use std::{
+    fs::{self, File},
+    os::unix::{fs::symlink, io::AsRawFd},
+    path::PathBuf,
+    process,
+    thread,
+};
+
+use futures::stream::TryStreamExt;
+use nix::sched::{unshare, CloneFlags};
+use tokio::runtime::Runtime;
+
+use crate::{
+    new_connection,
+    packet::{constants::NETNSA_NSID_NOT_ASSIGNED, nlas::nsid::Nla},
+    NsidHandle,
+};
+
+fn namespaces_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rtnetlink-nsid-{}-{}", name, process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    File::create(dir.join("not-a-namespace")).unwrap();
+    symlink("/proc/self/ns/net", dir.join("current")).unwrap();
+    dir
+}
+
+async fn get_id(nsid: &NsidHandle, file: &File) -> i32 {
+    let ids: Vec<_> = nsid
+        .get()
+        .fd(file.as_raw_fd())
+        .execute()
+        .try_collect()
+        .await
+        .unwrap();
+    ids.iter()
+        .flat_map(|msg| msg.nlas.iter())
+        .find_map(|nla| match nla {
+            Nla::Id(id) => Some(*id),
+            _ => None,
+        })
+        .unwrap()
+}
+
+#[test]
+fn resolve_path_ignores_namespaces_without_id() {
+    let dir = namespaces_dir("unassigned");
+
+    let rt = Runtime::new().unwrap();
+    let result = rt.block_on(async {
+        let (connection, handle, _) = new_connection().unwrap();
+        tokio::spawn(connection);
+        let nsid = handle.nsid();
+        // The namespace of the caller has no id in itself
+        let current = File::open("/proc/self/ns/net").unwrap();
+        let id = get_id(&nsid, &current).await;
+        (
+            id,
+            nsid.resolve_path_in(&dir, id).await,
+            nsid.resolve_path_in(&dir, 0x7fff_fff0).await,
+        )
+    });
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(result.0, NETNSA_NSID_NOT_ASSIGNED);
+    assert_eq!(result.1.unwrap(), None);
+    assert_eq!(result.2.unwrap(), None);
+}
+
+#[test]
+#[cfg_attr(not(feature = "test_as_root"), ignore)]
+fn resolve_path_finds_namespace_with_id() {
+    // Namespaces are per thread: create one in a thread that exits right away, the file keeps
+    // the namespace alive.
+    let namespace = thread::spawn(|| {
+        unshare(CloneFlags::CLONE_NEWNET).unwrap();
+        File::open("/proc/thread-self/ns/net").unwrap()
+    })
+    .join()
+    .unwrap();
+    let dir = namespaces_dir("assigned");
+    let target = format!("/proc/self/fd/{}", namespace.as_raw_fd());
+    symlink(target, dir.join("other")).unwrap();
+
+    let rt = Runtime::new().unwrap();
+    let result = rt.block_on(async {
+        let (connection, handle, _) = new_connection().unwrap();
+        tokio::spawn(connection);
+        let nsid = handle.nsid();
+        nsid.add(NETNSA_NSID_NOT_ASSIGNED)
+            .fd(namespace.as_raw_fd())
+            .execute()
+            .await
+            .unwrap();
+        let id = get_id(&nsid, &namespace).await;
+        (id, nsid.resolve_path_in(&dir, id).await)
+    });
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(result.0 >= 0);
+    assert_eq!(result.1.unwrap(), Some(dir.join("other")));
+}
@@ -0,0 +1,175 @@
+use std::net::IpAddr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Desired state of the network, to be applied with [`Handle::reconcile`](crate::Handle::reconcile).
+///
+/// With the `serde` feature, it can be loaded from any format supported by serde. In TOML for
+/// instance:
+///
+/// ```toml
+/// [[links]]
+/// name = "br0"
+/// kind = "bridge"
+///
+/// [[links]]
+/// name = "dummy0"
+/// kind = "dummy"
+/// master = "br0"
+///
+/// [[addresses]]
+/// link = "br0"
+/// address = "10.0.0.1"
+/// prefix_len = 24
+///
+/// [[routes]]
+/// destination = "10.1.0.0"
+/// prefix_len = 16
+/// gateway = "10.0.0.254"
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
+pub struct NetworkState {
+    pub links: Vec<LinkConfig>,
+    pub addresses: Vec<AddressConfig>,
+    pub routes: Vec<RouteConfig>,
+    pub rules: Vec<RuleConfig>,
+}
+
+/// Desired state of a link. The optional fields are left untouched when they are `None`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LinkConfig {
+    pub name: String,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub kind: LinkKind,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub state: LinkState,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub mtu: Option<u32>,
+    /// Name of the bridge, bond or VRF this link is attached to.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub master: Option<String>,
+}
+
+/// Kind of a link. Links of another kind than [`LinkKind::Existing`] are created if they do not
+/// exist, and re-created if they exist with another kind.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(tag = "kind", rename_all = "lowercase")
+)]
+pub enum LinkKind {
+    /// A link that is not created by the reconciliation, such as a physical interface. It must
+    /// exist.
+    Existing,
+    Dummy,
+    Bridge,
+    Vrf {
+        table: u32,
+    },
+    Veth {
+        peer: String,
+    },
+    Vlan {
+        parent: String,
+        id: u16,
+    },
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum LinkState {
+    Up,
+    Down,
+    /// The link must not exist.
+    Absent,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for LinkState {
+    fn default() -> Self {
+        LinkState::Up
+    }
+}
+
+/// An address assigned to a link. The addresses of a link that are not part of the desired state
+/// are removed, except for IPv6 link-local addresses.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AddressConfig {
+    pub link: String,
+    pub address: IpAddr,
+    pub prefix_len: u8,
+}
+
+/// A route. The routes created by the reconciliation are tagged with its protocol, and only the
+/// routes with this protocol that are not part of the desired state are removed.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RouteConfig {
+    pub destination: IpAddr,
+    pub prefix_len: u8,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub gateway: Option<IpAddr>,
+    /// Name of the output interface.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub link: Option<String>,
+    /// Routing table. Defaults to the main table.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub table: Option<u32>,
+    /// Metric of the route. When `None`, a route with any metric matches.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub metric: Option<u32>,
+}
+
+/// A routing rule. Like routes, rules are tagged with the protocol of the reconciliation.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RuleConfig {
+    /// Table to look up.
+    pub table: u32,
+    /// Priority of the rule. When `None`, a rule with any priority matches, and the kernel picks
+    /// one when the rule is created.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub priority: Option<u32>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub source: Option<IpAddr>,
+    /// Prefix length of `source`. Defaults to the full address length.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub source_prefix_len: Option<u8>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub destination: Option<IpAddr>,
+    /// Prefix length of `destination`. Defaults to the full address length.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub destination_prefix_len: Option<u8>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub fw_mark: Option<u32>,
+    /// Name of the input interface.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub input_interface: Option<String>,
+    /// Whether this is an IPv6 rule. This is only needed for rules without an address.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub ipv6: bool,
+}
+
+impl RuleConfig {
+    pub(crate) fn is_ipv6(&self) -> bool {
+        self.ipv6
+            || self.source.map(|a| a.is_ipv6()).unwrap_or(false)
+            || self.destination.map(|a| a.is_ipv6()).unwrap_or(false)
+    }
+}
+
+pub(crate) fn full_prefix_len(address: IpAddr) -> u8 {
+    match address {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    }
+}
@@ -0,0 +1,11 @@
+mod config;
+pub use self::config::*;
+
+mod plan;
+pub use self::plan::*;
+
+mod request;
+pub use self::request::*;
+
+#[cfg(test)]
+mod test;
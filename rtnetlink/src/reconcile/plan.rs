@@ -0,0 +1,700 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    net::IpAddr,
+};
+
+use crate::{
+    cache::{link_name, route_table, rule_table},
+    packet::{
+        nlas::{
+            link::{self, Info, InfoKind},
+            route,
+            rule,
+        },
+        AddressMessage,
+        LinkFlags,
+        LinkMessage,
+        RouteMessage,
+        RuleMessage,
+        AF_INET,
+        AF_INET6,
+        RT_TABLE_MAIN,
+    },
+    reconcile::config::full_prefix_len,
+    AddressConfig,
+    Error,
+    LinkConfig,
+    LinkKind,
+    LinkState,
+    NetworkState,
+    RouteConfig,
+    RuleConfig,
+};
+
+/// Changes of the properties of an existing link. The properties that are `None` are not changed.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LinkUpdate {
+    pub name: String,
+    pub up: Option<bool>,
+    pub mtu: Option<u32>,
+    pub master: Option<String>,
+}
+
+/// A single request of a [`Plan`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Change {
+    DelRule(RuleMessage),
+    DelRoute(RouteMessage),
+    DelAddress(AddressMessage),
+    DelLink(String),
+    AddLink(LinkConfig),
+    SetLink(LinkUpdate),
+    AddAddress(AddressConfig),
+    AddRoute(RouteConfig),
+    AddRule(RuleConfig),
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Change::DelRule(msg) => {
+                write!(f, "rule del")?;
+                if let Some(priority) = rule_priority(msg) {
+                    write!(f, " priority {}", priority)?;
+                }
+                write!(f, " table {}", rule_table(msg))
+            }
+            Change::DelRoute(msg) => {
+                let (destination, prefix_len) = route_destination(msg).unwrap_or_else(|| {
+                    let unspecified = if msg.header.address_family == AF_INET6 as u8 {
+                        IpAddr::from([0; 16])
+                    } else {
+                        IpAddr::from([0; 4])
+                    };
+                    (unspecified, msg.header.destination_prefix_length)
+                });
+                write!(
+                    f,
+                    "route del {}/{} table {}",
+                    destination,
+                    prefix_len,
+                    route_table(msg)
+                )
+            }
+            Change::DelAddress(msg) => match msg.prefix() {
+                Some((address, prefix_len)) => write!(
+                    f,
+                    "address del {}/{} index {}",
+                    address, prefix_len, msg.header.index
+                ),
+                None => write!(f, "address del index {}", msg.header.index),
+            },
+            Change::DelLink(name) => write!(f, "link del {}", name),
+            Change::AddLink(config) => {
+                write!(f, "link add {} type ", config.name)?;
+                match &config.kind {
+                    LinkKind::Existing => write!(f, "existing"),
+                    LinkKind::Dummy => write!(f, "dummy"),
+                    LinkKind::Bridge => write!(f, "bridge"),
+                    LinkKind::Vrf { table } => write!(f, "vrf table {}", table),
+                    LinkKind::Veth { peer } => write!(f, "veth peer {}", peer),
+                    LinkKind::Vlan { parent, id } => write!(f, "vlan link {} id {}", parent, id),
+                }
+            }
+            Change::SetLink(update) => {
+                write!(f, "link set {}", update.name)?;
+                match update.up {
+                    Some(true) => write!(f, " up")?,
+                    Some(false) => write!(f, " down")?,
+                    None => {}
+                }
+                if let Some(mtu) = update.mtu {
+                    write!(f, " mtu {}", mtu)?;
+                }
+                if let Some(master) = &update.master {
+                    write!(f, " master {}", master)?;
+                }
+                Ok(())
+            }
+            Change::AddAddress(config) => write!(
+                f,
+                "address add {}/{} dev {}",
+                config.address, config.prefix_len, config.link
+            ),
+            Change::AddRoute(config) => {
+                write!(f, "route add {}/{}", config.destination, config.prefix_len)?;
+                if let Some(gateway) = config.gateway {
+                    write!(f, " via {}", gateway)?;
+                }
+                if let Some(link) = &config.link {
+                    write!(f, " dev {}", link)?;
+                }
+                write!(f, " table {}", config.table.unwrap_or(RT_TABLE_MAIN as u32))?;
+                if let Some(metric) = config.metric {
+                    write!(f, " metric {}", metric)?;
+                }
+                Ok(())
+            }
+            Change::AddRule(config) => {
+                write!(f, "rule add")?;
+                if let Some(priority) = config.priority {
+                    write!(f, " priority {}", priority)?;
+                }
+                if let Some(source) = config.source {
+                    let prefix_len = config
+                        .source_prefix_len
+                        .unwrap_or_else(|| full_prefix_len(source));
+                    write!(f, " from {}/{}", source, prefix_len)?;
+                }
+                if let Some(destination) = config.destination {
+                    let prefix_len = config
+                        .destination_prefix_len
+                        .unwrap_or_else(|| full_prefix_len(destination));
+                    write!(f, " to {}/{}", destination, prefix_len)?;
+                }
+                if let Some(mark) = config.fw_mark {
+                    write!(f, " fwmark {}", mark)?;
+                }
+                if let Some(iif) = &config.input_interface {
+                    write!(f, " iif {}", iif)?;
+                }
+                write!(f, " table {}", config.table)
+            }
+        }
+    }
+}
+
+/// The ordered list of requests that bring the live state to the desired state.
+///
+/// Deletions come first, then the links are created (a link after the links it depends on) and
+/// configured, and finally the addresses, routes and rules are added.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Plan {
+    pub changes: Vec<Change>,
+}
+
+impl Plan {
+    /// Whether the live state already matches the desired state.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for change in self.changes.iter() {
+            writeln!(f, "{}", change)?;
+        }
+        Ok(())
+    }
+}
+
+/// The objects currently present in the kernel.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LiveState {
+    pub links: Vec<LinkMessage>,
+    pub addresses: Vec<AddressMessage>,
+    pub routes: Vec<RouteMessage>,
+    pub rules: Vec<RuleMessage>,
+}
+
+fn invalid(message: String) -> Error {
+    Error::InvalidNetworkState(message)
+}
+
+fn link_kind(link: &LinkMessage) -> Option<&InfoKind> {
+    link.nlas.iter().find_map(|nla| {
+        if let link::Nla::Info(infos) = nla {
+            infos.iter().find_map(|info| {
+                if let Info::Kind(kind) = info {
+                    Some(kind)
+                } else {
+                    None
+                }
+            })
+        } else {
+            None
+        }
+    })
+}
+
+fn info_kind(kind: &LinkKind) -> Option<InfoKind> {
+    match kind {
+        LinkKind::Existing => None,
+        LinkKind::Dummy => Some(InfoKind::Dummy),
+        LinkKind::Bridge => Some(InfoKind::Bridge),
+        LinkKind::Vrf { .. } => Some(InfoKind::Vrf),
+        LinkKind::Veth { .. } => Some(InfoKind::Veth),
+        LinkKind::Vlan { .. } => Some(InfoKind::Vlan),
+    }
+}
+
+/// Links a link depends on: they must exist before it is created.
+fn link_dependencies(config: &LinkConfig) -> Vec<&str> {
+    let mut dependencies = vec![];
+    if let Some(master) = &config.master {
+        dependencies.push(master.as_str());
+    }
+    if let LinkKind::Vlan { parent, .. } = &config.kind {
+        dependencies.push(parent.as_str());
+    }
+    dependencies
+}
+
+/// Links a live link depends on: its master, and its parent or veth peer.
+fn live_link_dependencies<'a>(link: &LinkMessage, names: &HashMap<u32, &'a str>) -> Vec<&'a str> {
+    link.nlas
+        .iter()
+        .filter_map(|nla| match nla {
+            link::Nla::Link(index) | link::Nla::Master(index) => names.get(index).copied(),
+            _ => None,
+        })
+        .collect()
+}
+
+fn link_mtu(link: &LinkMessage) -> Option<u32> {
+    link.nlas.iter().find_map(|nla| {
+        if let link::Nla::Mtu(mtu) = nla {
+            Some(*mtu)
+        } else {
+            None
+        }
+    })
+}
+
+fn link_master(link: &LinkMessage) -> Option<u32> {
+    link.nlas.iter().find_map(|nla| {
+        if let link::Nla::Master(index) = nla {
+            Some(*index)
+        } else {
+            None
+        }
+    })
+}
+
+/// The kernel omits the destination of default routes.
+fn route_destination(route: &RouteMessage) -> Option<(IpAddr, u8)> {
+    route.destination_prefix().or_else(|| {
+        if route.header.destination_prefix_length != 0 {
+            None
+        } else if route.header.address_family == AF_INET as u8 {
+            Some((IpAddr::from([0; 4]), 0))
+        } else if route.header.address_family == AF_INET6 as u8 {
+            Some((IpAddr::from([0; 16]), 0))
+        } else {
+            None
+        }
+    })
+}
+
+fn route_priority(route: &RouteMessage) -> Option<u32> {
+    route.nlas.iter().find_map(|nla| {
+        if let route::Nla::Priority(priority) = nla {
+            Some(*priority)
+        } else {
+            None
+        }
+    })
+}
+
+fn rule_priority(rule: &RuleMessage) -> Option<u32> {
+    rule.nlas.iter().find_map(|nla| {
+        if let rule::Nla::Priority(priority) = nla {
+            Some(*priority)
+        } else {
+            None
+        }
+    })
+}
+
+fn rule_protocol(rule: &RuleMessage) -> Option<u8> {
+    rule.nlas.iter().find_map(|nla| {
+        if let rule::Nla::Protocol(protocol) = nla {
+            Some(*protocol)
+        } else {
+            None
+        }
+    })
+}
+
+fn is_ipv6_link_local(address: IpAddr) -> bool {
+    match address {
+        IpAddr::V6(v6) => v6.segments()[0] & 0xffc0 == 0xfe80,
+        IpAddr::V4(_) => false,
+    }
+}
+
+impl Plan {
+    /// Compute the changes that bring `live` to `desired`. Routes and rules are only removed if
+    /// they have the given protocol.
+    pub(crate) fn compute(
+        desired: &NetworkState,
+        live: &LiveState,
+        protocol: u8,
+    ) -> Result<Plan, Error> {
+        let live_links: HashMap<&str, &LinkMessage> = live
+            .links
+            .iter()
+            .filter_map(|link| link_name(link).map(|name| (name, link)))
+            .collect();
+        let names: HashMap<u32, &str> = live_links
+            .iter()
+            .map(|(name, link)| (link.header.index, *name))
+            .collect();
+
+        // Links created along with a veth link
+        let peers: HashSet<&str> = desired
+            .links
+            .iter()
+            .filter_map(|config| match &config.kind {
+                LinkKind::Veth { peer } if config.state != LinkState::Absent => Some(peer.as_str()),
+                _ => None,
+            })
+            .collect();
+
+        let mut deleted_links = vec![];
+        let mut created_links = vec![];
+        let mut link_updates = vec![];
+        for config in desired.links.iter() {
+            let name = config.name.as_str();
+            match (config.state, live_links.get(name)) {
+                (LinkState::Absent, Some(_)) => deleted_links.push(name),
+                (LinkState::Absent, None) => {}
+                (_, None) if config.kind == LinkKind::Existing => {
+                    if !peers.contains(name) {
+                        return Err(invalid(format!("link {} does not exist", name)));
+                    }
+                    link_updates.push(new_link_update(config));
+                }
+                (_, None) => created_links.push(config),
+                (_, Some(link)) => {
+                    let expected = info_kind(&config.kind);
+                    if expected.is_some() && link_kind(link) != expected.as_ref() {
+                        deleted_links.push(name);
+                        created_links.push(config);
+                    } else if let Some(update) = link_update(config, link, &names) {
+                        link_updates.push(update);
+                    }
+                }
+            }
+        }
+
+        // The objects that go away with the deleted links do not need to be deleted explicitly,
+        // and the links re-created need to be configured again.
+        let gone: HashSet<&str> = deleted_links.iter().copied().collect();
+        let created: HashSet<&str> = created_links.iter().map(|c| c.name.as_str()).collect();
+        let exists = |name: &str| -> bool {
+            (live_links.contains_key(name) && !gone.contains(name))
+                || peers.contains(name)
+                || created.contains(name)
+        };
+        for update in link_updates.iter_mut() {
+            if let Some(config) = desired.links.iter().find(|c| c.name == update.name) {
+                if let Some(master) = &config.master {
+                    if gone.contains(master.as_str()) {
+                        update.master = Some(master.clone());
+                    }
+                }
+            }
+        }
+
+        for config in desired.links.iter() {
+            if config.state == LinkState::Absent {
+                continue;
+            }
+            for dependency in link_dependencies(config) {
+                if !exists(dependency) {
+                    return Err(invalid(format!(
+                        "link {} depends on link {}, which does not exist",
+                        config.name, dependency
+                    )));
+                }
+            }
+        }
+        let created_links = sort_links(created_links)?;
+
+        let mut managed_links: HashSet<&str> = desired
+            .links
+            .iter()
+            .filter(|config| config.state != LinkState::Absent)
+            .map(|config| config.name.as_str())
+            .collect();
+        for config in desired.addresses.iter() {
+            if !exists(&config.link) {
+                return Err(invalid(format!(
+                    "address {} is on link {}, which does not exist",
+                    config.address, config.link
+                )));
+            }
+            managed_links.insert(config.link.as_str());
+        }
+        for config in desired.routes.iter() {
+            if let Some(link) = &config.link {
+                if !exists(link) {
+                    return Err(invalid(format!(
+                        "route to {} goes through link {}, which does not exist",
+                        config.destination, link
+                    )));
+                }
+            }
+        }
+
+        // Only the objects attached to links that are not deleted are considered.
+        let is_live = |index: u32| -> bool {
+            match names.get(&index) {
+                Some(name) => !gone.contains(name),
+                None => false,
+            }
+        };
+
+        let mut changes = vec![];
+
+        let live_rules: Vec<&RuleMessage> = live
+            .rules
+            .iter()
+            .filter(|rule| rule_protocol(rule) == Some(protocol))
+            .collect();
+        let (rules_to_add, rules_to_del) = match_objects(&desired.rules, &live_rules, rule_matches);
+        changes.extend(rules_to_del.into_iter().cloned().map(Change::DelRule));
+
+        let live_routes: Vec<&RouteMessage> = live
+            .routes
+            .iter()
+            .filter(|route| u8::from(route.header.protocol) == protocol)
+            .filter(|route| match route.output_interface() {
+                Some(index) => is_live(index),
+                None => true,
+            })
+            .collect();
+        let (routes_to_add, routes_to_del) =
+            match_objects(&desired.routes, &live_routes, |config, route| {
+                route_matches(config, route, &names)
+            });
+        changes.extend(routes_to_del.into_iter().cloned().map(Change::DelRoute));
+
+        let live_addresses: Vec<&AddressMessage> = live
+            .addresses
+            .iter()
+            .filter(|address| is_live(address.header.index))
+            .filter(|address| managed_links.contains(names[&address.header.index]))
+            .filter(|address| match address.prefix() {
+                Some((address, _)) => !is_ipv6_link_local(address),
+                None => false,
+            })
+            .collect();
+        let (addresses_to_add, addresses_to_del) =
+            match_objects(&desired.addresses, &live_addresses, |config, address| {
+                names.get(&address.header.index) == Some(&config.link.as_str())
+                    && address.prefix() == Some((config.address, config.prefix_len))
+            });
+        changes.extend(
+            addresses_to_del
+                .into_iter()
+                .cloned()
+                .map(Change::DelAddress),
+        );
+
+        changes.extend(
+            sort_deleted_links(deleted_links, &live_links, &names)
+                .into_iter()
+                .map(|name| Change::DelLink(name.to_string())),
+        );
+        changes.extend(created_links.iter().map(|c| Change::AddLink((*c).clone())));
+        changes.extend(
+            created_links
+                .iter()
+                .map(|config| Change::SetLink(new_link_update(config))),
+        );
+        changes.extend(link_updates.into_iter().map(Change::SetLink));
+        changes.extend(
+            addresses_to_add
+                .into_iter()
+                .cloned()
+                .map(Change::AddAddress),
+        );
+        changes.extend(routes_to_add.into_iter().cloned().map(Change::AddRoute));
+        changes.extend(rules_to_add.into_iter().cloned().map(Change::AddRule));
+
+        Ok(Plan { changes })
+    }
+}
+
+/// Configuration of a link that has just been created.
+fn new_link_update(config: &LinkConfig) -> LinkUpdate {
+    LinkUpdate {
+        name: config.name.clone(),
+        up: Some(config.state == LinkState::Up),
+        mtu: config.mtu,
+        master: config.master.clone(),
+    }
+}
+
+/// Changes needed to configure an existing link, if any.
+fn link_update(
+    config: &LinkConfig,
+    link: &LinkMessage,
+    names: &HashMap<u32, &str>,
+) -> Option<LinkUpdate> {
+    let is_up = link.header.flags.contains(LinkFlags::IFF_UP);
+    let update = LinkUpdate {
+        name: config.name.clone(),
+        up: match config.state {
+            LinkState::Up if !is_up => Some(true),
+            LinkState::Down if is_up => Some(false),
+            _ => None,
+        },
+        mtu: config.mtu.filter(|mtu| link_mtu(link) != Some(*mtu)),
+        master: config.master.clone().filter(|master| {
+            link_master(link).and_then(|i| names.get(&i)) != Some(&master.as_str())
+        }),
+    };
+    if update.up.is_none() && update.mtu.is_none() && update.master.is_none() {
+        None
+    } else {
+        Some(update)
+    }
+}
+
+/// Order the links so that each link comes after the links it depends on.
+fn sort_links(mut links: Vec<&LinkConfig>) -> Result<Vec<&LinkConfig>, Error> {
+    let mut sorted: Vec<&LinkConfig> = vec![];
+    while !links.is_empty() {
+        let ready = links.iter().position(|config| {
+            link_dependencies(config).iter().all(|dependency| {
+                !links
+                    .iter()
+                    .any(|other| other.name == *dependency && other.name != config.name)
+            })
+        });
+        match ready {
+            Some(position) => sorted.push(links.remove(position)),
+            None => {
+                let names: Vec<&str> = links.iter().map(|config| config.name.as_str()).collect();
+                return Err(invalid(format!(
+                    "circular dependency between links {}",
+                    names.join(", ")
+                )));
+            }
+        }
+    }
+    Ok(sorted)
+}
+
+/// Order the deleted links so that each link comes before the links it depends on: deleting a
+/// link also deletes the VLANs on top of it. The two ends of a veth link depend on each other, and
+/// deleting one deletes the other, so cycles are broken arbitrarily.
+fn sort_deleted_links<'a>(
+    mut links: Vec<&'a str>,
+    live_links: &HashMap<&str, &LinkMessage>,
+    names: &HashMap<u32, &str>,
+) -> Vec<&'a str> {
+    let mut sorted = vec![];
+    while !links.is_empty() {
+        let position = links
+            .iter()
+            .position(|name| {
+                !links.iter().any(|other| {
+                    other != name
+                        && live_links
+                            .get(other)
+                            .map(|link| live_link_dependencies(link, names).contains(name))
+                            .unwrap_or(false)
+                })
+            })
+            .unwrap_or(0);
+        sorted.push(links.remove(position));
+    }
+    sorted
+}
+
+/// Pair the desired objects with the live ones. Return the desired objects that are missing and
+/// the live objects that are not desired.
+fn match_objects<'a, 'b, C, M, F>(
+    desired: &'a [C],
+    live: &[&'b M],
+    matches: F,
+) -> (Vec<&'a C>, Vec<&'b M>)
+where
+    F: Fn(&C, &M) -> bool,
+{
+    let mut claimed = vec![false; live.len()];
+    let mut missing = vec![];
+    for config in desired.iter() {
+        let found = live
+            .iter()
+            .enumerate()
+            .find(|(i, object)| !claimed[*i] && matches(config, object));
+        match found {
+            Some((i, _)) => claimed[i] = true,
+            None => missing.push(config),
+        }
+    }
+    let extra = live
+        .iter()
+        .zip(claimed)
+        .filter(|(_, claimed)| !claimed)
+        .map(|(object, _)| *object)
+        .collect();
+    (missing, extra)
+}
+
+fn route_matches(config: &RouteConfig, route: &RouteMessage, names: &HashMap<u32, &str>) -> bool {
+    if route_destination(route) != Some((config.destination, config.prefix_len)) {
+        return false;
+    }
+    if route_table(route) != config.table.unwrap_or(RT_TABLE_MAIN as u32) {
+        return false;
+    }
+    if route.gateway() != config.gateway {
+        return false;
+    }
+    if let Some(link) = &config.link {
+        if route.output_interface().and_then(|i| names.get(&i)) != Some(&link.as_str()) {
+            return false;
+        }
+    }
+    match config.metric {
+        Some(metric) => route_priority(route) == Some(metric),
+        None => true,
+    }
+}
+
+fn rule_matches(config: &RuleConfig, rule: &RuleMessage) -> bool {
+    let family = if config.is_ipv6() { AF_INET6 } else { AF_INET };
+    if rule.header.family != family as u8 || rule_table(rule) != config.table {
+        return false;
+    }
+    if let Some(priority) = config.priority {
+        if rule_priority(rule) != Some(priority) {
+            return false;
+        }
+    }
+
+    let mut fw_mark = None;
+    let mut input_interface = None;
+    for nla in rule.nlas.iter() {
+        match nla {
+            rule::Nla::FwMark(mark) => fw_mark = Some(*mark),
+            rule::Nla::Iifname(name) => input_interface = Some(name.clone()),
+            _ => {}
+        }
+    }
+
+    let expected_source = config.source.map(|address| {
+        let prefix_len = config
+            .source_prefix_len
+            .unwrap_or_else(|| full_prefix_len(address));
+        (address, prefix_len)
+    });
+    let expected_destination = config.destination.map(|address| {
+        let prefix_len = config
+            .destination_prefix_len
+            .unwrap_or_else(|| full_prefix_len(address));
+        (address, prefix_len)
+    });
+    rule.source_prefix() == expected_source
+        && rule.destination_prefix() == expected_destination
+        && fw_mark == config.fw_mark
+        && input_interface == config.input_interface
+}
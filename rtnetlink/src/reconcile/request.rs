@@ -0,0 +1,322 @@
+use futures::stream::TryStreamExt;
+use nix::errno::Errno;
+use std::collections::HashMap;
+
+use crate::{
+    cache::link_name,
+    packet::{
+        constants::*,
+        nlas::route,
+        RouteProtocol,
+        RouteScope,
+        RouteTable,
+    },
+    reconcile::{
+        config::full_prefix_len,
+        plan::LiveState,
+    },
+    AddressConfig,
+    Change,
+    Error,
+    Handle,
+    IpVersion,
+    LinkConfig,
+    LinkKind,
+    LinkUpdate,
+    NetworkState,
+    Plan,
+    RouteConfig,
+    RuleConfig,
+};
+
+/// A request to bring the network to a desired state, created with
+/// [`Handle::reconcile`](crate::Handle::reconcile).
+///
+/// The live links, addresses, routes and rules are compared with the desired [`NetworkState`],
+/// and only the differences are applied, so running the same request twice does nothing the
+/// second time. Routes and rules are tagged with a protocol (`RTPROT_STATIC` by default), and
+/// only the routes and rules with this protocol are removed when they are not desired anymore,
+/// so that the objects created by other programs are left alone.
+pub struct ReconcileRequest {
+    handle: Handle,
+    desired: NetworkState,
+    protocol: RouteProtocol,
+}
+
+impl ReconcileRequest {
+    pub(crate) fn new(handle: Handle, desired: NetworkState) -> Self {
+        ReconcileRequest {
+            handle,
+            desired,
+            protocol: RouteProtocol::Static,
+        }
+    }
+
+    /// Sets the protocol of the routes and rules managed by this request.
+    ///
+    /// Default is static route protocol.
+    pub fn protocol(mut self, protocol: RouteProtocol) -> Self {
+        self.protocol = protocol;
+        self
+    }
+
+    /// Compute the changes to apply without applying them (dry run).
+    pub async fn plan(self) -> Result<Plan, Error> {
+        let live = fetch_live_state(&self.handle).await?;
+        Plan::compute(&self.desired, &live, u8::from(self.protocol))
+    }
+
+    /// Apply the changes, and return them.
+    pub async fn execute(self) -> Result<Plan, Error> {
+        let ReconcileRequest {
+            handle,
+            desired,
+            protocol,
+        } = self;
+
+        let live = fetch_live_state(&handle).await?;
+        let plan = Plan::compute(&desired, &live, u8::from(protocol))?;
+
+        let mut links = LinkIndexes {
+            handle: handle.clone(),
+            indexes: live
+                .links
+                .iter()
+                .filter_map(|link| {
+                    link_name(link).map(|name| (name.to_string(), link.header.index))
+                })
+                .collect(),
+        };
+        for change in plan.changes.iter() {
+            apply(&handle, &mut links, protocol, change.clone()).await?;
+        }
+        Ok(plan)
+    }
+}
+
+async fn fetch_live_state(handle: &Handle) -> Result<LiveState, Error> {
+    let mut live = LiveState {
+        links: handle.link().get().execute().try_collect().await?,
+        addresses: handle.address().get().execute().try_collect().await?,
+        ..Default::default()
+    };
+    for version in [IpVersion::V4, IpVersion::V6] {
+        let routes: Vec<_> = handle
+            .route()
+            .get(version.clone())
+            .execute()
+            .try_collect()
+            .await?;
+        live.routes.extend(routes);
+        let rules: Vec<_> = handle.rule().get(version).execute().try_collect().await?;
+        live.rules.extend(rules);
+    }
+    Ok(live)
+}
+
+/// Index of the links by name, updated as links are created and deleted.
+struct LinkIndexes {
+    handle: Handle,
+    indexes: HashMap<String, u32>,
+}
+
+impl LinkIndexes {
+    async fn get(&mut self, name: &str) -> Result<u32, Error> {
+        if let Some(index) = self.indexes.get(name) {
+            return Ok(*index);
+        }
+        let not_found = || Error::InvalidNetworkState(format!("link {} not found", name));
+        let link = match self
+            .handle
+            .link()
+            .get()
+            .set_name_filter(name.to_string())
+            .execute()
+            .try_next()
+            .await
+        {
+            Ok(Some(link)) => link,
+            Ok(None) => return Err(not_found()),
+            Err(e) => return Err(e),
+        };
+        self.indexes.insert(name.to_string(), link.header.index);
+        Ok(link.header.index)
+    }
+}
+
+async fn apply(
+    handle: &Handle,
+    links: &mut LinkIndexes,
+    protocol: RouteProtocol,
+    change: Change,
+) -> Result<(), Error> {
+    match change {
+        Change::DelRule(msg) => ignore_missing(handle.rule().del(msg).execute().await),
+        Change::DelRoute(msg) => ignore_missing(handle.route().del(msg).execute().await),
+        Change::DelAddress(msg) => ignore_missing(handle.address().del(msg).execute().await),
+        Change::DelLink(name) => {
+            let index = links.get(&name).await?;
+            links.indexes.remove(&name);
+            ignore_missing(handle.clone().link().del(index).execute().await)
+        }
+        Change::AddLink(config) => add_link(handle, links, config).await,
+        Change::SetLink(update) => set_link(handle, links, update).await,
+        Change::AddAddress(config) => add_address(handle, links, config).await,
+        Change::AddRoute(config) => add_route(handle, links, protocol, config).await,
+        Change::AddRule(config) => add_rule(handle, protocol, config).await,
+    }
+}
+
+/// Objects can disappear before their deletion is applied, e.g. the peer of a deleted veth link,
+/// or concurrently with the plan: they are not reported as errors.
+fn ignore_missing(result: Result<(), Error>) -> Result<(), Error> {
+    match result {
+        Err(Error::NetlinkError(err))
+            if err.code == -(Errno::ENODEV as i32)
+                || err.code == -(Errno::ENOENT as i32)
+                || err.code == -(Errno::ESRCH as i32) =>
+        {
+            Ok(())
+        }
+        result => result,
+    }
+}
+
+async fn add_link(
+    handle: &Handle,
+    links: &mut LinkIndexes,
+    config: LinkConfig,
+) -> Result<(), Error> {
+    let request = handle.link().add();
+    let request = match config.kind {
+        LinkKind::Existing => return Ok(()),
+        LinkKind::Dummy => request.dummy(config.name),
+        LinkKind::Bridge => request.bridge(config.name),
+        LinkKind::Vrf { table } => request.vrf(config.name, table),
+        LinkKind::Veth { peer } => request.veth(config.name, peer),
+        LinkKind::Vlan { parent, id } => {
+            let parent = links.get(&parent).await?;
+            request.vlan(config.name, parent, id)
+        }
+    };
+    request.execute().await
+}
+
+async fn set_link(
+    handle: &Handle,
+    links: &mut LinkIndexes,
+    update: LinkUpdate,
+) -> Result<(), Error> {
+    let index = links.get(&update.name).await?;
+    let mut request = handle.link().set(index);
+    if let Some(master) = update.master {
+        request = request.master(links.get(&master).await?);
+    }
+    if let Some(mtu) = update.mtu {
+        request = request.mtu(mtu);
+    }
+    match update.up {
+        Some(true) => request = request.up(),
+        Some(false) => request = request.down(),
+        None => {}
+    }
+    request.execute().await
+}
+
+async fn add_address(
+    handle: &Handle,
+    links: &mut LinkIndexes,
+    config: AddressConfig,
+) -> Result<(), Error> {
+    let index = links.get(&config.link).await?;
+    handle
+        .address()
+        .add(index, config.address, config.prefix_len)
+        .execute()
+        .await
+}
+
+async fn add_route(
+    handle: &Handle,
+    links: &mut LinkIndexes,
+    protocol: RouteProtocol,
+    config: RouteConfig,
+) -> Result<(), Error> {
+    let invalid = |e| Error::InvalidNetworkState(format!("route to {}: {}", config.destination, e));
+
+    let mut request = handle.route().add().protocol(protocol);
+    if let Some(link) = &config.link {
+        request = request.output_interface(links.get(link).await?);
+    }
+    if config.gateway.is_none() {
+        request = request.scope(RouteScope::Link);
+    }
+
+    let message = request.message_mut();
+    message
+        .set_destination_prefix(config.destination, config.prefix_len)
+        .map_err(invalid)?;
+    if let Some(gateway) = config.gateway {
+        message.set_gateway(gateway).map_err(invalid)?;
+    }
+    if let Some(table) = config.table {
+        if table > 255 {
+            message.header.table = RouteTable::Unspec;
+        } else {
+            message.header.table = RouteTable::from(table as u8);
+        }
+        message.nlas.push(route::Nla::Table(table));
+    }
+    if let Some(metric) = config.metric {
+        message.nlas.push(route::Nla::Priority(metric));
+    }
+    request.execute().await
+}
+
+async fn add_rule(
+    handle: &Handle,
+    protocol: RouteProtocol,
+    config: RuleConfig,
+) -> Result<(), Error> {
+    let invalid = |e| Error::InvalidNetworkState(format!("rule to table {}: {}", config.table, e));
+
+    let mut request = handle
+        .rule()
+        .add()
+        .table_id(config.table)
+        .protocol(u8::from(protocol))
+        .action(FR_ACT_TO_TBL);
+    if let Some(priority) = config.priority {
+        request = request.priority(priority);
+    }
+    if let Some(mark) = config.fw_mark {
+        request = request.fw_mark(mark);
+    }
+    if let Some(iif) = config.input_interface.clone() {
+        request = request.input_interface(iif);
+    }
+
+    let message = request.message_mut();
+    message.header.family = if config.is_ipv6() {
+        AF_INET6 as u8
+    } else {
+        AF_INET as u8
+    };
+    if let Some(source) = config.source {
+        let prefix_len = config
+            .source_prefix_len
+            .unwrap_or_else(|| full_prefix_len(source));
+        message
+            .set_source_prefix(source, prefix_len)
+            .map_err(invalid)?;
+    }
+    if let Some(destination) = config.destination {
+        let prefix_len = config
+            .destination_prefix_len
+            .unwrap_or_else(|| full_prefix_len(destination));
+        message
+            .set_destination_prefix(destination, prefix_len)
+            .map_err(invalid)?;
+    }
+    request.execute().await
+}
@@ -0,0 +1,343 @@
+use std::net::IpAddr;
+
+use crate::{
+    packet::{
+        constants::{AF_INET6, RT_TABLE_MAIN},
+        nlas::{
+            link::{self, Info, InfoKind},
+            route,
+        },
+        AddressMessage,
+        LinkFlags,
+        LinkMessage,
+        RouteMessage,
+        RouteProtocol,
+        RouteTable,
+    },
+    reconcile::plan::LiveState,
+    AddressConfig,
+    Change,
+    Error,
+    LinkConfig,
+    LinkKind,
+    LinkState,
+    LinkUpdate,
+    NetworkState,
+    Plan,
+    RouteConfig,
+};
+
+const STATIC: u8 = 4;
+
+fn ip(address: &str) -> IpAddr {
+    address.parse().unwrap()
+}
+
+fn link_config(name: &str, kind: LinkKind) -> LinkConfig {
+    LinkConfig {
+        name: name.to_string(),
+        kind,
+        state: LinkState::Up,
+        mtu: None,
+        master: None,
+    }
+}
+
+fn live_link(index: u32, name: &str, kind: Option<InfoKind>) -> LinkMessage {
+    let mut message = LinkMessage::default();
+    message.header.index = index;
+    message.header.flags = LinkFlags::IFF_UP;
+    message.nlas.push(link::Nla::IfName(name.to_string()));
+    if let Some(kind) = kind {
+        message.nlas.push(link::Nla::Info(vec![Info::Kind(kind)]));
+    }
+    message
+}
+
+fn live_address(index: u32, address: &str, prefix_len: u8) -> AddressMessage {
+    let mut message = AddressMessage::default();
+    message.header.index = index;
+    message.set_prefix(ip(address), prefix_len).unwrap();
+    message
+}
+
+fn live_route(
+    index: u32,
+    destination: &str,
+    prefix_len: u8,
+    protocol: RouteProtocol,
+) -> RouteMessage {
+    let mut message = RouteMessage::default();
+    message.header.table = RouteTable::Main;
+    message.header.protocol = protocol;
+    message
+        .set_destination_prefix(ip(destination), prefix_len)
+        .unwrap();
+    message.nlas.push(route::Nla::Oif(index));
+    message
+}
+
+fn route_config(destination: &str, prefix_len: u8, link: &str) -> RouteConfig {
+    RouteConfig {
+        destination: ip(destination),
+        prefix_len,
+        gateway: None,
+        link: Some(link.to_string()),
+        table: None,
+        metric: None,
+    }
+}
+
+#[test]
+fn create_in_dependency_order() {
+    let mut port = link_config("dummy0", LinkKind::Dummy);
+    port.master = Some("br0".to_string());
+    let desired = NetworkState {
+        links: vec![port.clone(), link_config("br0", LinkKind::Bridge)],
+        addresses: vec![AddressConfig {
+            link: "br0".to_string(),
+            address: ip("10.0.0.1"),
+            prefix_len: 24,
+        }],
+        routes: vec![route_config("10.1.0.0", 16, "br0")],
+        ..Default::default()
+    };
+    let live = LiveState {
+        links: vec![live_link(1, "lo", None)],
+        ..Default::default()
+    };
+
+    let plan = Plan::compute(&desired, &live, STATIC).unwrap();
+    assert_eq!(
+        plan.changes,
+        vec![
+            Change::AddLink(link_config("br0", LinkKind::Bridge)),
+            Change::AddLink(port),
+            Change::SetLink(LinkUpdate {
+                name: "br0".to_string(),
+                up: Some(true),
+                mtu: None,
+                master: None,
+            }),
+            Change::SetLink(LinkUpdate {
+                name: "dummy0".to_string(),
+                up: Some(true),
+                mtu: None,
+                master: Some("br0".to_string()),
+            }),
+            Change::AddAddress(desired.addresses[0].clone()),
+            Change::AddRoute(desired.routes[0].clone()),
+        ]
+    );
+    assert_eq!(
+        plan.to_string(),
+        "link add br0 type bridge\n\
+         link add dummy0 type dummy\n\
+         link set br0 up\n\
+         link set dummy0 up master br0\n\
+         address add 10.0.0.1/24 dev br0\n\
+         route add 10.1.0.0/16 dev br0 table 254\n"
+    );
+}
+
+#[test]
+fn nothing_to_do() {
+    let desired = NetworkState {
+        links: vec![link_config("dummy0", LinkKind::Dummy)],
+        addresses: vec![AddressConfig {
+            link: "dummy0".to_string(),
+            address: ip("10.0.0.1"),
+            prefix_len: 24,
+        }],
+        routes: vec![route_config("10.1.0.0", 16, "dummy0")],
+        ..Default::default()
+    };
+    let live = LiveState {
+        links: vec![live_link(2, "dummy0", Some(InfoKind::Dummy))],
+        addresses: vec![
+            live_address(2, "10.0.0.1", 24),
+            // Link-local addresses are left alone
+            live_address(2, "fe80::1", 64),
+        ],
+        routes: vec![
+            live_route(2, "10.1.0.0", 16, RouteProtocol::Static),
+            // Routes created by other programs are left alone
+            live_route(2, "10.2.0.0", 16, RouteProtocol::Kernel),
+        ],
+        ..Default::default()
+    };
+
+    assert!(Plan::compute(&desired, &live, STATIC).unwrap().is_empty());
+}
+
+#[test]
+fn remove_and_update() {
+    let mut dummy = link_config("dummy0", LinkKind::Dummy);
+    dummy.state = LinkState::Down;
+    dummy.mtu = Some(1400);
+    let mut old = link_config("dummy1", LinkKind::Dummy);
+    old.state = LinkState::Absent;
+    let desired = NetworkState {
+        links: vec![dummy, old],
+        ..Default::default()
+    };
+    let live = LiveState {
+        links: vec![
+            live_link(2, "dummy0", Some(InfoKind::Dummy)),
+            live_link(3, "dummy1", Some(InfoKind::Dummy)),
+        ],
+        addresses: vec![
+            live_address(2, "10.0.0.1", 24),
+            // Removed along with the link
+            live_address(3, "10.0.1.1", 24),
+        ],
+        routes: vec![live_route(2, "10.1.0.0", 16, RouteProtocol::Static)],
+        ..Default::default()
+    };
+
+    let plan = Plan::compute(&desired, &live, STATIC).unwrap();
+    assert_eq!(
+        plan.changes,
+        vec![
+            Change::DelRoute(live.routes[0].clone()),
+            Change::DelAddress(live.addresses[0].clone()),
+            Change::DelLink("dummy1".to_string()),
+            Change::SetLink(LinkUpdate {
+                name: "dummy0".to_string(),
+                up: Some(false),
+                mtu: Some(1400),
+                master: None,
+            }),
+        ]
+    );
+}
+
+#[test]
+fn recreate_link_of_another_kind() {
+    let desired = NetworkState {
+        links: vec![link_config("br0", LinkKind::Bridge)],
+        ..Default::default()
+    };
+    let live = LiveState {
+        links: vec![live_link(2, "br0", Some(InfoKind::Dummy))],
+        ..Default::default()
+    };
+
+    let plan = Plan::compute(&desired, &live, STATIC).unwrap();
+    assert_eq!(plan.changes.len(), 3);
+    assert_eq!(plan.changes[0], Change::DelLink("br0".to_string()));
+    assert_eq!(
+        plan.changes[1],
+        Change::AddLink(link_config("br0", LinkKind::Bridge))
+    );
+}
+
+#[test]
+fn delete_in_reverse_dependency_order() {
+    let mut parent = link_config("eth1", LinkKind::Dummy);
+    parent.state = LinkState::Absent;
+    let mut vlan = link_config(
+        "eth1.10",
+        LinkKind::Vlan {
+            parent: "eth1".to_string(),
+            id: 10,
+        },
+    );
+    vlan.state = LinkState::Absent;
+    let desired = NetworkState {
+        links: vec![parent, vlan],
+        ..Default::default()
+    };
+    let mut live_vlan = live_link(3, "eth1.10", Some(InfoKind::Vlan));
+    live_vlan.nlas.push(link::Nla::Link(2));
+    let live = LiveState {
+        links: vec![live_link(2, "eth1", Some(InfoKind::Dummy)), live_vlan],
+        ..Default::default()
+    };
+
+    let plan = Plan::compute(&desired, &live, STATIC).unwrap();
+    assert_eq!(
+        plan.changes,
+        vec![
+            Change::DelLink("eth1.10".to_string()),
+            Change::DelLink("eth1".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn display_default_route_deletion() {
+    let mut route = RouteMessage::default();
+    route.header.address_family = AF_INET6 as u8;
+    route.header.table = RouteTable::Main;
+    route.header.destination_prefix_length = 64;
+    assert_eq!(
+        Change::DelRoute(route).to_string(),
+        format!("route del ::/64 table {}", RT_TABLE_MAIN)
+    );
+}
+
+#[test]
+fn invalid_state() {
+    let desired = NetworkState {
+        links: vec![link_config("eth0", LinkKind::Existing)],
+        ..Default::default()
+    };
+    assert!(matches!(
+        Plan::compute(&desired, &LiveState::default(), STATIC),
+        Err(Error::InvalidNetworkState(_))
+    ));
+
+    let mut a = link_config("a", LinkKind::Bridge);
+    a.master = Some("b".to_string());
+    let mut b = link_config("b", LinkKind::Bridge);
+    b.master = Some("a".to_string());
+    let desired = NetworkState {
+        links: vec![a, b],
+        ..Default::default()
+    };
+    assert!(matches!(
+        Plan::compute(&desired, &LiveState::default(), STATIC),
+        Err(Error::InvalidNetworkState(_))
+    ));
+}
+
+#[test]
+fn veth_peer() {
+    let desired = NetworkState {
+        links: vec![
+            link_config(
+                "veth0",
+                LinkKind::Veth {
+                    peer: "veth1".to_string(),
+                },
+            ),
+            link_config("veth1", LinkKind::Existing),
+        ],
+        ..Default::default()
+    };
+    let plan = Plan::compute(&desired, &LiveState::default(), STATIC).unwrap();
+    assert_eq!(plan.changes.len(), 3);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn deserialize() {
+    let desired: NetworkState = serde_json::from_str(
+        r#"{
+            "links": [
+                {"name": "vrf0", "kind": "vrf", "table": 10},
+                {"name": "eth0", "kind": "existing", "master": "vrf0", "mtu": 9000}
+            ],
+            "routes": [
+                {"destination": "0.0.0.0", "prefix_len": 0, "gateway": "10.0.0.254", "table": 10}
+            ]
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(desired.links[0].kind, LinkKind::Vrf { table: 10 });
+    assert_eq!(desired.links[1].state, LinkState::Up);
+    assert_eq!(desired.links[1].master.as_deref(), Some("vrf0"));
+    assert_eq!(desired.routes[0].gateway, Some(ip("10.0.0.254")));
+    assert!(desired.addresses.is_empty());
+}
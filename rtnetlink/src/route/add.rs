@@ -1,4 +1,4 @@
-use futures::stream::StreamExt;
+use futures::stream::TryStreamExt;
 use std::{
     marker::PhantomData,
     net::{Ipv4Addr, Ipv6Addr},
@@ -9,10 +9,14 @@ use netlink_packet_route::{
     nlas::route::Nla,
     NetlinkMessage,
     RouteMessage,
+    RouteProtocol,
+    RouteScope,
+    RouteTable,
+    RouteType,
     RtnlMessage,
 };
 
-use crate::{try_nl, Error, Handle};
+use crate::{try_nl, Batchable, Error, Handle};
 
 /// A request to create a new route. This is equivalent to the `ip route add` commands.
 pub struct RouteAddRequest<T = ()> {
@@ -25,10 +29,10 @@ impl<T> RouteAddRequest<T> {
     pub(crate) fn new(handle: Handle) -> Self {
         let mut message = RouteMessage::default();
 
-        message.header.table = RT_TABLE_MAIN;
-        message.header.protocol = RTPROT_STATIC;
-        message.header.scope = RT_SCOPE_UNIVERSE;
-        message.header.kind = RTN_UNICAST;
+        message.header.table = RouteTable::Main;
+        message.header.protocol = RouteProtocol::Static;
+        message.header.scope = RouteScope::Universe;
+        message.header.kind = RouteType::Unicast;
 
         RouteAddRequest {
             handle,
@@ -52,7 +56,7 @@ impl<T> RouteAddRequest<T> {
     /// Sets the route table.
     ///
     /// Default is main route table.
-    pub fn table(mut self, table: u8) -> Self {
+    pub fn table(mut self, table: RouteTable) -> Self {
         self.message.header.table = table;
         self
     }
@@ -60,7 +64,7 @@ impl<T> RouteAddRequest<T> {
     /// Sets the route protocol.
     ///
     /// Default is static route protocol.
-    pub fn protocol(mut self, protocol: u8) -> Self {
+    pub fn protocol(mut self, protocol: RouteProtocol) -> Self {
         self.message.header.protocol = protocol;
         self
     }
@@ -68,7 +72,7 @@ impl<T> RouteAddRequest<T> {
     /// Sets the route scope.
     ///
     /// Default is universe route scope.
-    pub fn scope(mut self, scope: u8) -> Self {
+    pub fn scope(mut self, scope: RouteScope) -> Self {
         self.message.header.scope = scope;
         self
     }
@@ -76,7 +80,7 @@ impl<T> RouteAddRequest<T> {
     /// Sets the route kind.
     ///
     /// Default is unicast route kind.
-    pub fn kind(mut self, kind: u8) -> Self {
+    pub fn kind(mut self, kind: RouteType) -> Self {
         self.message.header.kind = kind;
         self
     }
@@ -103,16 +107,9 @@ impl<T> RouteAddRequest<T> {
 
     /// Execute the request.
     pub async fn execute(self) -> Result<(), Error> {
-        let RouteAddRequest {
-            mut handle,
-            message,
-            ..
-        } = self;
-        let mut req = NetlinkMessage::from(RtnlMessage::NewRoute(message));
-        req.header.flags = NLM_F_REQUEST | NLM_F_ACK | NLM_F_EXCL | NLM_F_CREATE;
-
-        let mut response = handle.request(req)?;
-        while let Some(message) = response.next().await {
+        let mut handle = self.handle.clone();
+        let mut response = handle.request(self.into_message())?;
+        while let Some(message) = response.try_next().await? {
             try_nl!(message);
         }
         Ok(())
@@ -124,6 +121,15 @@ impl<T> RouteAddRequest<T> {
     }
 }
 
+impl<T> Batchable for RouteAddRequest<T> {
+    fn into_message(self) -> NetlinkMessage<RtnlMessage> {
+        let RouteAddRequest { message, .. } = self;
+        let mut req = NetlinkMessage::from(RtnlMessage::NewRoute(message));
+        req.header.flags = NLM_F_REQUEST | NLM_F_ACK | NLM_F_EXCL | NLM_F_CREATE;
+        req
+    }
+}
+
 impl RouteAddRequest<Ipv4Addr> {
     /// Sets the source address prefix.
     pub fn source_prefix(mut self, addr: Ipv4Addr, prefix_length: u8) -> Self {
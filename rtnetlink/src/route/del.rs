@@ -1,7 +1,8 @@
-use futures::stream::StreamExt;
+use futures::stream::TryStreamExt;
 
 use crate::{
     packet::{NetlinkMessage, NetlinkPayload, RouteMessage, RtnlMessage, NLM_F_ACK, NLM_F_REQUEST},
+    Batchable,
     Error,
     Handle,
 };
@@ -18,17 +19,11 @@ impl RouteDelRequest {
 
     /// Execute the request
     pub async fn execute(self) -> Result<(), Error> {
-        let RouteDelRequest {
-            mut handle,
-            message,
-        } = self;
-
-        let mut req = NetlinkMessage::from(RtnlMessage::DelRoute(message));
-        req.header.flags = NLM_F_REQUEST | NLM_F_ACK;
-        let mut response = handle.request(req)?;
-        while let Some(msg) = response.next().await {
+        let mut handle = self.handle.clone();
+        let mut response = handle.request(self.into_message())?;
+        while let Some(msg) = response.try_next().await? {
             if let NetlinkPayload::Error(e) = msg.payload {
-                return Err(Error::NetlinkError(e));
+                return Err(Error::from(e));
             }
         }
         Ok(())
@@ -38,3 +33,12 @@ impl RouteDelRequest {
         &mut self.message
     }
 }
+
+impl Batchable for RouteDelRequest {
+    fn into_message(self) -> NetlinkMessage<RtnlMessage> {
+        let RouteDelRequest { message, .. } = self;
+        let mut req = NetlinkMessage::from(RtnlMessage::DelRoute(message));
+        req.header.flags = NLM_F_REQUEST | NLM_F_ACK;
+        req
+    }
+}
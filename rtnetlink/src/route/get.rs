@@ -1,16 +1,22 @@
-use futures::{
-    future::{self, Either},
-    stream::{StreamExt, TryStream},
-    FutureExt,
-};
+use futures::stream::{StreamExt, TryStream};
 
-use netlink_packet_route::{constants::*, NetlinkMessage, RouteMessage, RtnlMessage};
+use netlink_packet_route::{
+    constants::*,
+    NetlinkMessage,
+    RouteMessage,
+    RouteProtocol,
+    RouteScope,
+    RouteTable,
+    RouteType,
+    RtnlMessage,
+};
 
-use crate::{try_rtnl, Error, Handle};
+use crate::{dump_request, try_rtnl, DumpInterruptedPolicy, Error, Handle};
 
 pub struct RouteGetRequest {
     handle: Handle,
     message: RouteMessage,
+    dump_interrupted: DumpInterruptedPolicy,
 }
 
 /// Internet Protocol (IP) version.
@@ -45,34 +51,43 @@ impl RouteGetRequest {
         // > is the wildcard.
         message.header.destination_prefix_length = 0;
         message.header.source_prefix_length = 0;
-        message.header.scope = RT_SCOPE_UNIVERSE;
-        message.header.kind = RTN_UNSPEC;
+        message.header.scope = RouteScope::Universe;
+        message.header.kind = RouteType::Unspec;
 
         // I don't know if these two fields matter
-        message.header.table = RT_TABLE_UNSPEC;
-        message.header.protocol = RTPROT_UNSPEC;
+        message.header.table = RouteTable::Unspec;
+        message.header.protocol = RouteProtocol::Unspec;
 
-        RouteGetRequest { handle, message }
+        RouteGetRequest {
+            handle,
+            message,
+            dump_interrupted: DumpInterruptedPolicy::default(),
+        }
     }
 
     pub fn message_mut(&mut self) -> &mut RouteMessage {
         &mut self.message
     }
 
+    /// Set what to do if the dump is interrupted by a concurrent change (see
+    /// [`DumpInterruptedPolicy`](enum.DumpInterruptedPolicy.html)). By default, the interruption
+    /// is ignored.
+    pub fn on_dump_interrupted(mut self, policy: DumpInterruptedPolicy) -> Self {
+        self.dump_interrupted = policy;
+        self
+    }
+
     pub fn execute(self) -> impl TryStream<Ok = RouteMessage, Error = Error> {
         let RouteGetRequest {
-            mut handle,
+            handle,
             message,
+            dump_interrupted,
         } = self;
 
         let mut req = NetlinkMessage::from(RtnlMessage::GetRoute(message));
         req.header.flags = NLM_F_REQUEST | NLM_F_DUMP;
 
-        match handle.request(req) {
-            Ok(response) => {
-                Either::Left(response.map(move |msg| Ok(try_rtnl!(msg, RtnlMessage::NewRoute))))
-            }
-            Err(e) => Either::Right(future::err::<RouteMessage, Error>(e).into_stream()),
-        }
+        dump_request(handle, req, dump_interrupted)
+            .map(move |msg| Ok(try_rtnl!(msg?, RtnlMessage::NewRoute)))
     }
 }
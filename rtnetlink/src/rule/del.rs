@@ -1,39 +1,57 @@
-use futures::stream::StreamExt;
+use futures::stream::TryStreamExt;
+use std::{
+    marker::PhantomData,
+    net::{Ipv4Addr, Ipv6Addr},
+};
 
-use crate::{
-    packet::{NetlinkMessage, RtnlMessage, RuleMessage, NLM_F_ACK, NLM_F_REQUEST},
-    try_nl,
-    Error,
-    Handle,
+use netlink_packet_route::{
+    constants::*,
+    nlas::rule::{Nla, PortRange, UidRange},
+    NetlinkMessage,
+    RtnlMessage,
+    RuleMessage,
 };
 
-pub struct RuleDelRequest {
+use crate::{try_nl, Batchable, Error, Handle};
+
+/// A request to delete a rule. This is equivalent to the `ip rule del` command.
+///
+/// The kernel deletes the first rule matching all the attributes that are set in the request, so
+/// the rule can either be given as a dumped [`RuleMessage`] or described with the builder
+/// methods, starting from [`RuleHandle::del_matching`](crate::RuleHandle::del_matching).
+pub struct RuleDelRequest<T = ()> {
     handle: Handle,
     message: RuleMessage,
+    _phantom: PhantomData<T>,
 }
 
-impl RuleDelRequest {
+impl<T> RuleDelRequest<T> {
     pub(crate) fn new(handle: Handle, message: RuleMessage) -> Self {
-        RuleDelRequest { handle, message }
+        RuleDelRequest {
+            handle,
+            message,
+            _phantom: Default::default(),
+        }
     }
 
     /// Execute the request
     pub async fn execute(self) -> Result<(), Error> {
-        let RuleDelRequest {
-            mut handle,
-            message,
-        } = self;
-
-        let mut req = NetlinkMessage::from(RtnlMessage::DelRule(message));
-        req.header.flags = NLM_F_REQUEST | NLM_F_ACK;
-        let mut response = handle.request(req)?;
-        while let Some(msg) = response.next().await {
+        let mut handle = self.handle.clone();
+        let mut response = handle.request(self.into_message())?;
+        while let Some(msg) = response.try_next().await? {
             try_nl!(msg);
         }
         Ok(())
     }
+}
 
-    pub fn message_mut(&mut self) -> &mut RuleMessage {
-        &mut self.message
+impl<T> Batchable for RuleDelRequest<T> {
+    fn into_message(self) -> NetlinkMessage<RtnlMessage> {
+        let RuleDelRequest { message, .. } = self;
+        let mut req = NetlinkMessage::from(RtnlMessage::DelRule(message));
+        req.header.flags = NLM_F_REQUEST | NLM_F_ACK;
+        req
     }
 }
+
+impl_rule_selector!(RuleDelRequest);
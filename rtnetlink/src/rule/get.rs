@@ -1,17 +1,14 @@
 use crate::IpVersion;
-use futures::{
-    future::{self, Either},
-    stream::{StreamExt, TryStream},
-    FutureExt,
-};
+use futures::stream::{StreamExt, TryStream};
 
 use netlink_packet_route::{constants::*, NetlinkMessage, RtnlMessage, RuleMessage};
 
-use crate::{try_rtnl, Error, Handle};
+use crate::{dump_request, try_rtnl, DumpInterruptedPolicy, Error, Handle};
 
 pub struct RuleGetRequest {
     handle: Handle,
     message: RuleMessage,
+    dump_interrupted: DumpInterruptedPolicy,
 }
 
 impl RuleGetRequest {
@@ -25,27 +22,36 @@ impl RuleGetRequest {
         message.header.action = FR_ACT_UNSPEC;
         message.header.table = RT_TABLE_UNSPEC;
 
-        RuleGetRequest { handle, message }
+        RuleGetRequest {
+            handle,
+            message,
+            dump_interrupted: DumpInterruptedPolicy::default(),
+        }
     }
 
     pub fn message_mut(&mut self) -> &mut RuleMessage {
         &mut self.message
     }
 
+    /// Set what to do if the dump is interrupted by a concurrent change (see
+    /// [`DumpInterruptedPolicy`](enum.DumpInterruptedPolicy.html)). By default, the interruption
+    /// is ignored.
+    pub fn on_dump_interrupted(mut self, policy: DumpInterruptedPolicy) -> Self {
+        self.dump_interrupted = policy;
+        self
+    }
+
     pub fn execute(self) -> impl TryStream<Ok = RuleMessage, Error = Error> {
         let RuleGetRequest {
-            mut handle,
+            handle,
             message,
+            dump_interrupted,
         } = self;
 
         let mut req = NetlinkMessage::from(RtnlMessage::GetRule(message));
         req.header.flags = NLM_F_REQUEST | NLM_F_DUMP;
 
-        match handle.request(req) {
-            Ok(response) => {
-                Either::Left(response.map(move |msg| Ok(try_rtnl!(msg, RtnlMessage::NewRule))))
-            }
-            Err(e) => Either::Right(future::err::<RuleMessage, Error>(e).into_stream()),
-        }
+        dump_request(handle, req, dump_interrupted)
+            .map(move |msg| Ok(try_rtnl!(msg?, RtnlMessage::NewRule)))
     }
 }
@@ -22,4 +22,13 @@ impl RuleHandle {
     pub fn del(&self, rule: RuleMessage) -> RuleDelRequest {
         RuleDelRequest::new(self.0.clone(), rule)
     }
+
+    /// Delete the first route rule entry matching the selector built with the returned request
+    /// (equivalent to `ip rule del` with a selector). Attributes that are not set are not
+    /// compared, and the table defaults to any table. The address family must be set with
+    /// [`v4`](struct.RuleDelRequest.html#method.v4) or
+    /// [`v6`](struct.RuleDelRequest.html#method.v6): the kernel rejects the request otherwise.
+    pub fn del_matching(&self) -> RuleDelRequest {
+        RuleDelRequest::new(self.0.clone(), RuleMessage::default())
+    }
 }
@@ -1,3 +1,219 @@
+/// Implement the builder methods shared by the requests that describe a rule: the attributes
+/// used to create a rule are the same as the ones used to select the rule to delete.
+macro_rules! impl_rule_selector {
+    ($request:ident) => {
+        impl<T> $request<T> {
+            /// Sets the input interface name.
+            pub fn input_interface(mut self, ifname: String) -> Self {
+                self.message.nlas.push(Nla::Iifname(ifname));
+                self
+            }
+
+            /// Sets the output interface name.
+            pub fn output_interface(mut self, ifname: String) -> Self {
+                self.message.nlas.push(Nla::OifName(ifname));
+                self
+            }
+
+            /// Sets the rule table.
+            ///
+            /// Use [`table_id`](Self::table_id) for tables with an id greater than 255.
+            pub fn table(mut self, table: u8) -> Self {
+                self.message.header.table = table;
+                self
+            }
+
+            /// Sets the rule table, using the `FRA_TABLE` attribute when the id does not fit in
+            /// the header.
+            pub fn table_id(mut self, table: u32) -> Self {
+                if table > 255 {
+                    self.message.header.table = RT_TABLE_UNSPEC;
+                    self.message.nlas.push(Nla::Table(table));
+                } else {
+                    self.message.header.table = table as u8;
+                }
+                self
+            }
+
+            /// Set the tos.
+            pub fn tos(mut self, tos: u8) -> Self {
+                self.message.header.tos = tos;
+                self
+            }
+
+            /// Set action.
+            pub fn action(mut self, action: u8) -> Self {
+                self.message.header.action = action;
+                self
+            }
+
+            /// Set the priority of the rule. Rules are evaluated by increasing priority.
+            pub fn priority(mut self, priority: u32) -> Self {
+                self.message.nlas.push(Nla::Priority(priority));
+                self
+            }
+
+            /// Match packets with the given firewall mark.
+            pub fn fw_mark(mut self, mark: u32) -> Self {
+                self.message.nlas.push(Nla::FwMark(mark));
+                self
+            }
+
+            /// Set the mask applied to the firewall mark before comparing it.
+            pub fn fw_mask(mut self, mask: u32) -> Self {
+                self.message.nlas.push(Nla::FwMask(mask));
+                self
+            }
+
+            /// Jump to the rule with the given priority. This also sets the action to
+            /// `FR_ACT_GOTO`.
+            pub fn goto(mut self, priority: u32) -> Self {
+                self.message.header.action = FR_ACT_GOTO;
+                self.message.nlas.push(Nla::Goto(priority));
+                self
+            }
+
+            /// Reject routing decisions whose prefix length is less than or equal to `len`.
+            pub fn suppress_prefix_len(mut self, len: u32) -> Self {
+                self.message.nlas.push(Nla::SuppressPrefixLen(len));
+                self
+            }
+
+            /// Reject routing decisions that use a device belonging to the given interface
+            /// group.
+            pub fn suppress_ifgroup(mut self, group: u32) -> Self {
+                self.message.nlas.push(Nla::SuppressIfGroup(group));
+                self
+            }
+
+            /// Match packets with the given tunnel id.
+            pub fn tun_id(mut self, id: u64) -> Self {
+                self.message.nlas.push(Nla::TunId(id));
+                self
+            }
+
+            /// Look up the table associated with the L3 master device of the input or output
+            /// interface. This cannot be combined with [`table`](Self::table).
+            pub fn l3mdev(mut self) -> Self {
+                self.message.nlas.push(Nla::L3MDev(1));
+                self
+            }
+
+            /// Set the protocol that installed the rule (`RTPROT_*`).
+            pub fn protocol(mut self, protocol: u8) -> Self {
+                self.message.nlas.push(Nla::Protocol(protocol));
+                self
+            }
+
+            /// Match packets with the given IP protocol (`IPPROTO_*`).
+            pub fn ip_proto(mut self, proto: u8) -> Self {
+                self.message.nlas.push(Nla::IpProto(proto));
+                self
+            }
+
+            /// Match packets sent by sockets owned by a user id in `start..=end`.
+            pub fn uid_range(mut self, start: u32, end: u32) -> Self {
+                self.message
+                    .nlas
+                    .push(Nla::UidRange(UidRange { start, end }));
+                self
+            }
+
+            /// Match packets with a source port in `start..=end`.
+            pub fn source_port_range(mut self, start: u16, end: u16) -> Self {
+                self.message
+                    .nlas
+                    .push(Nla::SourcePortRange(PortRange { start, end }));
+                self
+            }
+
+            /// Match packets with a destination port in `start..=end`.
+            pub fn destination_port_range(mut self, start: u16, end: u16) -> Self {
+                self.message
+                    .nlas
+                    .push(Nla::DestinationPortRange(PortRange { start, end }));
+                self
+            }
+
+            /// Build an IP v4 rule
+            pub fn v4(mut self) -> $request<Ipv4Addr> {
+                self.message.header.family = AF_INET as u8;
+                $request {
+                    handle: self.handle,
+                    message: self.message,
+                    _phantom: Default::default(),
+                }
+            }
+
+            /// Build an IP v6 rule
+            pub fn v6(mut self) -> $request<Ipv6Addr> {
+                self.message.header.family = AF_INET6 as u8;
+                $request {
+                    handle: self.handle,
+                    message: self.message,
+                    _phantom: Default::default(),
+                }
+            }
+
+            pub fn message_mut(&mut self) -> &mut RuleMessage {
+                &mut self.message
+            }
+        }
+
+        impl $request<Ipv4Addr> {
+            /// Sets the source address prefix.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `prefix_length` is greater than 32.
+            pub fn source_prefix(mut self, addr: Ipv4Addr, prefix_length: u8) -> Self {
+                self.message
+                    .set_source_prefix(addr.into(), prefix_length)
+                    .unwrap_or_else(|e| panic!("{}", e));
+                self
+            }
+
+            /// Sets the destination address prefix.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `prefix_length` is greater than 32.
+            pub fn destination_prefix(mut self, addr: Ipv4Addr, prefix_length: u8) -> Self {
+                self.message
+                    .set_destination_prefix(addr.into(), prefix_length)
+                    .unwrap_or_else(|e| panic!("{}", e));
+                self
+            }
+        }
+
+        impl $request<Ipv6Addr> {
+            /// Sets the source address prefix.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `prefix_length` is greater than 128.
+            pub fn source_prefix(mut self, addr: Ipv6Addr, prefix_length: u8) -> Self {
+                self.message
+                    .set_source_prefix(addr.into(), prefix_length)
+                    .unwrap_or_else(|e| panic!("{}", e));
+                self
+            }
+
+            /// Sets the destination address prefix.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `prefix_length` is greater than 128.
+            pub fn destination_prefix(mut self, addr: Ipv6Addr, prefix_length: u8) -> Self {
+                self.message
+                    .set_destination_prefix(addr.into(), prefix_length)
+                    .unwrap_or_else(|e| panic!("{}", e));
+                self
+            }
+        }
+    };
+}
+
 mod handle;
 pub use self::handle::*;
 
@@ -1,12 +1,10 @@
-use futures::{
-    future::{self, Either},
-    stream::{StreamExt, TryStream},
-    FutureExt,
-};
+use futures::stream::{StreamExt, TryStream};
 
 use crate::{
     packet::{NetlinkMessage, RtnlMessage, TcMessage, NLM_F_DUMP, NLM_F_REQUEST},
+    dump_request,
     try_rtnl,
+    DumpInterruptedPolicy,
     Error,
     Handle,
 };
@@ -14,6 +12,7 @@ use crate::{
 pub struct QDiscGetRequest {
     handle: Handle,
     message: TcMessage,
+    dump_interrupted: DumpInterruptedPolicy,
 }
 
 impl QDiscGetRequest {
@@ -21,117 +20,153 @@ impl QDiscGetRequest {
         QDiscGetRequest {
             handle,
             message: TcMessage::default(),
+            dump_interrupted: DumpInterruptedPolicy::default(),
         }
     }
 
+    /// Set what to do if the dump is interrupted by a concurrent change (see
+    /// [`DumpInterruptedPolicy`](enum.DumpInterruptedPolicy.html)). By default, the interruption
+    /// is ignored.
+    pub fn on_dump_interrupted(mut self, policy: DumpInterruptedPolicy) -> Self {
+        self.dump_interrupted = policy;
+        self
+    }
+
     /// Execute the request
     pub fn execute(self) -> impl TryStream<Ok = TcMessage, Error = Error> {
         let QDiscGetRequest {
-            mut handle,
+            handle,
             message,
+            dump_interrupted,
         } = self;
 
         let mut req = NetlinkMessage::from(RtnlMessage::GetQueueDiscipline(message));
         req.header.flags = NLM_F_REQUEST | NLM_F_DUMP;
 
-        match handle.request(req) {
-            Ok(response) => Either::Left(
-                response.map(move |msg| Ok(try_rtnl!(msg, RtnlMessage::NewQueueDiscipline))),
-            ),
-            Err(e) => Either::Right(future::err::<TcMessage, Error>(e).into_stream()),
-        }
+        dump_request(handle, req, dump_interrupted)
+            .map(move |msg| Ok(try_rtnl!(msg?, RtnlMessage::NewQueueDiscipline)))
     }
 }
 
 pub struct TrafficClassGetRequest {
     handle: Handle,
     message: TcMessage,
+    dump_interrupted: DumpInterruptedPolicy,
 }
 
 impl TrafficClassGetRequest {
     pub(crate) fn new(handle: Handle, ifindex: i32) -> Self {
         let mut message = TcMessage::default();
         message.header.index = ifindex;
-        TrafficClassGetRequest { handle, message }
+        TrafficClassGetRequest {
+            handle,
+            message,
+            dump_interrupted: DumpInterruptedPolicy::default(),
+        }
+    }
+
+    /// Set what to do if the dump is interrupted by a concurrent change (see
+    /// [`DumpInterruptedPolicy`](enum.DumpInterruptedPolicy.html)). By default, the interruption
+    /// is ignored.
+    pub fn on_dump_interrupted(mut self, policy: DumpInterruptedPolicy) -> Self {
+        self.dump_interrupted = policy;
+        self
     }
 
     /// Execute the request
     pub fn execute(self) -> impl TryStream<Ok = TcMessage, Error = Error> {
         let TrafficClassGetRequest {
-            mut handle,
+            handle,
             message,
+            dump_interrupted,
         } = self;
 
         let mut req = NetlinkMessage::from(RtnlMessage::GetTrafficClass(message));
         req.header.flags = NLM_F_REQUEST | NLM_F_DUMP;
 
-        match handle.request(req) {
-            Ok(response) => Either::Left(
-                response.map(move |msg| Ok(try_rtnl!(msg, RtnlMessage::NewTrafficClass))),
-            ),
-            Err(e) => Either::Right(future::err::<TcMessage, Error>(e).into_stream()),
-        }
+        dump_request(handle, req, dump_interrupted)
+            .map(move |msg| Ok(try_rtnl!(msg?, RtnlMessage::NewTrafficClass)))
     }
 }
 
 pub struct TrafficFilterGetRequest {
     handle: Handle,
     message: TcMessage,
+    dump_interrupted: DumpInterruptedPolicy,
 }
 
 impl TrafficFilterGetRequest {
     pub(crate) fn new(handle: Handle, ifindex: i32) -> Self {
         let mut message = TcMessage::default();
         message.header.index = ifindex;
-        TrafficFilterGetRequest { handle, message }
+        TrafficFilterGetRequest {
+            handle,
+            message,
+            dump_interrupted: DumpInterruptedPolicy::default(),
+        }
+    }
+
+    /// Set what to do if the dump is interrupted by a concurrent change (see
+    /// [`DumpInterruptedPolicy`](enum.DumpInterruptedPolicy.html)). By default, the interruption
+    /// is ignored.
+    pub fn on_dump_interrupted(mut self, policy: DumpInterruptedPolicy) -> Self {
+        self.dump_interrupted = policy;
+        self
     }
 
     /// Execute the request
     pub fn execute(self) -> impl TryStream<Ok = TcMessage, Error = Error> {
         let TrafficFilterGetRequest {
-            mut handle,
+            handle,
             message,
+            dump_interrupted,
         } = self;
 
         let mut req = NetlinkMessage::from(RtnlMessage::GetTrafficFilter(message));
         req.header.flags = NLM_F_REQUEST | NLM_F_DUMP;
 
-        match handle.request(req) {
-            Ok(response) => Either::Left(
-                response.map(move |msg| Ok(try_rtnl!(msg, RtnlMessage::NewTrafficFilter))),
-            ),
-            Err(e) => Either::Right(future::err::<TcMessage, Error>(e).into_stream()),
-        }
+        dump_request(handle, req, dump_interrupted)
+            .map(move |msg| Ok(try_rtnl!(msg?, RtnlMessage::NewTrafficFilter)))
     }
 }
 
 pub struct TrafficChainGetRequest {
     handle: Handle,
     message: TcMessage,
+    dump_interrupted: DumpInterruptedPolicy,
 }
 
 impl TrafficChainGetRequest {
     pub(crate) fn new(handle: Handle, ifindex: i32) -> Self {
         let mut message = TcMessage::default();
         message.header.index = ifindex;
-        TrafficChainGetRequest { handle, message }
+        TrafficChainGetRequest {
+            handle,
+            message,
+            dump_interrupted: DumpInterruptedPolicy::default(),
+        }
+    }
+
+    /// Set what to do if the dump is interrupted by a concurrent change (see
+    /// [`DumpInterruptedPolicy`](enum.DumpInterruptedPolicy.html)). By default, the interruption
+    /// is ignored.
+    pub fn on_dump_interrupted(mut self, policy: DumpInterruptedPolicy) -> Self {
+        self.dump_interrupted = policy;
+        self
     }
 
     /// Execute the request
     pub fn execute(self) -> impl TryStream<Ok = TcMessage, Error = Error> {
         let TrafficChainGetRequest {
-            mut handle,
+            handle,
             message,
+            dump_interrupted,
         } = self;
 
         let mut req = NetlinkMessage::from(RtnlMessage::GetTrafficChain(message));
         req.header.flags = NLM_F_REQUEST | NLM_F_DUMP;
 
-        match handle.request(req) {
-            Ok(response) => Either::Left(
-                response.map(move |msg| Ok(try_rtnl!(msg, RtnlMessage::NewTrafficChain))),
-            ),
-            Err(e) => Either::Right(future::err::<TcMessage, Error>(e).into_stream()),
-        }
+        dump_request(handle, req, dump_interrupted)
+            .map(move |msg| Ok(try_rtnl!(msg?, RtnlMessage::NewTrafficChain)))
     }
 }